@@ -0,0 +1,88 @@
+use burn::grad_clipping::{GradientClipping, GradientClippingConfig};
+use burn::optim::decay::WeightDecayConfig;
+use burn::optim::{AdamConfig, FusedAdamConfig, SimpleOptimizer};
+use burn::tensor::{backend::Backend, Distribution, Shape, Tensor};
+use burn_common::benchmark::{run_benchmark, Benchmark};
+use derive_new::new;
+
+/// Steps of Adam with weight decay and gradient clipping, either composed from the separate
+/// transforms or fused in a single sequence of operations.
+#[derive(new)]
+struct OptimBenchmark<B: Backend, O: SimpleOptimizer<B>, const D: usize> {
+    name: String,
+    optim: O,
+    /// Clipping applied before the step, for the optimizers not clipping the gradients themselves.
+    clipping: Option<GradientClipping>,
+    shape: Shape<D>,
+    num_repeats: usize,
+    device: B::Device,
+}
+
+impl<B: Backend, O: SimpleOptimizer<B>, const D: usize> Benchmark for OptimBenchmark<B, O, D> {
+    type Args = (Tensor<B, D>, Tensor<B, D>);
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn execute(&self, (mut tensor, grad): Self::Args) {
+        let mut state = None;
+        for _ in 0..self.num_repeats {
+            let grad = match &self.clipping {
+                Some(clipping) => clipping.clip_gradient(grad.clone()),
+                None => grad.clone(),
+            };
+            (tensor, state) = self.optim.step(1e-3, tensor, grad, state);
+        }
+    }
+
+    fn prepare(&self) -> Self::Args {
+        let tensor = Tensor::random_device(self.shape.clone(), Distribution::Default, &self.device);
+        let grad = Tensor::random_device(self.shape.clone(), Distribution::Default, &self.device);
+
+        (tensor, grad)
+    }
+
+    fn sync(&self) {
+        B::sync(&self.device)
+    }
+}
+
+#[allow(dead_code)]
+fn bench<B: Backend>(device: &B::Device) {
+    const D: usize = 2;
+    let shape: Shape<D> = [1024, 1024].into();
+    let num_repeats = 10;
+    let weight_decay = WeightDecayConfig::new(1e-2);
+    let clipping = GradientClippingConfig::Norm(1.0);
+
+    let adam = AdamConfig::new()
+        .with_weight_decay(Some(weight_decay.clone()))
+        .init_simple::<B>();
+    let fused = FusedAdamConfig::new()
+        .with_weight_decay(Some(weight_decay))
+        .with_grad_clipping(Some(clipping.clone()))
+        .init_simple::<B>();
+
+    println!("Backend {}", B::name());
+    run_benchmark(OptimBenchmark::<B, _, D>::new(
+        "Adam".into(),
+        adam,
+        Some(clipping.init()),
+        shape.clone(),
+        num_repeats,
+        device.clone(),
+    ));
+    run_benchmark(OptimBenchmark::<B, _, D>::new(
+        "Fused Adam".into(),
+        fused,
+        None,
+        shape,
+        num_repeats,
+        device.clone(),
+    ));
+}
+
+fn main() {
+    backend_comparison::bench_on_backend!();
+}
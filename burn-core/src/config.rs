@@ -2,13 +2,30 @@ use alloc::{format, string::String, string::ToString};
 pub use burn_derive::Config;
 
 /// Configuration IO error.
+///
+/// New kinds of errors may be added, so matching on it requires a wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum ConfigError {
     /// Invalid format.
     InvalidFormat(String),
 
     /// File not found.
     FileNotFound(String),
+
+    /// Key not matching any field of the configuration.
+    UnknownKey(String),
+
+    /// Value not representable by the type of its field, e.g. a fractional value of an integer.
+    InvalidValue {
+        /// The key of the field.
+        key: String,
+        /// The invalid value.
+        value: f64,
+    },
+
+    /// Value of a field outside of its valid range.
+    OutOfRange(String),
 }
 
 impl core::fmt::Display for ConfigError {
@@ -22,6 +39,15 @@ impl core::fmt::Display for ConfigError {
             Self::FileNotFound(err) => {
                 message += format!("File not found: {err}").as_str();
             }
+            Self::UnknownKey(key) => {
+                message += format!("Unknown key: {key}").as_str();
+            }
+            Self::InvalidValue { key, value } => {
+                message += format!("Invalid value for {key}: {value}").as_str();
+            }
+            Self::OutOfRange(err) => {
+                message += format!("Value out of range: {err}").as_str();
+            }
         };
 
         f.write_str(message.as_str())
@@ -79,6 +105,36 @@ pub trait Config: serde::Serialize + serde::de::DeserializeOwned {
         })?;
         config_from_str(content)
     }
+
+    /// Creates the configuration from a flat key-value map, e.g. from a hyperparameter sweep.
+    ///
+    /// Nested fields are named with their path separated by dots, e.g. `momentum.dampening`.
+    /// Boolean fields are enabled by any non-zero value, and integer fields require integral
+    /// values. The fields missing from the map take their default value.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - Values of the fields.
+    ///
+    /// # Returns
+    ///
+    /// The configuration, or an error if a key doesn't match any field, including the fields of
+    /// an optional configuration which is `None` by default, or if a value is invalid.
+    #[cfg(feature = "std")]
+    fn from_flat(values: &std::collections::HashMap<String, f64>) -> Result<Self, ConfigError> {
+        config_from_flat(values)
+    }
+}
+
+/// Validation of the values of a [configuration](Config), e.g. to report a misconfigured
+/// hyperparameter sweep before training.
+pub trait ValidateConfig: Config {
+    /// Check that the values are in their valid range.
+    ///
+    /// # Returns
+    ///
+    /// An [out of range](ConfigError::OutOfRange) error describing the first invalid value.
+    fn validate(&self) -> Result<(), ConfigError>;
 }
 
 /// Converts a configuration to a JSON string.
@@ -97,3 +153,63 @@ pub fn config_to_json<C: Config>(config: &C) -> String {
 fn config_from_str<C: Config>(content: &str) -> Result<C, ConfigError> {
     serde_json::from_str(content).map_err(|err| ConfigError::InvalidFormat(format!("{err}")))
 }
+
+#[cfg(feature = "std")]
+fn config_from_flat<C: Config>(
+    values: &std::collections::HashMap<String, f64>,
+) -> Result<C, ConfigError> {
+    use serde_json::{Map, Number, Value};
+
+    let number = |key: &String, value: f64| {
+        let invalid_value = || ConfigError::InvalidValue {
+            key: key.clone(),
+            value,
+        };
+        match value.fract() == 0.0 && value.abs() < i64::MAX as f64 {
+            true => Ok(Number::from(value as i64)),
+            false => Number::from_f64(value).ok_or_else(invalid_value),
+        }
+    };
+
+    // The fields missing from the map take their default value, the required fields are given.
+    let defaults = serde_json::from_value::<C>(Value::Object(Map::new())).or_else(|_| {
+        let mut given = Map::new();
+        for (key, value) in values {
+            given.insert(key.clone(), Value::Number(number(key, *value)?));
+        }
+        serde_json::from_value::<C>(Value::Object(given))
+            .map_err(|err| ConfigError::InvalidFormat(format!("{err}")))
+    })?;
+    let mut json = serde_json::to_value(defaults).unwrap();
+
+    for (key, value) in values {
+        let unknown_key = || ConfigError::UnknownKey(key.clone());
+        let mut field = &mut json;
+
+        // An optional configuration which is `None` is `null`, so its fields are unknown.
+        for name in key.split('.') {
+            field = field
+                .as_object_mut()
+                .and_then(|object| object.get_mut(name))
+                .ok_or_else(unknown_key)?;
+        }
+
+        let invalid_value = || ConfigError::InvalidValue {
+            key: key.clone(),
+            value: *value,
+        };
+        *field = match &*field {
+            Value::Bool(_) => Value::Bool(*value != 0.0),
+            Value::Number(number) if !number.is_f64() => {
+                if value.fract() != 0.0 {
+                    return Err(invalid_value());
+                }
+                Value::Number(Number::from(*value as i64))
+            }
+            Value::Object(_) | Value::Array(_) => return Err(unknown_key()),
+            _ => Value::Number(Number::from_f64(*value).ok_or_else(invalid_value)?),
+        };
+    }
+
+    serde_json::from_value(json).map_err(|err| ConfigError::InvalidFormat(format!("{err}")))
+}
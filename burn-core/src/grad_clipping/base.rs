@@ -1,6 +1,11 @@
 use crate as burn;
 
-use crate::{config::Config, tensor::Tensor};
+use super::{ClipStats, NormKind};
+use crate::{
+    config::{Config, ConfigError, ValidateConfig},
+    tensor::Tensor,
+};
+use alloc::format;
 use burn_tensor::backend::Backend;
 
 /// Gradient Clipping provides a way to mitigate exploding gradients
@@ -11,6 +16,35 @@ pub enum GradientClippingConfig {
 
     /// Clip the gradient by norm.
     Norm(f32),
+
+    /// Clip the gradient by L-infinity norm.
+    LInfNorm(f32),
+
+    /// Clip the gradient by the given kind of norm.
+    NormOfKind(f32, NormKind),
+
+    /// Clip the gradients by their global root mean square.
+    GlobalRms(f32),
+}
+
+impl ValidateConfig for GradientClippingConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        let threshold = match self {
+            GradientClippingConfig::Value(threshold)
+            | GradientClippingConfig::Norm(threshold)
+            | GradientClippingConfig::LInfNorm(threshold)
+            | GradientClippingConfig::NormOfKind(threshold, _)
+            | GradientClippingConfig::GlobalRms(threshold) => *threshold,
+        };
+
+        if threshold > 0.0 {
+            Ok(())
+        } else {
+            Err(ConfigError::OutOfRange(format!(
+                "The gradient clipping threshold must be positive, got {threshold}."
+            )))
+        }
+    }
 }
 
 impl GradientClippingConfig {
@@ -23,24 +57,102 @@ impl GradientClippingConfig {
         match self {
             GradientClippingConfig::Value(val) => GradientClipping::Value(*val),
             GradientClippingConfig::Norm(val) => GradientClipping::Norm(*val),
+            GradientClippingConfig::LInfNorm(val) => GradientClipping::LInfNorm(*val),
+            GradientClippingConfig::NormOfKind(val, kind) => {
+                GradientClipping::NormOfKind(*val, kind.clone())
+            }
+            GradientClippingConfig::GlobalRms(val) => GradientClipping::GlobalRms(*val),
         }
     }
 }
 
+/// Order of the gradient clipping relative to the weight decay of an optimizer, which differs
+/// between frameworks, see
+/// [with_grad_clipping_order](crate::optim::adaptor::OptimizerAdaptor::with_grad_clipping_order).
+/// The adaptation of the gradients by the optimizer always comes last.
+#[derive(Config, Debug, PartialEq)]
+pub enum GradientClippingOrder {
+    /// Clip the raw gradients, then add the weight decay.
+    BeforeWeightDecay,
+
+    /// Add the weight decay, then clip the gradients, so the decay is clipped too.
+    AfterWeightDecay,
+}
+
+/// Policy of the norm-based [gradient clipping](GradientClipping) when the norm of a gradient
+/// isn't finite, because the gradient contains NaN or infinite values.
+#[derive(Config, Debug, PartialEq)]
+pub enum NanPolicy {
+    /// Leave the gradient as is when its norm is NaN, so the invalid values can be caught
+    /// elsewhere. An infinite norm, e.g. when the sum of squares overflows, still clips the
+    /// gradient, scaling it to zero.
+    Skip,
+
+    /// Replace the whole gradient with zeros, so the parameter isn't updated.
+    Zero,
+
+    /// Panic, to stop the training as soon as a gradient is invalid.
+    Error,
+}
+
 /// Gradient Clipping provides a way to mitigate exploding gradients
 /// by clipping every component of the gradient by value or by norm during
 /// backpropagation.
+#[derive(Clone)]
 pub enum GradientClipping {
     /// Clip the gradient by value.
     Value(f32),
 
     /// Clip the gradient by norm.
     Norm(f32),
+
+    /// Clip the gradient by L-infinity norm, scaling the whole gradient so its largest absolute
+    /// component doesn't exceed the maximum. Unlike clipping by value, the direction of the
+    /// gradient is preserved.
+    LInfNorm(f32),
+
+    /// Clip the gradient by the given kind of norm, scaling the whole gradient like
+    /// [Norm](GradientClipping::Norm).
+    NormOfKind(f32, NormKind),
+
+    /// Clip the gradients by the root mean square of all their elements across the parameters,
+    /// scaling all the gradients by the same factor so the global RMS is at most the target.
+    /// Unlike the L2 norm, the RMS doesn't grow with the number of parameters, so the same target
+    /// suits models of different sizes.
+    ///
+    /// The global RMS is computed by the [optimizer adaptor](crate::optim::adaptor::OptimizerAdaptor),
+    /// a gradient clipped on its own is clipped by its own RMS.
+    GlobalRms(f32),
 }
 
 impl GradientClipping {
+    /// The threshold of the clipping, the maximum value or norm.
+    pub fn threshold(&self) -> f32 {
+        match self {
+            GradientClipping::Value(threshold)
+            | GradientClipping::Norm(threshold)
+            | GradientClipping::LInfNorm(threshold)
+            | GradientClipping::NormOfKind(threshold, _)
+            | GradientClipping::GlobalRms(threshold) => *threshold,
+        }
+    }
+
+    /// The same clipping with another threshold.
+    pub fn with_threshold(self, threshold: f32) -> Self {
+        match self {
+            GradientClipping::Value(_) => GradientClipping::Value(threshold),
+            GradientClipping::Norm(_) => GradientClipping::Norm(threshold),
+            GradientClipping::LInfNorm(_) => GradientClipping::LInfNorm(threshold),
+            GradientClipping::NormOfKind(_, kind) => GradientClipping::NormOfKind(threshold, kind),
+            GradientClipping::GlobalRms(_) => GradientClipping::GlobalRms(threshold),
+        }
+    }
+
     /// Clip the gradient.
     ///
+    /// The gradient is left as is when its norm is NaN, see
+    /// [clip_gradient_with_nan_policy](Self::clip_gradient_with_nan_policy).
+    ///
     /// # Arguments
     ///
     /// * `grad` - The gradient to clip.
@@ -49,9 +161,79 @@ impl GradientClipping {
     ///
     /// The clipped gradient.
     pub fn clip_gradient<B: Backend, const D: usize>(&self, grad: Tensor<B, D>) -> Tensor<B, D> {
+        self.clip_gradient_with_nan_policy(grad, &NanPolicy::Skip)
+    }
+
+    /// Clip the gradient, handling a norm that isn't finite with the given policy.
+    ///
+    /// The policy only applies to the norm-based clipping, clipping by value keeps NaN values.
+    ///
+    /// # Arguments
+    ///
+    /// * `grad` - The gradient to clip.
+    /// * `policy` - The policy when the norm of the gradient is NaN or infinite.
+    ///
+    /// # Returns
+    ///
+    /// The clipped gradient.
+    ///
+    /// # Panics
+    ///
+    /// Panics with [NanPolicy::Error] when the norm of the gradient isn't finite.
+    pub fn clip_gradient_with_nan_policy<B: Backend, const D: usize>(
+        &self,
+        grad: Tensor<B, D>,
+        policy: &NanPolicy,
+    ) -> Tensor<B, D> {
+        let (grad, _) = self.clip(grad, policy);
+        grad
+    }
+
+    /// Clip the gradient like [clip_gradient_with_nan_policy](Self::clip_gradient_with_nan_policy),
+    /// recording its norm in the given [statistics](ClipStats).
+    ///
+    /// # Arguments
+    ///
+    /// * `grad` - The gradient to clip.
+    /// * `policy` - The policy when the norm of the gradient is NaN or infinite.
+    /// * `stats` - The statistics updated with the norm of the gradient.
+    ///
+    /// # Returns
+    ///
+    /// The clipped gradient.
+    pub fn clip_gradient_with_stats<B: Backend, const D: usize>(
+        &self,
+        grad: Tensor<B, D>,
+        policy: &NanPolicy,
+        stats: &mut ClipStats,
+    ) -> Tensor<B, D> {
+        let (grad, norm) = self.clip(grad, policy);
+        if let Some(norm) = norm {
+            stats.record(norm, self.threshold());
+        }
+        grad
+    }
+
+    /// Clip the gradient, returning the norm of the gradient when it's computed and finite.
+    fn clip<B: Backend, const D: usize>(
+        &self,
+        grad: Tensor<B, D>,
+        policy: &NanPolicy,
+    ) -> (Tensor<B, D>, Option<f32>) {
         match self {
-            GradientClipping::Value(threshold) => self.clip_by_value(grad, *threshold),
-            GradientClipping::Norm(max_norm) => self.clip_by_norm(grad, *max_norm),
+            GradientClipping::Value(threshold) => (self.clip_by_value(grad, *threshold), None),
+            GradientClipping::Norm(max_norm) => {
+                self.clip_by_norm(grad, *max_norm, &NormKind::L2, policy)
+            }
+            GradientClipping::LInfNorm(max_norm) => {
+                self.clip_by_norm(grad, *max_norm, &NormKind::LInf, policy)
+            }
+            GradientClipping::NormOfKind(max_norm, kind) => {
+                self.clip_by_norm(grad, *max_norm, kind, policy)
+            }
+            GradientClipping::GlobalRms(max_rms) => {
+                self.clip_by_norm(grad, *max_rms, &NormKind::RMS, policy)
+            }
         }
     }
 
@@ -73,7 +255,9 @@ impl GradientClipping {
         &self,
         _grad: Tensor<B, D>,
         _threshold: f32,
-    ) -> Tensor<B, D> {
+        _kind: &NormKind,
+        _policy: &NanPolicy,
+    ) -> (Tensor<B, D>, Option<f32>) {
         todo!("Not yet supported on wasm");
     }
 
@@ -82,33 +266,49 @@ impl GradientClipping {
         &self,
         grad: Tensor<B, D>,
         threshold: f32,
-    ) -> Tensor<B, D> {
+        kind: &NormKind,
+        policy: &NanPolicy,
+    ) -> (Tensor<B, D>, Option<f32>) {
         use burn_tensor::ElementConversion;
 
-        let norm = Self::l2_norm(grad.clone());
+        let norm = super::tensor_norm(grad.clone(), kind);
         let norm_float = norm.into_scalar().elem::<f32>();
 
-        if norm_float > threshold {
+        let skip = norm_float.is_infinite() && *policy == NanPolicy::Skip;
+        if !norm_float.is_finite() && !skip {
+            return (Self::clip_non_finite(grad, norm_float, policy), None);
+        }
+
+        let grad = if norm_float > threshold {
             let scale = threshold / norm_float;
             grad.mul_scalar(scale)
         } else {
             grad
-        }
+        };
+
+        (grad, Some(norm_float).filter(|norm| norm.is_finite()))
     }
 
     #[cfg(any(feature = "wasm-sync", not(target_family = "wasm")))]
-    fn l2_norm<B: Backend, const D: usize>(tensor: Tensor<B, D>) -> Tensor<B, 1> {
-        let squared = tensor.powf(2.0);
-        let sum = squared.sum();
-
-        sum.sqrt()
+    fn clip_non_finite<B: Backend, const D: usize>(
+        grad: Tensor<B, D>,
+        norm: f32,
+        policy: &NanPolicy,
+    ) -> Tensor<B, D> {
+        match policy {
+            NanPolicy::Skip => grad,
+            NanPolicy::Zero => grad.zeros_like(),
+            NanPolicy::Error => {
+                panic!("The gradient norm is {norm}, the gradient can't be clipped.")
+            }
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tensor::Tensor;
+    use crate::tensor::{Data, Tensor};
     use crate::TestBackend;
 
     #[test]
@@ -140,4 +340,67 @@ mod tests {
             assert!(value <= 0.88);
         }
     }
+
+    #[test]
+    fn test_clip_by_linf_norm_preserves_direction() {
+        let gradient: Tensor<TestBackend, 1> = Tensor::from_floats([2.0, -1.0, 0.25]);
+
+        let clipped_by_norm = GradientClipping::LInfNorm(0.5).clip_gradient(gradient.clone());
+        let clipped_by_value = GradientClipping::Value(0.5).clip_gradient(gradient);
+
+        // The whole gradient is scaled so its largest component is 0.5.
+        clipped_by_norm
+            .to_data()
+            .assert_approx_eq(&Data::from([0.5, -0.25, 0.0625]), 5);
+        // Each component is clamped, which changes the direction.
+        clipped_by_value
+            .to_data()
+            .assert_approx_eq(&Data::from([0.5, -0.5, 0.25]), 5);
+
+        let gradient: Tensor<TestBackend, 1> = Tensor::from_floats([0.2, -0.4]);
+        let clipped_by_norm = GradientClipping::LInfNorm(0.5).clip_gradient(gradient.clone());
+        clipped_by_norm
+            .to_data()
+            .assert_approx_eq(&gradient.to_data(), 5);
+    }
+
+    #[test]
+    fn test_clip_by_norm_nan_policy() {
+        let gradient: Tensor<TestBackend, 1> = Tensor::from_floats([3.0, f32::NAN, 4.0]);
+        let clipping = GradientClipping::Norm(1.0);
+
+        // The NaN is kept, and the other values aren't scaled by the NaN norm.
+        let skipped = clipping
+            .clip_gradient_with_nan_policy(gradient.clone(), &NanPolicy::Skip)
+            .into_data();
+        assert_eq!(skipped.value[0], 3.0);
+        assert!(skipped.value[1].is_nan());
+        assert_eq!(skipped.value[2], 4.0);
+
+        let zeroed = clipping.clip_gradient_with_nan_policy(gradient, &NanPolicy::Zero);
+        zeroed
+            .to_data()
+            .assert_approx_eq(&Data::from([0.0, 0.0, 0.0]), 5);
+    }
+
+    #[test]
+    fn test_clip_by_norm_skip_policy_should_clip_an_infinite_norm() {
+        // The values are finite, but the sum of their squares overflows.
+        let gradient: Tensor<TestBackend, 1> = Tensor::from_floats([3.0, f32::MAX, f32::MAX]);
+
+        let clipped =
+            GradientClipping::Norm(1.0).clip_gradient_with_nan_policy(gradient, &NanPolicy::Skip);
+
+        clipped
+            .to_data()
+            .assert_approx_eq(&Data::from([0.0, 0.0, 0.0]), 5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_clip_by_norm_nan_policy_error() {
+        let gradient: Tensor<TestBackend, 1> = Tensor::from_floats([3.0, f32::INFINITY, 4.0]);
+
+        GradientClipping::Norm(1.0).clip_gradient_with_nan_policy(gradient, &NanPolicy::Error);
+    }
 }
@@ -0,0 +1,314 @@
+use super::{GlobalNormAccumulator, NormKind};
+use crate::module::{AutodiffModule, ModuleVisitor, ParamId};
+use crate::optim::GradientsParams;
+use crate::tensor::{backend::AutodiffBackend, Tensor};
+use burn_tensor::{backend::Backend, ElementConversion};
+use core::hash::Hash;
+use hashbrown::HashMap;
+
+/// Compute the global norm of the gradients of the given module, as if all the gradients were
+/// concatenated in a single tensor, e.g. to log it without clipping.
+///
+/// The gradients are stored without their type, so the module gives the rank of each gradient,
+/// like for [clip_by_global_norm]. The norm is accumulated with a [GlobalNormAccumulator] on the
+/// device of the first gradient, the only synchronization being the read of the result.
+///
+/// # Returns
+///
+/// The global norm, zero when the module has no gradient.
+pub fn global_grad_norm<B, M>(module: &M, grads: &GradientsParams, kind: NormKind) -> f32
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+{
+    grads
+        .accumulate_norm::<B, M>(module, &kind)
+        .map_or(0.0, |accumulator| {
+            accumulator.norm().into_scalar().elem::<f32>()
+        })
+}
+
+/// Clip the gradients of the given module so that their global L2 norm is at most `max_norm`.
+///
+/// The global norm is computed with a [GlobalNormAccumulator] on the given device, and all the
+/// gradients are scaled by the same factor, which preserves their direction.
+pub fn clip_by_global_norm<B: AutodiffBackend, M: AutodiffModule<B>>(
+    module: &M,
+    grads: GradientsParams,
+    max_norm: f32,
+    device: &<B::InnerBackend as Backend>::Device,
+) -> GradientsParams {
+    clip_by_global_norm_per_category(module, grads, max_norm, |_| (), device)
+}
+
+/// Clip the gradients of the given module like [clip_by_global_norm](clip_by_global_norm), also
+/// returning the scale applied to the gradients, e.g. to correlate the clipping with loss spikes.
+///
+/// # Returns
+///
+/// The clipped gradients and the scale `max_norm / norm`, `1.0` when no clipping occurred.
+pub fn clip_by_global_norm_with_scale<B: AutodiffBackend, M: AutodiffModule<B>>(
+    module: &M,
+    grads: GradientsParams,
+    max_norm: f32,
+    device: &<B::InnerBackend as Backend>::Device,
+) -> (GradientsParams, f32) {
+    let (grads, mut scales) = clip_with_scales(module, grads, max_norm, |_| (), device);
+    let scale = scales
+        .remove(&())
+        .map_or(1.0, |scale| scale.into_scalar().elem::<f32>());
+
+    (grads, scale)
+}
+
+/// Clip the gradients of the given module so that the combined L2 norm of the gradients of each
+/// category is at most `max_norm`, e.g. to clip the weights and the biases separately.
+///
+/// The category of each parameter is given by `categorize`. The gradients of a category are all
+/// scaled by the same factor, computed from their combined norm like
+/// [clip_by_global_norm](clip_by_global_norm), which is finer than clipping all the gradients
+/// together but coarser than clipping each gradient on its own.
+pub fn clip_by_global_norm_per_category<B, M, C, F>(
+    module: &M,
+    grads: GradientsParams,
+    max_norm: f32,
+    categorize: F,
+    device: &<B::InnerBackend as Backend>::Device,
+) -> GradientsParams
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+    C: Eq + Hash,
+    F: Fn(&ParamId) -> C,
+{
+    clip_with_scales(module, grads, max_norm, categorize, device).0
+}
+
+/// Clip the gradients of each category, returning the scale applied to each category.
+fn clip_with_scales<B, M, C, F>(
+    module: &M,
+    mut grads: GradientsParams,
+    max_norm: f32,
+    categorize: F,
+    device: &<B::InnerBackend as Backend>::Device,
+) -> (GradientsParams, HashMap<C, Tensor<B::InnerBackend, 1>>)
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+    C: Eq + Hash,
+    F: Fn(&ParamId) -> C,
+{
+    let mut accumulators = HashMap::new();
+    module.visit(&mut GlobalNormVisitor::<B, C, F>::new(
+        &grads,
+        &categorize,
+        device,
+        &mut accumulators,
+    ));
+
+    let scales: HashMap<C, Tensor<B::InnerBackend, 1>> = accumulators
+        .into_iter()
+        .map(|(category, accumulator)| {
+            let scale = accumulator
+                .norm()
+                .add_scalar(1e-6)
+                .powf(-1.0)
+                .mul_scalar(max_norm)
+                .clamp_max(1.0);
+            (category, scale)
+        })
+        .collect();
+    module.visit(&mut GlobalNormScaleVisitor::<B, C, F>::new(
+        &mut grads,
+        &categorize,
+        &scales,
+    ));
+
+    (grads, scales)
+}
+
+#[derive(new)]
+struct GlobalNormVisitor<'a, B: AutodiffBackend, C, F> {
+    grads: &'a GradientsParams,
+    categorize: &'a F,
+    device: &'a <B::InnerBackend as Backend>::Device,
+    accumulators: &'a mut HashMap<C, GlobalNormAccumulator<B::InnerBackend>>,
+}
+
+impl<'a, B, C, F> ModuleVisitor<B> for GlobalNormVisitor<'a, B, C, F>
+where
+    B: AutodiffBackend,
+    C: Eq + Hash,
+    F: Fn(&ParamId) -> C,
+{
+    fn visit<const D: usize>(&mut self, id: &ParamId, _tensor: &Tensor<B, D>) {
+        if let Some(grad) = self.grads.get::<B::InnerBackend, D>(id) {
+            self.accumulators
+                .entry((self.categorize)(id))
+                .or_insert_with(|| GlobalNormAccumulator::new(self.device))
+                .add(grad);
+        }
+    }
+}
+
+#[derive(new)]
+struct GlobalNormScaleVisitor<'a, B: AutodiffBackend, C, F> {
+    grads: &'a mut GradientsParams,
+    categorize: &'a F,
+    scales: &'a HashMap<C, Tensor<B::InnerBackend, 1>>,
+}
+
+impl<'a, B, C, F> ModuleVisitor<B> for GlobalNormScaleVisitor<'a, B, C, F>
+where
+    B: AutodiffBackend,
+    C: Eq + Hash,
+    F: Fn(&ParamId) -> C,
+{
+    fn visit<const D: usize>(&mut self, id: &ParamId, _tensor: &Tensor<B, D>) {
+        let Some(scale) = self.scales.get(&(self.categorize)(id)) else {
+            return;
+        };
+
+        if let Some(grad) = self.grads.remove::<B::InnerBackend, D>(id) {
+            let scale = scale.clone().to_device(&grad.device()).reshape([1; D]);
+            self.grads
+                .register::<B::InnerBackend, D>(id.clone(), grad.mul(scale));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nn::{Linear, LinearConfig},
+        TestAutodiffBackend, TestBackend,
+    };
+    use burn_tensor::{Data, ElementConversion};
+
+    #[test]
+    fn test_global_grad_norm_of_each_kind() {
+        let linear: Linear<TestAutodiffBackend> = LinearConfig::new(2, 2).init();
+        let mut grads = GradientsParams::new();
+        grads.register::<TestBackend, 2>(
+            linear.weight.id.clone(),
+            Tensor::from_floats([[3.0, 0.0], [0.0, -4.0]]),
+        );
+        grads.register::<TestBackend, 1>(
+            linear.bias.as_ref().unwrap().id.clone(),
+            Tensor::from_floats([12.0, 0.0]),
+        );
+
+        // The gradients concatenated are [3, 0, 0, -4, 12, 0].
+        let norm = |kind| global_grad_norm(&linear, &grads, kind);
+        assert!((norm(NormKind::L2) - 13.0).abs() < 1e-5);
+        assert!((norm(NormKind::L1) - 19.0).abs() < 1e-5);
+        assert!((norm(NormKind::LInf) - 12.0).abs() < 1e-5);
+        assert!((norm(NormKind::RMS) - (169.0f32 / 6.0).sqrt()).abs() < 1e-5);
+
+        let grads = GradientsParams::new();
+        assert_eq!(global_grad_norm(&linear, &grads, NormKind::L2), 0.0);
+    }
+
+    #[test]
+    fn test_clip_by_global_norm_scales_all_gradients() {
+        let linear: Linear<TestAutodiffBackend> = LinearConfig::new(2, 2).init();
+        let weight_id = linear.weight.id.clone();
+        let bias_id = linear.bias.as_ref().unwrap().id.clone();
+        let mut grads = GradientsParams::new();
+        grads.register::<TestBackend, 2>(
+            weight_id.clone(),
+            Tensor::from_floats([[3.0, 0.0], [0.0, 0.0]]),
+        );
+        grads.register::<TestBackend, 1>(bias_id.clone(), Tensor::from_floats([0.0, 4.0]));
+
+        let grads = clip_by_global_norm(&linear, grads, 1.0, &Default::default());
+
+        grads
+            .get::<TestBackend, 2>(&weight_id)
+            .unwrap()
+            .into_data()
+            .assert_approx_eq(&Data::from([[0.6, 0.0], [0.0, 0.0]]), 4);
+        grads
+            .get::<TestBackend, 1>(&bias_id)
+            .unwrap()
+            .into_data()
+            .assert_approx_eq(&Data::from([0.0, 0.8]), 4);
+    }
+
+    #[test]
+    fn test_clip_by_global_norm_reports_the_scale() {
+        let linear: Linear<TestAutodiffBackend> = LinearConfig::new(2, 2).init();
+        let grads = |factor: f32| {
+            let mut grads = GradientsParams::new();
+            grads.register::<TestBackend, 2>(
+                linear.weight.id.clone(),
+                Tensor::from_floats([[3.0 * factor, 0.0], [0.0, 0.0]]),
+            );
+            grads.register::<TestBackend, 1>(
+                linear.bias.as_ref().unwrap().id.clone(),
+                Tensor::from_floats([0.0, 4.0 * factor]),
+            );
+            grads
+        };
+
+        // The global norm is 20, four times the threshold.
+        let (_, scale) =
+            clip_by_global_norm_with_scale(&linear, grads(4.0), 5.0, &Default::default());
+        assert!((scale - 5.0 / 20.0).abs() < 1e-5);
+
+        let (_, scale) =
+            clip_by_global_norm_with_scale(&linear, grads(0.5), 5.0, &Default::default());
+        assert_eq!(scale, 1.0);
+    }
+
+    #[test]
+    fn test_clip_by_global_norm_per_category_scales_each_category() {
+        let linear_1: Linear<TestAutodiffBackend> = LinearConfig::new(2, 2).init();
+        let linear_2: Linear<TestAutodiffBackend> = LinearConfig::new(2, 1).init();
+        let weight_ids = [linear_1.weight.id.clone(), linear_2.weight.id.clone()];
+        let bias_ids = [
+            linear_1.bias.as_ref().unwrap().id.clone(),
+            linear_2.bias.as_ref().unwrap().id.clone(),
+        ];
+        let mut grads = GradientsParams::new();
+        grads.register::<TestBackend, 2>(
+            weight_ids[0].clone(),
+            Tensor::from_floats([[3.0, 0.0], [0.0, 0.0]]),
+        );
+        grads.register::<TestBackend, 2>(weight_ids[1].clone(), Tensor::from_floats([[0.0, 4.0]]));
+        grads.register::<TestBackend, 1>(bias_ids[0].clone(), Tensor::from_floats([0.0, 0.3]));
+        grads.register::<TestBackend, 1>(bias_ids[1].clone(), Tensor::from_floats([0.4]));
+
+        let categorize = |id: &ParamId| match weight_ids.contains(id) {
+            true => "weight",
+            false => "bias",
+        };
+        let linears = vec![linear_1, linear_2];
+        let grads =
+            clip_by_global_norm_per_category(&linears, grads, 1.0, categorize, &Default::default());
+
+        // The combined norm of the weights is 5, they are scaled by 1 / 5.
+        grads
+            .get::<TestBackend, 2>(&weight_ids[0])
+            .unwrap()
+            .into_data()
+            .assert_approx_eq(&Data::from([[0.6, 0.0], [0.0, 0.0]]), 4);
+        grads
+            .get::<TestBackend, 2>(&weight_ids[1])
+            .unwrap()
+            .into_data()
+            .assert_approx_eq(&Data::from([[0.0, 0.8]]), 4);
+        // The combined norm of the biases is 0.5, they aren't clipped.
+        grads
+            .get::<TestBackend, 1>(&bias_ids[0])
+            .unwrap()
+            .into_data()
+            .assert_approx_eq(&Data::from([0.0, 0.3]), 4);
+        grads
+            .get::<TestBackend, 1>(&bias_ids[1])
+            .unwrap()
+            .into_data()
+            .assert_approx_eq(&Data::from([0.4]), 4);
+    }
+}
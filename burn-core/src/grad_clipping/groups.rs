@@ -0,0 +1,170 @@
+use super::GradientClipping;
+use crate::module::ParamId;
+use alloc::string::{String, ToString};
+use hashbrown::HashMap;
+
+/// Gradient clipping with a different clipping for each group of parameters.
+///
+/// Parameters without an assigned group aren't handled by this struct, the optimizer falls back
+/// to its default [gradient clipping](GradientClipping) for them.
+#[derive(Default)]
+pub struct GradientClippingGroups {
+    groups: HashMap<String, GradientClipping>,
+    params: HashMap<ParamId, String>,
+}
+
+impl GradientClippingGroups {
+    /// Create an empty set of groups.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the clipping of a group, replacing the previous clipping of that group if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `group` - The name of the group.
+    /// * `clipping` - The gradient clipping applied to the parameters of the group.
+    ///
+    /// # Returns
+    ///
+    /// The groups.
+    pub fn with_group(mut self, group: &str, clipping: GradientClipping) -> Self {
+        self.groups.insert(group.to_string(), clipping);
+        self
+    }
+
+    /// Assigns parameters to a group, replacing their previous group if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `group` - The name of the group.
+    /// * `params` - The ids of the parameters to assign to the group.
+    ///
+    /// # Returns
+    ///
+    /// The groups.
+    pub fn with_params<I: IntoIterator<Item = ParamId>>(mut self, group: &str, params: I) -> Self {
+        for id in params {
+            self.params.insert(id, group.to_string());
+        }
+        self
+    }
+
+    /// Get the group of the given [parameter id](ParamId).
+    pub fn group(&self, id: &ParamId) -> Option<&str> {
+        self.params.get(id).map(|group| group.as_str())
+    }
+
+    /// Get the gradient clipping of the group of the given [parameter id](ParamId).
+    pub fn clipping(&self, id: &ParamId) -> Option<&GradientClipping> {
+        self.params.get(id).and_then(|group| self.groups.get(group))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate as burn;
+    use crate::{
+        module::{list_param_ids, Module},
+        nn::{Linear, LinearConfig},
+        optim::{GradientsParams, Optimizer, SgdConfig},
+        tensor::{backend::Backend, Tensor},
+        TestAutodiffBackend, TestBackend,
+    };
+
+    #[derive(Module, Debug)]
+    struct TwoLayers<B: Backend> {
+        embedding: Linear<B>,
+        head: Linear<B>,
+    }
+
+    #[test]
+    fn test_each_group_is_clipped_with_its_own_threshold() {
+        let model = TwoLayers::<TestAutodiffBackend> {
+            embedding: LinearConfig::new(4, 4).with_bias(false).init(),
+            head: LinearConfig::new(4, 4).with_bias(true).init(),
+        };
+        let embedding_id = model.embedding.weight.id.clone();
+        let head_weight_id = model.head.weight.id.clone();
+        let head_bias_id = model.head.bias.as_ref().unwrap().id.clone();
+        let groups = GradientClippingGroups::new()
+            .with_group("embedding", GradientClipping::Value(0.1))
+            .with_group("head", GradientClipping::Value(0.5))
+            .with_params("embedding", list_param_ids(&model.embedding))
+            .with_params("head", [head_weight_id.clone()]);
+        let mut optim = SgdConfig::new()
+            .init::<TestAutodiffBackend, TwoLayers<TestAutodiffBackend>>()
+            .with_grad_clipping(GradientClipping::Value(1.0))
+            .with_grad_clipping_groups(groups);
+
+        let mut grads = GradientsParams::new();
+        grads.register::<TestBackend, 2>(embedding_id, Tensor::ones([4, 4]).mul_scalar(10.0));
+        grads.register::<TestBackend, 2>(head_weight_id, Tensor::ones([4, 4]).mul_scalar(10.0));
+        grads.register::<TestBackend, 1>(head_bias_id, Tensor::ones([4]).mul_scalar(10.0));
+
+        let embedding_before = model.embedding.weight.val();
+        let head_weight_before = model.head.weight.val();
+        let head_bias_before = model.head.bias.as_ref().unwrap().val();
+        let model = optim.step(1.0, model, grads);
+
+        let embedding_delta = embedding_before - model.embedding.weight.val();
+        let head_weight_delta = head_weight_before - model.head.weight.val();
+        let head_bias_delta = head_bias_before - model.head.bias.as_ref().unwrap().val();
+
+        embedding_delta.into_data().assert_approx_eq(
+            &Tensor::<TestAutodiffBackend, 2>::ones([4, 4])
+                .mul_scalar(0.1)
+                .into_data(),
+            3,
+        );
+        head_weight_delta.into_data().assert_approx_eq(
+            &Tensor::<TestAutodiffBackend, 2>::ones([4, 4])
+                .mul_scalar(0.5)
+                .into_data(),
+            3,
+        );
+        head_bias_delta
+            .into_data()
+            .assert_approx_eq(&Tensor::<TestAutodiffBackend, 1>::ones([4]).into_data(), 3);
+    }
+
+    #[test]
+    fn test_params_outside_the_clipping_set_are_not_clipped() {
+        let model = TwoLayers::<TestAutodiffBackend> {
+            embedding: LinearConfig::new(4, 4).with_bias(false).init(),
+            head: LinearConfig::new(4, 4).with_bias(false).init(),
+        };
+        let embedding_id = model.embedding.weight.id.clone();
+        let head_id = model.head.weight.id.clone();
+        let mut optim = SgdConfig::new()
+            .init::<TestAutodiffBackend, TwoLayers<TestAutodiffBackend>>()
+            .with_grad_clipping(GradientClipping::Value(0.1))
+            .with_grad_clipping_params([embedding_id.clone()]);
+
+        let mut grads = GradientsParams::new();
+        grads.register::<TestBackend, 2>(embedding_id, Tensor::ones([4, 4]).mul_scalar(10.0));
+        grads.register::<TestBackend, 2>(head_id, Tensor::ones([4, 4]).mul_scalar(10.0));
+
+        let embedding_before = model.embedding.weight.val();
+        let head_before = model.head.weight.val();
+        let model = optim.step(1.0, model, grads);
+
+        let embedding_delta = embedding_before - model.embedding.weight.val();
+        let head_delta = head_before - model.head.weight.val();
+
+        embedding_delta.into_data().assert_approx_eq(
+            &Tensor::<TestAutodiffBackend, 2>::ones([4, 4])
+                .mul_scalar(0.1)
+                .into_data(),
+            3,
+        );
+        head_delta.into_data().assert_approx_eq(
+            &Tensor::<TestAutodiffBackend, 2>::ones([4, 4])
+                .mul_scalar(10.0)
+                .into_data(),
+            3,
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use super::GradientClipping;
+
+/// Gradient clipping with a threshold adapted to the training loss, tightening the threshold when
+/// the loss increases from one step to the next, a sign of instability, and relaxing it when the
+/// loss decreases, see
+/// [step_with_loss](crate::optim::adaptor::OptimizerAdaptor::step_with_loss).
+///
+/// The threshold is multiplied by `tighten` when the loss increases and by `relax` when it
+/// decreases, staying between the minimum threshold and the initial threshold. It's left as is
+/// when the loss doesn't change or isn't a number.
+#[derive(Clone)]
+pub struct LossAdaptiveGradientClipping {
+    clipping: GradientClipping,
+    threshold_min: f32,
+    threshold_max: f32,
+    tighten: f32,
+    relax: f32,
+}
+
+impl LossAdaptiveGradientClipping {
+    /// Create a loss adaptive threshold of the given gradient clipping.
+    ///
+    /// # Arguments
+    ///
+    /// * `clipping` - The gradient clipping, its threshold is the initial and maximum one.
+    /// * `tighten` - The factor in `(0, 1)` applied to the threshold when the loss increases.
+    /// * `relax` - The factor above `1` applied to the threshold when the loss decreases.
+    pub fn new(clipping: GradientClipping, tighten: f32, relax: f32) -> Self {
+        assert!(
+            tighten > 0.0 && tighten < 1.0,
+            "The tightening factor must be in (0, 1)."
+        );
+        assert!(relax > 1.0, "The relaxing factor must be greater than 1.");
+
+        Self {
+            threshold_min: 0.0,
+            threshold_max: clipping.threshold(),
+            clipping,
+            tighten,
+            relax,
+        }
+    }
+
+    /// Sets the minimum threshold, below which the threshold isn't tightened. Defaults to zero.
+    pub fn with_min_threshold(mut self, threshold: f32) -> Self {
+        assert!(
+            threshold <= self.threshold_max,
+            "The minimum threshold must not exceed the initial threshold."
+        );
+        self.threshold_min = threshold;
+        self
+    }
+
+    /// The gradient clipping with the current threshold.
+    pub fn clipping(&self) -> &GradientClipping {
+        &self.clipping
+    }
+
+    /// The current threshold of the clipping.
+    pub fn threshold(&self) -> f32 {
+        self.clipping.threshold()
+    }
+
+    /// Replace the current threshold, e.g. with the adapted threshold of a checkpoint, returning
+    /// the gradient clipping with the new threshold.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - The threshold, between the minimum threshold and the initial threshold.
+    pub fn reset_threshold(&mut self, threshold: f32) -> GradientClipping {
+        assert!(
+            threshold >= self.threshold_min && threshold <= self.threshold_max,
+            "The threshold must be between the minimum threshold and the initial threshold."
+        );
+        self.clipping = self.clipping.clone().with_threshold(threshold);
+        self.clipping.clone()
+    }
+
+    /// Adapt the threshold to the change of the loss, returning the gradient clipping with the
+    /// adapted threshold.
+    ///
+    /// # Arguments
+    ///
+    /// * `loss_previous` - The loss of the previous step.
+    /// * `loss` - The loss of the current step.
+    pub fn update(&mut self, loss_previous: f64, loss: f64) -> GradientClipping {
+        let threshold = self.threshold();
+        let threshold = if loss > loss_previous {
+            f32::max(threshold * self.tighten, self.threshold_min)
+        } else if loss < loss_previous {
+            f32::min(threshold * self.relax, self.threshold_max)
+        } else {
+            threshold
+        };
+
+        self.clipping = self.clipping.clone().with_threshold(threshold);
+        self.clipping.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::{Linear, LinearConfig};
+    use crate::optim::{GradientsParams, Optimizer, SgdConfig};
+    use crate::tensor::{Data, Tensor};
+    use crate::TestAutodiffBackend;
+
+    #[test]
+    fn test_threshold_tightens_with_rising_loss_and_relaxes_with_falling_loss() {
+        let mut clipping = LossAdaptiveGradientClipping::new(GradientClipping::Norm(8.0), 0.5, 2.0)
+            .with_min_threshold(1.5);
+        let losses = [1.0, 1.2, 1.5, 2.0, 1.8, 1.4, 1.1, 0.9];
+
+        let thresholds: Vec<f32> = losses
+            .windows(2)
+            .map(|losses| clipping.update(losses[0], losses[1]).threshold())
+            .collect();
+
+        // The threshold is bounded by the minimum while the loss rises, and by the initial
+        // threshold while it falls.
+        assert_eq!(thresholds, vec![4.0, 2.0, 1.5, 3.0, 6.0, 8.0, 8.0]);
+        assert_eq!(clipping.update(0.9, 0.9).threshold(), 8.0);
+        assert_eq!(clipping.update(0.9, f64::NAN).threshold(), 8.0);
+    }
+
+    #[test]
+    fn test_optimizer_clips_with_the_adapted_threshold() {
+        let mut linear: Linear<TestAutodiffBackend> =
+            LinearConfig::new(2, 1).with_bias(false).init();
+        let clipping = || LossAdaptiveGradientClipping::new(GradientClipping::Norm(8.0), 0.5, 2.0);
+        let mut optim = SgdConfig::new()
+            .init()
+            .with_loss_adaptive_grad_clipping(clipping());
+
+        // The gradient of the weight is the input, with a norm of 50, so the update has the norm
+        // of the threshold, tightened by the rising losses.
+        for (loss, threshold) in [(1.5, 4.0), (2.0, 2.0)] {
+            let weight_before = linear.weight.val().inner();
+            let x = Tensor::<TestAutodiffBackend, 2>::from_floats([[30.0, 40.0]]);
+            let grads = GradientsParams::from_grads(linear.forward(x).backward(), &linear);
+            linear = optim.step_with_loss(1.0, linear, grads, loss - 0.5, loss);
+
+            let update = weight_before.sub(linear.weight.val().inner());
+            update
+                .reshape([2])
+                .into_data()
+                .assert_approx_eq(&Data::from([0.6 * threshold, 0.8 * threshold]), 5);
+        }
+
+        // The adapted threshold is restored with the record.
+        let record = optim.to_record();
+        assert_eq!(record.loss_adaptive_threshold, Some(2.0));
+        let optim_resumed = SgdConfig::new()
+            .init::<TestAutodiffBackend, Linear<TestAutodiffBackend>>()
+            .with_loss_adaptive_grad_clipping(clipping())
+            .load_record(record);
+        let threshold = optim_resumed
+            .loss_adaptive_grad_clipping()
+            .unwrap()
+            .threshold();
+        assert_eq!(threshold, 2.0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    #[should_panic(expected = "can't be combined with a gradient clipping schedule")]
+    fn test_optimizer_rejects_a_clipping_schedule() {
+        let _optim = SgdConfig::new()
+            .init::<TestAutodiffBackend, Linear<TestAutodiffBackend>>()
+            .with_grad_clipping_schedule(crate::grad_clipping::GradientClippingSchedule::new(
+                GradientClipping::Norm(8.0),
+                crate::lr_scheduler::constant::ConstantLr::new(8.0),
+            ))
+            .with_loss_adaptive_grad_clipping(LossAdaptiveGradientClipping::new(
+                GradientClipping::Norm(8.0),
+                0.5,
+                2.0,
+            ));
+    }
+}
@@ -1,2 +1,17 @@
 mod base;
+mod global_norm;
+mod groups;
+mod loss_adaptive;
+mod norm;
+#[cfg(feature = "std")]
+mod schedule;
+mod stats;
+
 pub use base::*;
+pub use global_norm::*;
+pub use groups::*;
+pub use loss_adaptive::*;
+pub use norm::*;
+#[cfg(feature = "std")]
+pub use schedule::*;
+pub use stats::*;
@@ -0,0 +1,280 @@
+use crate as burn;
+
+use crate::{config::Config, tensor::Tensor};
+use alloc::vec;
+use burn_tensor::backend::Backend;
+
+/// Norm of a tensor, used by the norm-based [gradient clipping](super::GradientClipping) and the
+/// trust ratio of layer-wise adaptive optimizers.
+#[derive(Config, Debug, PartialEq)]
+pub enum NormKind {
+    /// Sum of the absolute values.
+    L1,
+
+    /// Square root of the sum of the squares.
+    L2,
+
+    /// Largest absolute value.
+    LInf,
+
+    /// Root mean square, the L2 norm normalized by the number of elements, so it doesn't grow
+    /// with the size of the tensor.
+    RMS,
+}
+
+/// Compute the norm of a tensor.
+///
+/// The [L2](NormKind::L2) and [RMS](NormKind::RMS) norms are computed with a
+/// [GlobalNormAccumulator], on the tensor scaled by its largest absolute value as done by the BLAS
+/// `nrm2` routine, so the sum of the squares doesn't overflow for large values or underflow for
+/// small ones, e.g. with half precision floats where the square of `256` already overflows.
+///
+/// # Returns
+///
+/// The norm, as a tensor of shape `[1]` on the device of the given tensor.
+pub fn tensor_norm<B: Backend, const D: usize>(
+    tensor: Tensor<B, D>,
+    kind: &NormKind,
+) -> Tensor<B, 1> {
+    match kind {
+        NormKind::L1 => tensor.abs().sum(),
+        NormKind::LInf => tensor.abs().max(),
+        NormKind::L2 | NormKind::RMS => {
+            let mut accumulator =
+                GlobalNormAccumulator::new(&tensor.device()).with_kind(kind.clone());
+            accumulator.add(tensor);
+            accumulator.norm()
+        }
+    }
+}
+
+/// Accumulates the norm of many tensors, as if they were concatenated in a single tensor, one
+/// tensor at a time, e.g. the global norm of the gradients of a module.
+///
+/// Each tensor is reduced as soon as it is available and folded on the reduction device, so the
+/// tensors never need to be gathered and the only synchronization happens when the norm is read.
+///
+/// The squares of the [L2](NormKind::L2) and [RMS](NormKind::RMS) norms are summed scaled by the
+/// largest absolute value seen so far, rescaling the sum when a larger value is folded, so the
+/// sum doesn't overflow or underflow like in [tensor_norm].
+pub struct GlobalNormAccumulator<B: Backend> {
+    device: B::Device,
+    kind: NormKind,
+    partial: Option<Tensor<B, 1>>,
+    scale: Option<Tensor<B, 1>>,
+    num_elements: usize,
+}
+
+impl<B: Backend> GlobalNormAccumulator<B> {
+    /// Create an empty accumulator of the [L2](NormKind::L2) norm, performing the reduction on
+    /// the given device.
+    pub fn new(device: &B::Device) -> Self {
+        Self {
+            device: device.clone(),
+            kind: NormKind::L2,
+            partial: None,
+            scale: None,
+            num_elements: 0,
+        }
+    }
+
+    /// Set the kind of norm to accumulate.
+    pub fn with_kind(mut self, kind: NormKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Fold a tensor into the accumulator.
+    pub fn add<const D: usize>(&mut self, tensor: Tensor<B, D>) {
+        self.num_elements += tensor.shape().num_elements();
+        let partial = match self.kind {
+            NormKind::L1 => tensor.abs().sum(),
+            NormKind::LInf => tensor.abs().max(),
+            NormKind::L2 | NormKind::RMS => return self.add_scaled(tensor),
+        }
+        .to_device(&self.device);
+
+        self.partial = Some(match self.partial.take() {
+            Some(accumulated) => match self.kind {
+                NormKind::LInf => Tensor::cat(vec![accumulated, partial], 0).max(),
+                _ => accumulated.add(partial),
+            },
+            None => partial,
+        });
+    }
+
+    /// Fold the sum of the squares of the tensor scaled by its largest absolute value, after
+    /// rescaling both sums by the new running maximum.
+    fn add_scaled<const D: usize>(&mut self, tensor: Tensor<B, D>) {
+        let max = tensor.clone().abs().max();
+        let sum_squares = tensor
+            .div(non_zero(max.clone()).reshape([1; D]))
+            .powf(2.0)
+            .sum()
+            .to_device(&self.device);
+        let max = max.to_device(&self.device);
+
+        let (scale, sum_squares) = match (self.scale.take(), self.partial.take()) {
+            (Some(scale), Some(accumulated)) => {
+                let scale_new = Tensor::cat(vec![scale.clone(), max.clone()], 0).max();
+                let divisor = non_zero(scale_new.clone());
+                let accumulated = accumulated.mul(scale.div(divisor.clone()).powf(2.0));
+                let sum_squares = sum_squares.mul(max.div(divisor).powf(2.0));
+                (scale_new, accumulated.add(sum_squares))
+            }
+            _ => (max, sum_squares),
+        };
+        self.scale = Some(scale);
+        self.partial = Some(sum_squares);
+    }
+
+    /// Whether no tensor was folded into the accumulator.
+    pub fn is_empty(&self) -> bool {
+        self.partial.is_none()
+    }
+
+    /// The number of elements of all the accumulated tensors.
+    pub fn num_elements(&self) -> usize {
+        self.num_elements
+    }
+
+    /// The norm of all the accumulated tensors, as a tensor of shape `[1]`, zero when no tensor
+    /// was accumulated.
+    pub fn norm(&self) -> Tensor<B, 1> {
+        let Some(partial) = self.partial.clone() else {
+            return Tensor::zeros_device([1], &self.device);
+        };
+
+        let reduced = match self.kind {
+            NormKind::L1 | NormKind::LInf => return partial,
+            NormKind::L2 => partial,
+            NormKind::RMS => partial.div_scalar(self.num_elements as f32),
+        };
+        let scale = self
+            .scale
+            .clone()
+            .expect("The scale is set with the sum of the squares");
+
+        // An infinite value is scaled to `inf / inf`, the norm is infinite instead of NaN.
+        let is_infinite = scale.clone().equal_elem(f32::INFINITY);
+        reduced
+            .sqrt()
+            .mul(scale)
+            .mask_fill(is_infinite, f32::INFINITY)
+    }
+}
+
+/// Replace a zero scale by one, so a zero tensor keeps a zero norm.
+fn non_zero<B: Backend>(scale: Tensor<B, 1>) -> Tensor<B, 1> {
+    scale.clone().mask_fill(scale.equal_elem(0.0), 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tensor::Data;
+    use crate::TestBackend;
+    use burn_tensor::ElementConversion;
+
+    #[test]
+    fn test_tensor_norm_kinds() {
+        let tensor = Tensor::<TestBackend, 2>::from_floats([[3.0, -4.0], [0.0, 0.0]]);
+        let norm = |kind| tensor_norm(tensor.clone(), &kind).into_data();
+
+        norm(NormKind::L1).assert_approx_eq(&Data::from([7.0]), 5);
+        norm(NormKind::L2).assert_approx_eq(&Data::from([5.0]), 5);
+        norm(NormKind::LInf).assert_approx_eq(&Data::from([4.0]), 5);
+        norm(NormKind::RMS).assert_approx_eq(&Data::from([2.5]), 5);
+    }
+
+    #[test]
+    fn test_tensor_norm_doesnt_overflow_or_underflow() {
+        // The squares of the large values overflow and the ones of the small values underflow in
+        // single precision, like the squares of values above 256 in half precision.
+        let large = Tensor::<TestBackend, 1>::from_floats([3.0e20, -4.0e20]);
+        let small = Tensor::<TestBackend, 1>::from_floats([3.0e-25, -4.0e-25]);
+        assert!(large.clone().powf(2.0).sum().into_scalar().is_infinite());
+        assert_eq!(small.clone().powf(2.0).sum().into_scalar(), 0.0);
+
+        tensor_norm(large.clone(), &NormKind::L2)
+            .div_scalar(1.0e20)
+            .into_data()
+            .assert_approx_eq(&Data::from([5.0]), 5);
+        tensor_norm(large, &NormKind::RMS)
+            .div_scalar(1.0e20)
+            .into_data()
+            .assert_approx_eq(&Data::from([3.5355339]), 5);
+        tensor_norm(small, &NormKind::L2)
+            .mul_scalar(1.0e25)
+            .into_data()
+            .assert_approx_eq(&Data::from([5.0]), 5);
+        tensor_norm(Tensor::<TestBackend, 1>::zeros([3]), &NormKind::L2)
+            .into_data()
+            .assert_approx_eq(&Data::from([0.0]), 5);
+    }
+
+    #[test]
+    fn test_incremental_global_norm_matches_batch_computation() {
+        let grads: [Tensor<TestBackend, 1>; 3] = [
+            Tensor::from_floats([1.0, -2.0, 3.0]),
+            Tensor::from_floats([4.0, 0.5]),
+            Tensor::from_floats([-1.5, 2.5, 6.0, 0.25]),
+        ];
+
+        let mut accumulator = GlobalNormAccumulator::new(&Default::default());
+        for grad in grads.iter() {
+            accumulator.add(grad.clone());
+        }
+        let norm_incremental = accumulator.norm().into_scalar().elem::<f32>();
+
+        let norm_batch = Tensor::cat(grads.to_vec(), 0)
+            .powf(2.0)
+            .sum()
+            .sqrt()
+            .into_scalar()
+            .elem::<f32>();
+
+        assert!((norm_incremental - norm_batch).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_incremental_global_norm_doesnt_overflow_or_underflow() {
+        let mut accumulator = GlobalNormAccumulator::<TestBackend>::new(&Default::default());
+        accumulator.add(Tensor::<TestBackend, 1>::from_floats([3.0e-25]));
+        accumulator.add(Tensor::<TestBackend, 1>::from_floats([-4.0e-25]));
+        accumulator
+            .norm()
+            .mul_scalar(1.0e25)
+            .into_data()
+            .assert_approx_eq(&Data::from([5.0]), 5);
+
+        // The running maximum grows with each tensor, so the sum is rescaled.
+        let mut accumulator =
+            GlobalNormAccumulator::<TestBackend>::new(&Default::default()).with_kind(NormKind::RMS);
+        accumulator.add(Tensor::<TestBackend, 1>::from_floats([0.0, 3.0e20]));
+        accumulator.add(Tensor::<TestBackend, 1>::from_floats([-4.0e20]));
+        accumulator
+            .norm()
+            .div_scalar(1.0e20)
+            .into_data()
+            .assert_approx_eq(&Data::from([2.8867513]), 5);
+    }
+
+    #[test]
+    fn test_norm_of_infinite_value_is_infinite() {
+        let tensor = Tensor::<TestBackend, 1>::from_floats([1.0, f32::INFINITY, -2.0]);
+        for kind in [NormKind::L1, NormKind::L2, NormKind::LInf, NormKind::RMS] {
+            let norm = tensor_norm(tensor.clone(), &kind)
+                .into_scalar()
+                .elem::<f32>();
+            assert_eq!(norm, f32::INFINITY, "{kind:?}");
+        }
+
+        let mut accumulator = GlobalNormAccumulator::<TestBackend>::new(&Default::default());
+        accumulator.add(Tensor::<TestBackend, 1>::from_floats([1.0, 2.0]));
+        accumulator.add(Tensor::<TestBackend, 1>::from_floats([f32::NEG_INFINITY]));
+        accumulator.add(Tensor::<TestBackend, 1>::from_floats([3.0]));
+        let norm = accumulator.norm().into_scalar().elem::<f32>();
+        assert_eq!(norm, f32::INFINITY);
+    }
+}
@@ -0,0 +1,138 @@
+use super::GradientClipping;
+use crate::lr_scheduler::LrScheduler;
+
+/// Gradient clipping with a threshold changing during training, e.g. to relax the clipping as
+/// training stabilizes, see
+/// [with_grad_clipping_schedule](crate::optim::adaptor::OptimizerAdaptor::with_grad_clipping_schedule).
+///
+/// The threshold is given by a [scheduler](LrScheduler), like a learning rate, and replaces the
+/// threshold of the gradient clipping at each step.
+pub struct GradientClippingSchedule<S> {
+    clipping: GradientClipping,
+    scheduler: S,
+}
+
+impl<S: LrScheduler> GradientClippingSchedule<S> {
+    /// Create a schedule of the threshold of the given gradient clipping.
+    ///
+    /// # Arguments
+    ///
+    /// * `clipping` - The gradient clipping, its threshold is replaced by the scheduled one.
+    /// * `scheduler` - The scheduler of the threshold.
+    pub fn new(clipping: GradientClipping, scheduler: S) -> Self {
+        Self {
+            clipping,
+            scheduler,
+        }
+    }
+
+    /// Perform the scheduler step, returning the gradient clipping with the scheduled threshold.
+    pub fn step(&mut self) -> GradientClipping {
+        let threshold = self.scheduler.step() as f32;
+
+        self.clipping.clone().with_threshold(threshold)
+    }
+
+    /// Move the schedule to the given step, see [seek](LrScheduler::seek).
+    pub fn seek(&mut self, step: usize) {
+        self.scheduler.seek(step);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lr_scheduler::lambda::LambdaLrScheduler;
+    use crate::nn::{Linear, LinearConfig};
+    use crate::optim::{GradientsParams, Optimizer, SgdConfig};
+    use crate::tensor::{Data, Tensor};
+    use crate::{TestAutodiffBackend, TestBackend};
+
+    #[test]
+    fn test_clipping_threshold_follows_the_schedule() {
+        let mut schedule = GradientClippingSchedule::new(
+            GradientClipping::Norm(100.0),
+            LambdaLrScheduler::new(|step| 1.0 + step as f64),
+        );
+        let gradient = Tensor::<TestBackend, 1>::from_floats([30.0, 40.0]);
+
+        let clipping = schedule.step();
+        assert_eq!(clipping.threshold(), 1.0);
+        clipping
+            .clip_gradient(gradient.clone())
+            .into_data()
+            .assert_approx_eq(&Data::from([0.6, 0.8]), 5);
+
+        for _ in 1..4 {
+            schedule.step();
+        }
+        let clipping = schedule.step();
+        assert_eq!(clipping.threshold(), 5.0);
+        clipping
+            .clip_gradient(gradient)
+            .into_data()
+            .assert_approx_eq(&Data::from([3.0, 4.0]), 5);
+    }
+
+    #[test]
+    fn test_optimizer_clips_with_the_scheduled_threshold() {
+        let mut linear: Linear<TestAutodiffBackend> =
+            LinearConfig::new(2, 1).with_bias(false).init();
+        let mut optim =
+            SgdConfig::new()
+                .init()
+                .with_grad_clipping_schedule(GradientClippingSchedule::new(
+                    GradientClipping::Norm(100.0),
+                    LambdaLrScheduler::new(|step| 1.0 + step as f64),
+                ));
+
+        // The gradient of the weight is the input, with a norm of 50, so the update has the norm
+        // of the threshold.
+        for threshold in [1.0, 2.0] {
+            let weight_before = linear.weight.val().inner();
+            let x = Tensor::<TestAutodiffBackend, 2>::from_floats([[30.0, 40.0]]);
+            let grads = GradientsParams::from_grads(linear.forward(x).backward(), &linear);
+            linear = optim.step(1.0, linear, grads);
+
+            let update = weight_before.sub(linear.weight.val().inner());
+            update
+                .reshape([2])
+                .into_data()
+                .assert_approx_eq(&Data::from([0.6 * threshold, 0.8 * threshold]), 5);
+        }
+    }
+
+    #[test]
+    fn test_skipped_step_advances_the_schedule_with_the_step_counter() {
+        let linear: Linear<TestAutodiffBackend> = LinearConfig::new(2, 1).with_bias(false).init();
+        let mut optim = SgdConfig::new()
+            .init()
+            .with_grad_clipping_schedule(GradientClippingSchedule::new(
+                GradientClipping::Norm(100.0),
+                LambdaLrScheduler::new(|step| 1.0 + step as f64),
+            ))
+            .with_skip_threshold(1000.0);
+        let mut step = |linear: Linear<TestAutodiffBackend>, scale: f32| {
+            let weight_before = linear.weight.val().inner();
+            let x = Tensor::<TestAutodiffBackend, 2>::from_floats([[30.0, 40.0]]);
+            let grads = linear.forward(x.mul_scalar(scale)).backward();
+            let grads = GradientsParams::from_grads(grads, &linear);
+            let linear = optim.step(1.0, linear, grads);
+            let update = weight_before.sub(linear.weight.val().inner()).reshape([2]);
+            (linear, update.into_data(), optim.num_steps())
+        };
+
+        let (linear, update, num_steps) = step(linear, 1.0);
+        update.assert_approx_eq(&Data::from([0.6, 0.8]), 5);
+        assert_eq!(num_steps, 1);
+
+        // The gradient norm exceeds the skip threshold, the schedule advances with the counter.
+        let (linear, update, num_steps) = step(linear, 1e5);
+        update.assert_approx_eq(&Data::from([0.0, 0.0]), 5);
+        assert_eq!(num_steps, 2);
+
+        let (_, update, num_steps) = step(linear, 1.0);
+        update.assert_approx_eq(&Data::from([1.8, 2.4]), 5);
+        assert_eq!(num_steps, 3);
+    }
+}
@@ -0,0 +1,128 @@
+use super::{GradientClipping, NanPolicy};
+use crate::tensor::Tensor;
+use burn_tensor::backend::Backend;
+
+/// Statistics of the norm-based [gradient clipping](GradientClipping), e.g. to report the
+/// fraction of the clipped gradients of each epoch.
+///
+/// Only the gradients with a finite norm are recorded, and clipping by value isn't recorded
+/// since it doesn't compute the norm of the gradients.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClipStats {
+    num_grads: usize,
+    num_clipped: usize,
+    norm_sum: f64,
+}
+
+impl ClipStats {
+    /// Record the norm of a gradient, clipped when the norm is above the threshold.
+    pub fn record(&mut self, norm: f32, threshold: f32) {
+        self.num_grads += 1;
+        if norm > threshold {
+            self.num_clipped += 1;
+        }
+        self.norm_sum += norm as f64;
+    }
+
+    /// The number of recorded gradients.
+    pub fn num_grads(&self) -> usize {
+        self.num_grads
+    }
+
+    /// The number of recorded gradients with a norm above the threshold.
+    pub fn num_clipped(&self) -> usize {
+        self.num_clipped
+    }
+
+    /// The fraction of the recorded gradients with a norm above the threshold, zero when no
+    /// gradient was recorded.
+    pub fn clip_ratio(&self) -> f64 {
+        match self.num_grads {
+            0 => 0.0,
+            num_grads => self.num_clipped as f64 / num_grads as f64,
+        }
+    }
+
+    /// The mean norm of the recorded gradients, before clipping.
+    pub fn mean_norm(&self) -> Option<f64> {
+        match self.num_grads {
+            0 => None,
+            num_grads => Some(self.norm_sum / num_grads as f64),
+        }
+    }
+
+    /// Clear the statistics, e.g. at the start of an epoch.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// [Gradient clipping](GradientClipping) recording its [statistics](ClipStats).
+#[derive(Clone)]
+pub struct TrackedGradientClipping {
+    clipping: GradientClipping,
+    policy: NanPolicy,
+    stats: ClipStats,
+}
+
+impl TrackedGradientClipping {
+    /// Track the statistics of the given clipping, handling a norm that isn't finite with the
+    /// given policy.
+    pub fn new(clipping: GradientClipping, policy: NanPolicy) -> Self {
+        Self {
+            clipping,
+            policy,
+            stats: ClipStats::default(),
+        }
+    }
+
+    /// Clip the gradient and record its norm, see
+    /// [clip_gradient_with_stats](GradientClipping::clip_gradient_with_stats).
+    pub fn clip_gradient<B: Backend, const D: usize>(
+        &mut self,
+        grad: Tensor<B, D>,
+    ) -> Tensor<B, D> {
+        self.clipping
+            .clip_gradient_with_stats(grad, &self.policy, &mut self.stats)
+    }
+
+    /// The statistics recorded since the creation or the last
+    /// [reset](Self::reset_clip_stats).
+    pub fn clip_stats(&self) -> &ClipStats {
+        &self.stats
+    }
+
+    /// Clear the recorded statistics, e.g. at the start of an epoch.
+    pub fn reset_clip_stats(&mut self) {
+        self.stats.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestBackend;
+
+    #[test]
+    fn test_reset_clip_stats_starts_fresh_statistics() {
+        let mut clipping =
+            TrackedGradientClipping::new(GradientClipping::Norm(1.0), NanPolicy::Skip);
+        let grad = |values: [f32; 2]| Tensor::<TestBackend, 1>::from_floats(values);
+
+        clipping.clip_gradient(grad([3.0, 4.0]));
+        clipping.clip_gradient(grad([0.3, 0.4]));
+        assert_eq!(clipping.clip_stats().num_grads(), 2);
+        assert_eq!(clipping.clip_stats().clip_ratio(), 0.5);
+        assert!((clipping.clip_stats().mean_norm().unwrap() - 2.75).abs() < 1e-5);
+
+        clipping.reset_clip_stats();
+        assert_eq!(clipping.clip_stats(), &ClipStats::default());
+        assert_eq!(clipping.clip_stats().clip_ratio(), 0.0);
+        assert_eq!(clipping.clip_stats().mean_norm(), None);
+
+        clipping.clip_gradient(grad([6.0, 8.0]));
+        assert_eq!(clipping.clip_stats().num_grads(), 1);
+        assert_eq!(clipping.clip_stats().num_clipped(), 1);
+        assert!((clipping.clip_stats().mean_norm().unwrap() - 10.0).abs() < 1e-5);
+    }
+}
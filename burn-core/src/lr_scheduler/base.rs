@@ -9,9 +9,57 @@ pub trait LrScheduler: Send + Sync {
     /// learning rate.
     fn step(&mut self) -> LearningRate;
 
+    /// Move the scheduler to the given step, as if [step](LrScheduler::step) was called `step`
+    /// times on a freshly created scheduler, whatever the current step is.
+    ///
+    /// Seeking to the step `0` resets the scheduler, e.g. for a warm restart.
+    ///
+    /// The default implementation calls [step](LrScheduler::step) `step` times, which is only
+    /// correct on a freshly created scheduler, so schedulers that can be moved back override it.
+    fn seek(&mut self, step: usize) {
+        for _ in 0..step {
+            self.step();
+        }
+    }
+
     /// Get the current state of the scheduler as a [record](Record).
     fn to_record(&self) -> Self::Record;
 
     /// Load the state of the scheduler as a [record](Record).
     fn load_record(self, record: Self::Record) -> Self;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct CountingScheduler {
+        num_steps: usize,
+    }
+
+    impl LrScheduler for CountingScheduler {
+        type Record = usize;
+
+        fn step(&mut self) -> LearningRate {
+            self.num_steps += 1;
+            self.num_steps as LearningRate
+        }
+
+        fn to_record(&self) -> Self::Record {
+            self.num_steps
+        }
+
+        fn load_record(self, record: Self::Record) -> Self {
+            Self { num_steps: record }
+        }
+    }
+
+    #[test]
+    fn test_default_seek_steps_a_fresh_scheduler() {
+        let mut scheduler = CountingScheduler { num_steps: 0 };
+
+        scheduler.seek(3);
+
+        assert_eq!(scheduler.step(), 4.0);
+    }
+}
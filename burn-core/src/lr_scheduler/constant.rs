@@ -24,6 +24,8 @@ impl LrScheduler for ConstantLr {
         self.lr
     }
 
+    fn seek(&mut self, _step: usize) {}
+
     fn to_record(&self) -> Self::Record {}
 
     fn load_record(self, _record: Self::Record) -> Self {
@@ -38,9 +40,26 @@ impl LrScheduler for LearningRate {
         *self
     }
 
+    fn seek(&mut self, _step: usize) {}
+
     fn to_record(&self) -> Self::Record {}
 
     fn load_record(self, _record: Self::Record) -> Self {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_scheduler_never_changes() {
+        let mut scheduler = ConstantLr::new(0.1);
+        scheduler.seek(10);
+
+        for _ in 0..100 {
+            assert_eq!(scheduler.step(), 0.1);
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate as burn;
+
+use super::LrScheduler;
+use crate::{config::Config, LearningRate};
+
+/// Configuration to create a [cosine annealing](CosineAnnealingLrScheduler) learning rate scheduler.
+#[derive(Config)]
+pub struct CosineAnnealingLrSchedulerConfig {
+    /// The initial learning rate.
+    init_lr: LearningRate,
+    /// The number of steps to anneal the learning rate from `init_lr` to `min_lr`.
+    num_iters: usize,
+    /// The minimum learning rate.
+    #[config(default = 0.0)]
+    min_lr: LearningRate,
+}
+
+/// Cosine annealing learning rate scheduler as described in
+/// [SGDR: Stochastic Gradient Descent with Warm Restarts](https://arxiv.org/abs/1608.03983),
+/// without restarts.
+///
+/// The learning rate stays at `min_lr` after `num_iters` steps.
+#[derive(Clone, Debug)]
+pub struct CosineAnnealingLrScheduler {
+    init_lr: LearningRate,
+    min_lr: LearningRate,
+    num_iters: usize,
+    current_iter: usize,
+}
+
+impl CosineAnnealingLrSchedulerConfig {
+    /// Initialize a new [cosine annealing](CosineAnnealingLrScheduler) learning rate scheduler.
+    pub fn init(&self) -> CosineAnnealingLrScheduler {
+        assert!(
+            self.num_iters > 0,
+            "The number of iterations must be greater than zero."
+        );
+
+        CosineAnnealingLrScheduler {
+            init_lr: self.init_lr,
+            min_lr: self.min_lr,
+            num_iters: self.num_iters,
+            current_iter: 0,
+        }
+    }
+}
+
+impl LrScheduler for CosineAnnealingLrScheduler {
+    type Record = usize;
+
+    fn step(&mut self) -> LearningRate {
+        let progress = self.current_iter as f64 / self.num_iters as f64;
+        self.current_iter = usize::min(self.current_iter + 1, self.num_iters);
+
+        let cosine = f64::cos(core::f64::consts::PI * progress);
+
+        self.min_lr + 0.5 * (self.init_lr - self.min_lr) * (1.0 + cosine)
+    }
+
+    fn seek(&mut self, step: usize) {
+        self.current_iter = usize::min(step, self.num_iters);
+    }
+
+    fn to_record(&self) -> Self::Record {
+        self.current_iter
+    }
+
+    fn load_record(mut self, record: Self::Record) -> Self {
+        self.current_iter = record;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seek_then_step_equals_sequential_steps() {
+        let config = CosineAnnealingLrSchedulerConfig::new(0.1, 200).with_min_lr(0.001);
+        let mut scheduler_sequential = config.init();
+        let mut scheduler_seek = config.init();
+
+        let mut lr_sequential = 0.0;
+        for _ in 0..101 {
+            lr_sequential = scheduler_sequential.step();
+        }
+        scheduler_seek.seek(100);
+        let lr_seek = scheduler_seek.step();
+
+        assert_eq!(lr_seek, lr_sequential);
+        assert_eq!(scheduler_seek.to_record(), scheduler_sequential.to_record());
+    }
+
+    #[test]
+    fn test_seek_back_to_zero_restarts_the_schedule() {
+        let mut scheduler = CosineAnnealingLrSchedulerConfig::new(0.1, 10).init();
+
+        let lr_first = scheduler.step();
+        for _ in 0..5 {
+            scheduler.step();
+        }
+        scheduler.seek(0);
+
+        assert_eq!(scheduler.step(), lr_first);
+    }
+
+    #[test]
+    fn test_function_decrease_to_min_lr() {
+        let mut scheduler = CosineAnnealingLrSchedulerConfig::new(0.1, 10)
+            .with_min_lr(0.01)
+            .init();
+
+        assert_eq!(scheduler.step(), 0.1);
+        let mut lr_current = 0.1;
+        for _ in 1..10 {
+            let lr = scheduler.step();
+            assert!(lr < lr_current, "Learning rate should decrease.");
+            lr_current = lr;
+        }
+        assert!((scheduler.step() - 0.01).abs() < 1e-12);
+    }
+}
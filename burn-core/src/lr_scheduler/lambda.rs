@@ -0,0 +1,70 @@
+use super::LrScheduler;
+use crate::LearningRate;
+
+/// Learning rate scheduler computing the learning rate with a function of the step.
+///
+/// # Notes
+///
+/// Functions can't be serialized, so the [record](LrScheduler::Record) only contains the step
+/// counter. The scheduler must be created with the same function before loading a record.
+#[derive(Clone)]
+pub struct LambdaLrScheduler<F> {
+    func: F,
+    step: usize,
+}
+
+impl<F> LambdaLrScheduler<F>
+where
+    F: Fn(usize) -> LearningRate + Send + Sync,
+{
+    /// Create a new lambda learning rate scheduler.
+    ///
+    /// The function receives the number of steps already performed, starting at zero, and
+    /// returns the learning rate.
+    pub fn new(func: F) -> Self {
+        Self { func, step: 0 }
+    }
+}
+
+impl<F> LrScheduler for LambdaLrScheduler<F>
+where
+    F: Fn(usize) -> LearningRate + Send + Sync,
+{
+    type Record = usize;
+
+    fn step(&mut self) -> LearningRate {
+        let lr = (self.func)(self.step);
+        self.step += 1;
+        lr
+    }
+
+    fn seek(&mut self, step: usize) {
+        self.step = step;
+    }
+
+    fn to_record(&self) -> Self::Record {
+        self.step
+    }
+
+    fn load_record(mut self, record: Self::Record) -> Self {
+        self.step = record;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lambda_scheduler_evaluates_function_at_current_step() {
+        let mut scheduler = LambdaLrScheduler::new(|step| 0.1 / (step + 1) as f64);
+
+        assert_eq!(scheduler.step(), 0.1);
+        assert_eq!(scheduler.step(), 0.05);
+
+        let mut scheduler = LambdaLrScheduler::new(|step| 0.1 / (step + 1) as f64)
+            .load_record(scheduler.to_record());
+        assert_eq!(scheduler.step(), 0.1 / 3.0);
+    }
+}
@@ -1,9 +1,21 @@
 /// Constant learning rate scheduler
 pub mod constant;
 
+/// Cosine annealing learning rate scheduler
+pub mod cosine;
+
+/// Lambda learning rate scheduler
+pub mod lambda;
+
 /// Noam Learning rate schedule
 pub mod noam;
 
+/// Tabular learning rate scheduler
+pub mod tabular;
+
+/// Warmup learning rate scheduler
+pub mod warmup;
+
 mod base;
 
 pub use base::*;
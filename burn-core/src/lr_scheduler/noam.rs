@@ -49,6 +49,10 @@ impl LrScheduler for NoamLrScheduler {
         self.init_lr * self.embedding_size.powf(-0.5) * f64::min(arg1, arg2)
     }
 
+    fn seek(&mut self, step: usize) {
+        self.step = step as f64;
+    }
+
     fn to_record(&self) -> Self::Record {
         self.step as usize
     }
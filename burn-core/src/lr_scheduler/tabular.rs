@@ -0,0 +1,203 @@
+use super::LrScheduler;
+use crate::{config::ConfigError, LearningRate};
+use alloc::format;
+use alloc::vec::Vec;
+
+/// Learning rate scheduler reading the learning rate from a table of `(step, lr)` pairs, e.g. to
+/// reproduce the exact schedule of a published training run.
+///
+/// The learning rate is linearly interpolated between the listed steps, and the first and last
+/// learning rates are held before the first and after the last listed step.
+///
+/// # Notes
+///
+/// The [record](LrScheduler::Record) only contains the step counter, the scheduler must be
+/// created with the same table before loading a record.
+#[derive(Clone, Debug)]
+pub struct TabularLrScheduler {
+    table: Vec<(usize, LearningRate)>,
+    step: usize,
+}
+
+#[derive(serde::Deserialize)]
+struct TabularLrEntry {
+    step: usize,
+    lr: LearningRate,
+}
+
+impl TabularLrScheduler {
+    /// Create a new tabular learning rate scheduler.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The learning rate of each listed step, in any order.
+    ///
+    /// # Panics
+    ///
+    /// When the table is empty or lists a step twice.
+    pub fn new(mut table: Vec<(usize, LearningRate)>) -> Self {
+        assert!(!table.is_empty(), "The learning rate table is empty.");
+        table.sort_by_key(|(step, _)| *step);
+        assert!(
+            table.windows(2).all(|pair| pair[0].0 != pair[1].0),
+            "The learning rate table lists a step twice."
+        );
+
+        Self { table, step: 0 }
+    }
+
+    /// Create the scheduler from CSV content with a `step,lr` pair on each line. A header line and
+    /// empty lines are skipped.
+    pub fn from_csv(content: &str) -> Result<Self, ConfigError> {
+        let mut table = Vec::new();
+
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || (index == 0 && line.starts_with("step")) {
+                continue;
+            }
+
+            let invalid_line = || ConfigError::InvalidFormat(format!("Invalid line: {line}"));
+            let (step, lr) = line.split_once(',').ok_or_else(invalid_line)?;
+            let step = step.trim().parse().map_err(|_| invalid_line())?;
+            let lr = lr.trim().parse().map_err(|_| invalid_line())?;
+            table.push((step, lr));
+        }
+
+        Self::from_table(table)
+    }
+
+    /// Create the scheduler from a JSON array of `{"step": ..., "lr": ...}` objects.
+    pub fn from_json(content: &str) -> Result<Self, ConfigError> {
+        let entries: Vec<TabularLrEntry> = serde_json::from_str(content)
+            .map_err(|err| ConfigError::InvalidFormat(format!("{err}")))?;
+
+        Self::from_table(
+            entries
+                .into_iter()
+                .map(|entry| (entry.step, entry.lr))
+                .collect(),
+        )
+    }
+
+    /// Load the scheduler from a file, in the JSON format when the file has the `json`
+    /// extension, and in the CSV format otherwise.
+    #[cfg(feature = "std")]
+    pub fn load<P: AsRef<std::path::Path>>(file: P) -> Result<Self, ConfigError> {
+        let file = file.as_ref();
+        let content = std::fs::read_to_string(file)
+            .map_err(|_| ConfigError::FileNotFound(file.to_string_lossy().into_owned()))?;
+
+        match file.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => Self::from_json(&content),
+            _ => Self::from_csv(&content),
+        }
+    }
+
+    fn from_table(mut table: Vec<(usize, LearningRate)>) -> Result<Self, ConfigError> {
+        if table.is_empty() {
+            return Err(ConfigError::InvalidFormat(
+                "The learning rate table is empty.".into(),
+            ));
+        }
+
+        table.sort_by_key(|(step, _)| *step);
+        if let Some(pair) = table.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            return Err(ConfigError::InvalidFormat(format!(
+                "The step {} is listed twice.",
+                pair[0].0
+            )));
+        }
+
+        Ok(Self::new(table))
+    }
+
+    fn lr(&self, step: usize) -> LearningRate {
+        let next = self.table.partition_point(|(listed, _)| *listed <= step);
+
+        if next == 0 {
+            return self.table[0].1;
+        }
+        if next == self.table.len() {
+            return self.table[next - 1].1;
+        }
+
+        let (step_start, lr_start) = self.table[next - 1];
+        let (step_end, lr_end) = self.table[next];
+        let progress = (step - step_start) as f64 / (step_end - step_start) as f64;
+
+        lr_start + (lr_end - lr_start) * progress
+    }
+}
+
+impl LrScheduler for TabularLrScheduler {
+    type Record = usize;
+
+    fn step(&mut self) -> LearningRate {
+        let lr = self.lr(self.step);
+        self.step += 1;
+        lr
+    }
+
+    fn seek(&mut self, step: usize) {
+        self.step = step;
+    }
+
+    fn to_record(&self) -> Self::Record {
+        self.step
+    }
+
+    fn load_record(mut self, record: Self::Record) -> Self {
+        self.step = record;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_lr(scheduler: &mut TabularLrScheduler, expected: &[LearningRate]) {
+        for expected in expected {
+            let lr = scheduler.step();
+            assert!((lr - expected).abs() < 1e-12, "{lr} != {expected}");
+        }
+    }
+
+    #[test]
+    fn test_tabular_scheduler_interpolates_listed_steps() {
+        let mut scheduler =
+            TabularLrScheduler::from_csv("step,lr\n1,0.1\n3,0.3\n\n5,0.1\n").unwrap();
+
+        // The first learning rate is held before step 1, and the last one after step 5.
+        assert_lr(&mut scheduler, &[0.1, 0.1, 0.2, 0.3, 0.2, 0.1, 0.1, 0.1]);
+
+        let mut scheduler = TabularLrScheduler::from_json(
+            r#"[{"step": 5, "lr": 0.1}, {"step": 1, "lr": 0.1}, {"step": 3, "lr": 0.3}]"#,
+        )
+        .unwrap()
+        .load_record(3);
+        assert_lr(&mut scheduler, &[0.3, 0.2, 0.1]);
+    }
+
+    #[test]
+    fn test_tabular_scheduler_err_invalid_table() {
+        assert!(TabularLrScheduler::from_csv("step,lr\n").is_err());
+        assert!(TabularLrScheduler::from_csv("1,0.1\n1,0.2\n").is_err());
+        assert!(TabularLrScheduler::from_csv("1;0.1\n").is_err());
+        assert!(TabularLrScheduler::from_json(r#"[{"step": 1}]"#).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_tabular_scheduler_loads_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("schedule.csv");
+        std::fs::write(&path, "0,1.0\n10,0.0\n").unwrap();
+
+        let mut scheduler = TabularLrScheduler::load(&path).unwrap();
+        scheduler.seek(4);
+
+        assert_lr(&mut scheduler, &[0.6, 0.5]);
+    }
+}
@@ -0,0 +1,89 @@
+use crate as burn;
+
+use super::LrScheduler;
+use crate::{record::Record, LearningRate};
+
+/// Learning rate scheduler with a linear warmup, scaling the learning rate of another scheduler
+/// by `(step + 1) / warmup_steps` during the first `warmup_steps` steps.
+///
+/// The inner scheduler advances during the warmup, so a
+/// [cosine annealing](super::cosine::CosineAnnealingLrScheduler) over the whole training gives
+/// the usual warmup followed by a cosine decay.
+#[derive(Clone, Debug)]
+pub struct WarmupLrScheduler<S> {
+    inner: S,
+    warmup_steps: usize,
+    step: usize,
+}
+
+/// [Warmup learning rate scheduler](WarmupLrScheduler) record.
+#[derive(Record)]
+pub struct WarmupLrSchedulerRecord<R: Record> {
+    inner: R,
+    step: usize,
+}
+
+impl<S: LrScheduler> WarmupLrScheduler<S> {
+    /// Create a new warmup learning rate scheduler.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The scheduler of the learning rate after the warmup.
+    /// * `warmup_steps` - The number of warmup steps, `0` to disable the warmup.
+    pub fn new(inner: S, warmup_steps: usize) -> Self {
+        Self {
+            inner,
+            warmup_steps,
+            step: 0,
+        }
+    }
+}
+
+impl<S: LrScheduler> LrScheduler for WarmupLrScheduler<S> {
+    type Record = WarmupLrSchedulerRecord<S::Record>;
+
+    fn step(&mut self) -> LearningRate {
+        let lr = self.inner.step();
+        let step = self.step;
+        self.step = usize::min(self.step + 1, self.warmup_steps);
+
+        if step < self.warmup_steps {
+            lr * (step + 1) as f64 / self.warmup_steps as f64
+        } else {
+            lr
+        }
+    }
+
+    fn seek(&mut self, step: usize) {
+        self.inner.seek(step);
+        self.step = usize::min(step, self.warmup_steps);
+    }
+
+    fn to_record(&self) -> Self::Record {
+        WarmupLrSchedulerRecord {
+            inner: self.inner.to_record(),
+            step: self.step,
+        }
+    }
+
+    fn load_record(mut self, record: Self::Record) -> Self {
+        self.inner = self.inner.load_record(record.inner);
+        self.step = record.step;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lr_scheduler::constant::ConstantLr;
+
+    #[test]
+    fn test_warmup_increases_linearly_then_follows_inner() {
+        let mut scheduler = WarmupLrScheduler::new(ConstantLr::new(1.0), 4);
+
+        let lrs: Vec<LearningRate> = (0..6).map(|_| scheduler.step()).collect();
+
+        assert_eq!(lrs, [0.25, 0.5, 0.75, 1.0, 1.0, 1.0]);
+    }
+}
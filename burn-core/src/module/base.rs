@@ -220,17 +220,40 @@ pub trait Module<B: Backend>: Clone + Send + Sync + core::fmt::Debug {
     ///
     /// The file extension is automatically added depending on the file recorder provided, you
     /// don't have to specify it.
+    ///
+    /// A [shape mismatch](crate::record::RecorderError::ShapeMismatch) error is returned, before
+    /// loading the record, when a recorded parameter doesn't have the same shape as the parameter
+    /// it would replace.
     fn load_file<FR: crate::record::FileRecorder, PB: Into<std::path::PathBuf>>(
         self,
         file_path: PB,
         recorder: &FR,
     ) -> Result<Self, crate::record::RecorderError> {
-        let record = recorder.load(file_path.into())?;
+        let record: Self::Record = recorder.load(file_path.into())?;
+        let shapes_recorded = record.summary().param_shapes;
+
+        for (expected, got) in param_shapes(&self).into_iter().zip(shapes_recorded) {
+            if expected != got {
+                return Err(crate::record::RecorderError::ShapeMismatch { expected, got });
+            }
+        }
 
         Ok(self.load_record(record))
     }
 }
 
+#[cfg(feature = "std")]
+fn param_shapes<B: Backend, M: Module<B>>(module: &M) -> Vec<Vec<usize>> {
+    module!(
+        visit = module,
+        ops = |tensor: &Tensor<B, D>, state: &mut Vec<Vec<usize>>| {
+            state.push(tensor.shape().dims.to_vec());
+        },
+        state = Vec<Vec<usize>>,
+        init = Vec::new
+    )
+}
+
 /// Module visitor trait.
 pub trait ModuleVisitor<B: Backend> {
     /// Visit a tensor in the module.
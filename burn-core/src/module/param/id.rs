@@ -2,7 +2,7 @@ use alloc::string::{String, ToString};
 use burn_common::id::IdGenerator;
 
 /// Parameter ID.
-#[derive(Debug, Hash, PartialEq, Eq, Clone)]
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub struct ParamId {
     value: String,
 }
@@ -3,14 +3,15 @@ use crate::{
     LearningRate,
 };
 
+use super::validation::{validate_non_negative, validate_positive};
 use super::{
-    decay::{WeightDecay, WeightDecayConfig},
-    Optimizer, SimpleOptimizer,
+    decay::{WeightDecay, WeightDecayConfig, WeightDecayWarmup},
+    SimpleOptimizer, Transform,
 };
-use crate::config::Config;
+use crate::config::{Config, ConfigError, ValidateConfig};
 use crate::optim::adaptor::OptimizerAdaptor;
 use crate::tensor::{backend::AutodiffBackend, Tensor};
-use burn_tensor::backend::Backend;
+use burn_tensor::{backend::Backend, Data, DataSerialize, ElementPrecision, Precision};
 
 /// AdaGrad configuration.
 #[derive(Config)]
@@ -19,10 +20,27 @@ pub struct AdaGradConfig {
     lr_decay: f64,
     #[config(default = 1e-5)]
     epsilon: f32,
+    /// If the squared gradients are accumulated in f32, while the update is computed in the float
+    /// type of the backend from the sum rounded to it.
+    ///
+    /// A backend has a single float type, so the f32 accumulator is kept on the host, which reads
+    /// back each gradient. Defaults to enabled only when the float type is half precision (f16 or
+    /// bf16), where the small squared gradients would be lost when added to a large sum.
+    f32_accumulator: Option<bool>,
+    /// Maximum value of the accumulated squared gradients of each coordinate, which bounds the
+    /// decay of the effective learning rate of frequently updated coordinates.
+    accumulator_max: Option<f32>,
+    /// Initial value of the accumulated squared gradients of each coordinate, which damps the
+    /// first steps compared to starting from the first squared gradient.
+    #[config(default = 0.0)]
+    initial_accumulator_value: f32,
     /// [Weight decay](WeightDecayConfig) config.
     weight_decay: Option<WeightDecayConfig>,
     /// [Gradient Clipping](GradientClippingConfig) config.
     grad_clipping: Option<GradientClippingConfig>,
+    /// [Gradient scale](crate::optim::adaptor::OptimizerAdaptor::with_grad_scale) of the adaptor.
+    #[config(default = 1.0)]
+    grad_scale: f32,
 }
 
 /// AdaGrad optimizer
@@ -44,24 +62,22 @@ impl<B: Backend> SimpleOptimizer<B> for AdaGrad<B> {
         &self,
         lr: LearningRate,
         tensor: Tensor<B, D>,
-        mut grad: Tensor<B, D>,
+        grad: Tensor<B, D>,
         state: Option<Self::State<D>>,
     ) -> (Tensor<B, D>, Option<Self::State<D>>) {
-        let mut state_lr_decay = None;
+        let state_lr_decay = state.map(|state| state.lr_decay);
 
-        if let Some(state) = state {
-            state_lr_decay = Some(state.lr_decay);
-        }
+        let (grad, _) = self.weight_decay.apply(lr, &tensor, grad, None);
+        let (grad, state_lr_decay) = self.lr_decay.apply(lr, &tensor, grad, state_lr_decay);
 
+        let state = AdaGradState::new(state_lr_decay);
+
+        let mut tensor = tensor - grad;
         if let Some(weight_decay) = &self.weight_decay {
-            grad = weight_decay.transform(grad, tensor.clone());
+            tensor = weight_decay.proximal(lr, tensor);
         }
 
-        let (grad, state_lr_decay) = self.lr_decay.transform(grad, lr, state_lr_decay);
-
-        let state = AdaGradState::new(state_lr_decay);
-
-        (tensor - grad, Some(state))
+        (tensor, Some(state))
     }
 
     fn to_device<const D: usize>(
@@ -71,27 +87,115 @@ impl<B: Backend> SimpleOptimizer<B> for AdaGrad<B> {
         state.lr_decay = state.lr_decay.to_device(device);
         state
     }
+
+    fn state_map_tensors<const D: usize, F>(mut state: Self::State<D>, func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        state.lr_decay = <LRDecay as Transform<B>>::state_map_tensors(state.lr_decay, func);
+        state
+    }
+
+    fn state_num_elements<const D: usize>(state: &Self::State<D>) -> usize {
+        <LRDecay as Transform<B>>::state_num_elements(&state.lr_decay)
+    }
+
+    fn state_num_steps<const D: usize>(state: &Self::State<D>) -> Option<usize> {
+        <LRDecay as Transform<B>>::state_num_steps(&state.lr_decay)
+    }
+
+    fn state_effective_lr<const D: usize>(
+        &self,
+        lr: LearningRate,
+        state: &Self::State<D>,
+    ) -> Option<Tensor<B, D>> {
+        Some(self.lr_decay.effective_lr(lr, &state.lr_decay))
+    }
+}
+
+impl ValidateConfig for AdaGradConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        validate_non_negative("lr_decay", self.lr_decay)?;
+        validate_positive("epsilon", self.epsilon)?;
+        if let Some(accumulator_max) = self.accumulator_max {
+            validate_positive("accumulator_max", accumulator_max)?;
+        }
+        validate_non_negative(
+            "initial_accumulator_value",
+            self.initial_accumulator_value as f64,
+        )?;
+        if let Some(weight_decay) = &self.weight_decay {
+            weight_decay.validate()?;
+        }
+        if let Some(clipping) = &self.grad_clipping {
+            clipping.validate()?;
+        }
+
+        Ok(())
+    }
 }
 
 impl AdaGradConfig {
+    /// Initialize AdaGrad as a [simple optimizer](SimpleOptimizer), to optimize tensors directly
+    /// with [step_tensors](crate::optim::step_tensors).
+    ///
+    /// The squared gradients are accumulated as given, without the clipping and the unscaling
+    /// that the adaptor of [init](Self::init) applies first.
+    pub fn init_simple<B: Backend>(&self) -> AdaGrad<B> {
+        AdaGrad {
+            lr_decay: self.init_lr_decay::<B>(),
+            weight_decay: self.weight_decay.as_ref().map(WeightDecay::new),
+        }
+    }
+
+    /// Initialize the adaptation of the gradients performed by AdaGrad as a
+    /// [transform](Transform), to build custom optimizers with
+    /// [TransformOptimizer](crate::optim::TransformOptimizer).
+    ///
+    /// Weight decay isn't applied by the transform.
+    pub fn init_lr_decay<B: Backend>(&self) -> LRDecay {
+        LRDecay {
+            lr_decay: self.lr_decay,
+            epsilon: self.epsilon,
+            f32_accumulator: self
+                .f32_accumulator
+                .unwrap_or_else(|| B::FloatElem::precision() == Precision::Half),
+            accumulator_max: self.accumulator_max,
+            initial_accumulator_value: self.initial_accumulator_value,
+        }
+    }
+
     /// Initialize AdaGrad optimizer.
     ///
     /// # Returns
     ///
     /// Returns an optimizer that can be used to optimize a module.
-    pub fn init<B: AutodiffBackend, M: AutodiffModule<B>>(&self) -> impl Optimizer<M, B> {
-        let optim = AdaGrad {
-            lr_decay: LRDecay {
-                lr_decay: self.lr_decay,
-                epsilon: self.epsilon,
-            },
-            weight_decay: self.weight_decay.as_ref().map(WeightDecay::new),
-        };
-
-        let mut optim = OptimizerAdaptor::from(optim);
+    pub fn init<B: AutodiffBackend, M: AutodiffModule<B>>(
+        &self,
+    ) -> OptimizerAdaptor<AdaGrad<B::InnerBackend>, M, B> {
+        // The adaptor completes the decay toward the initial values, and applies the warmed up
+        // decay itself.
+        let weight_decay = self
+            .weight_decay
+            .as_ref()
+            .and_then(WeightDecayConfig::for_simple_optimizer);
+        let optim = self
+            .clone()
+            .with_weight_decay(weight_decay)
+            .init_simple::<B::InnerBackend>();
+
+        let mut optim = OptimizerAdaptor::from(optim)
+            .with_grad_scale(self.grad_scale)
+            .with_config(self);
         if let Some(config) = &self.grad_clipping {
             optim = optim.with_grad_clipping(config.init());
         }
+        if let Some(config) = &self.weight_decay {
+            optim = optim.with_weight_decay(config.clone());
+        }
+        if let Some(config) = self.weight_decay.as_ref().filter(|c| c.warmup_steps > 0) {
+            optim = optim.with_weight_decay_warmup(WeightDecayWarmup::new(config));
+        }
         optim
     }
 }
@@ -101,36 +205,183 @@ impl AdaGradConfig {
 pub struct LRDecayState<B: Backend, const D: usize> {
     time: usize,
     sum: Tensor<B, D>,
+    /// The sum accumulated in f32, when the [f32 accumulator](AdaGradConfig::f32_accumulator) is
+    /// enabled.
+    sum_f32: Option<DataSerialize<f32>>,
 }
 
-struct LRDecay {
+/// AdaGrad [transform](Transform), dividing the gradients by the square root of the sum of the
+/// squared gradients with a decaying learning rate.
+pub struct LRDecay {
     lr_decay: f64,
     epsilon: f32,
+    f32_accumulator: bool,
+    accumulator_max: Option<f32>,
+    initial_accumulator_value: f32,
 }
 
 impl LRDecay {
+    /// Transforms a gradient, the output is already scaled by the learning rate.
     pub fn transform<B: Backend, const D: usize>(
         &self,
         grad: Tensor<B, D>,
         lr: LearningRate,
         lr_decay_state: Option<LRDecayState<B, D>>,
     ) -> (Tensor<B, D>, LRDecayState<B, D>) {
-        let state = if let Some(mut state) = lr_decay_state {
-            state.sum = state.sum.add(grad.clone().powf(2.));
-            state.time += 1;
-            state
-        } else {
-            LRDecayState::new(1, grad.clone().powf(2.))
+        let mut state = match self.f32_accumulator {
+            true => self.accumulate_f32(&grad, lr_decay_state),
+            false => self.accumulate(&grad, lr_decay_state),
         };
 
-        let new_lr = lr / (1. + (state.time as f64 - 1.) * self.lr_decay);
+        if let Some(accumulator_max) = self.accumulator_max {
+            state.sum = state.sum.clamp_max(accumulator_max);
+        }
 
-        let grad = grad
-            .div(state.sum.clone().sqrt().add_scalar(self.epsilon))
-            .mul_scalar(new_lr);
+        let new_lr = self.decayed_lr(lr, &state);
+
+        let grad = grad.div(self.denominator(&state)).mul_scalar(new_lr);
 
         (grad, state)
     }
+
+    /// Add the squared gradient to the sum, in the float type of the backend.
+    fn accumulate<B: Backend, const D: usize>(
+        &self,
+        grad: &Tensor<B, D>,
+        state: Option<LRDecayState<B, D>>,
+    ) -> LRDecayState<B, D> {
+        let grad_squared = grad.clone().powf(2.);
+
+        match state {
+            Some(mut state) => {
+                state.sum = state.sum.add(grad_squared);
+                state.sum_f32 = None;
+                state.time += 1;
+                state
+            }
+            None => {
+                let mut sum = grad_squared;
+                if self.initial_accumulator_value != 0.0 {
+                    sum = sum.add_scalar(self.initial_accumulator_value);
+                }
+                LRDecayState::new(1, sum, None)
+            }
+        }
+    }
+
+    /// Add the squared gradient to the sum in f32, and round the sum to the float type of the
+    /// backend.
+    fn accumulate_f32<B: Backend, const D: usize>(
+        &self,
+        grad: &Tensor<B, D>,
+        state: Option<LRDecayState<B, D>>,
+    ) -> LRDecayState<B, D> {
+        let device = grad.device();
+        let grad = grad.to_data().convert::<f32>();
+        let (time, mut sum) = match state {
+            // The sum in f32 is missing when the accumulator was disabled or the state mapped, so
+            // it restarts from the rounded sum.
+            Some(state) => (
+                state.time + 1,
+                state
+                    .sum_f32
+                    .unwrap_or_else(|| state.sum.into_data().convert::<f32>().serialize()),
+            ),
+            None => (
+                1,
+                DataSerialize::new(
+                    alloc::vec![self.initial_accumulator_value; grad.value.len()],
+                    grad.shape.dims.to_vec(),
+                ),
+            ),
+        };
+
+        for (sum, grad) in sum.value.iter_mut().zip(grad.value) {
+            *sum += grad * grad;
+            if let Some(accumulator_max) = self.accumulator_max {
+                *sum = sum.min(accumulator_max);
+            }
+        }
+
+        let sum_rounded = Data::<f32, D>::from(sum.clone()).convert();
+        LRDecayState::new(
+            time,
+            Tensor::from_data_device(sum_rounded, &device),
+            Some(sum),
+        )
+    }
+
+    /// The learning rate decayed with the number of steps of the state.
+    fn decayed_lr<B: Backend, const D: usize>(
+        &self,
+        lr: LearningRate,
+        state: &LRDecayState<B, D>,
+    ) -> LearningRate {
+        lr / (1. + (state.time as f64 - 1.) * self.lr_decay)
+    }
+
+    /// The effective learning rate of each coordinate of the next update, i.e. the decayed
+    /// learning rate divided by the [denominator](Self::denominator).
+    pub fn effective_lr<B: Backend, const D: usize>(
+        &self,
+        lr: LearningRate,
+        state: &LRDecayState<B, D>,
+    ) -> Tensor<B, D> {
+        let denominator = self.denominator(state);
+        // The next update is decayed with the number of steps after the one of the state.
+        let decayed_lr = lr / (1. + state.time as f64 * self.lr_decay);
+
+        denominator
+            .ones_like()
+            .mul_scalar(decayed_lr)
+            .div(denominator)
+    }
+
+    /// The inverse of the adaptive rate of each coordinate.
+    pub(crate) fn denominator<B: Backend, const D: usize>(
+        &self,
+        state: &LRDecayState<B, D>,
+    ) -> Tensor<B, D> {
+        state.sum.clone().sqrt().add_scalar(self.epsilon)
+    }
+}
+
+impl<B: Backend> Transform<B> for LRDecay {
+    type State<const D: usize> = LRDecayState<B, D>;
+
+    fn apply<const D: usize>(
+        &self,
+        lr: LearningRate,
+        _tensor: &Tensor<B, D>,
+        grad: Tensor<B, D>,
+        state: Option<Self::State<D>>,
+    ) -> (Tensor<B, D>, Self::State<D>) {
+        self.transform(grad, lr, state)
+    }
+
+    fn to_device<const D: usize>(state: Self::State<D>, device: &B::Device) -> Self::State<D> {
+        state.to_device(device)
+    }
+
+    fn state_map_tensors<const D: usize, F>(mut state: Self::State<D>, func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        state.sum = func(state.sum);
+        // The mapped sum is rounded, the sum in f32 restarts from it.
+        state.sum_f32 = None;
+        state
+    }
+
+    fn state_num_elements<const D: usize>(state: &Self::State<D>) -> usize {
+        let sum_f32 = state.sum_f32.as_ref().map_or(0, |sum| sum.value.len());
+
+        state.sum.shape().num_elements() + sum_f32
+    }
+
+    fn state_num_steps<const D: usize>(state: &Self::State<D>) -> Option<usize> {
+        Some(state.time)
+    }
 }
 
 impl<B: Backend, const D: usize> LRDecayState<B, D> {
@@ -144,6 +395,7 @@ impl<B: Backend, const D: usize> LRDecayState<B, D> {
     ///
     /// Returns state moved to device.
     pub fn to_device(mut self, device: &B::Device) -> Self {
+        // The sum in f32 is kept on the host.
         self.sum = self.sum.to_device(device);
         self
     }
@@ -152,11 +404,14 @@ impl<B: Backend, const D: usize> LRDecayState<B, D> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::module::{Module, Param};
-    use crate::optim::{GradientsParams, Optimizer};
+    use crate::config::config_to_json;
+    use crate::grad_clipping::{GradientClipping, GradientClippingOrder};
+    use crate::module::{Module, Param, ParamId};
+    use crate::optim::{record::AdaptorRecord, GradientsParams, Optimizer, TransformOptimizer};
     use crate::record::{BinFileRecorder, FullPrecisionSettings, Recorder};
     use crate::tensor::{Data, Distribution, Tensor};
     use crate::{nn, TestAutodiffBackend, TestBackend};
+    use hashbrown::HashMap;
 
     const LEARNING_RATE: LearningRate = 0.01;
 
@@ -180,6 +435,428 @@ mod tests {
 
         assert_eq!(state_optim_before.len(), state_optim_after.len());
     }
+
+    #[test]
+    fn test_adagrad_optimizer_num_params_and_state_bytes() {
+        let linear = nn::LinearConfig::new(6, 4).init();
+        let x = Tensor::<TestAutodiffBackend, 2>::random([2, 6], Distribution::Default);
+        let mut optimizer = create_adagrad();
+        assert_eq!(optimizer.num_params(), 0);
+        assert_eq!(optimizer.state_bytes(), 0);
+
+        let grads = linear.forward(x).backward();
+        let grads = GradientsParams::from_grads(grads, &linear);
+        let _linear = optimizer.step(LEARNING_RATE, linear, grads);
+
+        // One `sum` tensor for the weight (6 x 4) and the bias (4) in f32.
+        assert_eq!(optimizer.num_params(), 2);
+        assert_eq!(optimizer.state_bytes(), (6 * 4 + 4) * 4);
+    }
+
+    #[test]
+    fn test_adagrad_to_device_moves_all_states() {
+        let linear = nn::LinearConfig::new(6, 4).init();
+        let x = Tensor::<TestAutodiffBackend, 2>::random([2, 6], Distribution::Default);
+        let mut optimizer = create_adagrad();
+        let grads = linear.forward(x).backward();
+        let grads = GradientsParams::from_grads(grads, &linear);
+        let linear = optimizer.step(LEARNING_RATE, linear, grads);
+
+        let device = Default::default();
+        let mut record = optimizer.to_device(&device).to_record().params;
+        assert_eq!(record.len(), 2);
+
+        let state: AdaGradState<TestBackend, 2> =
+            record.remove(&linear.weight.id).unwrap().into_state();
+        assert_eq!(state.lr_decay.sum.device(), device);
+        let state: AdaGradState<TestBackend, 1> = record
+            .remove(&linear.bias.as_ref().unwrap().id)
+            .unwrap()
+            .into_state();
+        assert_eq!(state.lr_decay.sum.device(), device);
+    }
+
+    #[test]
+    fn test_adagrad_export_state_tensors_matches_accumulator() {
+        let linear = nn::LinearConfig::new(6, 4).init();
+        let x = Tensor::<TestAutodiffBackend, 2>::random([2, 6], Distribution::Default);
+        let mut optimizer = create_adagrad();
+        let grads = linear.forward(x).backward();
+        let grads = GradientsParams::from_grads(grads, &linear);
+        let linear = optimizer.step(LEARNING_RATE, linear, grads);
+
+        let mut tensors = optimizer.export_state_tensors();
+        assert_eq!(tensors.len(), 2);
+
+        // The state of AdaGrad is the sum of the squared gradients, flattened with its shape.
+        let mut exported = tensors.remove(&linear.weight.id).unwrap();
+        let state: AdaGradState<TestBackend, 2> = optimizer
+            .to_record()
+            .params
+            .remove(&linear.weight.id)
+            .unwrap()
+            .into_state();
+        assert_eq!(exported.len(), 1);
+        let exported = exported.remove(0);
+        assert_eq!(exported.shape, [6, 4]);
+        exported
+            .into_shaped::<2>()
+            .to_data()
+            .assert_approx_eq(&state.lr_decay.sum.into_data(), 6);
+    }
+
+    #[test]
+    fn test_adagrad_skips_step_with_extreme_gradient_norm() {
+        let linear = nn::LinearConfig::new(6, 6).init();
+        let x = Tensor::<TestAutodiffBackend, 2>::random([2, 6], Distribution::Default);
+        let mut optimizer = create_adagrad().with_skip_threshold(1000.0);
+        let grads = linear.forward(x.clone()).backward();
+        let grads = GradientsParams::from_grads(grads, &linear);
+        let linear = optimizer.step(LEARNING_RATE, linear, grads);
+        assert_eq!(optimizer.to_record().summary().num_steps, Some(1));
+
+        let weight = linear.weight.to_data();
+        let grads = linear.forward(x).mul_scalar(1e6).backward();
+        let grads = GradientsParams::from_grads(grads, &linear);
+        let linear = optimizer.step(LEARNING_RATE, linear, grads);
+
+        linear.weight.to_data().assert_approx_eq(&weight, 6);
+        assert_eq!(optimizer.to_record().summary().num_steps, Some(1));
+        assert!(optimizer.last_updated().is_empty());
+    }
+
+    #[test]
+    fn test_adagrad_clone_state_to_wider_linear() {
+        let linear = nn::LinearConfig::new(6, 6).init();
+        let x = Tensor::<TestAutodiffBackend, 2>::random([2, 6], Distribution::Default);
+        let mut optimizer = create_adagrad();
+        let grads = linear.forward(x).backward();
+        let grads = GradientsParams::from_grads(grads, &linear);
+        let linear = optimizer.step(LEARNING_RATE, linear, grads);
+
+        let linear_wide = nn::LinearConfig::new(8, 8).init::<TestAutodiffBackend>();
+        let weight_id = linear_wide.weight.id.clone();
+        let ids = HashMap::from([
+            (linear.weight.id.clone(), weight_id.clone()),
+            (
+                linear.bias.as_ref().unwrap().id.clone(),
+                linear_wide.bias.as_ref().unwrap().id.clone(),
+            ),
+        ]);
+        let optimizer_wide = optimizer.clone_state_to(create_adagrad(), &linear_wide, &ids, true);
+        assert_eq!(optimizer_wide.num_params(), 2);
+
+        let sum = |mut record: HashMap<_, AdaptorRecord<AdaGrad<TestBackend>, TestBackend>>,
+                   id: &ParamId| {
+            let state: AdaGradState<TestBackend, 2> = record.remove(id).unwrap().into_state();
+            state.lr_decay.sum
+        };
+        let sum_old = sum(optimizer.to_record().params, &linear.weight.id);
+        let sum_new = sum(optimizer_wide.to_record().params, &weight_id);
+
+        assert_eq!(sum_new.dims(), [8, 8]);
+        sum_new
+            .clone()
+            .slice([0..6, 0..6])
+            .to_data()
+            .assert_approx_eq(&sum_old.to_data(), 5);
+        sum_new
+            .clone()
+            .slice([6..8, 0..8])
+            .to_data()
+            .assert_approx_eq(&Tensor::<TestBackend, 2>::zeros([2, 8]).to_data(), 5);
+        sum_new
+            .slice([0..8, 6..8])
+            .to_data()
+            .assert_approx_eq(&Tensor::<TestBackend, 2>::zeros([8, 2]).to_data(), 5);
+    }
+
+    #[test]
+    fn test_adagrad_step_tensors_toward_target() {
+        let optim = AdaGradConfig::new().init_simple::<TestBackend>();
+        let target = Tensor::<TestBackend, 1>::from_floats([1.0, -2.0, 0.5]);
+        let mut tensors = vec![Tensor::<TestBackend, 1>::zeros([3])];
+        let mut states = Vec::new();
+        let distance =
+            |tensor: &Tensor<TestBackend, 1>| tensor.clone().sub(target.clone()).abs().sum();
+        let distance_initial = distance(&tensors[0]).into_scalar();
+
+        for _ in 0..100 {
+            // Gradient of the squared distance to the target.
+            let grads = vec![tensors[0].clone().sub(target.clone()).mul_scalar(2.0)];
+            (tensors, states) = crate::optim::step_tensors(&optim, 0.5, tensors, grads, states);
+        }
+
+        assert_eq!(states.len(), 1);
+        assert!(distance(&tensors[0]).into_scalar() < distance_initial / 10.0);
+    }
+
+    #[test]
+    fn test_adagrad_f32_accumulator_stores_the_sum_in_f32() {
+        let lr_decay = AdaGradConfig::new()
+            .with_f32_accumulator(Some(true))
+            .with_initial_accumulator_value(1.0)
+            .init_lr_decay::<TestBackend>();
+        let grad = || Tensor::<TestBackend, 1>::from_floats([1e-4, 2.0]);
+
+        let (_, mut state) = lr_decay.transform(grad(), LEARNING_RATE, None);
+        for _ in 0..999 {
+            (_, state) = lr_decay.transform(grad(), LEARNING_RATE, Some(state));
+        }
+
+        // The same additions in f32.
+        let mut expected = [1.0f32, 1.0];
+        for _ in 0..1000 {
+            expected[0] += 1e-4 * 1e-4;
+            expected[1] += 2.0 * 2.0;
+        }
+        assert_eq!(state.sum_f32.unwrap().value, expected.to_vec());
+        state
+            .sum
+            .into_data()
+            .assert_approx_eq(&Data::from(expected), 6);
+    }
+
+    #[test]
+    fn test_adagrad_f32_accumulator_matches_the_update_in_f32() {
+        let step = |f32_accumulator: bool| {
+            let optim = AdaGradConfig::new()
+                .with_f32_accumulator(Some(f32_accumulator))
+                .with_lr_decay(0.1)
+                .init_simple::<TestBackend>();
+            let mut tensor = Tensor::<TestBackend, 2>::ones([2, 3]);
+            let mut state = None;
+            for step in 1..4 {
+                let grad =
+                    Tensor::<TestBackend, 2>::from_floats([[0.1, -0.2, 0.3], [1.0, 2.0, -3.0]])
+                        .mul_scalar(step);
+                (tensor, state) = optim.step(LEARNING_RATE, tensor, grad, state);
+            }
+            tensor.into_data()
+        };
+
+        // The backend is in f32, so both accumulators lead to the same update.
+        step(true).assert_approx_eq(&step(false), 6);
+    }
+
+    #[cfg(feature = "candle")]
+    #[test]
+    fn test_adagrad_f32_accumulator_is_closer_to_reference_in_bf16() {
+        use burn_candle::Candle;
+        use burn_tensor::ElementConversion;
+
+        type B = Candle<half::bf16>;
+
+        let grad = || Tensor::<B, 1>::ones([1]).mul_scalar(0.03);
+        let grad_squared = grad().into_scalar().elem::<f64>().powi(2);
+        let sum = |f32_accumulator: bool| {
+            let lr_decay = AdaGradConfig::new()
+                .with_f32_accumulator(Some(f32_accumulator))
+                .init_lr_decay::<B>();
+            let (_, mut state) = lr_decay.transform(Tensor::<B, 1>::ones([1]), LEARNING_RATE, None);
+            for _ in 0..100 {
+                (_, state) = lr_decay.transform(grad(), LEARNING_RATE, Some(state));
+            }
+            state.sum.into_scalar().elem::<f64>()
+        };
+        // Each squared gradient is below half of the bf16 spacing around 1.0, so it's lost when
+        // added to the sum in bf16.
+        let reference = 1.0 + 100.0 * grad_squared;
+
+        let error_bf16 = (sum(false) - reference).abs();
+        let error_f32 = (sum(true) - reference).abs();
+
+        assert!(error_f32 < error_bf16 / 5.0);
+        // The f32 accumulator is the default in bf16.
+        assert!(AdaGradConfig::new().init_lr_decay::<B>().f32_accumulator);
+    }
+
+    #[test]
+    fn test_adagrad_effective_lr_stats_below_nominal_lr() {
+        let mut linear = nn::LinearConfig::new(6, 4).init();
+        let x = Tensor::<TestAutodiffBackend, 2>::ones([2, 6]);
+        let mut optimizer = create_adagrad();
+        assert!(optimizer.effective_lr_stats(LEARNING_RATE).is_empty());
+
+        for _ in 0..5 {
+            let grads = linear.forward(x.clone()).backward();
+            let grads = GradientsParams::from_grads(grads, &linear);
+            linear = optimizer.step(LEARNING_RATE, linear, grads);
+        }
+
+        // Each gradient of the weight is the batch size, so the sum of the squared gradients is
+        // 5 * 2^2 after five steps, and the effective learning rate is `lr / sqrt(20)`.
+        let stats = optimizer.effective_lr_stats(LEARNING_RATE);
+        let stats_weight = stats.get(&linear.weight.id).unwrap();
+        let expected = LEARNING_RATE / (20.0f64.sqrt() + 1e-5);
+        assert_eq!(stats.len(), 2);
+        for value in [stats_weight.min, stats_weight.mean, stats_weight.max] {
+            assert!((value - expected).abs() < 1e-6);
+        }
+        assert!(stats_weight.max < LEARNING_RATE);
+    }
+
+    #[test]
+    fn test_adagrad_effective_lr_is_decayed_for_the_next_update() {
+        let lr_decay = AdaGradConfig::new()
+            .with_lr_decay(0.1)
+            .with_epsilon(1e-8)
+            .init_lr_decay::<TestBackend>();
+        let grad = Tensor::<TestBackend, 1>::from_floats([2.0]);
+        let (_, mut state) = lr_decay.transform(grad.clone(), LEARNING_RATE, None);
+        for _ in 1..5 {
+            (_, state) = lr_decay.transform(grad.clone(), LEARNING_RATE, Some(state));
+        }
+
+        // The sum is 5 * 2^2 after five steps, and the next update is decayed by 1 + 5 * 0.1.
+        let effective_lr = lr_decay
+            .effective_lr(LEARNING_RATE, &state)
+            .into_data()
+            .value[0];
+        let expected = LEARNING_RATE / 1.5 / 20.0f64.sqrt();
+        assert!((effective_lr as f64 - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_adagrad_accumulator_max_retains_effective_lr() {
+        let update = |accumulator_max: Option<f32>| {
+            let lr_decay = AdaGradConfig::new()
+                .with_epsilon(1e-8)
+                .with_accumulator_max(accumulator_max)
+                .init_lr_decay::<TestBackend>();
+            // The first coordinate is hit at every step, the second one never.
+            let grad = Tensor::<TestBackend, 1>::from_floats([1.0, 0.0]);
+            let (mut update, mut state) = lr_decay.transform(grad.clone(), LEARNING_RATE, None);
+            for _ in 1..100 {
+                (update, state) = lr_decay.transform(grad.clone(), LEARNING_RATE, Some(state));
+            }
+            update.into_data().value[0] as f64
+        };
+
+        let update_unbounded = update(None);
+        let update_bounded = update(Some(4.0));
+
+        assert!((update_unbounded - LEARNING_RATE / 10.0).abs() < 1e-6);
+        assert!((update_bounded - LEARNING_RATE / 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_adagrad_initial_accumulator_value_damps_first_update() {
+        let update = |initial_accumulator_value: f32| {
+            let lr_decay = AdaGradConfig::new()
+                .with_epsilon(1e-8)
+                .with_initial_accumulator_value(initial_accumulator_value)
+                .init_lr_decay::<TestBackend>();
+            let grad = Tensor::<TestBackend, 1>::from_floats([3.0, 0.1]);
+            let (update, state) = lr_decay.transform(grad, LEARNING_RATE, None);
+            (update.into_data().value, state.sum.into_data().value)
+        };
+
+        let (update_default, sum_default) = update(0.0);
+        let (update_seeded, sum_seeded) = update(16.0);
+
+        assert!((sum_default[0] - 9.0).abs() < 1e-4);
+        assert!((sum_seeded[0] - 25.0).abs() < 1e-4);
+
+        // Without seeding, the first update is `lr * sign(grad)` whatever the gradient scale.
+        assert!((update_default[0] as f64 - LEARNING_RATE).abs() < 1e-6);
+        assert!((update_default[1] as f64 - LEARNING_RATE).abs() < 1e-6);
+        // With seeding, `lr * grad / sqrt(grad^2 + 16)`.
+        assert!((update_seeded[0] as f64 - LEARNING_RATE * 3.0 / 5.0).abs() < 1e-6);
+        assert!(update_seeded[1] < update_default[1] / 10.0);
+    }
+
+    #[test]
+    fn test_adagrad_grad_clipping_order() {
+        type B = TestAutodiffBackend;
+        type M = nn::Linear<TestAutodiffBackend>;
+
+        let weight_decay_config = WeightDecayConfig::new(0.5);
+        let config = AdaGradConfig::new()
+            .with_weight_decay(Some(weight_decay_config.clone()))
+            .with_grad_clipping(Some(GradientClippingConfig::Value(0.1)));
+        let linear = nn::LinearConfig::new(6, 6).init::<TestAutodiffBackend>();
+        let x = Tensor::<TestAutodiffBackend, 2>::random([2, 6], Distribution::Default);
+        let weight_decay = || WeightDecay::<TestBackend>::new(&weight_decay_config);
+        let clipping = || GradientClipping::Value(0.1);
+        let lr_decay = || config.init_lr_decay::<TestBackend>();
+
+        let before = train_with_clipping(config.init::<B, M>(), &linear, &x);
+        let before_expected = train_with_clipping(
+            OptimizerAdaptor::from(TransformOptimizer::new(
+                <GradientClipping as Transform<TestBackend>>::chain(clipping(), weight_decay())
+                    .chain(lr_decay()),
+            )),
+            &linear,
+            &x,
+        );
+        let after = train_with_clipping(
+            OptimizerAdaptor::from(config.init_simple::<TestBackend>())
+                .with_grad_clipping(clipping())
+                .with_weight_decay(weight_decay_config.clone())
+                .with_grad_clipping_order(GradientClippingOrder::AfterWeightDecay),
+            &linear,
+            &x,
+        );
+        let after_expected = train_with_clipping(
+            OptimizerAdaptor::from(TransformOptimizer::new(
+                weight_decay().chain(clipping()).chain(lr_decay()),
+            )),
+            &linear,
+            &x,
+        );
+
+        before.assert_approx_eq(&before_expected, 5);
+        after.assert_approx_eq(&after_expected, 5);
+        assert_ne!(before, after);
+    }
+
+    fn train_with_clipping<O: Optimizer<nn::Linear<TestAutodiffBackend>, TestAutodiffBackend>>(
+        mut optim: O,
+        linear: &nn::Linear<TestAutodiffBackend>,
+        x: &Tensor<TestAutodiffBackend, 2>,
+    ) -> Data<f32, 2> {
+        let mut linear = linear.clone();
+        // Large gradients, so the clipping matters.
+        for _ in 0..3 {
+            let grads = linear.forward(x.clone()).mul_scalar(10.0).backward();
+            let grads = GradientsParams::from_grads(grads, &linear);
+            linear = optim.step(LEARNING_RATE, linear, grads);
+        }
+        linear.weight.to_data()
+    }
+
+    #[test]
+    fn test_adagrad_config_json_round_trips() {
+        let config = AdaGradConfig::new()
+            .with_lr_decay(0.5)
+            .with_accumulator_max(Some(10.0))
+            .with_weight_decay(Some(WeightDecayConfig::new(0.05)))
+            .with_grad_clipping(Some(GradientClippingConfig::Norm(1.0)));
+        let optim = config.init::<TestAutodiffBackend, nn::Linear<TestAutodiffBackend>>();
+
+        let json = optim.config_json().unwrap();
+        let config_loaded = AdaGradConfig::load_binary(json.as_bytes()).unwrap();
+
+        assert_eq!(config_to_json(&config_loaded), config_to_json(&config));
+        assert_eq!(config_loaded.lr_decay, 0.5);
+        assert_eq!(config_loaded.accumulator_max, Some(10.0));
+    }
+
+    #[test]
+    fn test_adagrad_f32_accumulator_defaults_to_half_precision_only() {
+        let optim =
+            AdaGradConfig::new().init::<TestAutodiffBackend, nn::Linear<TestAutodiffBackend>>();
+        let linear = nn::LinearConfig::new(2, 2).init();
+        let x = Tensor::<TestAutodiffBackend, 2>::random([1, 2], Distribution::Default);
+        let mut optim = optim;
+        let grads = GradientsParams::from_grads(linear.forward(x).backward(), &linear);
+        let _linear = optim.step(LEARNING_RATE, linear, grads);
+
+        // f32 backend: only the `sum` state of the weight (2 x 2) and the bias (2).
+        assert_eq!(optim.state_bytes(), (2 * 2 + 2) * 4);
+    }
+
     const ASSERT_PRECISION: usize = 6;
 
     #[test]
@@ -269,6 +946,9 @@ mod tests {
             lr_decay: LRDecay {
                 lr_decay: config.lr_decay,
                 epsilon: config.epsilon,
+                f32_accumulator: config.f32_accumulator.unwrap_or(false),
+                accumulator_max: config.accumulator_max,
+                initial_accumulator_value: config.initial_accumulator_value,
             },
             weight_decay: config.weight_decay.as_ref().map(WeightDecay::new),
         }
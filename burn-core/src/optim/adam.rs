@@ -3,11 +3,13 @@ use crate::{
     LearningRate,
 };
 
+use super::validation::{validate_beta, validate_positive};
 use super::{
-    decay::{WeightDecay, WeightDecayConfig},
-    Optimizer, SimpleOptimizer,
+    decay::{WeightDecay, WeightDecayConfig, WeightDecayWarmup},
+    momentum::{MomentumConfig, MomentumState},
+    Sgd, SgdState, SimpleOptimizer, StateConversion,
 };
-use crate::config::Config;
+use crate::config::{Config, ConfigError, ValidateConfig};
 use crate::optim::adaptor::OptimizerAdaptor;
 use crate::tensor::{backend::AutodiffBackend, Tensor};
 use burn_tensor::{backend::Backend, ElementConversion};
@@ -28,6 +30,9 @@ pub struct AdamConfig {
     weight_decay: Option<WeightDecayConfig>,
     /// [Gradient Clipping](GradientClippingConfig) config.
     grad_clipping: Option<GradientClippingConfig>,
+    /// [Gradient scale](crate::optim::adaptor::OptimizerAdaptor::with_grad_scale) of the adaptor.
+    #[config(default = 1.0)]
+    grad_scale: f32,
 }
 
 /// Adam optimizer as described in the paper [Adam: A Method for Stochastic Optimization](https://arxiv.org/pdf/1412.6980.pdf).
@@ -39,7 +44,7 @@ pub struct Adam<B: Backend> {
 /// Adam state.
 #[derive(Record, Clone, new)]
 pub struct AdamState<B: Backend, const D: usize> {
-    momentum: AdaptiveMomentumState<B, D>,
+    pub(crate) momentum: AdaptiveMomentumState<B, D>,
 }
 
 impl<B: Backend> SimpleOptimizer<B> for Adam<B> {
@@ -66,8 +71,12 @@ impl<B: Backend> SimpleOptimizer<B> for Adam<B> {
 
         let state = AdamState::new(state_momentum);
         let delta = grad.mul_scalar(lr);
+        let mut tensor = tensor - delta;
+        if let Some(weight_decay) = &self.weight_decay {
+            tensor = weight_decay.proximal(lr, tensor);
+        }
 
-        (tensor - delta, Some(state))
+        (tensor, Some(state))
     }
 
     fn to_device<const D: usize>(
@@ -77,38 +86,148 @@ impl<B: Backend> SimpleOptimizer<B> for Adam<B> {
         state.momentum = state.momentum.to_device(device);
         state
     }
+
+    fn state_map_tensors<const D: usize, F>(mut state: Self::State<D>, func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        state.momentum.moment_1 = func(state.momentum.moment_1);
+        state.momentum.moment_2 = func(state.momentum.moment_2);
+        state
+    }
+
+    fn state_num_elements<const D: usize>(state: &Self::State<D>) -> usize {
+        state.momentum.moment_1.shape().num_elements()
+            + state.momentum.moment_2.shape().num_elements()
+    }
+
+    fn state_num_steps<const D: usize>(state: &Self::State<D>) -> Option<usize> {
+        Some(state.momentum.time)
+    }
+
+    fn state_effective_lr<const D: usize>(
+        &self,
+        lr: LearningRate,
+        state: &Self::State<D>,
+    ) -> Option<Tensor<B, D>> {
+        let momentum = &state.momentum;
+        Some(adaptive_effective_lr(
+            lr,
+            &momentum.moment_2,
+            self.momentum.beta_2,
+            self.momentum.epsilon,
+            momentum.time,
+        ))
+    }
+}
+
+impl ValidateConfig for AdamConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        validate_beta("beta_1", self.beta_1)?;
+        validate_beta("beta_2", self.beta_2)?;
+        validate_positive("epsilon", self.epsilon)?;
+        if let Some(weight_decay) = &self.weight_decay {
+            weight_decay.validate()?;
+        }
+        if let Some(clipping) = &self.grad_clipping {
+            clipping.validate()?;
+        }
+
+        Ok(())
+    }
 }
 
 impl AdamConfig {
-    /// Initialize Adam optimizer.
-    ///
-    /// # Returns
+    /// Initialize Adam as a [simple optimizer](SimpleOptimizer), to optimize tensors directly
+    /// with [step_tensors](crate::optim::step_tensors).
     ///
-    /// Returns an optimizer that can be used to optimize a module.
-    pub fn init<B: AutodiffBackend, M: AutodiffModule<B>>(&self) -> impl Optimizer<M, B> {
-        let optim = Adam {
+    /// The gradients are neither clipped nor unscaled. The update is invariant to the scale of
+    /// the gradients except for `epsilon`, which becomes relatively smaller with a larger scale.
+    pub fn init_simple<B: Backend>(&self) -> Adam<B> {
+        Adam {
             momentum: AdaptiveMomentum {
                 beta_1: self.beta_1,
                 beta_2: self.beta_2,
                 epsilon: self.epsilon,
             },
             weight_decay: self.weight_decay.as_ref().map(WeightDecay::new),
-        };
+        }
+    }
 
-        let mut optim = OptimizerAdaptor::from(optim);
+    /// Initialize Adam optimizer.
+    ///
+    /// # Returns
+    ///
+    /// Returns an optimizer that can be used to optimize a module.
+    pub fn init<B: AutodiffBackend, M: AutodiffModule<B>>(
+        &self,
+    ) -> OptimizerAdaptor<Adam<B::InnerBackend>, M, B> {
+        // The adaptor completes the decay toward the initial values, and applies the warmed up
+        // decay itself.
+        let weight_decay = self
+            .weight_decay
+            .as_ref()
+            .and_then(WeightDecayConfig::for_simple_optimizer);
+        let optim = self
+            .clone()
+            .with_weight_decay(weight_decay)
+            .init_simple::<B::InnerBackend>();
+        let mut optim = OptimizerAdaptor::from(optim)
+            .with_grad_scale(self.grad_scale)
+            .with_config(self);
         if let Some(config) = &self.grad_clipping {
             optim = optim.with_grad_clipping(config.init());
         }
+        if let Some(config) = &self.weight_decay {
+            optim = optim.with_weight_decay(config.clone());
+        }
+        if let Some(config) = self.weight_decay.as_ref().filter(|c| c.warmup_steps > 0) {
+            optim = optim.with_weight_decay_warmup(WeightDecayWarmup::new(config));
+        }
         optim
     }
 }
 
+/// [State conversion](StateConversion) warm-starting the momentum of [SGD](Sgd) from the first
+/// moment of Adam, so the momentum isn't lost when switching from Adam to SGD during training.
+///
+/// The velocity is the bias-corrected first moment, an average of the gradients, scaled by
+/// `(1 - dampening) / (1 - momentum)`, the ratio between the velocity of SGD and the average of
+/// the gradients when the gradients are constant.
+pub struct AdamToSgdMomentum {
+    beta_1: f32,
+    scale: f64,
+}
+
+impl AdamToSgdMomentum {
+    /// Create the conversion from the config of Adam and the momentum config of SGD.
+    pub fn new(adam: &AdamConfig, momentum: &MomentumConfig) -> Self {
+        Self {
+            beta_1: adam.beta_1,
+            scale: (1.0 - momentum.dampening) / (1.0 - momentum.momentum),
+        }
+    }
+}
+
+impl<B: Backend> StateConversion<B, Adam<B>, Sgd<B>> for AdamToSgdMomentum {
+    fn convert<const D: usize>(&self, state: AdamState<B, D>) -> SgdState<B, D> {
+        let state = state.momentum;
+        let bias_correction = 1.0 - self.beta_1.powi(state.time as i32);
+        let velocity = state
+            .moment_1
+            .div_scalar(bias_correction)
+            .mul_scalar(self.scale);
+
+        SgdState::new(Some(MomentumState::new(velocity)))
+    }
+}
+
 /// Adaptive momentum state.
 #[derive(Record, new, Clone)]
 pub struct AdaptiveMomentumState<B: Backend, const D: usize> {
-    time: usize,
-    moment_1: Tensor<B, D>,
-    moment_2: Tensor<B, D>,
+    pub(crate) time: usize,
+    pub(crate) moment_1: Tensor<B, D>,
+    pub(crate) moment_2: Tensor<B, D>,
 }
 
 struct AdaptiveMomentum {
@@ -165,6 +284,25 @@ impl AdaptiveMomentum {
     }
 }
 
+/// The effective learning rate of each coordinate of the adaptive moment optimizers, i.e. the
+/// learning rate divided by the denominator of the last update, `sqrt(moment_2_corrected) +
+/// epsilon`, which is applied to the bias-corrected first moment.
+pub(crate) fn adaptive_effective_lr<B: Backend, const D: usize>(
+    lr: LearningRate,
+    moment_2: &Tensor<B, D>,
+    beta_2: f32,
+    epsilon: f32,
+    time: usize,
+) -> Tensor<B, D> {
+    let denominator = moment_2
+        .clone()
+        .div_scalar(1f32 - beta_2.powi(time as i32))
+        .sqrt()
+        .add_scalar(epsilon);
+
+    denominator.ones_like().mul_scalar(lr).div(denominator)
+}
+
 impl<B: Backend, const D: usize> AdaptiveMomentumState<B, D> {
     /// Move state to device.
     ///
@@ -185,9 +323,13 @@ impl<B: Backend, const D: usize> AdaptiveMomentumState<B, D> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::module::{Module, Param};
-    use crate::optim::{GradientsParams, Optimizer};
-    use crate::record::{BinFileRecorder, FullPrecisionSettings, Recorder};
+    use crate::module::{Module, Param, ParamId};
+    use crate::optim::{
+        adaptor::OptimizerAdaptorRecord, record::AdaptorRecord, GradientsParams, Optimizer,
+    };
+    use crate::record::{
+        BinFileRecorder, FullPrecisionSettings, QuantizedPrecisionSettings, Recorder,
+    };
     use crate::tensor::{Data, Distribution, Tensor};
     use crate::{nn, TestAutodiffBackend, TestBackend};
 
@@ -213,6 +355,77 @@ mod tests {
 
         assert_eq!(state_optim_before.len(), state_optim_after.len());
     }
+
+    #[test]
+    fn test_adam_optimizer_quantized_state_record() {
+        let linear = nn::LinearConfig::new(32, 32).init();
+        let weight_id = linear.weight.id.clone();
+        let x = Tensor::<TestAutodiffBackend, 2>::random([2, 32], Distribution::Default);
+        let mut optimizer = create_adam();
+        let grads = linear.forward(x).backward();
+        let grads = GradientsParams::from_grads(grads, &linear);
+        let linear = optimizer.step(LEARNING_RATE, linear, grads);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path_full = dir.path().join("full");
+        let path_quantized = dir.path().join("quantized");
+        let recorder_full = BinFileRecorder::<FullPrecisionSettings>::default();
+        let recorder_quantized = BinFileRecorder::<QuantizedPrecisionSettings>::default();
+        recorder_full
+            .record(optimizer.to_record(), path_full.clone())
+            .unwrap();
+        recorder_quantized
+            .record(optimizer.to_record(), path_quantized.clone())
+            .unwrap();
+
+        let size_full = std::fs::metadata(path_full.with_extension("bin"))
+            .unwrap()
+            .len();
+        let size_quantized = std::fs::metadata(path_quantized.with_extension("bin"))
+            .unwrap()
+            .len();
+        assert!(size_quantized * 3 < size_full);
+
+        // The relative error is bounded for the small values as well as the large ones.
+        let expected = moment_2(optimizer.to_record(), &weight_id);
+        let actual = moment_2(
+            recorder_quantized.load(path_quantized.clone()).unwrap(),
+            &weight_id,
+        );
+        for (expected, actual) in expected.value.iter().zip(actual.value.iter()) {
+            assert!((expected - actual).abs() <= expected.abs() * 0.1);
+        }
+
+        // Resuming from the quantized state steps close to resuming from the full state.
+        let x = Tensor::<TestAutodiffBackend, 2>::random([2, 32], Distribution::Default);
+        let grads = || GradientsParams::from_grads(linear.forward(x.clone()).backward(), &linear);
+        let mut optimizer_full = create_adam().load_record(recorder_full.load(path_full).unwrap());
+        let mut optimizer_quantized =
+            create_adam().load_record(recorder_quantized.load(path_quantized).unwrap());
+        let linear_full = optimizer_full.step(LEARNING_RATE, linear.clone(), grads());
+        let linear_quantized = optimizer_quantized.step(LEARNING_RATE, linear.clone(), grads());
+
+        let weight_full = linear_full.weight.val().into_data();
+        let weight_quantized = linear_quantized.weight.val().into_data();
+        for (full, quantized) in weight_full.value.iter().zip(weight_quantized.value.iter()) {
+            assert!((full - quantized).abs() <= LEARNING_RATE as f32 * 0.2);
+        }
+    }
+
+    #[test]
+    fn test_quantized_settings_keep_module_params_exact() {
+        let linear: nn::Linear<TestBackend> = nn::LinearConfig::new(32, 32).init();
+        let weight = linear.weight.val().into_data();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("module");
+        let recorder = BinFileRecorder::<QuantizedPrecisionSettings>::default();
+        recorder.record(linear.into_record(), path.clone()).unwrap();
+        let record: nn::LinearRecord<TestBackend> = recorder.load(path).unwrap();
+
+        assert_eq!(record.weight.val().into_data(), weight);
+    }
+
     const ASSERT_PRECISION: usize = 2;
 
     #[test]
@@ -284,6 +497,25 @@ mod tests {
         weight_updated.assert_approx_eq(&weights_expected, ASSERT_PRECISION);
     }
 
+    #[test]
+    fn test_adam_effective_lr_uses_the_corrected_second_moment() {
+        let optimizer = AdamConfig::new()
+            .with_epsilon(1e-8)
+            .init_simple::<TestBackend>();
+        let tensor = Tensor::<TestBackend, 1>::from_floats([1.0, -2.0]);
+        let grad = Tensor::from_floats([0.5, -4.0]);
+        let (_, state) = optimizer.step(LEARNING_RATE, tensor, grad, None);
+
+        // After one step, the bias-corrected second moment is the squared gradient.
+        let effective_lr = optimizer
+            .state_effective_lr(LEARNING_RATE, &state.unwrap())
+            .unwrap();
+        effective_lr.to_data().assert_approx_eq(
+            &Data::from([(LEARNING_RATE / 0.5) as f32, (LEARNING_RATE / 4.0) as f32]),
+            5,
+        );
+    }
+
     #[test]
     fn test_adam_optimizer_no_nan() {
         let linear = given_linear_layer(
@@ -323,6 +555,74 @@ mod tests {
         assert!(!state_updated.weight.to_data().value[0].is_nan());
     }
 
+    #[test]
+    fn test_adam_config_from_flat() {
+        let values = std::collections::HashMap::from([
+            ("beta_1".to_string(), 0.8),
+            ("beta_2".to_string(), 0.99),
+            ("epsilon".to_string(), 1e-8),
+        ]);
+
+        let config = AdamConfig::from_flat(&values).unwrap();
+
+        assert_eq!(config.beta_1, 0.8);
+        assert_eq!(config.beta_2, 0.99);
+        assert_eq!(config.epsilon, 1e-8);
+        assert!(config.weight_decay.is_none());
+        assert_eq!(config.grad_scale, 1.0);
+
+        let values = std::collections::HashMap::from([("beta_3".to_string(), 0.9)]);
+        let err = AdamConfig::from_flat(&values).unwrap_err();
+        assert!(matches!(err, crate::config::ConfigError::UnknownKey(key) if key == "beta_3"));
+
+        // The weight decay is disabled by default, so its penalty isn't a known key.
+        let values = std::collections::HashMap::from([("weight_decay.penalty".to_string(), 0.1)]);
+        let err = AdamConfig::from_flat(&values).unwrap_err();
+        assert!(
+            matches!(err, crate::config::ConfigError::UnknownKey(key) if key == "weight_decay.penalty")
+        );
+
+        let values = std::collections::HashMap::from([("epsilon".to_string(), f64::NAN)]);
+        let err = AdamConfig::from_flat(&values).unwrap_err();
+        assert!(matches!(
+            err,
+            crate::config::ConfigError::InvalidValue { key, value } if key == "epsilon" && value.is_nan()
+        ));
+    }
+
+    #[test]
+    fn test_adam_config_validate() {
+        let invalid_value = |config: AdamConfig| match config.validate() {
+            Err(crate::config::ConfigError::OutOfRange(message)) => message,
+            result => panic!("Expected an invalid value, got {result:?}"),
+        };
+
+        assert!(AdamConfig::new().validate().is_ok());
+        assert!(AdamConfig::new().with_beta_1(0.0).validate().is_ok());
+        assert_eq!(
+            invalid_value(AdamConfig::new().with_beta_1(1.0)),
+            "beta_1 must be in [0, 1), got 1."
+        );
+        assert_eq!(
+            invalid_value(AdamConfig::new().with_beta_2(-0.1)),
+            "beta_2 must be in [0, 1), got -0.1."
+        );
+        assert_eq!(
+            invalid_value(AdamConfig::new().with_epsilon(0.0)),
+            "epsilon must be positive, got 0."
+        );
+        assert_eq!(
+            invalid_value(AdamConfig::new().with_weight_decay(Some(WeightDecayConfig::new(-0.1)))),
+            "weight_decay.penalty must be non-negative, got -0.1."
+        );
+        assert_eq!(
+            invalid_value(
+                AdamConfig::new().with_grad_clipping(Some(GradientClippingConfig::Norm(0.0)))
+            ),
+            "The gradient clipping threshold must be positive, got 0."
+        );
+    }
+
     fn given_linear_layer(
         weight: Data<f32, 2>,
         bias: Data<f32, 1>,
@@ -335,6 +635,17 @@ mod tests {
         nn::LinearConfig::new(6, 6).init_with(record)
     }
 
+    fn moment_2(
+        mut record: OptimizerAdaptorRecord<
+            AdaptorRecord<Adam<TestBackend>, TestBackend>,
+            TestBackend,
+        >,
+        id: &ParamId,
+    ) -> Data<f32, 2> {
+        let state: AdamState<TestBackend, 2> = record.params.remove(id).unwrap().into_state();
+        state.momentum.moment_2.into_data()
+    }
+
     fn create_adam(
     ) -> OptimizerAdaptor<Adam<TestBackend>, nn::Linear<TestAutodiffBackend>, TestAutodiffBackend>
     {
@@ -2,10 +2,11 @@ use crate::{
     self as burn, grad_clipping::GradientClippingConfig, module::AutodiffModule, record::Record,
     LearningRate,
 };
-use std::marker::PhantomData;
+use core::marker::PhantomData;
 
-use super::{Optimizer, SimpleOptimizer};
-use crate::config::Config;
+use super::validation::{validate_beta, validate_non_negative, validate_positive};
+use super::{adam::adaptive_effective_lr, SimpleOptimizer};
+use crate::config::{Config, ConfigError, ValidateConfig};
 use crate::optim::adaptor::OptimizerAdaptor;
 use crate::tensor::{backend::AutodiffBackend, Tensor};
 use burn_tensor::{backend::Backend, ElementConversion};
@@ -25,14 +26,23 @@ pub struct AdamWConfig {
     /// Weight decay config.
     #[config(default = 1e-4)]
     weight_decay: f32,
+    /// If the weight decay is scaled by the learning rate (`lr * weight_decay * param`), as done
+    /// in PyTorch. Otherwise the decay is applied as `weight_decay * param`, independently of
+    /// the learning rate schedule.
+    #[config(default = true)]
+    weight_decay_scaled_by_lr: bool,
     /// [Gradient Clipping](GradientClippingConfig) config.
     grad_clipping: Option<GradientClippingConfig>,
+    /// [Gradient scale](crate::optim::adaptor::OptimizerAdaptor::with_grad_scale) of the adaptor.
+    #[config(default = 1.0)]
+    grad_scale: f32,
 }
 
 /// AdamW optimizer as described in the paper [Decoupled Weight Decay Regularization, Loshchilov and Hutter, 2019](https://arxiv.org/abs/1711.05101).
 pub struct AdamW<B: Backend> {
     momentum: AdaptiveMomentumW,
     weight_decay: f32,
+    weight_decay_scaled_by_lr: bool,
     _phantom: PhantomData<B>,
 }
 
@@ -57,7 +67,11 @@ impl<B: Backend> SimpleOptimizer<B> for AdamW<B> {
         // State of the optimizer.
         state: Option<Self::State<D>>,
     ) -> (Tensor<B, D>, Option<Self::State<D>>) {
-        let tensor_updated = tensor.clone() - tensor.mul_scalar(lr).mul_scalar(self.weight_decay);
+        let decay = match self.weight_decay_scaled_by_lr {
+            true => lr * self.weight_decay as f64,
+            false => self.weight_decay as f64,
+        };
+        let tensor_updated = tensor.clone() - tensor.mul_scalar(decay);
 
         let (raw_delta, momentum_state) = self.momentum.transform(grad, state.map(|s| s.momentum));
 
@@ -75,6 +89,53 @@ impl<B: Backend> SimpleOptimizer<B> for AdamW<B> {
         state.momentum = state.momentum.to_device(device);
         state
     }
+
+    fn state_map_tensors<const D: usize, F>(mut state: Self::State<D>, func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        state.momentum.moment_1 = func(state.momentum.moment_1);
+        state.momentum.moment_2 = func(state.momentum.moment_2);
+        state
+    }
+
+    fn state_num_elements<const D: usize>(state: &Self::State<D>) -> usize {
+        state.momentum.moment_1.shape().num_elements()
+            + state.momentum.moment_2.shape().num_elements()
+    }
+
+    fn state_num_steps<const D: usize>(state: &Self::State<D>) -> Option<usize> {
+        Some(state.momentum.time)
+    }
+
+    fn state_effective_lr<const D: usize>(
+        &self,
+        lr: LearningRate,
+        state: &Self::State<D>,
+    ) -> Option<Tensor<B, D>> {
+        let momentum = &state.momentum;
+        Some(adaptive_effective_lr(
+            lr,
+            &momentum.moment_2,
+            self.momentum.beta_2,
+            self.momentum.epsilon,
+            momentum.time,
+        ))
+    }
+}
+
+impl ValidateConfig for AdamWConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        validate_beta("beta_1", self.beta_1)?;
+        validate_beta("beta_2", self.beta_2)?;
+        validate_positive("epsilon", self.epsilon)?;
+        validate_non_negative("weight_decay", self.weight_decay)?;
+        if let Some(clipping) = &self.grad_clipping {
+            clipping.validate()?;
+        }
+
+        Ok(())
+    }
 }
 
 impl AdamWConfig {
@@ -83,7 +144,9 @@ impl AdamWConfig {
     /// # Returns
     ///
     /// Returns an optimizer that can be used to optimize a module.
-    pub fn init<B: AutodiffBackend, M: AutodiffModule<B>>(&self) -> impl Optimizer<M, B> {
+    pub fn init<B: AutodiffBackend, M: AutodiffModule<B>>(
+        &self,
+    ) -> OptimizerAdaptor<AdamW<B::InnerBackend>, M, B> {
         let optim = AdamW {
             momentum: AdaptiveMomentumW {
                 beta_1: self.beta_1,
@@ -91,10 +154,13 @@ impl AdamWConfig {
                 epsilon: self.epsilon,
             },
             weight_decay: self.weight_decay,
+            weight_decay_scaled_by_lr: self.weight_decay_scaled_by_lr,
             _phantom: Default::default(),
         };
 
-        let mut optim = OptimizerAdaptor::from(optim);
+        let mut optim = OptimizerAdaptor::from(optim)
+            .with_grad_scale(self.grad_scale)
+            .with_config(self);
         if let Some(config) = &self.grad_clipping {
             optim = optim.with_grad_clipping(config.init());
         }
@@ -299,6 +365,38 @@ mod tests {
         weight_updated.assert_approx_eq(&weights_expected, ASSERT_PRECISION);
     }
 
+    #[test]
+    fn test_adamw_weight_decay_scaled_by_lr() {
+        let decayed = step_with_zero_grad(AdamWConfig::new().with_weight_decay(0.5));
+
+        decayed.assert_approx_eq(&Data::from([[0.995, 0.995], [0.995, 0.995]]), 5);
+    }
+
+    #[test]
+    fn test_adamw_weight_decay_not_scaled_by_lr() {
+        let decayed = step_with_zero_grad(
+            AdamWConfig::new()
+                .with_weight_decay(0.5)
+                .with_weight_decay_scaled_by_lr(false),
+        );
+
+        decayed.assert_approx_eq(&Data::from([[0.5, 0.5], [0.5, 0.5]]), 5);
+    }
+
+    fn step_with_zero_grad(config: AdamWConfig) -> Data<f32, 2> {
+        let linear = nn::LinearConfig::new(2, 2).init_with(nn::LinearRecord {
+            weight: Param::from(Tensor::<TestAutodiffBackend, 2>::ones([2, 2])),
+            bias: None,
+        });
+        let mut optimizer = config.init();
+        let mut grads = GradientsParams::new();
+        grads.register::<TestBackend, 2>(linear.weight.id.clone(), Tensor::zeros([2, 2]));
+
+        let linear = optimizer.step(LEARNING_RATE, linear, grads);
+
+        linear.weight.to_data()
+    }
+
     #[test]
     fn test_adam_optimizer_no_nan() {
         let linear = given_linear_layer(
@@ -361,6 +459,7 @@ mod tests {
                 epsilon: config.epsilon,
             },
             weight_decay: config.weight_decay,
+            weight_decay_scaled_by_lr: config.weight_decay_scaled_by_lr,
             _phantom: Default::default(),
         }
         .into()
@@ -0,0 +1,339 @@
+use crate::{
+    self as burn, grad_clipping::GradientClippingConfig, module::AutodiffModule, record::Record,
+    LearningRate,
+};
+use core::marker::PhantomData;
+
+use super::validation::{validate_beta, validate_non_negative, validate_positive};
+use super::{adam::adaptive_effective_lr, SimpleOptimizer};
+use crate::config::{Config, ConfigError, ValidateConfig};
+use crate::optim::adaptor::OptimizerAdaptor;
+use crate::tensor::{backend::AutodiffBackend, Tensor};
+use burn_tensor::{backend::Backend, ElementConversion};
+
+/// Adan configuration.
+#[derive(Config)]
+pub struct AdanConfig {
+    /// Decay of the first moment of the gradients.
+    #[config(default = 0.98)]
+    beta_1: f32,
+    /// Decay of the first moment of the gradient differences.
+    #[config(default = 0.92)]
+    beta_2: f32,
+    /// Decay of the second moment of the Nesterov gradients.
+    #[config(default = 0.99)]
+    beta_3: f32,
+    /// A value required for numerical stability.
+    #[config(default = 1e-8)]
+    epsilon: f32,
+    /// Decoupled weight decay, scaled by the learning rate.
+    #[config(default = 0.02)]
+    weight_decay: f32,
+    /// [Gradient Clipping](GradientClippingConfig) config.
+    grad_clipping: Option<GradientClippingConfig>,
+    /// [Gradient scale](crate::optim::adaptor::OptimizerAdaptor::with_grad_scale) of the adaptor.
+    #[config(default = 1.0)]
+    grad_scale: f32,
+}
+
+/// Adan optimizer as described in the paper [Adan: Adaptive Nesterov Momentum Algorithm for Faster Optimizing Deep Models](https://arxiv.org/abs/2208.06677).
+pub struct Adan<B: Backend> {
+    momentum: AdaptiveNesterovMomentum,
+    weight_decay: f32,
+    _phantom: PhantomData<B>,
+}
+
+/// Adan state.
+#[derive(Record, Clone, new)]
+pub struct AdanState<B: Backend, const D: usize> {
+    momentum: AdaptiveNesterovMomentumState<B, D>,
+}
+
+impl<B: Backend> SimpleOptimizer<B> for Adan<B> {
+    type State<const D: usize> = AdanState<B, D>;
+
+    fn step<const D: usize>(
+        &self,
+        lr: LearningRate,
+        tensor: Tensor<B, D>,
+        grad: Tensor<B, D>,
+        state: Option<Self::State<D>>,
+    ) -> (Tensor<B, D>, Option<Self::State<D>>) {
+        let (update, momentum) = self
+            .momentum
+            .transform(grad, state.map(|state| state.momentum));
+
+        // Decoupled weight decay, applied to the parameters before the update.
+        let decay_rate = lr * (self.weight_decay as f64);
+        let tensor = tensor.mul_scalar(1.0 - decay_rate) - update.mul_scalar(lr);
+
+        (tensor, Some(AdanState::new(momentum)))
+    }
+
+    fn to_device<const D: usize>(
+        mut state: Self::State<D>,
+        device: &<B as Backend>::Device,
+    ) -> Self::State<D> {
+        state.momentum = state.momentum.to_device(device);
+        state
+    }
+
+    fn state_map_tensors<const D: usize, F>(mut state: Self::State<D>, func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        state.momentum.moment_1 = func(state.momentum.moment_1);
+        state.momentum.moment_diff = func(state.momentum.moment_diff);
+        state.momentum.moment_2 = func(state.momentum.moment_2);
+        state.momentum.grad_previous = func(state.momentum.grad_previous);
+        state
+    }
+
+    fn state_num_elements<const D: usize>(state: &Self::State<D>) -> usize {
+        state.momentum.moment_1.shape().num_elements()
+            + state.momentum.moment_diff.shape().num_elements()
+            + state.momentum.moment_2.shape().num_elements()
+            + state.momentum.grad_previous.shape().num_elements()
+    }
+
+    fn state_num_steps<const D: usize>(state: &Self::State<D>) -> Option<usize> {
+        Some(state.momentum.time)
+    }
+
+    fn state_effective_lr<const D: usize>(
+        &self,
+        lr: LearningRate,
+        state: &Self::State<D>,
+    ) -> Option<Tensor<B, D>> {
+        let momentum = &state.momentum;
+        Some(adaptive_effective_lr(
+            lr,
+            &momentum.moment_2,
+            self.momentum.beta_3,
+            self.momentum.epsilon,
+            momentum.time,
+        ))
+    }
+}
+
+impl ValidateConfig for AdanConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        validate_beta("beta_1", self.beta_1)?;
+        validate_beta("beta_2", self.beta_2)?;
+        validate_beta("beta_3", self.beta_3)?;
+        validate_positive("epsilon", self.epsilon)?;
+        validate_non_negative("weight_decay", self.weight_decay)?;
+        if let Some(clipping) = &self.grad_clipping {
+            clipping.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl AdanConfig {
+    /// Initialize Adan optimizer.
+    ///
+    /// # Returns
+    ///
+    /// Returns an optimizer that can be used to optimize a module.
+    pub fn init<B: AutodiffBackend, M: AutodiffModule<B>>(
+        &self,
+    ) -> OptimizerAdaptor<Adan<B::InnerBackend>, M, B> {
+        let optim = Adan {
+            momentum: AdaptiveNesterovMomentum {
+                beta_1: self.beta_1,
+                beta_2: self.beta_2,
+                beta_3: self.beta_3,
+                epsilon: self.epsilon,
+            },
+            weight_decay: self.weight_decay,
+            _phantom: Default::default(),
+        };
+
+        let mut optim = OptimizerAdaptor::from(optim)
+            .with_grad_scale(self.grad_scale)
+            .with_config(self);
+        if let Some(config) = &self.grad_clipping {
+            optim = optim.with_grad_clipping(config.init());
+        }
+        optim
+    }
+}
+
+/// Adaptive Nesterov momentum state.
+#[derive(Record, new, Clone)]
+pub struct AdaptiveNesterovMomentumState<B: Backend, const D: usize> {
+    time: usize,
+    moment_1: Tensor<B, D>,
+    moment_diff: Tensor<B, D>,
+    moment_2: Tensor<B, D>,
+    grad_previous: Tensor<B, D>,
+}
+
+struct AdaptiveNesterovMomentum {
+    beta_1: f32,
+    beta_2: f32,
+    beta_3: f32,
+    epsilon: f32,
+}
+
+impl AdaptiveNesterovMomentum {
+    pub fn transform<B: Backend, const D: usize>(
+        &self,
+        grad: Tensor<B, D>,
+        state: Option<AdaptiveNesterovMomentumState<B, D>>,
+    ) -> (Tensor<B, D>, AdaptiveNesterovMomentumState<B, D>) {
+        let state = if let Some(mut state) = state {
+            let grad_diff = grad.clone().sub(state.grad_previous);
+
+            // Update first moment estimate.
+            state.moment_1 = state
+                .moment_1
+                .mul_scalar(self.beta_1)
+                .add(grad.clone().mul_scalar(1.0 - self.beta_1));
+
+            // Update first moment estimate of the gradient differences.
+            state.moment_diff = state
+                .moment_diff
+                .mul_scalar(self.beta_2)
+                .add(grad_diff.clone().mul_scalar(1.0 - self.beta_2));
+
+            // Update second moment estimate of the Nesterov gradient.
+            let grad_nesterov = grad.clone().add(grad_diff.mul_scalar(self.beta_2));
+            state.moment_2 = state
+                .moment_2
+                .mul_scalar(self.beta_3)
+                .add(grad_nesterov.powf(2.0).mul_scalar(1.0 - self.beta_3));
+
+            state.grad_previous = grad;
+            state.time += 1;
+
+            state
+        } else {
+            // The first gradient difference is zero.
+            let moment_1 = grad.clone().mul_scalar(1.0 - self.beta_1);
+            let moment_diff = grad.zeros_like();
+            let moment_2 = grad.clone().powf(2.0).mul_scalar(1.0 - self.beta_3);
+
+            AdaptiveNesterovMomentumState::new(1, moment_1, moment_diff, moment_2, grad)
+        };
+
+        let time: i32 = (state.time as i32).elem();
+
+        // Compute bias-corrected estimates.
+        let moment_1_corrected = state
+            .moment_1
+            .clone()
+            .div_scalar(1f32 - self.beta_1.powi(time));
+        let moment_diff_corrected = state
+            .moment_diff
+            .clone()
+            .div_scalar(1f32 - self.beta_2.powi(time));
+        let moment_2_corrected = state
+            .moment_2
+            .clone()
+            .div_scalar(1f32 - self.beta_3.powi(time));
+
+        // Compute update delta. This still needs to be scaled by the learning rate.
+        let update_delta = moment_1_corrected
+            .add(moment_diff_corrected.mul_scalar(self.beta_2))
+            .div(moment_2_corrected.sqrt().add_scalar(self.epsilon));
+
+        (update_delta, state)
+    }
+}
+
+impl<B: Backend, const D: usize> AdaptiveNesterovMomentumState<B, D> {
+    /// Move state to device.
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - Device to move state to.
+    ///
+    /// # Returns
+    ///
+    /// Returns state moved to device.
+    pub fn to_device(mut self, device: &B::Device) -> Self {
+        self.moment_1 = self.moment_1.to_device(device);
+        self.moment_diff = self.moment_diff.to_device(device);
+        self.moment_2 = self.moment_2.to_device(device);
+        self.grad_previous = self.grad_previous.to_device(device);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tensor::Data;
+    use crate::TestBackend;
+
+    const LEARNING_RATE: LearningRate = 0.1;
+    const ASSERT_PRECISION: usize = 4;
+
+    #[test]
+    fn test_adan_optimizer_with_numbers() {
+        let optimizer = create_adan();
+        let tensor = Tensor::<TestBackend, 1>::from_floats([1.0, -2.0, 0.5]);
+        let grads: [Tensor<TestBackend, 1>; 3] = [
+            Tensor::from_floats([0.5, -1.0, 2.0]),
+            Tensor::from_floats([0.3, -0.5, 1.0]),
+            Tensor::from_floats([0.1, 0.2, -0.4]),
+        ];
+        let tensors_expected = [
+            [0.898000, -1.896000, 0.399000],
+            [0.812488, -1.820258, 0.326252],
+            [0.754126, -1.811391, 0.320373],
+        ];
+
+        let mut tensor = tensor;
+        let mut state = None;
+        for (grad, tensor_expected) in grads.iter().zip(tensors_expected) {
+            let (tensor_updated, state_updated) =
+                optimizer.step(LEARNING_RATE, tensor, grad.clone(), state);
+            tensor_updated
+                .to_data()
+                .assert_approx_eq(&Data::from(tensor_expected), ASSERT_PRECISION);
+
+            let momentum = &state_updated.as_ref().unwrap().momentum;
+            momentum
+                .grad_previous
+                .to_data()
+                .assert_approx_eq(&grad.to_data(), ASSERT_PRECISION);
+
+            tensor = tensor_updated;
+            state = state_updated;
+        }
+
+        let momentum = state.unwrap().momentum;
+        assert_eq!(momentum.time, 3);
+        momentum.moment_1.to_data().assert_approx_eq(
+            &Data::from([0.017484, -0.025008, 0.050016]),
+            ASSERT_PRECISION,
+        );
+        momentum.moment_diff.to_data().assert_approx_eq(
+            &Data::from([-0.030720, 0.092800, -0.185600]),
+            ASSERT_PRECISION,
+        );
+        momentum.moment_2.to_data().assert_approx_eq(
+            &Data::from([0.002654, 0.016940, 0.067761]),
+            ASSERT_PRECISION,
+        );
+    }
+
+    fn create_adan() -> Adan<TestBackend> {
+        let config = AdanConfig::new();
+
+        Adan {
+            momentum: AdaptiveNesterovMomentum {
+                beta_1: config.beta_1,
+                beta_2: config.beta_2,
+                beta_3: config.beta_3,
+                epsilon: config.epsilon,
+            },
+            weight_decay: config.weight_decay,
+            _phantom: Default::default(),
+        }
+    }
+}
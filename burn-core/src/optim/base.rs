@@ -1,8 +1,14 @@
 use super::GradientsParams;
-use crate::module::AutodiffModule;
+use crate::module::{AutodiffModule, ParamId};
 use crate::record::Record;
-use crate::tensor::backend::AutodiffBackend;
+use crate::tensor::{
+    backend::{AutodiffBackend, Backend},
+    Tensor,
+};
 use crate::LearningRate;
+use alloc::string::String;
+use alloc::vec::Vec;
+use hashbrown::HashMap;
 
 /// General trait to optimize [module](AutodiffModule).
 pub trait Optimizer<M, B>: Send + Sync
@@ -22,4 +28,183 @@ where
 
     /// Load the state of the optimizer as a [record](Record).
     fn load_record(self, record: Self::Record) -> Self;
+
+    /// Load the state of the optimizer from a file using the provided
+    /// [file recorder](crate::record::FileRecorder), see
+    /// [Module::load_file](crate::module::Module::load_file).
+    ///
+    /// The typed [errors](crate::record::RecorderError) of the recorder are returned instead of
+    /// panicking, e.g. a [deserialize](crate::record::RecorderError::Deserialize) error when the
+    /// file holds the state of another optimizer.
+    #[cfg(feature = "std")]
+    fn load_file<FR: crate::record::FileRecorder, PB: Into<std::path::PathBuf>>(
+        self,
+        file_path: PB,
+        recorder: &FR,
+    ) -> Result<Self, crate::record::RecorderError>
+    where
+        Self: Sized,
+    {
+        let record = recorder.load(file_path.into())?;
+
+        Ok(self.load_record(record))
+    }
+
+    /// Move the state of all the parameters to the given device.
+    ///
+    /// The state is otherwise moved to the device of each gradient during the step, this is useful
+    /// to move a loaded optimizer before the first step.
+    fn to_device(self, device: &B::Device) -> Self
+    where
+        Self: Sized;
+
+    /// Copy the state of the parameters of the given module into the target optimizer.
+    ///
+    /// The state of each parameter of `module` is taken from the parameter with the same id in
+    /// this optimizer, or from the parameter mapped to it in `ids` (from the old id to the new id),
+    /// which is useful for model surgery where the parameters are recreated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shape of a state differs from the shape of its new parameter, unless
+    /// `adapt_shapes` is enabled, in which case the overlapping region of the state is copied and
+    /// the new entries are zeroed.
+    fn clone_state_to(
+        &self,
+        target: Self,
+        module: &M,
+        ids: &HashMap<ParamId, ParamId>,
+        adapt_shapes: bool,
+    ) -> Self
+    where
+        Self: Sized;
+
+    /// The number of parameter tensors that have a state in the optimizer, not their number of
+    /// elements, see [state_bytes](Optimizer::state_bytes) for the size of the state.
+    fn num_params(&self) -> usize;
+
+    /// Estimate of the memory used by the tensors of the optimizer state, in bytes.
+    fn state_bytes(&self) -> usize;
+
+    /// The configuration the optimizer was initialized with, serialized to JSON, `None` when the
+    /// optimizer wasn't created from a configuration.
+    ///
+    /// This is independent of the [record](Optimizer::Record), so the hyperparameters can be
+    /// logged by experiment trackers. The learning rate isn't included, since it is given to
+    /// each [step](Optimizer::step).
+    fn config_json(&self) -> Option<String>;
+
+    /// The parameters updated by the last [step](Optimizer::step).
+    ///
+    /// Only the parameters that received a gradient are updated, so frozen parameters and
+    /// parameters missing from sparse or masked gradients are absent.
+    fn last_updated(&self) -> Vec<ParamId>;
+
+    /// Clone the tensors of the state of each parameter, flattened with their shape, e.g. the
+    /// accumulator of AdaGrad or the two moments of Adam, for analyses not covered by the
+    /// summaries of the [record](Optimizer::Record).
+    ///
+    /// The tensors of a parameter are in the order of its state.
+    fn export_state_tensors(&self) -> HashMap<ParamId, Vec<StateTensor<B::InnerBackend>>>;
+}
+
+/// Implement the methods of [Optimizer] handling the state of an optimizer wrapper by delegating
+/// them to the wrapped optimizer in the given field, all of them or only the listed ones.
+macro_rules! delegate_optimizer {
+    ($field:ident) => {
+        delegate_optimizer!(
+            $field,
+            [
+                to_device,
+                clone_state_to,
+                num_params,
+                state_bytes,
+                config_json,
+                last_updated,
+                export_state_tensors
+            ]
+        );
+    };
+    ($field:ident, [$($method:ident),*]) => {
+        $(delegate_optimizer!(@$method $field);)*
+    };
+    (@to_device $field:ident) => {
+        fn to_device(mut self, device: &B::Device) -> Self {
+            self.$field = self.$field.to_device(device);
+            self
+        }
+    };
+    (@clone_state_to $field:ident) => {
+        fn clone_state_to(
+            &self,
+            mut target: Self,
+            module: &M,
+            ids: &hashbrown::HashMap<$crate::module::ParamId, $crate::module::ParamId>,
+            adapt_shapes: bool,
+        ) -> Self {
+            target.$field = self
+                .$field
+                .clone_state_to(target.$field, module, ids, adapt_shapes);
+            target
+        }
+    };
+    (@num_params $field:ident) => {
+        fn num_params(&self) -> usize {
+            self.$field.num_params()
+        }
+    };
+    (@state_bytes $field:ident) => {
+        fn state_bytes(&self) -> usize {
+            self.$field.state_bytes()
+        }
+    };
+    (@config_json $field:ident) => {
+        fn config_json(&self) -> Option<alloc::string::String> {
+            self.$field.config_json()
+        }
+    };
+    (@last_updated $field:ident) => {
+        fn last_updated(&self) -> alloc::vec::Vec<$crate::module::ParamId> {
+            self.$field.last_updated()
+        }
+    };
+    (@export_state_tensors $field:ident) => {
+        fn export_state_tensors(
+            &self,
+        ) -> hashbrown::HashMap<
+            $crate::module::ParamId,
+            alloc::vec::Vec<$crate::optim::StateTensor<B::InnerBackend>>,
+        > {
+            self.$field.export_state_tensors()
+        }
+    };
+}
+
+pub(crate) use delegate_optimizer;
+
+/// A tensor of the state of an optimizer, e.g. a moment of Adam, flattened with the shape it has
+/// in the state, see [export_state_tensors](Optimizer::export_state_tensors).
+#[derive(Clone, Debug)]
+pub struct StateTensor<B: Backend> {
+    /// The tensor, flattened.
+    pub tensor: Tensor<B, 1>,
+    /// The shape of the tensor in the state, usually the shape of its parameter.
+    pub shape: Vec<usize>,
+}
+
+impl<B: Backend> StateTensor<B> {
+    /// The tensor with its shape in the state.
+    ///
+    /// # Panics
+    ///
+    /// When `D` isn't the rank of the tensor in the state.
+    pub fn into_shaped<const D: usize>(self) -> Tensor<B, D> {
+        let rank = self.shape.len();
+        let dims: [usize; D] = self
+            .shape
+            .try_into()
+            .unwrap_or_else(|_| panic!("The state tensor has a rank of {rank}, not {D}."));
+
+        self.tensor.reshape(dims)
+    }
 }
@@ -0,0 +1,202 @@
+use super::adaptor::OptimizerAdaptor;
+use super::decay::WeightDecayOverrides;
+use super::{ScheduledOptimizer, SimpleOptimizer};
+use crate::config::ConfigError;
+use crate::lr_scheduler::{
+    cosine::{CosineAnnealingLrScheduler, CosineAnnealingLrSchedulerConfig},
+    warmup::WarmupLrScheduler,
+    LrScheduler,
+};
+use crate::module::{AutodiffModule, ParamId};
+use crate::tensor::backend::AutodiffBackend;
+use crate::LearningRate;
+
+/// Fluent builder assembling an [optimizer](OptimizerAdaptor) with its learning rate schedule,
+/// the clipping of the global norm of the gradients and the parameters excluded from the weight
+/// decay, into a [scheduled optimizer](ScheduledOptimizer).
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let optim = TrainingOptimizerBuilder::new_warmup_cosine(
+///     AdamConfig::new().init(),
+///     1e-3,
+///     1e-5,
+///     1_000,
+///     100_000,
+/// )
+/// .with_global_norm_clipping(1.0)
+/// .with_no_decay(biases)?
+/// .build();
+/// ```
+pub struct TrainingOptimizerBuilder<O, M, B, S>
+where
+    O: SimpleOptimizer<B::InnerBackend>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    optim: OptimizerAdaptor<O, M, B>,
+    scheduler: S,
+    max_global_norm: Option<f32>,
+}
+
+impl<O, M, B> TrainingOptimizerBuilder<O, M, B, WarmupLrScheduler<CosineAnnealingLrScheduler>>
+where
+    O: SimpleOptimizer<B::InnerBackend>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    /// Create a builder for the given optimizer, with a linear warmup to `peak_lr` followed by a
+    /// cosine decay to `min_lr` at the end of the training, see [WarmupLrScheduler].
+    ///
+    /// # Arguments
+    ///
+    /// * `optim` - The optimizer.
+    /// * `peak_lr` - The learning rate at the end of the warmup.
+    /// * `min_lr` - The learning rate at the end of the training.
+    /// * `warmup_steps` - The number of warmup steps.
+    /// * `num_steps` - The total number of training steps, including the warmup.
+    pub fn new_warmup_cosine(
+        optim: OptimizerAdaptor<O, M, B>,
+        peak_lr: LearningRate,
+        min_lr: LearningRate,
+        warmup_steps: usize,
+        num_steps: usize,
+    ) -> Self {
+        let cosine = CosineAnnealingLrSchedulerConfig::new(peak_lr, num_steps)
+            .with_min_lr(min_lr)
+            .init();
+
+        Self::new(optim, WarmupLrScheduler::new(cosine, warmup_steps))
+    }
+}
+
+impl<O, M, B, S> TrainingOptimizerBuilder<O, M, B, S>
+where
+    O: SimpleOptimizer<B::InnerBackend>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+    S: LrScheduler,
+{
+    /// Create a builder for the given optimizer and learning rate scheduler.
+    pub fn new(optim: OptimizerAdaptor<O, M, B>, scheduler: S) -> Self {
+        Self {
+            optim,
+            scheduler,
+            max_global_norm: None,
+        }
+    }
+
+    /// Sets the maximum global L2 norm of the gradients, above which all the gradients are scaled
+    /// down by the same factor, see [clip_by_global_norm](crate::grad_clipping::clip_by_global_norm).
+    pub fn with_global_norm_clipping(mut self, max_norm: f32) -> Self {
+        self.max_global_norm = Some(max_norm);
+        self
+    }
+
+    /// Excludes parameters from the weight decay, e.g. the biases and the normalization layers.
+    ///
+    /// The weight decay of the optimizer is removed from their gradients, see
+    /// [WeightDecayOverrides].
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The ids of the parameters without weight decay.
+    ///
+    /// # Returns
+    ///
+    /// The builder, or an [out of range](ConfigError::OutOfRange) error when the optimizer has an
+    /// [L1 proximal](crate::optim::decay::WeightDecayKind::L1Proximal) decay, whose proximal step
+    /// can't be undone.
+    pub fn with_no_decay<I: IntoIterator<Item = ParamId>>(
+        mut self,
+        params: I,
+    ) -> Result<Self, ConfigError> {
+        if !self.optim.can_reduce_weight_decay() {
+            return Err(ConfigError::OutOfRange(
+                "The parameters can't be excluded from the L1 proximal weight decay of the \
+                 optimizer, its proximal step can't be undone."
+                    .into(),
+            ));
+        }
+
+        let overrides =
+            WeightDecayOverrides::new().with_params(params.into_iter().map(|id| (id, 0.0)));
+        self.optim = self.optim.with_weight_decay_overrides(overrides);
+        Ok(self)
+    }
+
+    /// Build the [scheduled optimizer](ScheduledOptimizer).
+    pub fn build(self) -> ScheduledOptimizer<OptimizerAdaptor<O, M, B>, S, M, B> {
+        ScheduledOptimizer::new(self.optim, self.scheduler, self.max_global_norm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grad_clipping::clip_by_global_norm_with_scale;
+    use crate::lr_scheduler::constant::ConstantLr;
+    use crate::nn::{Linear, LinearConfig};
+    use crate::optim::decay::{WeightDecayConfig, WeightDecayKind};
+    use crate::optim::{AdamConfig, GradientsParams, Optimizer};
+    use crate::tensor::{Distribution, Tensor};
+    use crate::TestAutodiffBackend;
+
+    type B = TestAutodiffBackend;
+
+    #[test]
+    fn test_builder_activates_schedule_clipping_and_no_decay() {
+        let weight_decay = WeightDecayConfig::new(0.5);
+        let adam = AdamConfig::new().with_weight_decay(Some(weight_decay.clone()));
+        let linear: Linear<B> = LinearConfig::new(4, 2).init();
+        let bias_id = linear.bias.as_ref().unwrap().id.clone();
+        let mut optim = TrainingOptimizerBuilder::new_warmup_cosine(adam.init(), 0.1, 0.0, 2, 10)
+            .with_global_norm_clipping(1.0)
+            .with_no_decay([bias_id.clone()])
+            .unwrap()
+            .build();
+        let x = Tensor::<B, 2>::random([3, 4], Distribution::Default).mul_scalar(100.0);
+        let grads =
+            || GradientsParams::from_grads(linear.forward(x.clone()).sum().backward(), &linear);
+
+        // The same step, with each piece applied by hand.
+        let (grads_clipped, scale) =
+            clip_by_global_norm_with_scale(&linear, grads(), 1.0, &Default::default());
+        let overrides = WeightDecayOverrides::new().with_params([(bias_id, 0.0)]);
+        let linear_expected = adam.init().with_weight_decay_overrides(overrides).step(
+            0.05,
+            linear.clone(),
+            grads_clipped,
+        );
+
+        let grads = grads();
+        let linear = optim.step(linear, grads);
+
+        // Half of the peak learning rate at the first warmup step.
+        assert_eq!(optim.last_lr(), Some(0.05));
+        assert!(scale < 1.0);
+        linear
+            .weight
+            .to_data()
+            .assert_approx_eq(&linear_expected.weight.to_data(), 5);
+        linear
+            .bias
+            .unwrap()
+            .to_data()
+            .assert_approx_eq(&linear_expected.bias.unwrap().to_data(), 5);
+    }
+
+    #[test]
+    fn test_builder_rejects_no_decay_with_l1_proximal_decay() {
+        let weight_decay = WeightDecayConfig::new(0.5).with_kind(WeightDecayKind::L1Proximal);
+        let adam = AdamConfig::new().with_weight_decay(Some(weight_decay));
+        let linear: Linear<B> = LinearConfig::new(4, 2).init();
+        let bias_id = linear.bias.as_ref().unwrap().id.clone();
+
+        let result = TrainingOptimizerBuilder::new(adam.init(), ConstantLr::new(0.1))
+            .with_no_decay([bias_id]);
+
+        assert!(matches!(result, Err(ConfigError::OutOfRange(_))));
+    }
+}
@@ -0,0 +1,250 @@
+use crate as burn;
+
+use super::{GradientsParams, Optimizer, StateTensor};
+use crate::module::{AutodiffModule, ModuleVisitor, ParamId};
+use crate::record::Record;
+use crate::tensor::{backend::AutodiffBackend, Tensor};
+use crate::LearningRate;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use hashbrown::HashMap;
+
+/// Function assigning a parameter to the first optimizer of a
+/// [composite optimizer](CompositeOptimizer), given its id and its rank.
+pub type ParamPartition = Box<dyn Fn(&ParamId, usize) -> bool + Send + Sync>;
+
+/// Optimizer updating a partition of the parameters with one optimizer and the other parameters
+/// with another one, e.g. [AdamW](super::AdamW) for the weights and [SGD](super::Sgd) for the
+/// biases.
+///
+/// At each step, the gradients are split according to the partition, then each optimizer takes
+/// a step with its own gradients, so each parameter is updated by a single optimizer.
+pub struct CompositeOptimizer<O1, O2, M, B>
+where
+    O1: Optimizer<M, B>,
+    O2: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    first: O1,
+    second: O2,
+    partition: ParamPartition,
+    phantom: PhantomData<(M, B)>,
+}
+
+/// [Composite optimizer](CompositeOptimizer) record.
+#[derive(Record)]
+pub struct CompositeOptimizerRecord<R1: Record, R2: Record> {
+    first: R1,
+    second: R2,
+}
+
+impl<O1, O2, M, B> CompositeOptimizer<O1, O2, M, B>
+where
+    O1: Optimizer<M, B>,
+    O2: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    /// Create a composite optimizer with the given partition of the parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `first` - The optimizer of the parameters for which the partition returns `true`.
+    /// * `second` - The optimizer of the other parameters.
+    /// * `partition` - Function receiving the id and the rank of a parameter.
+    pub fn new<F>(first: O1, second: O2, partition: F) -> Self
+    where
+        F: Fn(&ParamId, usize) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            first,
+            second,
+            partition: Box::new(partition),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Create a composite optimizer updating the parameters of rank two or more, e.g. the
+    /// weights, with the first optimizer, and the vectors, e.g. the biases and the norm scales,
+    /// with the second optimizer.
+    pub fn by_rank(matrices: O1, vectors: O2) -> Self {
+        Self::new(matrices, vectors, |_, rank| rank >= 2)
+    }
+
+    /// The optimizer of the parameters for which the partition returns `true`.
+    pub fn first(&self) -> &O1 {
+        &self.first
+    }
+
+    /// The optimizer of the other parameters.
+    pub fn second(&self) -> &O2 {
+        &self.second
+    }
+}
+
+impl<O1, O2, M, B> Optimizer<M, B> for CompositeOptimizer<O1, O2, M, B>
+where
+    O1: Optimizer<M, B>,
+    O2: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    type Record = CompositeOptimizerRecord<O1::Record, O2::Record>;
+
+    fn step(&mut self, lr: LearningRate, module: M, mut grads: GradientsParams) -> M {
+        let mut grads_first = GradientsParams::new();
+        module.visit(&mut PartitionGradients::<B>::new(
+            &mut grads,
+            &mut grads_first,
+            &self.partition,
+        ));
+
+        let module = self.first.step(lr, module, grads_first);
+        self.second.step(lr, module, grads)
+    }
+
+    fn to_record(&self) -> Self::Record {
+        CompositeOptimizerRecord {
+            first: self.first.to_record(),
+            second: self.second.to_record(),
+        }
+    }
+
+    fn load_record(mut self, record: Self::Record) -> Self {
+        self.first = self.first.load_record(record.first);
+        self.second = self.second.load_record(record.second);
+        self
+    }
+
+    fn to_device(mut self, device: &B::Device) -> Self {
+        self.first = self.first.to_device(device);
+        self.second = self.second.to_device(device);
+        self
+    }
+
+    fn clone_state_to(
+        &self,
+        mut target: Self,
+        module: &M,
+        ids: &HashMap<ParamId, ParamId>,
+        adapt_shapes: bool,
+    ) -> Self {
+        target.first = self
+            .first
+            .clone_state_to(target.first, module, ids, adapt_shapes);
+        target.second = self
+            .second
+            .clone_state_to(target.second, module, ids, adapt_shapes);
+        target
+    }
+
+    fn num_params(&self) -> usize {
+        self.first.num_params() + self.second.num_params()
+    }
+
+    fn state_bytes(&self) -> usize {
+        self.first.state_bytes() + self.second.state_bytes()
+    }
+
+    fn config_json(&self) -> Option<String> {
+        match (self.first.config_json(), self.second.config_json()) {
+            (None, None) => None,
+            (first, second) => Some(format!(
+                "{{\"first\": {}, \"second\": {}}}",
+                first.as_deref().unwrap_or("null"),
+                second.as_deref().unwrap_or("null")
+            )),
+        }
+    }
+
+    fn last_updated(&self) -> Vec<ParamId> {
+        let mut ids = self.first.last_updated();
+        ids.extend(self.second.last_updated());
+        ids
+    }
+
+    fn export_state_tensors(&self) -> HashMap<ParamId, Vec<StateTensor<B::InnerBackend>>> {
+        let mut tensors = self.first.export_state_tensors();
+        tensors.extend(self.second.export_state_tensors());
+        tensors
+    }
+}
+
+/// Move the gradients of the parameters of the first partition to their own gradients.
+#[derive(new)]
+struct PartitionGradients<'a, B: AutodiffBackend> {
+    grads: &'a mut GradientsParams,
+    grads_first: &'a mut GradientsParams,
+    partition: &'a ParamPartition,
+    phantom: PhantomData<B>,
+}
+
+impl<'a, B: AutodiffBackend> ModuleVisitor<B> for PartitionGradients<'a, B> {
+    fn visit<const D: usize>(&mut self, id: &ParamId, _tensor: &Tensor<B, D>) {
+        if !(self.partition)(id, D) {
+            return;
+        }
+
+        if let Some(grad) = self.grads.remove::<B::InnerBackend, D>(id) {
+            self.grads_first
+                .register::<B::InnerBackend, D>(id.clone(), grad);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::{Linear, LinearConfig};
+    use crate::optim::{AdamConfig, SgdConfig};
+    use crate::tensor::Distribution;
+    use crate::TestAutodiffBackend;
+
+    type B = TestAutodiffBackend;
+    type M = Linear<TestAutodiffBackend>;
+
+    const LEARNING_RATE: LearningRate = 0.1;
+
+    #[test]
+    fn test_weights_use_adam_and_biases_use_sgd() {
+        let x = Tensor::<B, 2>::random([2, 4], Distribution::Default);
+        let grads = |linear: &M| {
+            GradientsParams::from_grads(linear.forward(x.clone()).sum().backward(), linear)
+        };
+        let mut composite = CompositeOptimizer::by_rank(
+            AdamConfig::new().init::<B, M>(),
+            SgdConfig::new().init::<B, M>(),
+        );
+        let mut adam = AdamConfig::new().init::<B, M>();
+        let mut sgd = SgdConfig::new().init::<B, M>();
+        let mut linear: M = LinearConfig::new(4, 2).init();
+        let mut linear_adam = linear.clone();
+        let mut linear_sgd = linear.clone();
+
+        for _ in 0..2 {
+            linear = composite.step(LEARNING_RATE, linear.clone(), grads(&linear));
+            linear_adam = adam.step(LEARNING_RATE, linear_adam.clone(), grads(&linear_adam));
+            linear_sgd = sgd.step(LEARNING_RATE, linear_sgd.clone(), grads(&linear_sgd));
+        }
+
+        // The gradients of the layer don't depend on its parameters, so each parameter follows
+        // the optimizer it's assigned to.
+        linear
+            .weight
+            .to_data()
+            .assert_approx_eq(&linear_adam.weight.to_data(), 5);
+        linear
+            .bias
+            .as_ref()
+            .unwrap()
+            .to_data()
+            .assert_approx_eq(&linear_sgd.bias.as_ref().unwrap().to_data(), 5);
+        // Each optimizer only keeps the state of its own parameter.
+        assert_eq!(composite.first().num_params(), 1);
+        assert_eq!(composite.second().num_params(), 1);
+    }
+}
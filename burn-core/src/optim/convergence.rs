@@ -0,0 +1,383 @@
+use crate as burn;
+
+use super::{base::delegate_optimizer, GradientsParams, Optimizer};
+use crate::config::Config;
+use crate::grad_clipping::{tensor_norm, NormKind};
+use crate::module::{AutodiffModule, ModuleMapper, ModuleVisitor, ParamId};
+use crate::tensor::{backend::AutodiffBackend, Tensor};
+use crate::LearningRate;
+use burn_tensor::ElementConversion;
+use core::marker::PhantomData;
+use hashbrown::{HashMap, HashSet};
+use std::collections::VecDeque;
+
+/// Configuration to create an [update ratio monitor](UpdateRatioMonitor).
+#[derive(Config)]
+pub struct UpdateRatioMonitorConfig {
+    /// The number of most recent ratios kept, which bounds the patience of
+    /// [converged](UpdateRatioMonitor::converged).
+    #[config(default = 100)]
+    window: usize,
+    /// A value required for numerical stability.
+    #[config(default = 1e-12)]
+    epsilon: f64,
+}
+
+/// Optimizer wrapper measuring the relative size of the updates, as a signal for early stopping.
+///
+/// After each step, the ratio `||update|| / ||param||` is computed for each updated parameter and
+/// averaged across the module. The frozen parameters and the ones without a gradient aren't
+/// updated, so they are skipped, like the ones missing from the
+/// [updated parameters](Optimizer::last_updated) of the optimizer when it keeps track of them. The training has converged when the average ratio stays below a
+/// threshold for a number of consecutive steps, see [converged](UpdateRatioMonitor::converged).
+///
+/// The ratios of each parameter are also kept, to detect the parameters that stopped moving
+/// while the others are still training, see [stalled_params](UpdateRatioMonitor::stalled_params).
+///
+/// # Notes
+///
+/// The average ratio is read back from the device after each step.
+pub struct UpdateRatioMonitor<O, M, B>
+where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    optim: O,
+    window: usize,
+    epsilon: f64,
+    ratios: VecDeque<f64>,
+    param_ratios: HashMap<ParamId, VecDeque<f64>>,
+    phantom: PhantomData<(M, B)>,
+}
+
+impl UpdateRatioMonitorConfig {
+    /// Wrap the given optimizer to monitor the relative size of its updates.
+    pub fn init<O, M, B>(&self, optim: O) -> UpdateRatioMonitor<O, M, B>
+    where
+        O: Optimizer<M, B>,
+        M: AutodiffModule<B>,
+        B: AutodiffBackend,
+    {
+        assert!(
+            self.window > 0,
+            "The window must contain at least one step."
+        );
+
+        UpdateRatioMonitor {
+            optim,
+            window: self.window,
+            epsilon: self.epsilon,
+            ratios: VecDeque::with_capacity(self.window),
+            param_ratios: HashMap::new(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<O, M, B> UpdateRatioMonitor<O, M, B>
+where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    /// The average ratio `||update|| / ||param||` of the last step, if any parameter was updated.
+    pub fn update_ratio(&self) -> Option<f64> {
+        self.ratios.back().copied()
+    }
+
+    /// If the average update ratio was below the threshold for the last `patience` steps.
+    ///
+    /// Steps without any updated parameter aren't counted.
+    pub fn converged(&self, threshold: f64, patience: usize) -> bool {
+        if patience == 0 || self.ratios.len() < patience {
+            return false;
+        }
+
+        self.ratios
+            .iter()
+            .rev()
+            .take(patience)
+            .all(|ratio| *ratio < threshold)
+    }
+
+    /// The parameters whose update ratio was below the threshold for their last `patience`
+    /// updates, sorted by id.
+    ///
+    /// Steps without an update of a parameter aren't counted for that parameter.
+    pub fn stalled_params(&self, threshold: f64, patience: usize) -> Vec<ParamId> {
+        if patience == 0 {
+            return Vec::new();
+        }
+
+        let mut params: Vec<ParamId> = self
+            .param_ratios
+            .iter()
+            .filter(|(_, ratios)| {
+                ratios.len() >= patience
+                    && ratios
+                        .iter()
+                        .rev()
+                        .take(patience)
+                        .all(|ratio| *ratio < threshold)
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+        params.sort();
+        params
+    }
+
+    /// Freeze the [stalled parameters](Self::stalled_params) of the module, so no gradient is
+    /// computed for them anymore.
+    ///
+    /// # Returns
+    ///
+    /// The module with the stalled parameters frozen.
+    pub fn freeze_stalled(&self, module: M, threshold: f64, patience: usize) -> M {
+        let params = self.stalled_params(threshold, patience);
+        if params.is_empty() {
+            return module;
+        }
+
+        module.map(&mut ParamsFreezer::<B>::new(&params))
+    }
+
+    fn record_param_ratios(&mut self, ratios: Vec<(ParamId, Tensor<B::InnerBackend, 1>)>) {
+        if ratios.is_empty() {
+            return;
+        }
+
+        // A single concatenated tensor is read back, instead of one small transfer per
+        // parameter.
+        let device = ratios[0].1.device();
+        let (ids, ratios): (Vec<_>, Vec<_>) = ratios
+            .into_iter()
+            .map(|(id, ratio)| (id, ratio.to_device(&device)))
+            .unzip();
+        let values = Tensor::cat(ratios, 0).into_data().convert::<f64>().value;
+
+        for (id, ratio) in ids.into_iter().zip(values) {
+            let ratios = self.param_ratios.entry(id).or_default();
+            if ratios.len() == self.window {
+                ratios.pop_front();
+            }
+            ratios.push_back(ratio);
+        }
+    }
+}
+
+impl<O, M, B> Optimizer<M, B> for UpdateRatioMonitor<O, M, B>
+where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    type Record = O::Record;
+
+    fn step(&mut self, lr: LearningRate, module: M, grads: GradientsParams) -> M {
+        let mut params = HashMap::new();
+        module.visit(&mut ParamsCollector::<B>::new(&mut params, &grads));
+
+        let module = self.optim.step(lr, module, grads);
+        retain_updated::<O, M, B>(&mut params, &self.optim);
+
+        let mut ratios = UpdateRatios::<B>::new(&mut params, self.epsilon, None, 0);
+        module.visit(&mut ratios);
+
+        if let Some(sum) = ratios.sum {
+            let ratio = sum.into_scalar().elem::<f64>() / ratios.count as f64;
+            if self.ratios.len() == self.window {
+                self.ratios.pop_front();
+            }
+            self.ratios.push_back(ratio);
+        }
+        self.record_param_ratios(ratios.per_param);
+
+        module
+    }
+
+    fn to_record(&self) -> Self::Record {
+        self.optim.to_record()
+    }
+
+    fn load_record(mut self, record: Self::Record) -> Self {
+        self.optim = self.optim.load_record(record);
+        self
+    }
+
+    delegate_optimizer!(optim);
+}
+
+/// Collect the parameters with a gradient, flattened, before the step.
+#[derive(new)]
+pub(super) struct ParamsCollector<'a, B: AutodiffBackend> {
+    params: &'a mut HashMap<ParamId, Tensor<B::InnerBackend, 1>>,
+    grads: &'a GradientsParams,
+}
+
+impl<'a, B: AutodiffBackend> ModuleVisitor<B> for ParamsCollector<'a, B> {
+    fn visit<const D: usize>(&mut self, id: &ParamId, tensor: &Tensor<B, D>) {
+        if !self.grads.contains(id) {
+            return;
+        }
+        let num_elements = tensor.shape().num_elements();
+        self.params
+            .insert(id.clone(), tensor.clone().inner().reshape([num_elements]));
+    }
+}
+
+/// Keep the parameters [updated](Optimizer::last_updated) by the last step of the optimizer, all
+/// of them when the optimizer doesn't keep track of the updated parameters.
+pub(super) fn retain_updated<O, M, B>(
+    params: &mut HashMap<ParamId, Tensor<B::InnerBackend, 1>>,
+    optim: &O,
+) where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    let updated = optim.last_updated();
+    if updated.is_empty() {
+        return;
+    }
+
+    let updated: HashSet<ParamId> = updated.into_iter().collect();
+    params.retain(|id, _| updated.contains(id));
+}
+
+#[derive(new)]
+pub(super) struct UpdateRatios<'a, B: AutodiffBackend> {
+    params: &'a mut HashMap<ParamId, Tensor<B::InnerBackend, 1>>,
+    epsilon: f64,
+    pub(super) sum: Option<Tensor<B::InnerBackend, 1>>,
+    pub(super) count: usize,
+    /// The ratio of each parameter.
+    #[new(default)]
+    pub(super) per_param: Vec<(ParamId, Tensor<B::InnerBackend, 1>)>,
+}
+
+impl<'a, B: AutodiffBackend> ModuleVisitor<B> for UpdateRatios<'a, B> {
+    fn visit<const D: usize>(&mut self, id: &ParamId, tensor: &Tensor<B, D>) {
+        // The parameters are removed, so a parameter visited more than once is only counted once.
+        let Some(param) = self.params.remove(id) else {
+            return;
+        };
+        let num_elements = tensor.shape().num_elements();
+        let update = tensor
+            .clone()
+            .inner()
+            .reshape([num_elements])
+            .sub(param.clone());
+        let update_norm = tensor_norm(update, &NormKind::L2);
+        let param_norm = tensor_norm(param, &NormKind::L2);
+
+        let ratio = update_norm.div(param_norm.add_scalar(self.epsilon));
+        self.per_param.push((id.clone(), ratio.clone()));
+        self.sum = Some(match self.sum.take() {
+            Some(sum) => {
+                let device = sum.device();
+                sum.add(ratio.to_device(&device))
+            }
+            None => ratio,
+        });
+        self.count += 1;
+    }
+}
+
+#[derive(new)]
+struct ParamsFreezer<'a, B: AutodiffBackend> {
+    params: &'a [ParamId],
+    phantom: PhantomData<B>,
+}
+
+impl<'a, B: AutodiffBackend> ModuleMapper<B> for ParamsFreezer<'a, B> {
+    fn map<const D: usize>(&mut self, id: &ParamId, tensor: Tensor<B, D>) -> Tensor<B, D> {
+        match self.params.contains(id) {
+            true => tensor.set_require_grad(false),
+            false => tensor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::{Linear, LinearConfig};
+    use crate::optim::SgdConfig;
+    use crate::TestAutodiffBackend;
+
+    type B = TestAutodiffBackend;
+    type M = Linear<TestAutodiffBackend>;
+
+    #[test]
+    fn test_converged_after_patience_window_of_small_updates() {
+        let mut optim = UpdateRatioMonitorConfig::new().init(SgdConfig::new().init::<B, M>());
+        let mut linear: M = LinearConfig::new(4, 4).init();
+        let x = Tensor::<B, 2>::ones([2, 4]);
+        assert_eq!(optim.update_ratio(), None);
+
+        // The gradients are constant, so halving the learning rate halves the update ratio.
+        let mut lr = 0.001;
+        let mut ratio_first = None;
+        let mut converged = Vec::new();
+        for _ in 0..10 {
+            let grads = GradientsParams::from_grads(linear.forward(x.clone()).backward(), &linear);
+            linear = optim.step(lr, linear, grads);
+            lr /= 2.0;
+
+            let ratio_first = *ratio_first.get_or_insert(optim.update_ratio().unwrap());
+            converged.push(optim.converged(ratio_first / 100.0, 3));
+        }
+
+        // The ratio is below the threshold from the 8th step, so for 3 steps at the 10th one.
+        assert_eq!(
+            converged,
+            [false; 9].into_iter().chain([true]).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_stalled_params_only_include_params_that_stopped_moving() {
+        let mut optim = UpdateRatioMonitorConfig::new().init(SgdConfig::new().init::<B, M>());
+        let mut linear: M = LinearConfig::new(4, 4).init();
+        let weight_id = linear.weight.id.clone();
+
+        // The gradient of the weight is the input, which vanishes after the first steps, while
+        // the gradient of the bias stays constant.
+        for step in 0..6 {
+            let scale = if step < 2 { 1.0 } else { 0.0 };
+            let x = Tensor::<B, 2>::ones([2, 4]).mul_scalar(scale);
+            let grads = GradientsParams::from_grads(linear.forward(x).backward(), &linear);
+            linear = optim.step(0.1, linear, grads);
+        }
+
+        assert_eq!(optim.stalled_params(1e-6, 4), [weight_id.clone()]);
+        assert!(optim.stalled_params(1e-6, 5).is_empty());
+
+        let linear = optim.freeze_stalled(linear, 1e-6, 4);
+        assert!(!linear.weight.is_require_grad());
+        assert!(linear.bias.unwrap().is_require_grad());
+    }
+
+    #[test]
+    fn test_frozen_params_are_skipped() {
+        let mut optim = UpdateRatioMonitorConfig::new().init(SgdConfig::new().init::<B, M>());
+        let mut linear: M = LinearConfig::new(4, 4).init();
+        linear.weight = linear.weight.no_grad();
+        let bias = linear.bias.as_ref().unwrap().val().inner();
+
+        let x = Tensor::<B, 2>::ones([2, 4]);
+        let grads = GradientsParams::from_grads(linear.forward(x).backward(), &linear);
+        let linear = optim.step(0.1, linear, grads);
+
+        // Only the bias is updated, so the average is its own ratio, and the frozen weight doesn't
+        // stall.
+        let update = linear.bias.unwrap().val().inner().sub(bias.clone());
+        let expected = tensor_norm(update, &NormKind::L2)
+            .div(tensor_norm(bias, &NormKind::L2))
+            .into_scalar()
+            .elem::<f64>();
+        assert!((optim.update_ratio().unwrap() - expected).abs() < 1e-6);
+        assert!(optim.stalled_params(1e-6, 1).is_empty());
+    }
+}
@@ -0,0 +1,220 @@
+use crate::{record::Record, LearningRate};
+
+use super::SimpleOptimizer;
+use crate::tensor::Tensor;
+use alloc::vec::Vec;
+use burn_tensor::backend::Backend;
+use core::marker::PhantomData;
+
+/// State of a [custom optimizer](CustomOptimizer), chosen by the user, e.g. `()` for a stateless
+/// update rule, a tensor for a momentum or a vector of tensors for the two moments of Adam.
+///
+/// The tensors of the state are flattened, like the parameter given to the update rule.
+pub trait CustomState<B: Backend>: Record + Clone + 'static {
+    /// Move the tensors of the state to the given device.
+    fn to_device(self, device: &B::Device) -> Self;
+
+    /// The number of elements of all the tensors of the state.
+    fn num_elements(&self) -> usize;
+}
+
+impl<B: Backend> CustomState<B> for () {
+    fn to_device(self, _device: &B::Device) -> Self {}
+
+    fn num_elements(&self) -> usize {
+        0
+    }
+}
+
+impl<B: Backend> CustomState<B> for Tensor<B, 1> {
+    fn to_device(self, device: &B::Device) -> Self {
+        Tensor::to_device(self, device)
+    }
+
+    fn num_elements(&self) -> usize {
+        self.shape().num_elements()
+    }
+}
+
+impl<B: Backend> CustomState<B> for Vec<Tensor<B, 1>> {
+    fn to_device(self, device: &B::Device) -> Self {
+        self.into_iter()
+            .map(|tensor| tensor.to_device(device))
+            .collect()
+    }
+
+    fn num_elements(&self) -> usize {
+        self.iter()
+            .map(|tensor| tensor.shape().num_elements())
+            .sum()
+    }
+}
+
+/// [Simple optimizer](SimpleOptimizer) whose update rule is a closure, to prototype an update rule
+/// without writing a dedicated optimizer.
+///
+/// The closure receives the learning rate, the parameter, its gradient and its
+/// [state](CustomState), and returns the updated parameter with its new state, `None` to keep no
+/// state. The parameter and the gradient are flattened, so a single closure works for parameters
+/// of any rank.
+///
+/// # Notes
+///
+/// The state is flattened too, so it isn't adapted when it's
+/// [cloned](crate::optim::Optimizer::clone_state_to) to a parameter with another shape.
+pub struct CustomOptimizer<B, S, F> {
+    step: F,
+    phantom: PhantomData<(B, S)>,
+}
+
+impl<B, S, F> CustomOptimizer<B, S, F>
+where
+    B: Backend,
+    S: CustomState<B>,
+    F: Fn(LearningRate, Tensor<B, 1>, Tensor<B, 1>, Option<S>) -> (Tensor<B, 1>, Option<S>)
+        + Send
+        + Sync,
+{
+    /// Create a custom optimizer with the given update rule.
+    pub fn new(step: F) -> Self {
+        Self {
+            step,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<B, S, F> SimpleOptimizer<B> for CustomOptimizer<B, S, F>
+where
+    B: Backend,
+    S: CustomState<B>,
+    F: Fn(LearningRate, Tensor<B, 1>, Tensor<B, 1>, Option<S>) -> (Tensor<B, 1>, Option<S>)
+        + Send
+        + Sync,
+{
+    type State<const D: usize> = S;
+
+    fn step<const D: usize>(
+        &self,
+        lr: LearningRate,
+        tensor: Tensor<B, D>,
+        grad: Tensor<B, D>,
+        state: Option<Self::State<D>>,
+    ) -> (Tensor<B, D>, Option<Self::State<D>>) {
+        let shape = tensor.shape();
+        let num_elements = shape.num_elements();
+
+        let (tensor, state) = (self.step)(
+            lr,
+            tensor.reshape([num_elements]),
+            grad.reshape([num_elements]),
+            state,
+        );
+
+        (tensor.reshape(shape), state)
+    }
+
+    fn to_device<const D: usize>(state: Self::State<D>, device: &B::Device) -> Self::State<D> {
+        CustomState::to_device(state, device)
+    }
+
+    fn state_map_tensors<const D: usize, F2>(state: Self::State<D>, _func: F2) -> Self::State<D>
+    where
+        F2: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        // The tensors of the state are flattened, so they don't have the shape of the parameter.
+        state
+    }
+
+    fn state_num_elements<const D: usize>(state: &Self::State<D>) -> usize {
+        state.num_elements()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as burn;
+    use crate::nn::{Linear, LinearConfig};
+    use crate::optim::adaptor::OptimizerAdaptor;
+    use crate::optim::{GradientsParams, Optimizer, SgdConfig};
+    use crate::tensor::Distribution;
+    use crate::{TestAutodiffBackend, TestBackend};
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    static NUM_MOVED: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Record, Clone)]
+    struct MovedState<B: Backend> {
+        grad: Tensor<B, 1>,
+    }
+
+    impl<B: Backend> CustomState<B> for MovedState<B> {
+        fn to_device(self, device: &B::Device) -> Self {
+            NUM_MOVED.fetch_add(1, Ordering::Relaxed);
+            Self {
+                grad: self.grad.to_device(device),
+            }
+        }
+
+        fn num_elements(&self) -> usize {
+            self.grad.shape().num_elements()
+        }
+    }
+
+    #[test]
+    fn test_to_device_moves_the_state_of_every_param() {
+        let optim = CustomOptimizer::<TestBackend, MovedState<TestBackend>, _>::new(
+            |lr, tensor, grad, _state| {
+                let tensor = tensor.sub(grad.clone().mul_scalar(lr));
+                (tensor, Some(MovedState { grad }))
+            },
+        );
+        let mut optim: OptimizerAdaptor<_, Linear<TestAutodiffBackend>, _> =
+            OptimizerAdaptor::from(optim);
+        let linear: Linear<TestAutodiffBackend> = LinearConfig::new(4, 3).init();
+        let x = Tensor::<TestAutodiffBackend, 2>::random([2, 4], Distribution::Default);
+        let grads = GradientsParams::from_grads(linear.forward(x).backward(), &linear);
+        let _linear = optim.step(0.1, linear, grads);
+
+        // The test backend has a single device, so the calls are counted instead of the devices.
+        let num_moved = NUM_MOVED.load(Ordering::Relaxed);
+        let optim = optim.to_device(&Default::default());
+
+        assert_eq!(NUM_MOVED.load(Ordering::Relaxed) - num_moved, 2);
+        assert_eq!(optim.num_params(), 2);
+    }
+
+    #[test]
+    fn test_custom_optimizer_matches_builtin_sgd() {
+        let sgd = CustomOptimizer::<TestBackend, (), _>::new(|lr, tensor, grad, _state| {
+            (tensor.sub(grad.mul_scalar(lr)), None)
+        });
+        let mut optim_custom: OptimizerAdaptor<_, Linear<TestAutodiffBackend>, _> =
+            OptimizerAdaptor::from(sgd);
+        let mut optim_builtin = SgdConfig::new().init();
+        let mut linear_custom: Linear<TestAutodiffBackend> = LinearConfig::new(4, 3).init();
+        let mut linear_builtin = linear_custom.clone();
+
+        for _ in 0..3 {
+            let x = Tensor::<TestAutodiffBackend, 2>::random([2, 4], Distribution::Default);
+            let grads = linear_custom.forward(x.clone()).backward();
+            let grads = GradientsParams::from_grads(grads, &linear_custom);
+            linear_custom = optim_custom.step(0.1, linear_custom, grads);
+            let grads = linear_builtin.forward(x).backward();
+            let grads = GradientsParams::from_grads(grads, &linear_builtin);
+            linear_builtin = optim_builtin.step(0.1, linear_builtin, grads);
+        }
+
+        linear_custom
+            .weight
+            .to_data()
+            .assert_approx_eq(&linear_builtin.weight.to_data(), 6);
+        linear_custom
+            .bias
+            .unwrap()
+            .to_data()
+            .assert_approx_eq(&linear_builtin.bias.unwrap().to_data(), 6);
+        assert_eq!(optim_custom.num_params(), 0);
+    }
+}
@@ -0,0 +1,226 @@
+use crate as burn;
+
+use super::{base::delegate_optimizer, GradientsParams, Optimizer};
+use crate::config::Config;
+use crate::module::{AutodiffModule, ModuleVisitor, ParamId};
+use crate::tensor::{backend::AutodiffBackend, Tensor};
+use crate::LearningRate;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use hashbrown::{HashMap, HashSet};
+use std::collections::VecDeque;
+
+/// Configuration to create a [dead gradient monitor](DeadGradientMonitor).
+#[derive(Config)]
+pub struct DeadGradientMonitorConfig {
+    /// The number of most recent steps over which the fraction of zero gradients is computed.
+    #[config(default = 100)]
+    window: usize,
+}
+
+/// Optimizer wrapper measuring the fraction of the gradient elements that are exactly zero, to
+/// detect dead units, e.g. the weights of a layer fed by ReLU activations that are never
+/// positive.
+///
+/// The fraction of each parameter is computed over the gradients of the last steps, see
+/// [dead_fraction](DeadGradientMonitor::dead_fraction), and stays near 1 when the upstream
+/// activations are dead.
+///
+/// # Notes
+///
+/// The number of zero elements is read back from the device after each step.
+pub struct DeadGradientMonitor<O, M, B>
+where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    optim: O,
+    window: usize,
+    /// The number of zero elements and the number of elements of the last gradients of each
+    /// parameter.
+    counts: HashMap<ParamId, VecDeque<(usize, usize)>>,
+    phantom: PhantomData<(M, B)>,
+}
+
+impl DeadGradientMonitorConfig {
+    /// Wrap the given optimizer to monitor the zero elements of the gradients.
+    pub fn init<O, M, B>(&self, optim: O) -> DeadGradientMonitor<O, M, B>
+    where
+        O: Optimizer<M, B>,
+        M: AutodiffModule<B>,
+        B: AutodiffBackend,
+    {
+        assert!(
+            self.window > 0,
+            "The window must contain at least one step."
+        );
+
+        DeadGradientMonitor {
+            optim,
+            window: self.window,
+            counts: HashMap::new(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<O, M, B> DeadGradientMonitor<O, M, B>
+where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    /// The fraction of the elements of the gradients of the parameter that were exactly zero
+    /// over the window, if the parameter had a gradient.
+    pub fn dead_fraction(&self, id: &ParamId) -> Option<f64> {
+        let counts = self.counts.get(id)?;
+        let (num_zeros, num_elements) =
+            counts
+                .iter()
+                .fold((0, 0), |(zeros, elements), (num_zeros, num_elements)| {
+                    (zeros + num_zeros, elements + num_elements)
+                });
+
+        match num_elements {
+            0 => None,
+            num_elements => Some(num_zeros as f64 / num_elements as f64),
+        }
+    }
+
+    /// The parameters with a [dead fraction](Self::dead_fraction) of at least the threshold,
+    /// e.g. `0.99`, sorted by id.
+    pub fn dead_params(&self, threshold: f64) -> Vec<ParamId> {
+        let mut params: Vec<ParamId> = self
+            .counts
+            .keys()
+            .filter(|id| {
+                self.dead_fraction(id)
+                    .map_or(false, |fraction| fraction >= threshold)
+            })
+            .cloned()
+            .collect();
+        params.sort();
+        params
+    }
+
+    fn record_counts(&mut self, counts: Vec<(ParamId, Tensor<B::InnerBackend, 1>, usize)>) {
+        if counts.is_empty() {
+            return;
+        }
+
+        // The parameters may live on different devices, their counts are moved to the device of
+        // the first one to be read back with a single transfer.
+        let device = counts[0].1.device();
+        let mut ids = Vec::with_capacity(counts.len());
+        let mut zeros = Vec::with_capacity(counts.len());
+        for (id, num_zeros, num_elements) in counts {
+            ids.push((id, num_elements));
+            zeros.push(num_zeros.to_device(&device));
+        }
+        let zeros = Tensor::cat(zeros, 0).into_data().convert::<f64>().value;
+
+        for ((id, num_elements), num_zeros) in ids.into_iter().zip(zeros) {
+            let counts = self.counts.entry(id).or_default();
+            if counts.len() == self.window {
+                counts.pop_front();
+            }
+            counts.push_back((num_zeros as usize, num_elements));
+        }
+    }
+}
+
+impl<O, M, B> Optimizer<M, B> for DeadGradientMonitor<O, M, B>
+where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    type Record = O::Record;
+
+    fn step(&mut self, lr: LearningRate, module: M, grads: GradientsParams) -> M {
+        let mut counts = Vec::new();
+        module.visit(&mut GradZeroCounter::<B>::new(&grads, &mut counts));
+        self.record_counts(counts);
+
+        self.optim.step(lr, module, grads)
+    }
+
+    fn to_record(&self) -> Self::Record {
+        self.optim.to_record()
+    }
+
+    fn load_record(mut self, record: Self::Record) -> Self {
+        self.optim = self.optim.load_record(record);
+        self
+    }
+
+    delegate_optimizer!(optim);
+}
+
+/// Count the zero elements of the gradient of each parameter, as a tensor of shape `[1]`, with
+/// the number of elements of the gradient.
+#[derive(new)]
+struct GradZeroCounter<'a, B: AutodiffBackend> {
+    grads: &'a GradientsParams,
+    counts: &'a mut Vec<(ParamId, Tensor<B::InnerBackend, 1>, usize)>,
+    #[new(default)]
+    visited: HashSet<ParamId>,
+}
+
+impl<'a, B: AutodiffBackend> ModuleVisitor<B> for GradZeroCounter<'a, B> {
+    fn visit<const D: usize>(&mut self, id: &ParamId, _tensor: &Tensor<B, D>) {
+        if !self.visited.insert(id.clone()) {
+            return;
+        }
+        let Some(grad) = self.grads.get::<B::InnerBackend, D>(id) else {
+            return;
+        };
+
+        let num_elements = grad.shape().num_elements();
+        let num_zeros = grad.equal_elem(0.0).float().sum();
+        self.counts.push((id.clone(), num_zeros, num_elements));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::{Linear, LinearConfig};
+    use crate::optim::SgdConfig;
+    use crate::{TestAutodiffBackend, TestBackend};
+
+    type B = TestAutodiffBackend;
+    type M = Linear<TestAutodiffBackend>;
+
+    #[test]
+    fn test_dead_fraction_of_mostly_zero_gradients() {
+        let mut linear: M = LinearConfig::new(10, 10).with_bias(false).init();
+        let mut optim = DeadGradientMonitorConfig::new()
+            .with_window(2)
+            .init(SgdConfig::new().init::<B, M>());
+        let id = linear.weight.id.clone();
+        let grads = |num_nonzero: usize| {
+            let mut values = [0.0; 100];
+            values[..num_nonzero].fill(1.0);
+            let grad = Tensor::<TestBackend, 1>::from_floats(values).reshape([10, 10]);
+            let mut grads = GradientsParams::new();
+            grads.register(id.clone(), grad);
+            grads
+        };
+        assert_eq!(optim.dead_fraction(&id), None);
+
+        linear = optim.step(0.1, linear, grads(10));
+        assert_eq!(optim.dead_fraction(&id), Some(0.9));
+        assert!(optim.dead_params(0.95).is_empty());
+
+        // The window contains 90 + 100 zero elements out of 200.
+        linear = optim.step(0.1, linear, grads(0));
+        assert_eq!(optim.dead_fraction(&id), Some(0.95));
+        assert_eq!(optim.dead_params(0.95), vec![id.clone()]);
+
+        // The first step left the window.
+        let _linear = optim.step(0.1, linear, grads(50));
+        assert_eq!(optim.dead_fraction(&id), Some(0.75));
+    }
+}
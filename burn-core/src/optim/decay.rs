@@ -1,16 +1,86 @@
 use burn_tensor::backend::Backend;
 
+use super::{validation::validate_non_negative, Transform};
 use crate as burn;
 use crate::record::Record;
+use crate::LearningRate;
 
-use crate::config::Config;
+use crate::config::{Config, ConfigError, ValidateConfig};
+use crate::module::ParamId;
 use crate::tensor::{ElementConversion, Tensor};
+use hashbrown::HashMap;
+
+/// Kind of penalty of the [weight decay](WeightDecay).
+#[derive(Config, Debug, PartialEq)]
+pub enum WeightDecayKind {
+    /// L2 penalty, adding `penalty * tensor` to the gradient.
+    L2,
+
+    /// L1 penalty, adding the subgradient `penalty * sign(tensor)` to the gradient, which promotes
+    /// sparsity. The subgradient is zero for a parameter at zero.
+    L1,
+
+    /// L1 penalty applied as a proximal step: the gradient is left as is, and the parameters are
+    /// shrunk toward zero by `lr * penalty` after the step, the ones with a smaller magnitude being
+    /// set to exactly zero.
+    L1Proximal,
+
+    /// L2 penalty toward the initial values of the parameters, adding `penalty * (tensor - init)`
+    /// to the gradient, e.g. to stay close to the pretrained weights when fine-tuning.
+    ///
+    /// The initial values are given with
+    /// [with_weight_decay_init](crate::optim::adaptor::OptimizerAdaptor::with_weight_decay_init),
+    /// the parameters without one are decayed toward zero like with the [L2](Self::L2) penalty.
+    /// Only the [optimizer adaptor](crate::optim::adaptor::OptimizerAdaptor) knows the initial
+    /// values, so a simple optimizer used on its own can't decay toward them.
+    L2ToInit,
+}
 
 /// Configuration to create [weight decay](WeightDecay).
 #[derive(Config)]
 pub struct WeightDecayConfig {
-    /// L2 penalty.
+    /// The penalty coefficient.
     pub penalty: f64,
+    /// Minimum rank of the tensors to decay, e.g. `2` to skip biases and other vectors.
+    #[config(default = 0)]
+    pub decay_min_rank: usize,
+    /// The [kind](WeightDecayKind) of penalty.
+    #[config(default = "WeightDecayKind::L2")]
+    pub kind: WeightDecayKind,
+    /// The number of steps over which the penalty ramps up linearly from zero, see
+    /// [WeightDecayWarmup]. The full penalty is applied from the first step by default.
+    #[config(default = 0)]
+    pub warmup_steps: usize,
+}
+
+impl WeightDecayConfig {
+    /// The decay applied by a simple optimizer wrapped by the
+    /// [optimizer adaptor](crate::optim::adaptor::OptimizerAdaptor): the decay
+    /// [toward the initial values](WeightDecayKind::L2ToInit) is an [L2](WeightDecayKind::L2)
+    /// penalty, completed by the adaptor with the initial values.
+    pub(crate) fn within_adaptor(&self) -> Self {
+        let mut config = self.clone();
+        if config.kind == WeightDecayKind::L2ToInit {
+            config.kind = WeightDecayKind::L2;
+        }
+        config
+    }
+
+    /// The decay applied by a simple optimizer wrapped by the
+    /// [optimizer adaptor](crate::optim::adaptor::OptimizerAdaptor), `None` during a
+    /// [warmup](WeightDecayWarmup) since the adaptor applies the warmed up decay itself.
+    pub(crate) fn for_simple_optimizer(&self) -> Option<Self> {
+        match self.warmup_steps {
+            0 => Some(self.within_adaptor()),
+            _ => None,
+        }
+    }
+}
+
+impl ValidateConfig for WeightDecayConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        validate_non_negative("weight_decay.penalty", self.penalty)
+    }
 }
 
 /// State of [weight decay](WeightDecay).
@@ -22,13 +92,28 @@ pub struct WeightDecayState<B: Backend, const D: usize> {
 /// Weight decay implementation that transforms gradients.
 pub struct WeightDecay<B: Backend> {
     penalty: B::FloatElem,
+    decay_min_rank: usize,
+    kind: WeightDecayKind,
 }
 
 impl<B: Backend> WeightDecay<B> {
     /// Creates a new [weight decay](WeightDecay) from a [config](WeightDecayConfig).
+    ///
+    /// # Panics
+    ///
+    /// Panics for the decay [toward the initial values](WeightDecayKind::L2ToInit), which only
+    /// the [optimizer adaptor](crate::optim::adaptor::OptimizerAdaptor) can apply.
     pub fn new(config: &WeightDecayConfig) -> Self {
+        assert!(
+            config.kind != WeightDecayKind::L2ToInit,
+            "The weight decay toward the initial values requires them, so it's only supported by \
+             the optimizer adaptor."
+        );
+
         Self {
             penalty: config.penalty.elem(),
+            decay_min_rank: config.decay_min_rank,
+            kind: config.kind.clone(),
         }
     }
 
@@ -41,13 +126,272 @@ impl<B: Backend> WeightDecay<B> {
     ///
     /// # Returns
     ///
-    /// * `grad` - Transformed gradient.
+    /// * `grad` - Transformed gradient, unchanged for tensors with a rank lower than the
+    ///   minimum rank.
     pub fn transform<const D: usize>(
         &self,
         grad: Tensor<B, D>,
         tensor: Tensor<B, D>,
     ) -> Tensor<B, D> {
-        tensor.mul_scalar(self.penalty).add(grad)
+        if D < self.decay_min_rank {
+            return grad;
+        }
+
+        match self.kind {
+            WeightDecayKind::L2 => tensor.mul_scalar(self.penalty).add(grad),
+            WeightDecayKind::L1 => {
+                let sign = tensor
+                    .zeros_like()
+                    .mask_fill(tensor.clone().greater_elem(0.0), 1.0)
+                    .mask_fill(tensor.lower_elem(0.0), -1.0);
+
+                sign.mul_scalar(self.penalty).add(grad)
+            }
+            WeightDecayKind::L1Proximal => grad,
+            WeightDecayKind::L2ToInit => unreachable!("Rejected when created."),
+        }
+    }
+
+    /// Applies the proximal step of the [L1 proximal](WeightDecayKind::L1Proximal) penalty to the
+    /// parameter after the optimizer step, and leaves it unchanged for the other kinds.
+    ///
+    /// # Arguments
+    ///
+    /// * `lr` - The learning rate of the step.
+    /// * `tensor` - Tensor param after the step.
+    ///
+    /// # Returns
+    ///
+    /// * `tensor` - The parameter shrunk toward zero by `lr * penalty`, with the components of a
+    ///   smaller magnitude set to zero.
+    pub fn proximal<const D: usize>(&self, lr: LearningRate, tensor: Tensor<B, D>) -> Tensor<B, D> {
+        if self.kind != WeightDecayKind::L1Proximal || D < self.decay_min_rank {
+            return tensor;
+        }
+
+        // Soft-thresholding: sign(x) * max(|x| - threshold, 0).
+        let threshold = lr * self.penalty.elem::<f64>();
+        let shrinkage = tensor.clone().clamp(-threshold, threshold);
+
+        tensor.sub(shrinkage)
+    }
+}
+
+impl<B: Backend> Transform<B> for WeightDecay<B> {
+    type State<const D: usize> = ();
+
+    fn apply<const D: usize>(
+        &self,
+        _lr: LearningRate,
+        tensor: &Tensor<B, D>,
+        grad: Tensor<B, D>,
+        _state: Option<Self::State<D>>,
+    ) -> (Tensor<B, D>, Self::State<D>) {
+        (self.transform(grad, tensor.clone()), ())
+    }
+
+    fn to_device<const D: usize>(state: Self::State<D>, _device: &B::Device) -> Self::State<D> {
+        state
+    }
+
+    fn state_map_tensors<const D: usize, F>(state: Self::State<D>, _func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        state
+    }
+
+    fn state_num_elements<const D: usize>(_state: &Self::State<D>) -> usize {
+        0
+    }
+}
+
+/// Weight decay coefficients overriding the decay of the optimizer for some parameters, e.g. to
+/// decay the classifier head more strongly, see
+/// [with_weight_decay_overrides](crate::optim::adaptor::OptimizerAdaptor::with_weight_decay_overrides).
+///
+/// Parameters without an override keep the decay of the optimizer, and an override has the same
+/// [kind](WeightDecayKind) as the decay of the optimizer. The coupled [L2](WeightDecayKind::L2)
+/// and [L1](WeightDecayKind::L1) overrides replace the decay added to the gradient, while the
+/// [L1 proximal](WeightDecayKind::L1Proximal) overrides add a proximal step after the one of the
+/// optimizer, so they can't be smaller than the decay of the optimizer. They don't apply to
+/// decoupled weight decay like [AdamW](crate::optim::AdamW).
+#[derive(Clone, Debug, Default)]
+pub struct WeightDecayOverrides {
+    params: HashMap<ParamId, f64>,
+}
+
+impl WeightDecayOverrides {
+    /// Creates empty overrides.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the decay of parameters, replacing their previous override if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The ids of the parameters with their penalty, e.g. a map.
+    ///
+    /// # Returns
+    ///
+    /// The overrides.
+    pub fn with_params<I: IntoIterator<Item = (ParamId, f64)>>(mut self, params: I) -> Self {
+        self.params.extend(params);
+        self
+    }
+
+    /// Get the decay of the given [parameter id](ParamId), `None` when it keeps the decay of the
+    /// optimizer.
+    pub fn penalty(&self, id: &ParamId) -> Option<f64> {
+        self.params.get(id).copied()
+    }
+
+    /// Transforms a gradient, before the optimizer adds its own decay.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the parameter.
+    /// * `weight_decay` - The weight decay of the optimizer, `None` without weight decay.
+    /// * `grad` - Gradient to transform.
+    /// * `tensor` - Tensor param of the last iteration.
+    ///
+    /// # Returns
+    ///
+    /// * `grad` - The gradient with the difference between the override and the decay of the
+    ///   optimizer, unchanged for parameters without an override and for the
+    ///   [L1 proximal](WeightDecayKind::L1Proximal) decay.
+    pub fn transform<B: Backend, const D: usize>(
+        &self,
+        id: &ParamId,
+        weight_decay: Option<&WeightDecayConfig>,
+        grad: Tensor<B, D>,
+        tensor: Tensor<B, D>,
+    ) -> Tensor<B, D> {
+        match self.difference::<D>(id, weight_decay) {
+            Some(config) => WeightDecay::<B>::new(&config.within_adaptor()).transform(grad, tensor),
+            None => grad,
+        }
+    }
+
+    /// Applies the proximal step of the override, after the optimizer step.
+    ///
+    /// Since two successive proximal steps shrink the parameter by the sum of their thresholds,
+    /// only the difference between the override and the decay of the optimizer is applied.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the parameter.
+    /// * `weight_decay` - The weight decay of the optimizer, `None` without weight decay.
+    /// * `lr` - The learning rate of the step.
+    /// * `tensor` - Tensor param after the step.
+    ///
+    /// # Returns
+    ///
+    /// * `tensor` - The parameter shrunk toward zero by the difference, unchanged for parameters
+    ///   without an override and for the coupled decays.
+    ///
+    /// # Panics
+    ///
+    /// When the override is smaller than the [L1 proximal](WeightDecayKind::L1Proximal) decay of
+    /// the optimizer, which can't be undone.
+    pub fn proximal<B: Backend, const D: usize>(
+        &self,
+        id: &ParamId,
+        weight_decay: Option<&WeightDecayConfig>,
+        lr: LearningRate,
+        tensor: Tensor<B, D>,
+    ) -> Tensor<B, D> {
+        match self.difference::<D>(id, weight_decay) {
+            Some(config) if config.kind == WeightDecayKind::L1Proximal => {
+                assert!(
+                    config.penalty >= 0.0,
+                    "The L1 proximal weight decay of the parameter {id} can't be overridden by a \
+                     smaller penalty, the proximal step of the optimizer can't be undone."
+                );
+                WeightDecay::<B>::new(&config).proximal(lr, tensor)
+            }
+            _ => tensor,
+        }
+    }
+
+    /// The decay between the override of the parameter and the decay of the optimizer, with the
+    /// kind of the decay of the optimizer.
+    fn difference<const D: usize>(
+        &self,
+        id: &ParamId,
+        weight_decay: Option<&WeightDecayConfig>,
+    ) -> Option<WeightDecayConfig> {
+        let penalty = self.penalty(id)?;
+        let (kind, penalty_optim) = match weight_decay {
+            // Tensors below the minimum rank aren't decayed by the optimizer.
+            Some(config) if D < config.decay_min_rank => (config.kind.clone(), 0.0),
+            Some(config) => (config.kind.clone(), config.penalty),
+            None => (WeightDecayKind::L2, 0.0),
+        };
+
+        Some(WeightDecayConfig::new(penalty - penalty_optim).with_kind(kind))
+    }
+}
+
+/// Linear warmup of the [weight decay](WeightDecay), ramping the penalty from zero to its target
+/// over the first [warmup steps](WeightDecayConfig::warmup_steps) to avoid over-regularizing the
+/// parameters early in training, see
+/// [with_weight_decay_warmup](crate::optim::adaptor::OptimizerAdaptor::with_weight_decay_warmup).
+///
+/// The penalty at step `k` is `penalty * k / warmup_steps`, so no decay is applied at the first
+/// step and the full penalty from step `warmup_steps` on. The warmup scales the penalty
+/// coefficient, so it applies to every [kind](WeightDecayKind) of decay, the
+/// [L1 proximal](WeightDecayKind::L1Proximal) one included.
+#[derive(Clone, Debug)]
+pub struct WeightDecayWarmup {
+    config: WeightDecayConfig,
+    step: usize,
+}
+
+impl WeightDecayWarmup {
+    /// Creates the warmup of the weight decay of the given [config](WeightDecayConfig), which must
+    /// be the weight decay of the optimizer.
+    pub fn new(config: &WeightDecayConfig) -> Self {
+        Self {
+            config: config.clone(),
+            step: 0,
+        }
+    }
+
+    /// The penalty applied at the current step.
+    pub fn penalty(&self) -> f64 {
+        let warmup_steps = self.config.warmup_steps;
+        if self.step >= warmup_steps {
+            return self.config.penalty;
+        }
+
+        self.config.penalty * self.step as f64 / warmup_steps as f64
+    }
+
+    /// Advances the warmup to the next step.
+    pub fn step(&mut self) {
+        self.step = usize::min(self.step + 1, self.config.warmup_steps);
+    }
+
+    /// The decay applied at the current step, with the warmed up penalty.
+    ///
+    /// The decay [toward the initial values](WeightDecayKind::L2ToInit) is an
+    /// [L2](WeightDecayKind::L2) penalty, completed by the adaptor with the initial values.
+    pub fn config(&self) -> WeightDecayConfig {
+        let mut config = self.config.within_adaptor();
+        config.penalty = self.penalty();
+        config
+    }
+
+    /// The number of steps of the warmup performed, at most the number of warmup steps.
+    pub(crate) fn num_steps(&self) -> usize {
+        self.step
+    }
+
+    /// Moves the warmup to the given step, e.g. when resuming a training.
+    pub(crate) fn seek(&mut self, step: usize) {
+        self.step = usize::min(step, self.config.warmup_steps);
     }
 }
 
@@ -66,3 +410,341 @@ impl<B: Backend, const D: usize> WeightDecayState<B, D> {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        module::Module,
+        nn::{Linear, LinearConfig},
+        optim::adaptor::OptimizerAdaptor,
+        optim::{GradientsParams, Optimizer, SgdConfig},
+        tensor::Data,
+        TestAutodiffBackend, TestBackend,
+    };
+
+    #[test]
+    fn test_weight_decay_config_loads_configs_saved_without_the_new_fields() {
+        let config = WeightDecayConfig::load_binary(br#"{"penalty": 0.1}"#).unwrap();
+
+        assert_eq!(config.penalty, 0.1);
+        assert_eq!(config.decay_min_rank, 0);
+        assert_eq!(config.kind, WeightDecayKind::L2);
+        assert_eq!(config.warmup_steps, 0);
+    }
+
+    #[test]
+    fn test_weight_decay_skips_tensors_below_min_rank() {
+        let linear: Linear<TestAutodiffBackend> = LinearConfig::new(4, 4).init();
+        let mut optim = SgdConfig::new()
+            .with_weight_decay(Some(WeightDecayConfig::new(0.5).with_decay_min_rank(2)))
+            .init();
+        let mut grads = GradientsParams::new();
+        grads.register(
+            linear.weight.id.clone(),
+            linear.weight.val().inner().zeros_like(),
+        );
+        let bias = linear.bias.as_ref().unwrap();
+        grads.register(bias.id.clone(), bias.val().inner().zeros_like());
+
+        let record_before = linear.clone().into_record();
+        let record_after = optim.step(1.0, linear, grads).into_record();
+
+        record_after
+            .bias
+            .unwrap()
+            .to_data()
+            .assert_approx_eq(&record_before.bias.unwrap().to_data(), 5);
+        record_after
+            .weight
+            .to_data()
+            .assert_approx_eq(&record_before.weight.val().mul_scalar(0.5).into_data(), 5);
+    }
+
+    #[test]
+    fn test_l1_weight_decay_adds_the_subgradient() {
+        let optim = SgdConfig::new()
+            .with_weight_decay(Some(
+                WeightDecayConfig::new(0.1).with_kind(WeightDecayKind::L1),
+            ))
+            .init_simple::<TestBackend>();
+        let tensor = Tensor::<TestBackend, 1>::from_floats([2.0, -0.05, 0.0]);
+
+        // The decay doesn't depend on the magnitude, and parameters at zero stay at zero.
+        let (tensor, _) = optim.step(0.5, tensor, Tensor::from_floats([0.2, 0.0, 0.0]), None);
+        tensor
+            .to_data()
+            .assert_approx_eq(&Data::from([1.85, 0.0, 0.0]), 5);
+    }
+
+    #[test]
+    fn test_l1_proximal_weight_decay_zeros_small_weights() {
+        let optim = SgdConfig::new()
+            .with_weight_decay(Some(
+                WeightDecayConfig::new(0.2).with_kind(WeightDecayKind::L1Proximal),
+            ))
+            .init_simple::<TestBackend>();
+        let tensor = Tensor::<TestBackend, 1>::from_floats([0.5, -0.05, 0.08, -0.3]);
+
+        // The gradient step comes first, then the weights are shrunk by 0.5 * 0.2 = 0.1, the
+        // smaller ones being set to exactly zero instead of oscillating around it.
+        let grad = Tensor::from_floats([0.0, 0.0, -0.04, 0.0]);
+        let (tensor, _) = optim.step(0.5, tensor, grad, None);
+        tensor
+            .to_data()
+            .assert_approx_eq(&Data::from([0.4, 0.0, 0.0, -0.2]), 5);
+    }
+
+    #[test]
+    fn test_weight_decay_warmup_ramps_the_penalty() {
+        let config = WeightDecayConfig::new(0.5).with_warmup_steps(4);
+        let mut warmup = WeightDecayWarmup::new(&config);
+        let mut penalties = Vec::new();
+        for _ in 0..6 {
+            penalties.push(warmup.penalty());
+            warmup.step();
+        }
+        assert_eq!(penalties, vec![0.0, 0.125, 0.25, 0.375, 0.5, 0.5]);
+
+        let linear: Linear<TestAutodiffBackend> = LinearConfig::new(4, 4).with_bias(false).init();
+        let mut optim = SgdConfig::new()
+            .with_weight_decay(Some(config))
+            .init::<TestAutodiffBackend, Linear<TestAutodiffBackend>>();
+        let id = linear.weight.id.clone();
+        let zeros = linear.weight.val().inner().zeros_like();
+        let grads = || {
+            let mut grads = GradientsParams::new();
+            grads.register(id.clone(), zeros.clone());
+            grads
+        };
+
+        // Without gradient, the weight only shrinks by the decay of the step.
+        let weight = linear.weight.val().inner();
+        let mut linear = optim.step(1.0, linear, grads());
+        linear
+            .weight
+            .val()
+            .inner()
+            .to_data()
+            .assert_approx_eq(&weight.to_data(), 5);
+
+        for _ in 1..4 {
+            linear = optim.step(1.0, linear, grads());
+        }
+        let weight = linear.weight.val().inner();
+        let linear = optim.step(1.0, linear, grads());
+        linear
+            .weight
+            .val()
+            .inner()
+            .to_data()
+            .assert_approx_eq(&weight.mul_scalar(0.5).to_data(), 5);
+    }
+
+    #[test]
+    fn test_weight_decay_warmup_ramps_the_l1_proximal_penalty() {
+        let linear: Linear<TestAutodiffBackend> = LinearConfig::new(4, 4).with_bias(false).init();
+        let mut optim = SgdConfig::new()
+            .with_weight_decay(Some(
+                WeightDecayConfig::new(0.2)
+                    .with_kind(WeightDecayKind::L1Proximal)
+                    .with_warmup_steps(2),
+            ))
+            .init::<TestAutodiffBackend, Linear<TestAutodiffBackend>>();
+        let id = linear.weight.id.clone();
+        let zeros = linear.weight.val().inner().zeros_like();
+        let grads = || {
+            let mut grads = GradientsParams::new();
+            grads.register(id.clone(), zeros.clone());
+            grads
+        };
+
+        // No shrinkage at the first step, then half of the full one.
+        let weight = linear.weight.val().inner();
+        let linear = optim.step(1.0, linear, grads());
+        linear
+            .weight
+            .val()
+            .inner()
+            .to_data()
+            .assert_approx_eq(&weight.to_data(), 5);
+
+        let linear = optim.step(1.0, linear, grads());
+        let expected = weight.clone().sub(weight.clamp(-0.1, 0.1));
+        linear
+            .weight
+            .val()
+            .inner()
+            .to_data()
+            .assert_approx_eq(&expected.to_data(), 5);
+    }
+
+    #[test]
+    fn test_weight_decay_to_init_pulls_toward_the_initial_values() {
+        let linear: Linear<TestAutodiffBackend> = LinearConfig::new(4, 4).with_bias(false).init();
+        let config = || {
+            SgdConfig::new().with_weight_decay(Some(
+                WeightDecayConfig::new(0.5).with_kind(WeightDecayKind::L2ToInit),
+            ))
+        };
+        let mut optim = config().init().with_weight_decay_init(&linear);
+        let id = linear.weight.id.clone();
+        let init = linear.weight.val().inner();
+        let grads = |grad: Tensor<TestBackend, 2>| {
+            let mut grads = GradientsParams::new();
+            grads.register(id.clone(), grad);
+            grads
+        };
+        let assert_weight = |linear: &Linear<TestAutodiffBackend>, offset: f64| {
+            linear
+                .weight
+                .val()
+                .inner()
+                .to_data()
+                .assert_approx_eq(&init.clone().sub_scalar(offset).to_data(), 5);
+        };
+
+        // At its initial value, the weight isn't decayed.
+        let linear = optim.step(1.0, linear, grads(init.ones_like()));
+        assert_weight(&linear, 1.0);
+
+        // Without gradient, the weight moves halfway back to its initial value, not toward zero.
+        let record = optim.to_record();
+        let linear_resumed = optim.step(1.0, linear.clone(), grads(init.zeros_like()));
+        assert_weight(&linear_resumed, 0.5);
+
+        // The initial values are part of the record.
+        let mut optim = config().init().load_record(record);
+        let linear = optim.step(1.0, linear, grads(init.zeros_like()));
+        assert_weight(&linear, 0.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "only supported by the optimizer adaptor")]
+    fn test_weight_decay_to_init_is_rejected_without_the_adaptor() {
+        SgdConfig::new()
+            .with_weight_decay(Some(
+                WeightDecayConfig::new(0.5).with_kind(WeightDecayKind::L2ToInit),
+            ))
+            .init_simple::<TestBackend>();
+    }
+
+    #[derive(Module, Debug)]
+    struct TwoLayers<B: Backend> {
+        embedding: Linear<B>,
+        head: Linear<B>,
+    }
+
+    #[test]
+    fn test_weight_decay_overrides_scale_each_decay() {
+        let model = TwoLayers::<TestAutodiffBackend> {
+            embedding: LinearConfig::new(4, 4).with_bias(false).init(),
+            head: LinearConfig::new(4, 2).init(),
+        };
+        let decay = WeightDecayConfig::new(0.1);
+        let overrides = WeightDecayOverrides::new().with_params([
+            (model.embedding.weight.id.clone(), 0.2),
+            (model.head.weight.id.clone(), 0.6),
+        ]);
+        let mut optim: OptimizerAdaptor<_, TwoLayers<TestAutodiffBackend>, _> =
+            OptimizerAdaptor::from(
+                SgdConfig::new()
+                    .with_weight_decay(Some(decay.clone()))
+                    .init_simple::<TestBackend>(),
+            )
+            .with_weight_decay(decay)
+            .with_weight_decay_overrides(overrides);
+
+        // Without gradient, each parameter only shrinks by its own decay.
+        let mut grads = GradientsParams::new();
+        grads.register(
+            model.embedding.weight.id.clone(),
+            model.embedding.weight.val().inner().zeros_like(),
+        );
+        grads.register(
+            model.head.weight.id.clone(),
+            model.head.weight.val().inner().zeros_like(),
+        );
+        let bias = model.head.bias.as_ref().unwrap();
+        grads.register(bias.id.clone(), bias.val().inner().zeros_like());
+
+        let record_before = model.clone().into_record();
+        let record_after = optim.step(1.0, model, grads).into_record();
+
+        let expected = [
+            (record_before.embedding.weight.val(), 0.8),
+            (record_before.head.weight.val(), 0.4),
+        ];
+        let actual = [record_after.embedding.weight, record_after.head.weight];
+        for ((before, scale), after) in expected.into_iter().zip(actual) {
+            after
+                .to_data()
+                .assert_approx_eq(&before.mul_scalar(scale).into_data(), 5);
+        }
+        // The bias has no override, so it keeps the decay of the optimizer.
+        record_after.head.bias.unwrap().to_data().assert_approx_eq(
+            &record_before
+                .head
+                .bias
+                .unwrap()
+                .val()
+                .mul_scalar(0.9)
+                .into_data(),
+            5,
+        );
+    }
+
+    fn step_with_override(
+        decay: WeightDecayConfig,
+        penalty: f64,
+    ) -> (Tensor<TestBackend, 2>, Tensor<TestBackend, 2>) {
+        let linear: Linear<TestAutodiffBackend> = LinearConfig::new(4, 4).with_bias(false).init();
+        let id = linear.weight.id.clone();
+        let mut optim = SgdConfig::new()
+            .with_weight_decay(Some(decay))
+            .init::<TestAutodiffBackend, Linear<TestAutodiffBackend>>()
+            .with_weight_decay_overrides(
+                WeightDecayOverrides::new().with_params([(id.clone(), penalty)]),
+            );
+        let mut grads = GradientsParams::new();
+        grads.register(id, linear.weight.val().inner().zeros_like());
+
+        let before = linear.weight.val().inner();
+        let after = optim.step(0.1, linear, grads).weight.val().inner();
+        (before, after)
+    }
+
+    #[test]
+    fn test_weight_decay_overrides_keep_the_l1_kind() {
+        let kind = WeightDecayKind::L1;
+        let (before, after) =
+            step_with_override(WeightDecayConfig::new(0.1).with_kind(kind.clone()), 0.3);
+
+        // Without gradient, the weight only moves by the subgradient of the override.
+        let decay = WeightDecay::<TestBackend>::new(&WeightDecayConfig::new(0.3).with_kind(kind))
+            .transform(before.zeros_like(), before.clone());
+        after
+            .to_data()
+            .assert_approx_eq(&before.sub(decay.mul_scalar(0.1)).into_data(), 5);
+    }
+
+    #[test]
+    fn test_weight_decay_overrides_add_a_proximal_step() {
+        let kind = WeightDecayKind::L1Proximal;
+        let (before, after) =
+            step_with_override(WeightDecayConfig::new(0.1).with_kind(kind.clone()), 0.3);
+
+        // Both proximal steps shrink the weight by the override in total.
+        let expected =
+            WeightDecay::<TestBackend>::new(&WeightDecayConfig::new(0.3).with_kind(kind))
+                .proximal(0.1, before);
+        after.to_data().assert_approx_eq(&expected.into_data(), 5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_weight_decay_overrides_cant_reduce_the_proximal_decay() {
+        let config = WeightDecayConfig::new(0.1).with_kind(WeightDecayKind::L1Proximal);
+        step_with_override(config, 0.0);
+    }
+}
@@ -0,0 +1,116 @@
+use super::{base::delegate_optimizer, GradientsParams, Optimizer};
+use crate::module::AutodiffModule;
+use crate::tensor::backend::AutodiffBackend;
+use crate::LearningRate;
+use core::marker::PhantomData;
+
+/// Optimizer wrapper running the steps of the inner optimizer but discarding the updates of the
+/// parameters, e.g. to validate a training loop without changing the model.
+///
+/// The inner optimizer performs its whole step, including the update of its state, so the timing
+/// stays realistic, then the module given to the step is returned unchanged.
+pub struct DryRun<O, M, B>
+where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    optim: O,
+    phantom: PhantomData<(M, B)>,
+}
+
+impl<O, M, B> DryRun<O, M, B>
+where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    /// Wrap the given optimizer to discard its updates.
+    pub fn new(optim: O) -> Self {
+        Self {
+            optim,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The inner optimizer.
+    pub fn inner(&self) -> &O {
+        &self.optim
+    }
+
+    /// Unwrap the inner optimizer, e.g. to start training with the state of the dry run.
+    pub fn into_inner(self) -> O {
+        self.optim
+    }
+}
+
+impl<O, M, B> Optimizer<M, B> for DryRun<O, M, B>
+where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    type Record = O::Record;
+
+    fn step(&mut self, lr: LearningRate, module: M, grads: GradientsParams) -> M {
+        let _updated = self.optim.step(lr, module.clone(), grads);
+
+        module
+    }
+
+    fn to_record(&self) -> Self::Record {
+        self.optim.to_record()
+    }
+
+    fn load_record(mut self, record: Self::Record) -> Self {
+        self.optim = self.optim.load_record(record);
+        self
+    }
+
+    delegate_optimizer!(optim);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::module::Module;
+    use crate::nn::{Linear, LinearConfig};
+    use crate::optim::AdamConfig;
+    use crate::tensor::{Distribution, Tensor};
+    use crate::TestAutodiffBackend;
+
+    type B = TestAutodiffBackend;
+
+    #[test]
+    fn test_dry_run_keeps_the_module_and_advances_the_state() {
+        let mut linear: Linear<B> = LinearConfig::new(4, 2).init();
+        let record_before = linear.clone().into_record();
+        let weight_id = linear.weight.id.clone();
+        let mut optim = DryRun::new(AdamConfig::new().init());
+
+        for _ in 0..2 {
+            let x = Tensor::<B, 2>::random([3, 4], Distribution::Default);
+            let grads = GradientsParams::from_grads(linear.forward(x).sum().backward(), &linear);
+            linear = optim.step(0.1, linear, grads);
+        }
+
+        let record_after = linear.into_record();
+        assert_eq!(
+            record_after.weight.to_data(),
+            record_before.weight.to_data()
+        );
+        assert_eq!(
+            record_after.bias.unwrap().to_data(),
+            record_before.bias.unwrap().to_data()
+        );
+        assert_eq!(optim.num_params(), 2);
+        assert_eq!(optim.last_updated().len(), 2);
+        let state = optim
+            .into_inner()
+            .to_record()
+            .params
+            .remove(&weight_id)
+            .unwrap();
+        assert_eq!(state.into_state::<2>().momentum.time, 2);
+    }
+}
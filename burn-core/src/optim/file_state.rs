@@ -0,0 +1,367 @@
+use crate::{self as burn, LearningRate};
+
+use super::SimpleOptimizer;
+use crate::config::Config;
+use crate::record::{bin_config, FullPrecisionSettings, PrecisionSettings, Record};
+use crate::tensor::Tensor;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use burn_common::id::IdGenerator;
+use burn_tensor::backend::Backend;
+use core::sync::atomic::{AtomicBool, Ordering};
+use memmap2::{Mmap, MmapMut};
+use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+
+/// Configuration to create the [file-backed state](FileBackedState) wrapper.
+#[derive(Config)]
+pub struct FileBackedStateConfig {
+    /// The directory of the state files, created if missing.
+    directory: String,
+}
+
+/// Simple optimizer wrapper keeping the state of the inner optimizer in memory-mapped files
+/// instead of memory, for optimizer states too large to fit in RAM.
+///
+/// The state of each parameter is written to a new file after each step, and mapped back at the
+/// next step, so only the state of the parameter being updated is in memory. The
+/// [record](crate::optim::Optimizer::to_record) of the optimizer only contains the paths of the
+/// files, which must be kept to restore the optimizer.
+///
+/// The files are never modified once written, so a record keeps the state of its step. A file is
+/// deleted once no state or record references it, unless the record was saved, e.g. in a
+/// checkpoint, in which case the file belongs to the checkpoint.
+///
+/// # Notes
+///
+/// The state is loaded on the default device, so this is meant for CPU backends.
+pub struct FileBackedState<O> {
+    optim: O,
+    directory: PathBuf,
+}
+
+/// State of the [file-backed state](FileBackedState) wrapper, the location of the state of the
+/// inner optimizer.
+#[derive(Clone)]
+pub struct FileBackedStateHandle {
+    file: Arc<StateFile>,
+    num_steps: Option<usize>,
+}
+
+/// [File-backed state handle](FileBackedStateHandle) item.
+///
+/// The state file is kept once the item is serialized, i.e. saved by a
+/// [recorder](crate::record::Recorder).
+#[derive(Deserialize, Clone, Debug)]
+pub struct FileBackedStateHandleItem {
+    /// The path of the state file.
+    path: String,
+    /// The number of steps of the inner state, if known.
+    num_steps: Option<usize>,
+    /// The state file of the recorded handle, marked as recorded when the item is serialized.
+    #[serde(skip)]
+    file: Option<Arc<StateFile>>,
+}
+
+/// State file, deleted once it isn't referenced anymore, unless it was recorded.
+#[derive(Debug)]
+struct StateFile {
+    path: PathBuf,
+    recorded: AtomicBool,
+}
+
+impl Drop for StateFile {
+    fn drop(&mut self) {
+        if !self.recorded.load(Ordering::Relaxed) {
+            // The file can already be gone, e.g. with its temporary directory.
+            std::fs::remove_file(&self.path).ok();
+        }
+    }
+}
+
+impl FileBackedStateHandle {
+    /// The path of the state file.
+    pub fn path(&self) -> &Path {
+        &self.file.path
+    }
+}
+
+impl Record for FileBackedStateHandle {
+    type Item<S: PrecisionSettings> = FileBackedStateHandleItem;
+
+    fn into_item<S: PrecisionSettings>(self) -> Self::Item<S> {
+        FileBackedStateHandleItem {
+            path: self.file.path.to_string_lossy().to_string(),
+            num_steps: self.num_steps,
+            file: Some(self.file),
+        }
+    }
+
+    fn from_item<S: PrecisionSettings>(item: Self::Item<S>) -> Self {
+        let file = StateFile {
+            path: PathBuf::from(item.path),
+            recorded: AtomicBool::new(true),
+        };
+
+        Self {
+            file: Arc::new(file),
+            num_steps: item.num_steps,
+        }
+    }
+}
+
+impl Serialize for FileBackedStateHandleItem {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        // The item is only serialized when a recorder saves it, so the file belongs to the saved
+        // record from now on.
+        if let Some(file) = self.file.as_ref() {
+            file.recorded.store(true, Ordering::Relaxed);
+        }
+
+        let mut item = serializer.serialize_struct("FileBackedStateHandleItem", 2)?;
+        item.serialize_field("path", &self.path)?;
+        item.serialize_field("num_steps", &self.num_steps)?;
+        item.end()
+    }
+}
+
+impl FileBackedStateConfig {
+    /// Wrap the given simple optimizer to keep its state in files.
+    ///
+    /// The wrapper is itself a [simple optimizer](SimpleOptimizer), so it can be used with
+    /// [OptimizerAdaptor](crate::optim::adaptor::OptimizerAdaptor).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the directory can't be created.
+    pub fn init<O>(&self, optim: O) -> FileBackedState<O> {
+        let directory = PathBuf::from(&self.directory);
+        std::fs::create_dir_all(&directory).expect("The state directory should be created.");
+
+        FileBackedState { optim, directory }
+    }
+}
+
+fn load<R: Record>(handle: &FileBackedStateHandle) -> R {
+    let path = handle.path();
+    let file = File::open(path)
+        .unwrap_or_else(|err| panic!("The state file {} should be opened: {err}", path.display()));
+    // SAFETY: The state files are never modified after being written.
+    let mmap = unsafe { Mmap::map(&file) }
+        .unwrap_or_else(|err| panic!("The state file {} should be mapped: {err}", path.display()));
+    let item: R::Item<FullPrecisionSettings> =
+        bincode::serde::decode_borrowed_from_slice(&mmap, bin_config()).unwrap_or_else(|err| {
+            panic!("The state file {} should be loaded: {err}", path.display())
+        });
+
+    R::from_item(item)
+}
+
+fn save<R: Record>(state: R, directory: &Path, num_steps: Option<usize>) -> FileBackedStateHandle {
+    // The name is unique across runs, so the files of a loaded record are never overwritten.
+    let path = directory.join(format!("state-{}.bin", IdGenerator::generate()));
+    let bytes =
+        bincode::serde::encode_to_vec(state.into_item::<FullPrecisionSettings>(), bin_config())
+            .expect("The state should be serialized.");
+    write_mapped(&path, &bytes)
+        .unwrap_or_else(|err| panic!("The state file {} should be saved: {err}", path.display()));
+    let file = StateFile {
+        path,
+        recorded: AtomicBool::new(false),
+    };
+
+    FileBackedStateHandle {
+        file: Arc::new(file),
+        num_steps,
+    }
+}
+
+fn write_mapped(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .open(path)?;
+    file.set_len(bytes.len() as u64)?;
+    if bytes.is_empty() {
+        return Ok(());
+    }
+
+    // SAFETY: The file was just created with a unique name, so it isn't mapped elsewhere.
+    let mut mmap = unsafe { MmapMut::map_mut(&file)? };
+    mmap.copy_from_slice(bytes);
+    mmap.flush()
+}
+
+impl<B, O> SimpleOptimizer<B> for FileBackedState<O>
+where
+    B: Backend,
+    O: SimpleOptimizer<B>,
+{
+    type State<const D: usize> = FileBackedStateHandle;
+
+    fn step<const D: usize>(
+        &self,
+        lr: LearningRate,
+        tensor: Tensor<B, D>,
+        grad: Tensor<B, D>,
+        state: Option<Self::State<D>>,
+    ) -> (Tensor<B, D>, Option<Self::State<D>>) {
+        let state_inner = state.as_ref().map(load::<O::State<D>>);
+        let (tensor, state_inner) = self.optim.step(lr, tensor, grad, state_inner);
+
+        // The updated state is written to a new file, since the previous one can still be
+        // referenced by a record, and is deleted with the previous handle otherwise.
+        let state = state_inner.map(|state_inner| {
+            let num_steps = O::state_num_steps(&state_inner);
+            save(state_inner, &self.directory, num_steps)
+        });
+
+        (tensor, state)
+    }
+
+    fn to_device<const D: usize>(state: Self::State<D>, _device: &B::Device) -> Self::State<D> {
+        state
+    }
+
+    fn state_map_tensors<const D: usize, F>(state: Self::State<D>, func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        // The mapped state is written to a new file, since the original state can still be used.
+        let state_inner = O::state_map_tensors(load::<O::State<D>>(&state), func);
+        let directory = state.path().parent().unwrap_or(Path::new("."));
+
+        save(state_inner, directory, state.num_steps)
+    }
+
+    fn state_num_elements<const D: usize>(_state: &Self::State<D>) -> usize {
+        // The state isn't kept in memory.
+        0
+    }
+
+    fn state_num_steps<const D: usize>(state: &Self::State<D>) -> Option<usize> {
+        state.num_steps
+    }
+
+    fn state_effective_lr<const D: usize>(
+        &self,
+        lr: LearningRate,
+        state: &Self::State<D>,
+    ) -> Option<Tensor<B, D>> {
+        self.optim
+            .state_effective_lr(lr, &load::<O::State<D>>(state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optim::{AdamConfig, AdamState};
+    use crate::record::{BinBytesRecorder, Recorder};
+    use crate::tensor::Distribution;
+    use crate::TestBackend;
+
+    const LEARNING_RATE: LearningRate = 0.01;
+
+    #[test]
+    fn test_file_backed_state_matches_in_memory_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let adam = AdamConfig::new();
+        let optim_file = FileBackedStateConfig::new(dir.path().to_string_lossy().to_string())
+            .init(adam.init_simple::<TestBackend>());
+        let optim_memory = adam.init_simple::<TestBackend>();
+        let mut tensor_file = Tensor::<TestBackend, 2>::random([256, 256], Distribution::Default);
+        let mut tensor_memory = tensor_file.clone();
+        let (mut state_file, mut state_memory) = (None, None);
+
+        for _ in 0..3 {
+            let grad = Tensor::<TestBackend, 2>::random([256, 256], Distribution::Default);
+            (tensor_file, state_file) =
+                optim_file.step(LEARNING_RATE, tensor_file, grad.clone(), state_file);
+            (tensor_memory, state_memory) =
+                optim_memory.step(LEARNING_RATE, tensor_memory, grad, state_memory);
+        }
+
+        tensor_file
+            .to_data()
+            .assert_approx_eq(&tensor_memory.to_data(), 5);
+
+        // The files of the previous steps aren't referenced anymore, so they are deleted.
+        let handle = state_file.unwrap();
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+        assert_eq!(handle.num_steps, Some(3));
+        let state: AdamState<TestBackend, 2> = load(&handle);
+        let (state, state_memory) = (state.momentum, state_memory.unwrap().momentum);
+        assert_eq!(state.time, 3);
+        state
+            .moment_1
+            .to_data()
+            .assert_approx_eq(&state_memory.moment_1.to_data(), 5);
+        state
+            .moment_2
+            .to_data()
+            .assert_approx_eq(&state_memory.moment_2.to_data(), 5);
+    }
+
+    #[test]
+    fn test_file_backed_state_keeps_the_referenced_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let optim = FileBackedStateConfig::new(dir.path().to_string_lossy().to_string())
+            .init(AdamConfig::new().init_simple::<TestBackend>());
+        let step = |tensor: Tensor<TestBackend, 1>, state: Option<FileBackedStateHandle>| {
+            let grad = Tensor::<TestBackend, 1>::random([8], Distribution::Default);
+            optim.step(LEARNING_RATE, tensor, grad, state)
+        };
+        let tensor = Tensor::<TestBackend, 1>::random([8], Distribution::Default);
+        let (tensor, state) = step(tensor, None);
+        let (handle_cloned, handle_recorded) = (state.clone().unwrap(), state.clone().unwrap());
+        let recorder = BinBytesRecorder::<FullPrecisionSettings>::default();
+        let bytes = recorder.record(handle_recorded, ()).unwrap();
+
+        // The state of the first step is unchanged by the next steps.
+        let (tensor, state) = step(tensor, state);
+        let (_tensor, state) = step(tensor, state);
+        let state_first: AdamState<TestBackend, 1> = load(&handle_cloned);
+        assert_eq!(state_first.momentum.time, 1);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+
+        // The recorded file is kept, the other files are deleted with their last handle.
+        drop((handle_cloned, state));
+        let handle: FileBackedStateHandle = recorder.load(bytes).unwrap();
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+        assert!(handle.path().exists());
+    }
+
+    #[test]
+    fn test_file_backed_state_deletes_the_files_converted_but_not_saved() {
+        let dir = tempfile::tempdir().unwrap();
+        let optim = FileBackedStateConfig::new(dir.path().to_string_lossy().to_string())
+            .init(AdamConfig::new().init_simple::<TestBackend>());
+        let recorder = BinBytesRecorder::<FullPrecisionSettings>::default();
+        let mut tensor = Tensor::<TestBackend, 1>::random([8], Distribution::Default);
+        let mut state: Option<FileBackedStateHandle> = None;
+        let mut saved = None;
+
+        for step in 0..5 {
+            let grad = Tensor::<TestBackend, 1>::random([8], Distribution::Default);
+            (tensor, state) = optim.step(LEARNING_RATE, tensor, grad, state);
+
+            // Converting the handle to an item doesn't keep its file, only saving it does.
+            let handle = state.clone().unwrap();
+            let _item = handle.clone().into_item::<FullPrecisionSettings>();
+            if step == 1 {
+                saved = Some(recorder.record(handle, ()).unwrap());
+            }
+        }
+
+        // The saved file and the file of the current state are left.
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+        drop(state);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+        let handle: FileBackedStateHandle = recorder.load(saved.unwrap()).unwrap();
+        assert_eq!(handle.num_steps, Some(2));
+    }
+}
@@ -0,0 +1,243 @@
+use crate::grad_clipping::{tensor_norm, GradientClippingConfig, NormKind};
+use crate::module::AutodiffModule;
+use crate::{self as burn, LearningRate};
+
+use super::validation::validate_positive;
+use super::SimpleOptimizer;
+use crate::config::{Config, ConfigError, ValidateConfig};
+use crate::optim::adaptor::OptimizerAdaptor;
+use crate::record::Record;
+use crate::tensor::Tensor;
+use burn_tensor::backend::{AutodiffBackend, Backend};
+
+/// Configuration to create the [Fromage](Fromage) optimizer.
+#[derive(Config)]
+pub struct FromageConfig {
+    /// Bound of the norm of each parameter, relative to its norm before the first step, e.g. `1`
+    /// to keep the norm from growing. The norm isn't bounded by default.
+    p_bound: Option<f64>,
+    /// [Gradient Clipping](GradientClippingConfig) config.
+    grad_clipping: Option<GradientClippingConfig>,
+    /// [Gradient scale](crate::optim::adaptor::OptimizerAdaptor::with_grad_scale) of the adaptor.
+    #[config(default = 1.0)]
+    grad_scale: f32,
+}
+
+/// Fromage optimizer as described in the paper
+/// [On the distance between two neural networks and the stability of learning](https://arxiv.org/abs/2002.03432).
+///
+/// The gradient of each parameter is normalized and scaled by the norm of the parameter, so the
+/// update is a fixed fraction `lr` of the parameter whatever the scale of the gradient:
+///
+/// `param = (param - lr * ||param|| / ||grad|| * grad) / sqrt(1 + lr^2)`
+///
+/// The division by `sqrt(1 + lr^2)` keeps the norm of the parameter from growing when the
+/// gradient is orthogonal to it. The gradient isn't normalized when the parameter or its
+/// gradient is zero.
+pub struct Fromage {
+    p_bound: Option<f64>,
+}
+
+/// State of [Fromage](Fromage), only kept when the norm of the parameters is bounded.
+#[derive(Record, Clone, new)]
+pub struct FromageState<B: Backend, const D: usize> {
+    /// The largest norm of the parameter, as a tensor of shape `[1]`.
+    max_norm: Tensor<B, 1>,
+}
+
+impl ValidateConfig for FromageConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        if let Some(p_bound) = self.p_bound {
+            validate_positive("p_bound", p_bound)?;
+        }
+        if let Some(clipping) = &self.grad_clipping {
+            clipping.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromageConfig {
+    /// Initialize Fromage as a [simple optimizer](SimpleOptimizer), to optimize tensors directly
+    /// with [step_tensors](crate::optim::step_tensors).
+    ///
+    /// Fromage normalizes the gradient, so neither its scale nor clipping it by norm changes the
+    /// update, only the clipping by value of [init](Self::init) would.
+    pub fn init_simple(&self) -> Fromage {
+        if let Err(err) = self.validate() {
+            panic!("{err}");
+        }
+
+        Fromage {
+            p_bound: self.p_bound,
+        }
+    }
+
+    /// Initialize Fromage optimizer.
+    ///
+    /// # Returns
+    ///
+    /// Returns an optimizer that can be used to optimize a module.
+    pub fn init<B: AutodiffBackend, M: AutodiffModule<B>>(
+        &self,
+    ) -> OptimizerAdaptor<Fromage, M, B> {
+        let mut optim = OptimizerAdaptor::from(self.init_simple())
+            .with_grad_scale(self.grad_scale)
+            .with_config(self);
+        if let Some(config) = &self.grad_clipping {
+            optim = optim.with_grad_clipping(config.init());
+        }
+        optim
+    }
+}
+
+impl<B: Backend> SimpleOptimizer<B> for Fromage {
+    type State<const D: usize> = FromageState<B, D>;
+
+    fn step<const D: usize>(
+        &self,
+        lr: LearningRate,
+        tensor: Tensor<B, D>,
+        grad: Tensor<B, D>,
+        state: Option<Self::State<D>>,
+    ) -> (Tensor<B, D>, Option<Self::State<D>>) {
+        let param_norm = tensor_norm(tensor.clone(), &NormKind::L2);
+        let grad_norm = tensor_norm(grad.clone(), &NormKind::L2);
+
+        // A zero norm has no direction to normalize, the gradient is then used as is.
+        let ratio = param_norm
+            .clone()
+            .div(grad_norm.clone())
+            .mask_fill(param_norm.clone().equal_elem(0.0), 1.0)
+            .mask_fill(grad_norm.equal_elem(0.0), 1.0);
+        let update = grad.mul(ratio.reshape([1; D])).mul_scalar(lr);
+        let tensor = tensor.sub(update).div_scalar((1.0 + lr * lr).sqrt());
+
+        let Some(p_bound) = self.p_bound else {
+            return (tensor, None);
+        };
+
+        let max_norm = match state {
+            Some(state) => state.max_norm,
+            None => param_norm.mul_scalar(p_bound),
+        };
+        let norm = tensor_norm(tensor.clone(), &NormKind::L2);
+        let scale = max_norm
+            .clone()
+            .div(norm.clone())
+            .clamp_max(1.0)
+            .mask_fill(norm.equal_elem(0.0), 1.0);
+        let tensor = tensor.mul(scale.reshape([1; D]));
+
+        (tensor, Some(FromageState::new(max_norm)))
+    }
+
+    fn to_device<const D: usize>(mut state: Self::State<D>, device: &B::Device) -> Self::State<D> {
+        state.max_norm = state.max_norm.to_device(device);
+        state
+    }
+
+    fn state_map_tensors<const D: usize, F>(state: Self::State<D>, _func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        // The maximum norm doesn't depend on the shape of the parameter.
+        state
+    }
+
+    fn state_num_elements<const D: usize>(state: &Self::State<D>) -> usize {
+        state.max_norm.shape().num_elements()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tensor::Data;
+    use crate::TestBackend;
+
+    const LEARNING_RATE: LearningRate = 0.1;
+
+    fn step(
+        optim: &Fromage,
+        tensor: [f32; 2],
+        grad: [f32; 2],
+        state: Option<FromageState<TestBackend, 1>>,
+    ) -> (Tensor<TestBackend, 1>, Option<FromageState<TestBackend, 1>>) {
+        optim.step(
+            LEARNING_RATE,
+            Tensor::from_floats(tensor),
+            Tensor::from_floats(grad),
+            state,
+        )
+    }
+
+    #[test]
+    fn test_fromage_update_is_proportional_to_param_norm() {
+        let optim = FromageConfig::new().init_simple();
+        let update_norm = |tensor: [f32; 2]| {
+            let (tensor_updated, _) = step(&optim, tensor, [1.0, 1.0], None);
+            let tensor_updated = tensor_updated.mul_scalar((1.0 + LEARNING_RATE.powi(2)).sqrt());
+            Tensor::<TestBackend, 1>::from_floats(tensor)
+                .sub(tensor_updated)
+                .powf(2.0)
+                .sum()
+                .sqrt()
+                .into_scalar()
+        };
+
+        // The parameters have a norm of 5 and 10, so the updates a norm of 0.5 and 1.
+        let update_small = update_norm([3.0, 4.0]);
+        let update_large = update_norm([6.0, 8.0]);
+
+        assert!((update_small - 0.5).abs() < 1e-5);
+        assert!((update_large - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_fromage_is_robust_to_gradient_scaling() {
+        let optim = FromageConfig::new().init_simple();
+
+        let (tensor, state) = step(&optim, [3.0, 4.0], [0.5, -1.0], None);
+        let (tensor_scaled, _) = step(&optim, [3.0, 4.0], [500.0, -1000.0], None);
+
+        assert!(state.is_none());
+        tensor_scaled
+            .to_data()
+            .assert_approx_eq(&tensor.to_data(), 5);
+    }
+
+    #[test]
+    fn test_fromage_p_bound_limits_param_norm() {
+        let optim = FromageConfig::new().with_p_bound(Some(1.0)).init_simple();
+        let mut tensor = [3.0, 4.0];
+        let mut state = None;
+
+        // The updates point away from the origin, so the norm grows without the bound.
+        for _ in 0..5 {
+            let grad = [-tensor[0], -tensor[1]];
+            let (tensor_updated, state_updated) = step(&optim, tensor, grad, state);
+            let values = tensor_updated.into_data().value;
+            tensor = [values[0], values[1]];
+            state = state_updated;
+        }
+
+        Tensor::<TestBackend, 1>::from_floats(tensor)
+            .to_data()
+            .assert_approx_eq(&Data::from([3.0, 4.0]), 5);
+        assert_eq!(state.unwrap().max_norm.into_scalar(), 5.0);
+    }
+
+    #[test]
+    fn test_fromage_config_validate() {
+        assert!(FromageConfig::new()
+            .with_p_bound(Some(1.0))
+            .validate()
+            .is_ok());
+        assert!(matches!(
+            FromageConfig::new().with_p_bound(Some(0.0)).validate(),
+            Err(ConfigError::OutOfRange(message)) if message == "p_bound must be positive, got 0."
+        ));
+    }
+}
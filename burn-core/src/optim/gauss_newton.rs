@@ -0,0 +1,330 @@
+use crate as burn;
+
+use super::{GradientsParams, Optimizer};
+use crate::config::Config;
+use crate::module::{AutodiffModule, ModuleMapper, ParamId};
+use crate::tensor::{backend::AutodiffBackend, Data, Shape, Tensor};
+use crate::LearningRate;
+use alloc::vec::Vec;
+use burn_tensor::backend::Backend;
+use core::marker::PhantomData;
+
+/// Configuration to create the [block Gauss-Newton](BlockGaussNewton) optimizer.
+#[derive(Config)]
+pub struct BlockGaussNewtonConfig {
+    /// Parameters with at most this number of elements are optimized with Gauss-Newton steps,
+    /// the other ones with the fallback optimizer.
+    #[config(default = 64)]
+    max_block_size: usize,
+    /// Damping added to the diagonal of each Gauss-Newton block, which keeps the step in a trust
+    /// region around the current parameters.
+    #[config(default = 1e-4)]
+    damping: f64,
+}
+
+/// Block-diagonal second-order optimizer for small but curvature-sensitive parameters.
+///
+/// For each parameter with at most `max_block_size` elements, the damped Gauss-Newton block
+/// `J^T J + damping * I` is built from the Jacobian-vector products of the model outputs (or
+/// residuals) with respect to that parameter, and the step solves the block against the gradient.
+/// This is a Levenberg-Marquardt step, which reaches the minimum of a quadratic loss at once.
+/// The larger parameters are optimized with the fallback optimizer, e.g. [Adam](super::Adam).
+///
+/// # Notes
+///
+/// This doesn't implement [Optimizer], since each step needs the Jacobian-vector products.
+pub struct BlockGaussNewton<O, M, B>
+where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    fallback: O,
+    max_block_size: usize,
+    damping: f64,
+    phantom: PhantomData<(M, B)>,
+}
+
+impl BlockGaussNewtonConfig {
+    /// Initialize the block Gauss-Newton optimizer with the optimizer of the large parameters.
+    pub fn init<O, M, B>(&self, fallback: O) -> BlockGaussNewton<O, M, B>
+    where
+        O: Optimizer<M, B>,
+        M: AutodiffModule<B>,
+        B: AutodiffBackend,
+    {
+        BlockGaussNewton {
+            fallback,
+            max_block_size: self.max_block_size,
+            damping: self.damping,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<O, M, B> BlockGaussNewton<O, M, B>
+where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    /// Perform the optimizer step.
+    ///
+    /// # Arguments
+    ///
+    /// * `lr` - The learning rate, `1.0` performs the full Gauss-Newton step.
+    /// * `module` - The module to optimize.
+    /// * `grads` - The gradients of the module parameters.
+    /// * `jvp` - Computes the product of the Jacobian of the model outputs with respect to the
+    ///   given parameter with a direction, both flattened to one dimension. It is only called for
+    ///   the parameters optimized with Gauss-Newton steps.
+    ///
+    /// # Returns
+    ///
+    /// The updated module.
+    pub fn step<J>(&mut self, lr: LearningRate, module: M, mut grads: GradientsParams, jvp: J) -> M
+    where
+        J: Fn(&ParamId, Tensor<B::InnerBackend, 1>) -> Tensor<B::InnerBackend, 1>,
+    {
+        let mut mapper =
+            GaussNewtonMapper::<B, J>::new(&mut grads, &jvp, self.max_block_size, self.damping, lr);
+        let module = module.map(&mut mapper);
+
+        self.fallback.step(lr, module, grads)
+    }
+
+    /// Get the state of the fallback optimizer as a [record](crate::record::Record), the
+    /// Gauss-Newton steps are stateless.
+    pub fn to_record(&self) -> O::Record {
+        self.fallback.to_record()
+    }
+
+    /// Load the state of the fallback optimizer.
+    pub fn load_record(mut self, record: O::Record) -> Self {
+        self.fallback = self.fallback.load_record(record);
+        self
+    }
+}
+
+#[derive(new)]
+struct GaussNewtonMapper<'a, B: AutodiffBackend, J> {
+    grads: &'a mut GradientsParams,
+    jvp: &'a J,
+    max_block_size: usize,
+    damping: f64,
+    lr: LearningRate,
+    phantom: PhantomData<B>,
+}
+
+impl<'a, B, J> ModuleMapper<B> for GaussNewtonMapper<'a, B, J>
+where
+    B: AutodiffBackend,
+    J: Fn(&ParamId, Tensor<B::InnerBackend, 1>) -> Tensor<B::InnerBackend, 1>,
+{
+    fn map<const D: usize>(&mut self, id: &ParamId, tensor: Tensor<B, D>) -> Tensor<B, D> {
+        let shape = tensor.shape();
+        let num_elements = shape.num_elements();
+        if num_elements > self.max_block_size {
+            return tensor;
+        }
+        // The gradient is removed, so the fallback optimizer skips the parameter.
+        let Some(grad) = self.grads.remove::<B::InnerBackend, D>(id) else {
+            return tensor;
+        };
+        let device = grad.device();
+
+        // Transposed Jacobian, with one row per element of the parameter.
+        let rows = (0..num_elements)
+            .map(|index| {
+                let direction = Tensor::one_hot(index, num_elements).to_device(&device);
+                let column = (self.jvp)(id, direction);
+                let [num_outputs] = column.dims();
+                column.reshape([1, num_outputs])
+            })
+            .collect();
+        let jacobian_transposed = Tensor::cat(rows, 0);
+        let block = jacobian_transposed
+            .clone()
+            .matmul(jacobian_transposed.transpose());
+
+        let delta = solve_damped(
+            block.into_data().convert::<f64>().value,
+            grad.reshape([num_elements])
+                .into_data()
+                .convert::<f64>()
+                .value,
+            self.damping,
+        );
+        let delta = Data::new(delta, Shape::new([num_elements]))
+            .convert::<<B::InnerBackend as Backend>::FloatElem>();
+        let delta = Tensor::<B::InnerBackend, 1>::from_data_device(delta, &device).reshape(shape);
+
+        let is_require_grad = tensor.is_require_grad();
+        let mut tensor = Tensor::from_inner(tensor.inner() - delta.mul_scalar(self.lr));
+        if is_require_grad {
+            tensor = tensor.require_grad();
+        }
+        tensor
+    }
+}
+
+/// Solve `(block + damping * I) x = rhs` with the Cholesky decomposition, where `block` is a
+/// symmetric positive semi-definite matrix stored in row-major order.
+#[allow(clippy::needless_range_loop)]
+fn solve_damped(mut block: Vec<f64>, mut rhs: Vec<f64>, damping: f64) -> Vec<f64> {
+    let n = rhs.len();
+    for i in 0..n {
+        block[i * n + i] += damping;
+    }
+
+    // Decomposition `L L^T`, with `L` stored in the lower triangle.
+    for j in 0..n {
+        let mut diag = block[j * n + j];
+        for k in 0..j {
+            diag -= block[j * n + k] * block[j * n + k];
+        }
+        let diag = diag.max(f64::MIN_POSITIVE).sqrt();
+        block[j * n + j] = diag;
+
+        for i in j + 1..n {
+            let mut value = block[i * n + j];
+            for k in 0..j {
+                value -= block[i * n + k] * block[j * n + k];
+            }
+            block[i * n + j] = value / diag;
+        }
+    }
+
+    // Forward substitution with `L`, then backward substitution with `L^T`.
+    for i in 0..n {
+        for k in 0..i {
+            rhs[i] -= block[i * n + k] * rhs[k];
+        }
+        rhs[i] /= block[i * n + i];
+    }
+    for i in (0..n).rev() {
+        for k in i + 1..n {
+            rhs[i] -= block[k * n + i] * rhs[k];
+        }
+        rhs[i] /= block[i * n + i];
+    }
+
+    rhs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        module::Param,
+        nn::{Linear, LinearConfig, LinearRecord},
+        optim::AdamConfig,
+        TestAutodiffBackend, TestBackend,
+    };
+    use burn_tensor::{Distribution, ElementConversion};
+
+    type B = TestAutodiffBackend;
+
+    #[test]
+    fn test_block_gauss_newton_reaches_quadratic_minimum_faster_than_adam() {
+        let x = Tensor::<B, 2>::from_floats([[1.0, 2.0], [3.0, -1.0], [0.5, 0.5], [-2.0, 1.0]]);
+        // Targets of the linear map with weight [[2.0], [-1.0]] and bias [0.5].
+        let y = Tensor::<B, 2>::from_floats([[0.5], [7.5], [1.0], [-4.5]]);
+        let loss = |linear: &Linear<B>| {
+            linear
+                .forward(x.clone())
+                .sub(y.clone())
+                .powf(2.0)
+                .sum()
+                .mul_scalar(0.5)
+        };
+        let weight_id = linear().weight.id.clone();
+        let x_inner = x.clone().inner();
+        // The residuals are linear in the parameters, so their Jacobian-vector products are
+        // the forward pass of the direction.
+        let jvp = |id: &ParamId, direction: Tensor<TestBackend, 1>| match id == &weight_id {
+            true => x_inner
+                .clone()
+                .matmul(direction.reshape([2, 1]))
+                .reshape([4]),
+            false => direction.reshape([1, 1]).repeat(0, 4).reshape([4]),
+        };
+
+        let mut gauss_newton =
+            BlockGaussNewtonConfig::new().init(AdamConfig::new().init::<B, Linear<B>>());
+        let mut adam = AdamConfig::new().init::<B, Linear<B>>();
+        let (mut linear_gauss_newton, mut linear_adam) = (linear(), linear());
+
+        for _ in 0..3 {
+            let grads = GradientsParams::from_grads(
+                loss(&linear_gauss_newton).backward(),
+                &linear_gauss_newton,
+            );
+            linear_gauss_newton = gauss_newton.step(1.0, linear_gauss_newton, grads, jvp);
+            let grads = GradientsParams::from_grads(loss(&linear_adam).backward(), &linear_adam);
+            linear_adam = adam.step(0.1, linear_adam, grads);
+        }
+
+        let loss_gauss_newton = loss(&linear_gauss_newton).into_scalar().elem::<f32>();
+        let loss_adam = loss(&linear_adam).into_scalar().elem::<f32>();
+        assert!(loss_gauss_newton < 1e-4);
+        assert!(loss_adam > 100.0 * loss_gauss_newton);
+        linear_gauss_newton
+            .weight
+            .to_data()
+            .assert_approx_eq(&Data::from([[2.0], [-1.0]]), 3);
+    }
+
+    #[test]
+    fn test_block_gauss_newton_steps_large_params_with_the_fallback() {
+        // The weight has 72 elements, more than the default maximum block size of 64.
+        let linear = LinearConfig::new(8, 9).init::<B>();
+        let x = Tensor::<B, 2>::random([2, 8], Distribution::Default);
+        let loss = |linear: &Linear<B>| linear.forward(x.clone()).powf(2.0).sum().mul_scalar(0.5);
+        let (weight_id, bias_id) = (
+            linear.weight.id.clone(),
+            linear.bias.as_ref().unwrap().id.clone(),
+        );
+        // The bias is added to each of the two rows of the outputs.
+        let jvp = |id: &ParamId, direction: Tensor<TestBackend, 1>| {
+            assert_eq!(id, &bias_id, "Only the bias is optimized with Gauss-Newton");
+            direction.reshape([1, 9]).repeat(0, 2).reshape([18])
+        };
+
+        let grads = GradientsParams::from_grads(loss(&linear).backward(), &linear);
+        let grad_bias = grads.get::<TestBackend, 1>(&bias_id).unwrap();
+        let bias = linear.bias.as_ref().unwrap().val().inner();
+        let mut gauss_newton = BlockGaussNewtonConfig::new()
+            .with_damping(0.0)
+            .init(AdamConfig::new().init::<B, Linear<B>>());
+        let linear_gauss_newton = gauss_newton.step(1.0, linear.clone(), grads, jvp);
+
+        let grads = GradientsParams::from_grads(loss(&linear).backward(), &linear);
+        let mut adam = AdamConfig::new().init::<B, Linear<B>>();
+        let linear_adam = adam.step(1.0, linear, grads);
+
+        assert_eq!(linear_gauss_newton.weight.id, weight_id);
+        linear_gauss_newton
+            .weight
+            .to_data()
+            .assert_approx_eq(&linear_adam.weight.to_data(), 5);
+        // The Gauss-Newton block of the bias is `2 * I`.
+        linear_gauss_newton
+            .bias
+            .unwrap()
+            .val()
+            .inner()
+            .into_data()
+            .assert_approx_eq(&(bias - grad_bias.div_scalar(2.0)).into_data(), 5);
+    }
+
+    fn linear() -> Linear<B> {
+        LinearConfig::new(2, 1).init_with(LinearRecord {
+            weight: Param::new(ParamId::from("weight"), Tensor::from_floats([[0.0], [0.0]])),
+            bias: Some(Param::new(
+                ParamId::from("bias"),
+                Tensor::from_floats([0.0]),
+            )),
+        })
+    }
+}
@@ -1,14 +1,43 @@
 use core::marker::PhantomData;
 
+use crate as burn;
+use crate::config::Config;
 use crate::module::{AutodiffModule, ModuleVisitor, ParamId};
+use crate::LearningRate;
 
-use burn_tensor::{backend::AutodiffBackend, Tensor};
+use alloc::vec::Vec;
+use burn_tensor::{
+    backend::{AutodiffBackend, Backend},
+    ElementConversion, Tensor,
+};
+use hashbrown::{HashMap, HashSet};
 
-use super::GradientsParams;
+use super::{base::delegate_optimizer, GradientsParams, Optimizer};
+use crate::grad_clipping::{ClipStats, GradientClipping, NanPolicy, NormKind};
+
+/// How the [gradients accumulator](GradientsAccumulator) combines the gradients.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GradientsAccumulationMode {
+    /// Sum the gradients.
+    #[default]
+    Sum,
+    /// Average the gradients incrementally with `mean += (grad - mean) / n`, which avoids the
+    /// large intermediate sums that can overflow with low precision float types.
+    RunningMean,
+}
 
 /// Accumulate gradients into a single [Gradients](AutodiffBackend::Gradients) object.
 pub struct GradientsAccumulator<M> {
     grads: GradientsParams,
+    mode: GradientsAccumulationMode,
+    num_samples: usize,
+    /// The number of samples of the accumulated gradients of each parameter, which differs from
+    /// the total when a parameter has no gradient in some micro-batches.
+    num_samples_params: HashMap<ParamId, usize>,
+    grad_clipping: Option<GradientClipping>,
+    grad_clipping_nan_policy: NanPolicy,
+    clip_per_microbatch: bool,
+    clip_stats: ClipStats,
     phantom: PhantomData<M>,
 }
 
@@ -21,11 +50,76 @@ impl<M> Default for GradientsAccumulator<M> {
 impl<M> GradientsAccumulator<M> {
     /// Create a new gradients accumulator.
     pub fn new() -> Self {
+        Self::with_mode(GradientsAccumulationMode::Sum)
+    }
+
+    /// Create a new gradients accumulator combining the gradients with the given mode.
+    pub fn with_mode(mode: GradientsAccumulationMode) -> Self {
         Self {
             grads: GradientsParams::new(),
+            mode,
+            num_samples: 0,
+            num_samples_params: HashMap::new(),
+            grad_clipping: None,
+            grad_clipping_nan_policy: NanPolicy::Skip,
+            clip_per_microbatch: false,
+            clip_stats: ClipStats::default(),
             phantom: PhantomData,
         }
     }
+
+    /// Sets the gradient clipping, applied either to the gradients of each micro-batch before
+    /// they are accumulated, or to the accumulated gradients by
+    /// [grads_clipped](Self::grads_clipped).
+    ///
+    /// The gradients are clipped like the [optimizer adaptor](super::adaptor::OptimizerAdaptor)
+    /// does, the [global RMS](GradientClipping::GlobalRms) being computed over all the gradients
+    /// of the module.
+    ///
+    /// # Arguments
+    ///
+    /// * `clipping` - The gradient clipping.
+    /// * `clip_per_microbatch` - If the gradients of each micro-batch are clipped, instead of the
+    ///   accumulated gradients.
+    ///
+    /// # Returns
+    ///
+    /// The accumulator.
+    pub fn with_grad_clipping(
+        mut self,
+        clipping: GradientClipping,
+        clip_per_microbatch: bool,
+    ) -> Self {
+        self.grad_clipping = Some(clipping);
+        self.clip_per_microbatch = clip_per_microbatch;
+        self
+    }
+
+    /// Sets how the gradient clipping handles a gradient norm that isn't finite, see
+    /// [NanPolicy]. Defaults to [NanPolicy::Skip].
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The policy.
+    ///
+    /// # Returns
+    ///
+    /// The accumulator.
+    pub fn with_grad_clipping_nan_policy(mut self, policy: NanPolicy) -> Self {
+        self.grad_clipping_nan_policy = policy;
+        self
+    }
+
+    /// The [statistics](ClipStats) of the gradient clipping since the creation of the
+    /// accumulator or the last [reset](Self::reset_clip_stats).
+    pub fn clip_stats(&self) -> &ClipStats {
+        &self.clip_stats
+    }
+
+    /// Clear the statistics of the gradient clipping, e.g. at the start of an epoch.
+    pub fn reset_clip_stats(&mut self) {
+        self.clip_stats.reset();
+    }
 }
 
 impl<M> GradientsAccumulator<M> {
@@ -34,23 +128,271 @@ impl<M> GradientsAccumulator<M> {
     where
         M: AutodiffModule<B>,
     {
-        let mut visitor = ModuleGradsAccumulator::<M>::new(&mut self.grads, grads);
+        self.accumulate_weighted(module, grads, 1);
+    }
+
+    /// Accumulate the gradients of the mean loss of a micro-batch with the given number of
+    /// samples, so micro-batches of different sizes are weighted correctly, e.g. a smaller last
+    /// micro-batch.
+    ///
+    /// With the [running mean](GradientsAccumulationMode::RunningMean), the accumulated gradients
+    /// are the weighted mean `sum(grad_i * n_i) / sum(n_i)`, the gradients of the mean loss over
+    /// all the samples. With the [sum](GradientsAccumulationMode::Sum), they are the weighted sum
+    /// `sum(grad_i * n_i)`, the gradients of the summed loss.
+    ///
+    /// The running mean of a parameter is over the micro-batches with a gradient of the
+    /// parameter, so a parameter unused by some micro-batches isn't scaled down.
+    pub fn accumulate_weighted<B: AutodiffBackend>(
+        &mut self,
+        module: &M,
+        mut grads: GradientsParams,
+        num_samples: usize,
+    ) where
+        M: AutodiffModule<B>,
+    {
+        assert!(num_samples > 0, "The number of samples must be positive.");
+
+        if self.clip_per_microbatch {
+            self.clip(module, &mut grads);
+        }
+
+        self.num_samples += num_samples;
+        let mut visitor = ModuleGradsAccumulator::<M> {
+            grads: &mut self.grads,
+            grads_new: grads,
+            mode: self.mode,
+            num_samples,
+            num_samples_params: &mut self.num_samples_params,
+            phantom: PhantomData,
+        };
         module.visit(&mut visitor);
     }
 
+    /// The number of samples accumulated since the last reset, the number of accumulated
+    /// gradients when they aren't weighted.
+    pub fn num_samples(&self) -> usize {
+        self.num_samples
+    }
+
+    /// Return the accumulated gradients, clipped unless the clipping was applied to each
+    /// micro-batch, and reset the accumulator state.
+    pub fn grads_clipped<B: AutodiffBackend>(&mut self, module: &M) -> GradientsParams
+    where
+        M: AutodiffModule<B>,
+    {
+        let mut grads = self.grads();
+        if !self.clip_per_microbatch {
+            self.clip(module, &mut grads);
+        }
+
+        grads
+    }
+
+    /// Clip the given gradients with the gradient clipping, if any.
+    fn clip<B: AutodiffBackend>(&mut self, module: &M, grads: &mut GradientsParams)
+    where
+        M: AutodiffModule<B>,
+    {
+        let Some(clipping) = self.grad_clipping.as_ref() else {
+            return;
+        };
+
+        let global_rms_scale = match clipping {
+            GradientClipping::GlobalRms(target) => {
+                let Some(accumulator) = grads.accumulate_norm::<B, M>(module, &NormKind::RMS)
+                else {
+                    return;
+                };
+                let rms = accumulator.norm().into_scalar().elem::<f32>();
+                if !rms.is_finite() {
+                    match self.grad_clipping_nan_policy {
+                        NanPolicy::Skip if rms.is_nan() => return,
+                        NanPolicy::Skip | NanPolicy::Zero => 0.0,
+                        NanPolicy::Error => panic!(
+                            "The global gradient RMS is {rms}, the gradients can't be clipped."
+                        ),
+                    }
+                } else {
+                    self.clip_stats.record(rms, *target);
+                    match rms > *target {
+                        true => target / rms,
+                        false => return,
+                    }
+                }
+            }
+            _ => 1.0,
+        };
+
+        module.visit(&mut ClipGradients::<B> {
+            grads,
+            clipping,
+            nan_policy: &self.grad_clipping_nan_policy,
+            clip_stats: &mut self.clip_stats,
+            global_rms_scale,
+            visited: HashSet::new(),
+            phantom: PhantomData,
+        });
+    }
+
     /// Return the accumulated gradients and reset the accumulator state.
+    ///
+    /// The accumulated gradients aren't clipped, see [grads_clipped](Self::grads_clipped).
     pub fn grads(&mut self) -> GradientsParams {
         let mut grads = GradientsParams::new();
         core::mem::swap(&mut self.grads, &mut grads);
+        self.num_samples = 0;
+        self.num_samples_params.clear();
 
         grads
     }
 }
 
-#[derive(new)]
+/// Configuration to create an [accumulated optimizer](AccumulatedOptimizer).
+#[derive(Config)]
+pub struct AccumulatedOptimizerConfig {
+    /// The number of micro-batches whose gradients are accumulated before each optimizer step.
+    num_microbatches: usize,
+    /// If the gradients are averaged over the micro-batches, the gradients of the loss averaged
+    /// over all their samples, instead of summed.
+    #[config(default = true)]
+    mean: bool,
+}
+
+/// Optimizer wrapper accumulating the gradients of several micro-batches, and performing a single
+/// step of the wrapped optimizer with the accumulated gradients.
+///
+/// The [step](Optimizer::step) of each micro-batch only accumulates its gradients and returns the
+/// module unchanged, except the step of the last micro-batch. The wrapped optimizer only sees the
+/// effective steps, so its step counter, e.g. for the bias correction of
+/// [Adam](super::Adam), advances once per effective step and matches a single batch with all
+/// the samples.
+///
+/// # Notes
+///
+/// The learning rate of the last micro-batch is used for the effective step, and the gradients
+/// being accumulated aren't part of the [record](Optimizer::to_record). The optimizer can only be
+/// [moved to another device](Optimizer::to_device) between effective steps.
+pub struct AccumulatedOptimizer<O, M, B>
+where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    optim: O,
+    accumulator: GradientsAccumulator<M>,
+    num_microbatches: usize,
+    num_accumulated: usize,
+    stepped: bool,
+    phantom: PhantomData<B>,
+}
+
+impl AccumulatedOptimizerConfig {
+    /// Wrap the given optimizer to accumulate the gradients of the micro-batches.
+    pub fn init<O, M, B>(&self, optim: O) -> AccumulatedOptimizer<O, M, B>
+    where
+        O: Optimizer<M, B>,
+        M: AutodiffModule<B>,
+        B: AutodiffBackend,
+    {
+        assert!(
+            self.num_microbatches > 0,
+            "The number of micro-batches must be positive."
+        );
+        let mode = match self.mean {
+            true => GradientsAccumulationMode::RunningMean,
+            false => GradientsAccumulationMode::Sum,
+        };
+
+        AccumulatedOptimizer {
+            optim,
+            accumulator: GradientsAccumulator::with_mode(mode),
+            num_microbatches: self.num_microbatches,
+            num_accumulated: 0,
+            stepped: false,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<O, M, B> AccumulatedOptimizer<O, M, B>
+where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    /// The number of micro-batches accumulated since the last effective step.
+    pub fn num_accumulated(&self) -> usize {
+        self.num_accumulated
+    }
+
+    /// The wrapped optimizer.
+    pub fn inner(&self) -> &O {
+        &self.optim
+    }
+}
+
+impl<O, M, B> Optimizer<M, B> for AccumulatedOptimizer<O, M, B>
+where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    type Record = O::Record;
+
+    fn step(&mut self, lr: LearningRate, module: M, grads: GradientsParams) -> M {
+        self.accumulator.accumulate::<B>(&module, grads);
+        self.num_accumulated += 1;
+        self.stepped = self.num_accumulated == self.num_microbatches;
+        if !self.stepped {
+            return module;
+        }
+
+        self.num_accumulated = 0;
+        let grads = self.accumulator.grads();
+        self.optim.step(lr, module, grads)
+    }
+
+    fn to_record(&self) -> Self::Record {
+        self.optim.to_record()
+    }
+
+    fn load_record(mut self, record: Self::Record) -> Self {
+        self.optim = self.optim.load_record(record);
+        self
+    }
+
+    fn to_device(mut self, device: &B::Device) -> Self {
+        // The accumulated gradients can't be moved without their module.
+        assert_eq!(
+            self.num_accumulated, 0,
+            "The optimizer can't be moved to another device while accumulating gradients."
+        );
+        self.optim = self.optim.to_device(device);
+        self
+    }
+
+    delegate_optimizer!(
+        optim,
+        [clone_state_to, num_params, state_bytes, config_json]
+    );
+
+    fn last_updated(&self) -> Vec<ParamId> {
+        match self.stepped {
+            true => self.optim.last_updated(),
+            false => Vec::new(),
+        }
+    }
+
+    delegate_optimizer!(optim, [export_state_tensors]);
+}
+
 struct ModuleGradsAccumulator<'a, M> {
     grads: &'a mut GradientsParams,
     grads_new: GradientsParams,
+    mode: GradientsAccumulationMode,
+    /// The number of samples of the new gradients.
+    num_samples: usize,
+    num_samples_params: &'a mut HashMap<ParamId, usize>,
     phantom: PhantomData<M>,
 }
 
@@ -59,10 +401,25 @@ impl<'a, B: AutodiffBackend, M: AutodiffModule<B>> ModuleVisitor<B>
 {
     fn visit<const D: usize>(&mut self, id: &ParamId, _tensor: &Tensor<B, D>) {
         let grad_updated = match self.grads_new.remove::<B::InnerBackend, D>(id) {
-            Some(new) => match self.grads.remove::<B::InnerBackend, D>(id) {
-                Some(grad) => grad.add(new),
-                None => new,
-            },
+            Some(new) => {
+                let num_samples = self.num_samples_params.entry(id.clone()).or_insert(0);
+                *num_samples += self.num_samples;
+                // The share of the samples of the parameter for the running mean.
+                let weight_mean = self.num_samples as f32 / *num_samples as f32;
+
+                match (self.grads.remove::<B::InnerBackend, D>(id), self.mode) {
+                    (Some(grad), GradientsAccumulationMode::Sum) => {
+                        grad.add(scale(new, self.num_samples as f32))
+                    }
+                    (Some(mean), GradientsAccumulationMode::RunningMean) => {
+                        let delta = scale(new.sub(mean.clone()), weight_mean);
+                        mean.add(delta)
+                    }
+                    // The first gradients of the running mean have a weight of one.
+                    (None, GradientsAccumulationMode::Sum) => scale(new, self.num_samples as f32),
+                    (None, GradientsAccumulationMode::RunningMean) => new,
+                }
+            }
             None => match self.grads.remove::<B::InnerBackend, D>(id) {
                 Some(grad) => grad,
                 None => return,
@@ -74,14 +431,53 @@ impl<'a, B: AutodiffBackend, M: AutodiffModule<B>> ModuleVisitor<B>
     }
 }
 
+struct ClipGradients<'a, B: AutodiffBackend> {
+    grads: &'a mut GradientsParams,
+    clipping: &'a GradientClipping,
+    nan_policy: &'a NanPolicy,
+    clip_stats: &'a mut ClipStats,
+    /// The scale of the gradients clipped by their global RMS, computed before the visit.
+    global_rms_scale: f32,
+    visited: HashSet<ParamId>,
+    phantom: PhantomData<B>,
+}
+
+impl<'a, B: AutodiffBackend> ModuleVisitor<B> for ClipGradients<'a, B> {
+    fn visit<const D: usize>(&mut self, id: &ParamId, _tensor: &Tensor<B, D>) {
+        // A shared parameter is only clipped once.
+        if !self.visited.insert(id.clone()) {
+            return;
+        }
+        let Some(grad) = self.grads.remove::<B::InnerBackend, D>(id) else {
+            return;
+        };
+
+        let grad = match self.clipping {
+            GradientClipping::GlobalRms(_) if self.global_rms_scale == 0.0 => grad.zeros_like(),
+            GradientClipping::GlobalRms(_) => scale(grad, self.global_rms_scale),
+            clipping => clipping.clip_gradient_with_stats(grad, self.nan_policy, self.clip_stats),
+        };
+        self.grads.register::<B::InnerBackend, D>(id.clone(), grad);
+    }
+}
+
+fn scale<B: Backend, const D: usize>(grad: Tensor<B, D>, weight: f32) -> Tensor<B, D> {
+    match weight == 1.0 {
+        true => grad,
+        false => grad.mul_scalar(weight),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
         nn::{Linear, LinearConfig},
-        TestAutodiffBackend,
+        optim::AdamConfig,
+        record::Record,
+        TestAutodiffBackend, TestBackend,
     };
-    use burn_tensor::Distribution;
+    use burn_tensor::{Data, Distribution};
 
     #[test]
     fn test_accumulate_gradients_one_step() {
@@ -112,6 +508,207 @@ mod tests {
         assert_eq!(grads.len(), 2)
     }
 
+    #[test]
+    fn test_accumulate_gradients_running_mean_does_not_overflow() {
+        let mut accumulator =
+            GradientsAccumulator::with_mode(GradientsAccumulationMode::RunningMean);
+        let layer = LinearConfig::new(2, 2)
+            .with_bias(false)
+            .init::<TestAutodiffBackend>();
+        let values: [f32; 3] = [3.0e38, 2.0e38, 3.2e38];
+
+        for value in values {
+            let mut grads = GradientsParams::new();
+            grads.register::<TestBackend, 2>(
+                layer.weight.id.clone(),
+                Tensor::ones([2, 2]).mul_scalar(value),
+            );
+            accumulator.accumulate(&layer, grads);
+        }
+
+        // The sum of the gradients overflows f32, but not their mean.
+        let reference = values.iter().map(|value| *value as f64).sum::<f64>() / 3.0;
+        let mean = accumulator
+            .grads()
+            .remove::<TestBackend, 2>(&layer.weight.id)
+            .unwrap()
+            .into_data();
+        for value in mean.value {
+            assert!(value.is_finite());
+            assert!((value as f64 - reference).abs() / reference < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_running_mean_of_param_missing_from_some_microbatches() {
+        let mut accumulator =
+            GradientsAccumulator::with_mode(GradientsAccumulationMode::RunningMean);
+        let layer = LinearConfig::new(2, 2).init::<TestAutodiffBackend>();
+        let bias_id = layer.bias.as_ref().unwrap().id.clone();
+
+        for value in [1.0, 3.0] {
+            let mut grads = GradientsParams::new();
+            grads.register::<TestBackend, 2>(
+                layer.weight.id.clone(),
+                Tensor::ones([2, 2]).mul_scalar(value),
+            );
+            // The bias only has a gradient in the first micro-batch.
+            if value == 1.0 {
+                grads
+                    .register::<TestBackend, 1>(bias_id.clone(), Tensor::ones([2]).mul_scalar(5.0));
+            }
+            accumulator.accumulate(&layer, grads);
+        }
+
+        let mut grads = accumulator.grads();
+        grads
+            .remove::<TestBackend, 2>(&layer.weight.id)
+            .unwrap()
+            .into_data()
+            .assert_approx_eq(&Data::from([[2.0, 2.0], [2.0, 2.0]]), 5);
+        grads
+            .remove::<TestBackend, 1>(&bias_id)
+            .unwrap()
+            .into_data()
+            .assert_approx_eq(&Data::from([5.0, 5.0]), 5);
+    }
+
+    #[test]
+    fn test_accumulate_weighted_matches_single_batch() {
+        let mut accumulator =
+            GradientsAccumulator::with_mode(GradientsAccumulationMode::RunningMean);
+        let layer = layer();
+        let x = Tensor::<TestAutodiffBackend, 2>::random([4, 20], Distribution::Default);
+        let mean_loss_grads = |x: Tensor<TestAutodiffBackend, 2>| {
+            let loss = layer.forward(x).sum_dim(1).mean();
+            GradientsParams::from_grads(loss.backward(), &layer)
+        };
+
+        accumulator.accumulate_weighted(&layer, mean_loss_grads(x.clone().slice([0..3])), 3);
+        accumulator.accumulate_weighted(&layer, mean_loss_grads(x.clone().slice([3..4])), 1);
+
+        assert_eq!(accumulator.num_samples(), 4);
+        let mut grads = accumulator.grads();
+        let mut grads_expected = mean_loss_grads(x);
+        let weight = grads.remove::<TestBackend, 2>(&layer.weight.id).unwrap();
+        let weight_expected = grads_expected
+            .remove::<TestBackend, 2>(&layer.weight.id)
+            .unwrap();
+        weight
+            .into_data()
+            .assert_approx_eq(&weight_expected.into_data(), 5);
+    }
+
+    #[test]
+    fn test_clipping_per_microbatch_differs_from_clipping_the_sum() {
+        let layer = LinearConfig::new(2, 1)
+            .with_bias(false)
+            .init::<TestAutodiffBackend>();
+        let accumulate = |clip_per_microbatch| {
+            let mut accumulator = GradientsAccumulator::new()
+                .with_grad_clipping(GradientClipping::Norm(1.0), clip_per_microbatch);
+            for grad in [[3.0, 4.0], [0.0, 0.5]] {
+                let mut grads = GradientsParams::new();
+                grads.register::<TestBackend, 2>(
+                    layer.weight.id.clone(),
+                    Tensor::<TestBackend, 1>::from_floats(grad).reshape([2, 1]),
+                );
+                accumulator.accumulate(&layer, grads);
+            }
+
+            let grad: Tensor<TestBackend, 2> = accumulator
+                .grads_clipped(&layer)
+                .remove(&layer.weight.id)
+                .unwrap();
+            grad.reshape([2]).into_data()
+        };
+
+        // The large gradient is clipped to [0.6, 0.8] before being added to the small one.
+        accumulate(true).assert_approx_eq(&Data::from([0.6, 1.3]), 5);
+        // The sum [3, 4.5] is clipped to a unit norm.
+        let norm = (3.0f32 * 3.0 + 4.5 * 4.5).sqrt();
+        accumulate(false).assert_approx_eq(&Data::from([3.0 / norm, 4.5 / norm]), 5);
+    }
+
+    #[test]
+    fn test_global_rms_clipping_is_over_all_the_gradients() {
+        let layer = LinearConfig::new(2, 1).init::<TestAutodiffBackend>();
+        let mut accumulator =
+            GradientsAccumulator::new().with_grad_clipping(GradientClipping::GlobalRms(1.0), false);
+        let mut grads = GradientsParams::new();
+        grads.register::<TestBackend, 2>(
+            layer.weight.id.clone(),
+            Tensor::from_floats([[3.0], [4.0]]),
+        );
+        grads.register::<TestBackend, 1>(
+            layer.bias.as_ref().unwrap().id.clone(),
+            Tensor::from_floats([0.0]),
+        );
+        accumulator.accumulate(&layer, grads);
+
+        let mut grads = accumulator.grads_clipped(&layer);
+
+        // The RMS of the 3 elements is sqrt(25 / 3), the RMS of the weight alone is sqrt(25 / 2).
+        let scale = (3.0f32 / 25.0).sqrt();
+        let weight: Tensor<TestBackend, 2> = grads.remove(&layer.weight.id).unwrap();
+        weight
+            .into_data()
+            .assert_approx_eq(&Data::from([[3.0 * scale], [4.0 * scale]]), 5);
+        assert_eq!(accumulator.clip_stats().num_grads(), 1);
+        assert_eq!(accumulator.clip_stats().num_clipped(), 1);
+    }
+
+    #[test]
+    fn test_accumulated_adam_matches_single_batch_adam() {
+        let mut optim_single = AdamConfig::new().init();
+        let mut optim_accumulated: AccumulatedOptimizer<_, Linear<TestAutodiffBackend>, _> =
+            AccumulatedOptimizerConfig::new(3).init(AdamConfig::new().init());
+        let mut layer_single = layer();
+        let mut layer_accumulated = layer_single.clone();
+        let mean_loss_grads = |layer: &Linear<TestAutodiffBackend>, x| {
+            let loss = layer.forward(x).sum_dim(1).mean();
+            GradientsParams::from_grads(loss.backward(), layer)
+        };
+
+        for _ in 0..2 {
+            let x = Tensor::<TestAutodiffBackend, 2>::random([6, 20], Distribution::Default);
+            let grads = mean_loss_grads(&layer_single, x.clone());
+            layer_single = optim_single.step(0.01, layer_single, grads);
+
+            for i in 0..3 {
+                assert_eq!(optim_accumulated.num_accumulated(), i);
+                let grads =
+                    mean_loss_grads(&layer_accumulated, x.clone().slice([2 * i..2 * i + 2]));
+                layer_accumulated = optim_accumulated.step(0.01, layer_accumulated, grads);
+            }
+        }
+
+        // Adam only counted the two effective steps, so the bias corrections are the same.
+        assert_eq!(optim_single.to_record().summary().num_steps, Some(2));
+        assert_eq!(optim_accumulated.to_record().summary().num_steps, Some(2));
+        layer_accumulated
+            .weight
+            .to_data()
+            .assert_approx_eq(&layer_single.weight.to_data(), 5);
+        layer_accumulated
+            .bias
+            .unwrap()
+            .to_data()
+            .assert_approx_eq(&layer_single.bias.unwrap().to_data(), 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "while accumulating gradients")]
+    fn test_accumulated_optimizer_cant_move_between_microbatches() {
+        let mut optim: AccumulatedOptimizer<_, Linear<TestAutodiffBackend>, _> =
+            AccumulatedOptimizerConfig::new(2).init(AdamConfig::new().init());
+        let layer = layer();
+        let grads = GradientsParams::from_grads(layer.forward(random_tensor()).backward(), &layer);
+        let _layer = optim.step(0.01, layer, grads);
+
+        let _optim = optim.to_device(&Default::default());
+    }
+
     fn layer() -> Linear<TestAutodiffBackend> {
         LinearConfig::new(20, 20).with_bias(true).init()
     }
@@ -0,0 +1,176 @@
+use crate::{self as burn, record::Record, LearningRate};
+
+use super::validation::validate_beta;
+use super::SimpleOptimizer;
+use crate::config::{Config, ConfigError, ValidateConfig};
+use crate::tensor::Tensor;
+use burn_tensor::backend::Backend;
+
+/// Configuration to create the [gradient smoothing](GradientSmoothing) wrapper.
+#[derive(Config)]
+pub struct GradientSmoothingConfig {
+    /// Decay of the exponential moving average of the gradients.
+    #[config(default = 0.9)]
+    beta: f64,
+}
+
+/// Simple optimizer wrapper smoothing the raw gradients with an exponential moving average before
+/// they are given to the inner optimizer.
+///
+/// The smoothed gradient `beta * smoothed + (1 - beta) * grad` starts from zero, and is
+/// independent of any momentum of the inner optimizer, so even optimizers without momentum like
+/// [AdaGrad](super::AdaGrad) receive smoothed gradients.
+pub struct GradientSmoothing<O> {
+    optim: O,
+    beta: f64,
+}
+
+/// State of [gradient smoothing](GradientSmoothing).
+#[derive(Record, Clone, new)]
+pub struct GradientSmoothingState<B: Backend, T: Record, const D: usize> {
+    inner: Option<T>,
+    smoothed: Tensor<B, D>,
+}
+
+impl ValidateConfig for GradientSmoothingConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        validate_beta("beta", self.beta)
+    }
+}
+
+impl GradientSmoothingConfig {
+    /// Wrap the given simple optimizer to smooth its gradients.
+    ///
+    /// The wrapper is itself a [simple optimizer](SimpleOptimizer), so it can be used with
+    /// [OptimizerAdaptor](crate::optim::adaptor::OptimizerAdaptor), which records the smoothed
+    /// gradients with the state of the inner optimizer.
+    pub fn init<O>(&self, optim: O) -> GradientSmoothing<O> {
+        if let Err(err) = self.validate() {
+            panic!("{err}");
+        }
+
+        GradientSmoothing {
+            optim,
+            beta: self.beta,
+        }
+    }
+}
+
+impl<B, O> SimpleOptimizer<B> for GradientSmoothing<O>
+where
+    B: Backend,
+    O: SimpleOptimizer<B>,
+{
+    type State<const D: usize> = GradientSmoothingState<B, O::State<D>, D>;
+
+    fn step<const D: usize>(
+        &self,
+        lr: LearningRate,
+        tensor: Tensor<B, D>,
+        grad: Tensor<B, D>,
+        state: Option<Self::State<D>>,
+    ) -> (Tensor<B, D>, Option<Self::State<D>>) {
+        let grad = grad.mul_scalar(1.0 - self.beta);
+        let (state_inner, smoothed) = match state {
+            Some(state) => (state.inner, state.smoothed.mul_scalar(self.beta).add(grad)),
+            None => (None, grad),
+        };
+
+        let (tensor, state_inner) = self.optim.step(lr, tensor, smoothed.clone(), state_inner);
+        let state = GradientSmoothingState::new(state_inner, smoothed);
+
+        (tensor, Some(state))
+    }
+
+    fn to_device<const D: usize>(mut state: Self::State<D>, device: &B::Device) -> Self::State<D> {
+        state.inner = state.inner.map(|state| O::to_device(state, device));
+        state.smoothed = state.smoothed.to_device(device);
+        state
+    }
+
+    fn state_map_tensors<const D: usize, F>(mut state: Self::State<D>, func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        state.inner = state.inner.map(|state| O::state_map_tensors(state, &func));
+        state.smoothed = func(state.smoothed);
+        state
+    }
+
+    fn state_num_elements<const D: usize>(state: &Self::State<D>) -> usize {
+        state.inner.as_ref().map(O::state_num_elements).unwrap_or(0)
+            + state.smoothed.shape().num_elements()
+    }
+
+    fn state_num_steps<const D: usize>(state: &Self::State<D>) -> Option<usize> {
+        state.inner.as_ref().and_then(O::state_num_steps)
+    }
+
+    fn state_effective_lr<const D: usize>(
+        &self,
+        lr: LearningRate,
+        state: &Self::State<D>,
+    ) -> Option<Tensor<B, D>> {
+        state
+            .inner
+            .as_ref()
+            .and_then(|state| self.optim.state_effective_lr(lr, state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optim::{SgdConfig, SgdState};
+    use crate::record::FullPrecisionSettings;
+    use crate::tensor::Data;
+    use crate::TestBackend;
+
+    const LEARNING_RATE: LearningRate = 1.0;
+    const ASSERT_PRECISION: usize = 5;
+
+    #[test]
+    fn test_gradient_smoothing_feeds_ema_of_raw_gradients() {
+        let optimizer = GradientSmoothingConfig::new()
+            .with_beta(0.5)
+            .init(SgdConfig::new().init_simple::<TestBackend>());
+        let grads = [[4.0, -2.0], [0.0, 2.0], [2.0, 0.0]];
+        // Plain SGD with a learning rate of 1 subtracts the smoothed gradient.
+        let smoothed_expected = [[2.0, -1.0], [1.0, 0.5], [1.5, 0.25]];
+
+        let mut tensor = Tensor::<TestBackend, 1>::zeros([2]);
+        let mut state = None;
+        for (grad, smoothed) in grads.into_iter().zip(smoothed_expected) {
+            let (tensor_updated, state_updated) = optimizer.step(
+                LEARNING_RATE,
+                tensor.clone(),
+                Tensor::from_floats(grad),
+                state,
+            );
+            tensor
+                .sub(tensor_updated.clone())
+                .to_data()
+                .assert_approx_eq(&Data::from(smoothed), ASSERT_PRECISION);
+
+            tensor = tensor_updated;
+            state = state_updated;
+        }
+
+        // The smoothed gradient is kept in the record.
+        let item = state.unwrap().into_item::<FullPrecisionSettings>();
+        let state: GradientSmoothingState<TestBackend, SgdState<TestBackend, 1>, 1> =
+            Record::from_item(item);
+        state
+            .smoothed
+            .to_data()
+            .assert_approx_eq(&Data::from([1.5, 0.25]), ASSERT_PRECISION);
+    }
+
+    #[test]
+    #[should_panic(expected = "beta must be in [0, 1), got 1.")]
+    fn test_gradient_smoothing_init_validates_the_config() {
+        GradientSmoothingConfig::new()
+            .with_beta(1.0)
+            .init(SgdConfig::new().init_simple::<TestBackend>());
+    }
+}
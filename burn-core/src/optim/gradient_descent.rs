@@ -0,0 +1,202 @@
+use crate::grad_clipping::GradientClippingConfig;
+use crate::module::AutodiffModule;
+use crate::{self as burn, LearningRate};
+
+use super::decay::{WeightDecay, WeightDecayConfig, WeightDecayWarmup};
+use super::SimpleOptimizer;
+use crate::config::{Config, ConfigError, ValidateConfig};
+use crate::optim::adaptor::OptimizerAdaptor;
+use crate::tensor::Tensor;
+use burn_tensor::backend::{AutodiffBackend, Backend};
+
+/// Configuration to create the [gradient descent](GradientDescent) optimizer.
+#[derive(Config)]
+pub struct GradientDescentConfig {
+    /// [Weight decay](WeightDecayConfig) config.
+    weight_decay: Option<WeightDecayConfig>,
+    /// [Gradient Clipping](GradientClippingConfig) config.
+    gradient_clipping: Option<GradientClippingConfig>,
+    /// [Gradient scale](crate::optim::adaptor::OptimizerAdaptor::with_grad_scale) of the adaptor.
+    #[config(default = 1.0)]
+    grad_scale: f32,
+}
+
+/// Plain gradient descent, updating each parameter with `tensor - lr * grad`.
+///
+/// Unlike [Sgd](super::Sgd), the optimizer has no state at all, so it's the simplest
+/// [simple optimizer](SimpleOptimizer) and a reference to implement new ones.
+pub struct GradientDescent<B: Backend> {
+    weight_decay: Option<WeightDecay<B>>,
+}
+
+impl ValidateConfig for GradientDescentConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        if let Some(weight_decay) = &self.weight_decay {
+            weight_decay.validate()?;
+        }
+        if let Some(clipping) = &self.gradient_clipping {
+            clipping.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl GradientDescentConfig {
+    /// Initialize gradient descent as a [simple optimizer](SimpleOptimizer), to optimize tensors
+    /// directly with [step_tensors](crate::optim::step_tensors).
+    ///
+    /// The tensors are updated with the gradients as given, the `gradient_clipping` and
+    /// `grad_scale` of the config being applied by the adaptor of [init](Self::init).
+    pub fn init_simple<B: Backend>(&self) -> GradientDescent<B> {
+        GradientDescent {
+            weight_decay: self.weight_decay.as_ref().map(WeightDecay::new),
+        }
+    }
+
+    /// Initialize gradient descent optimizer.
+    ///
+    /// # Returns
+    ///
+    /// Returns an optimizer that can be used to optimize a module.
+    pub fn init<B: AutodiffBackend, M: AutodiffModule<B>>(
+        &self,
+    ) -> OptimizerAdaptor<GradientDescent<B::InnerBackend>, M, B> {
+        // The adaptor completes the decay toward the initial values, and applies the warmed up
+        // decay itself.
+        let weight_decay = self
+            .weight_decay
+            .as_ref()
+            .and_then(WeightDecayConfig::for_simple_optimizer);
+        let optim = self
+            .clone()
+            .with_weight_decay(weight_decay)
+            .init_simple::<B::InnerBackend>();
+        let mut optim = OptimizerAdaptor::from(optim)
+            .with_grad_scale(self.grad_scale)
+            .with_config(self);
+        if let Some(config) = &self.gradient_clipping {
+            optim = optim.with_grad_clipping(config.init());
+        }
+        if let Some(config) = &self.weight_decay {
+            optim = optim.with_weight_decay(config.clone());
+        }
+        if let Some(config) = self.weight_decay.as_ref().filter(|c| c.warmup_steps > 0) {
+            optim = optim.with_weight_decay_warmup(WeightDecayWarmup::new(config));
+        }
+        optim
+    }
+}
+
+impl<B: Backend> SimpleOptimizer<B> for GradientDescent<B> {
+    type State<const D: usize> = ();
+
+    fn step<const D: usize>(
+        &self,
+        lr: LearningRate,
+        tensor: Tensor<B, D>,
+        mut grad: Tensor<B, D>,
+        _state: Option<Self::State<D>>,
+    ) -> (Tensor<B, D>, Option<Self::State<D>>) {
+        if let Some(weight_decay) = &self.weight_decay {
+            grad = weight_decay.transform(grad, tensor.clone());
+        }
+
+        let mut tensor = tensor - grad.mul_scalar(lr);
+        if let Some(weight_decay) = &self.weight_decay {
+            tensor = weight_decay.proximal(lr, tensor);
+        }
+
+        (tensor, None)
+    }
+
+    fn to_device<const D: usize>(state: Self::State<D>, _device: &B::Device) -> Self::State<D> {
+        state
+    }
+
+    fn state_map_tensors<const D: usize, F>(state: Self::State<D>, _func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        state
+    }
+
+    fn state_num_elements<const D: usize>(_state: &Self::State<D>) -> usize {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::{Linear, LinearConfig};
+    use crate::optim::{GradientsParams, Optimizer};
+    use crate::tensor::Distribution;
+    use crate::{TestAutodiffBackend, TestBackend};
+
+    const LEARNING_RATE: LearningRate = 0.02;
+
+    #[test]
+    fn test_update_is_exactly_lr_times_grad() {
+        let optim = GradientDescentConfig::new().init_simple::<TestBackend>();
+        let tensor = Tensor::<TestBackend, 2>::random([4, 3], Distribution::Default);
+        let grad = Tensor::<TestBackend, 2>::random([4, 3], Distribution::Default);
+
+        let (updated, state) = optim.step(LEARNING_RATE, tensor.clone(), grad.clone(), None);
+
+        let expected = tensor - grad.mul_scalar(LEARNING_RATE);
+        assert_eq!(updated.into_data(), expected.into_data());
+        assert!(state.is_none());
+    }
+
+    #[test]
+    fn test_optimizer_keeps_no_state() {
+        type B = TestAutodiffBackend;
+        let linear: Linear<B> = LinearConfig::new(4, 2).init();
+        let x = Tensor::<B, 2>::random([2, 4], Distribution::Default);
+        let grads = GradientsParams::from_grads(linear.forward(x).backward(), &linear);
+        let mut optim = GradientDescentConfig::new().init::<B, Linear<B>>();
+
+        let _linear = optim.step(LEARNING_RATE, linear, grads);
+
+        assert!(optim.to_record().is_empty());
+        assert_eq!(optim.num_params(), 0);
+        assert_eq!(optim.state_bytes(), 0);
+    }
+
+    #[test]
+    fn test_gradient_descent_config_validate() {
+        assert!(GradientDescentConfig::new().validate().is_ok());
+        assert!(matches!(
+            GradientDescentConfig::new()
+                .with_weight_decay(Some(WeightDecayConfig::new(-1.0)))
+                .validate(),
+            Err(ConfigError::OutOfRange(_))
+        ));
+    }
+
+    #[test]
+    fn test_grad_scale_scales_the_update() {
+        type B = TestAutodiffBackend;
+        let linear: Linear<B> = LinearConfig::new(4, 2).with_bias(false).init();
+        let mut grads = GradientsParams::new();
+        grads.register(
+            linear.weight.id.clone(),
+            linear.weight.val().inner().ones_like(),
+        );
+        let weight = linear.weight.val().inner();
+        let mut optim = GradientDescentConfig::new()
+            .with_grad_scale(0.5)
+            .init::<B, Linear<B>>();
+
+        let linear = optim.step(LEARNING_RATE, linear, grads);
+
+        let expected = weight.sub_scalar(0.5 * LEARNING_RATE);
+        linear
+            .weight
+            .val()
+            .inner()
+            .into_data()
+            .assert_approx_eq(&expected.into_data(), 6);
+    }
+}
@@ -1,17 +1,24 @@
 use burn_tensor::{
     backend::{AutodiffBackend, Backend},
     container::TensorContainer,
-    Tensor,
+    Data, Tensor,
 };
 
+use crate::grad_clipping::{GlobalNormAccumulator, NormKind};
 use crate::module::{AutodiffModule, ParamId};
+use alloc::vec::Vec;
+use hashbrown::{HashMap, HashSet};
 
-use super::visitor::{GradientsParamsChangeDevice, GradientsParamsConverter};
+use super::visitor::{
+    GradientsParamsChangeDevice, GradientsParamsConverter, GradientsParamsFlatten,
+    GradientsParamsMerge, GradientsParamsNorm, GradientsParamsUnflatten,
+};
 
 /// Data type that contains gradients for parameters.
 #[derive(Default)]
 pub struct GradientsParams {
     container: TensorContainer<ParamId>,
+    num_elements: HashMap<ParamId, usize>,
 }
 
 impl GradientsParams {
@@ -38,6 +45,7 @@ impl GradientsParams {
     where
         B: Backend,
     {
+        self.num_elements.remove(id);
         self.container.remove(id)
     }
 
@@ -50,9 +58,16 @@ impl GradientsParams {
     where
         B: Backend,
     {
+        self.num_elements
+            .insert(id.clone(), value.shape().num_elements());
         self.container.register(id, value)
     }
 
+    /// If a gradients tensor is registered for the given [parameter id](ParamId).
+    pub fn contains(&self, id: &ParamId) -> bool {
+        self.num_elements.contains_key(id)
+    }
+
     /// The number of gradients tensors registered.
     pub fn len(&self) -> usize {
         self.container.len()
@@ -63,6 +78,37 @@ impl GradientsParams {
         self.len() == 0
     }
 
+    /// The total number of elements of the gradients tensors registered.
+    pub fn total_elements(&self) -> usize {
+        self.num_elements.values().sum()
+    }
+
+    /// Iterate over the [parameter ids](ParamId) of the gradients tensors registered, sorted by
+    /// id.
+    pub fn ids(&self) -> impl Iterator<Item = &ParamId> {
+        let mut ids: Vec<&ParamId> = self.container.ids().collect();
+        ids.sort();
+        ids.into_iter()
+    }
+
+    /// Iterate over the gradients tensors registered with the given backend and dimension, the
+    /// gradients of another dimension being skipped.
+    ///
+    /// # Notes
+    ///
+    /// The tensors are stored without their type, so the gradients of a module with parameters of
+    /// several dimensions are iterated once per dimension. The gradients are sorted by
+    /// [parameter id](ParamId), so reductions over the iteration are reproducible, whatever the
+    /// order of registration.
+    pub fn iter<B, const D: usize>(&self) -> impl Iterator<Item = (&ParamId, Tensor<B, D>)>
+    where
+        B: Backend,
+    {
+        let mut tensors: Vec<(&ParamId, Tensor<B, D>)> = self.container.iter().collect();
+        tensors.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+        tensors.into_iter()
+    }
+
     /// Change the device of each tensor gradients registered for the given [module](AutodiffModule).
     pub fn to_device<B: AutodiffBackend, M: AutodiffModule<B>>(
         mut self,
@@ -74,7 +120,145 @@ impl GradientsParams {
         self
     }
 
+    /// Merge the gradients of another backward pass of the given [module](AutodiffModule), e.g.
+    /// when the loss terms of a model are backpropagated separately.
+    ///
+    /// The gradients of the parameters present in both are summed, on the device of this
+    /// gradient, and the other gradients are added as is. The gradients of the other backward
+    /// pass not belonging to the module are dropped.
+    ///
+    /// The gradients are stored without their type, so summing them needs the rank of each
+    /// parameter, given by the module like for [to_device](Self::to_device).
+    pub fn merge<B: AutodiffBackend, M: AutodiffModule<B>>(
+        mut self,
+        mut other: GradientsParams,
+        module: &M,
+    ) -> Self {
+        let mut visitor = GradientsParamsMerge::<M, B>::new(&mut self, &mut other);
+        module.visit(&mut visitor);
+        self
+    }
+
+    /// Reduce each tensor gradients registered for the given [module](AutodiffModule) across
+    /// replicas, e.g. to average them before the optimizer step in data parallel training.
+    ///
+    /// The operation is implemented by the communication backend, which owns the transport. It
+    /// receives the gradients flattened to one dimension, in the order the module parameters are
+    /// visited, so every replica of the same module sees the same layout. Each tensor must be
+    /// replaced by the reduced one, with the same number of elements.
+    pub fn all_reduce<B, M, F>(&mut self, module: &M, op: F)
+    where
+        B: AutodiffBackend,
+        M: AutodiffModule<B>,
+        F: FnOnce(&mut [Tensor<B::InnerBackend, 1>]),
+    {
+        let mut ids = Vec::new();
+        let mut tensors = Vec::new();
+        module.visit(&mut GradientsParamsFlatten::<M, B>::new(
+            self,
+            &mut ids,
+            &mut tensors,
+        ));
+
+        op(&mut tensors);
+
+        let mut reduced: HashMap<ParamId, Tensor<B::InnerBackend, 1>> =
+            ids.into_iter().zip(tensors).collect();
+        module.visit(&mut GradientsParamsUnflatten::<M, B>::new(
+            self,
+            &mut reduced,
+        ));
+    }
+
+    /// The global L2 norm of the tensor gradients registered for the given
+    /// [module](AutodiffModule), on the device of the first gradient.
+    ///
+    /// Returns `None` when the module has no gradient.
+    pub fn global_norm<B, M>(&self, module: &M) -> Option<Tensor<B::InnerBackend, 1>>
+    where
+        B: AutodiffBackend,
+        M: AutodiffModule<B>,
+    {
+        self.accumulate_norm::<B, M>(module, &NormKind::L2)
+            .map(|accumulator| accumulator.norm())
+    }
+
+    /// The global root mean square of the tensor gradients registered for the given
+    /// [module](AutodiffModule), over all their elements, on the device of the first gradient.
+    ///
+    /// Unlike the [global norm](Self::global_norm), it doesn't grow with the number of
+    /// parameters. Returns `None` when the module has no gradient.
+    pub fn global_rms<B, M>(&self, module: &M) -> Option<Tensor<B::InnerBackend, 1>>
+    where
+        B: AutodiffBackend,
+        M: AutodiffModule<B>,
+    {
+        self.accumulate_norm::<B, M>(module, &NormKind::RMS)
+            .map(|accumulator| accumulator.norm())
+    }
+
+    /// Accumulate the norm of the gradients of the module on the device of the first gradient,
+    /// each parameter being counted once.
+    ///
+    /// Returns `None` when the module has no gradient.
+    pub(crate) fn accumulate_norm<B, M>(
+        &self,
+        module: &M,
+        kind: &NormKind,
+    ) -> Option<GlobalNormAccumulator<B::InnerBackend>>
+    where
+        B: AutodiffBackend,
+        M: AutodiffModule<B>,
+    {
+        let mut accumulator = None;
+        module.visit(&mut GradientsParamsNorm::<M, B>::new(
+            self,
+            kind,
+            &mut accumulator,
+            HashSet::new(),
+        ));
+
+        accumulator
+    }
+
+    /// Create the gradients from raw data keyed by [parameter id](ParamId), on the default device.
+    ///
+    /// See [from_data_device](GradientsParams::from_data_device).
+    pub fn from_data<B, const D: usize, I>(data: I) -> Self
+    where
+        B: Backend,
+        I: IntoIterator<Item = (ParamId, Data<f32, D>)>,
+    {
+        Self::from_data_device::<B, D, I>(data, &B::Device::default())
+    }
+
+    /// Create the gradients from raw data keyed by [parameter id](ParamId), e.g. gradients
+    /// computed outside of the autodiff backend, or fixed gradients to test an optimizer.
+    ///
+    /// # Notes
+    ///
+    /// The backend is the inner backend of the [autodiff backend](AutodiffBackend) of the module,
+    /// and each gradient must have the shape of its parameter. Gradients of parameters with
+    /// another rank can be added with [register](GradientsParams::register).
+    pub fn from_data_device<B, const D: usize, I>(data: I, device: &B::Device) -> Self
+    where
+        B: Backend,
+        I: IntoIterator<Item = (ParamId, Data<f32, D>)>,
+    {
+        let mut grads = Self::new();
+        for (id, data) in data {
+            grads.register::<B, D>(id, Tensor::from_data_device(data.convert(), device));
+        }
+        grads
+    }
+
     /// Extract each tensor gradients for the given [module](AutodiffModule).
+    ///
+    /// # Notes
+    ///
+    /// The gradients are registered once per [parameter id](ParamId). A tensor held more than once
+    /// by the module, e.g. with a recomputed forward pass, is counted once, while the gradients of
+    /// tied parameters, sharing their id with their own tensors, are summed.
     pub fn from_grads<B: AutodiffBackend, M: AutodiffModule<B>>(
         grads: B::Gradients,
         module: &M,
@@ -90,10 +274,12 @@ impl GradientsParams {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate as burn;
     use crate::{
         module::{list_param_ids, Module},
         nn::{Linear, LinearConfig},
-        TestAutodiffBackend,
+        optim::{AdaGradConfig, Optimizer, SgdConfig},
+        TestAutodiffBackend, TestBackend,
     };
     use burn_tensor::{backend::Backend, Distribution};
 
@@ -119,6 +305,231 @@ mod tests {
         LinearConfig::new(20, 20).with_bias(true).init()
     }
 
+    #[derive(Module, Debug)]
+    struct SharedLayer<B: Backend> {
+        layer: Linear<B>,
+        layer_recomputed: Linear<B>,
+    }
+
+    #[test]
+    fn test_convert_grads_with_recomputed_params_should_not_double_count() {
+        let layer = layer();
+        // The recomputed layer holds the same parameter tensors as the layer.
+        let shared = SharedLayer {
+            layer: layer.clone(),
+            layer_recomputed: layer.clone(),
+        };
+        let x = random_tensor();
+
+        let grads = GradientsParams::from_grads(layer.forward(x.clone()).backward(), &layer);
+        let loss = shared.layer_recomputed.forward(x);
+        let grads_shared = GradientsParams::from_grads(loss.backward(), &shared);
+
+        assert_eq!(grads_shared.len(), grads.len());
+        assert_same_grads(&layer, &grads_shared, &grads);
+    }
+
+    #[test]
+    fn test_convert_grads_should_sum_tied_params() {
+        let layer = layer();
+        let device = <TestAutodiffBackend as Backend>::Device::default();
+        // The tied layer shares the parameter ids of the layer, with its own tensors.
+        let tied = SharedLayer {
+            layer: layer.clone(),
+            layer_recomputed: layer.clone().fork(&device),
+        };
+        let (x_1, x_2) = (random_tensor(), random_tensor());
+
+        let grads = GradientsParams::from_grads(layer.forward(x_1.clone()).backward(), &layer);
+        let grads_2 = GradientsParams::from_grads(layer.forward(x_2.clone()).backward(), &layer);
+        let grads = grads.merge::<TestAutodiffBackend, _>(grads_2, &layer);
+        let loss = tied
+            .layer
+            .forward(x_1)
+            .add(tied.layer_recomputed.forward(x_2));
+        let grads_tied = GradientsParams::from_grads(loss.backward(), &tied);
+
+        assert_eq!(grads_tied.len(), grads.len());
+        assert_same_grads(&layer, &grads_tied, &grads);
+    }
+
+    fn assert_same_grads(
+        layer: &Linear<TestAutodiffBackend>,
+        grads: &GradientsParams,
+        expected: &GradientsParams,
+    ) {
+        let weight_id = &layer.weight.id;
+        grads
+            .get::<TestBackend, 2>(weight_id)
+            .unwrap()
+            .into_data()
+            .assert_approx_eq(
+                &expected
+                    .get::<TestBackend, 2>(weight_id)
+                    .unwrap()
+                    .into_data(),
+                5,
+            );
+        let bias_id = &layer.bias.as_ref().unwrap().id;
+        grads
+            .get::<TestBackend, 1>(bias_id)
+            .unwrap()
+            .into_data()
+            .assert_approx_eq(
+                &expected.get::<TestBackend, 1>(bias_id).unwrap().into_data(),
+                5,
+            );
+    }
+
+    #[test]
+    fn test_all_reduce_averages_replica_gradients() {
+        let device = <TestAutodiffBackend as Backend>::Device::default();
+        let replica_1 = layer();
+        let replica_2 = replica_1.clone().fork(&device);
+        let mut grads_1 =
+            GradientsParams::from_grads(replica_1.forward(random_tensor()).backward(), &replica_1);
+        let mut grads_2 =
+            GradientsParams::from_grads(replica_2.forward(random_tensor()).backward(), &replica_2);
+        let weight_id = &replica_1.weight.id;
+        let weight_expected = replica_1.weight.val().inner()
+            - grads_1
+                .get::<TestBackend, 2>(weight_id)
+                .unwrap()
+                .add(grads_2.get::<TestBackend, 2>(weight_id).unwrap())
+                .div_scalar(2);
+
+        // Mock all-reduce: the gradients of the second replica are received, then averaged with
+        // the ones of the first replica.
+        let mut received = Vec::new();
+        grads_2.all_reduce(&replica_2, |tensors| received = tensors.to_vec());
+        grads_1.all_reduce(&replica_1, |tensors| {
+            for (tensor, other) in tensors.iter_mut().zip(received) {
+                *tensor = tensor.clone().add(other).div_scalar(2);
+            }
+        });
+
+        let mut optim = SgdConfig::new().init();
+        let replica_1 = optim.step(1.0, replica_1, grads_1);
+
+        replica_1
+            .weight
+            .to_data()
+            .assert_approx_eq(&weight_expected.into_data(), 5);
+    }
+
+    #[test]
+    fn test_from_data_matches_backward_gradients() {
+        let layer = LinearConfig::new(4, 2)
+            .with_bias(false)
+            .init::<TestAutodiffBackend>();
+        let weight_id = layer.weight.id.clone();
+        let x = Tensor::<TestAutodiffBackend, 2>::random([2, 4], Distribution::Default);
+        let grads = GradientsParams::from_grads(layer.forward(x).backward(), &layer);
+        let data = grads
+            .get::<TestBackend, 2>(&weight_id)
+            .unwrap()
+            .into_data()
+            .convert::<f32>();
+        let mut grads_data = std::collections::HashMap::new();
+        grads_data.insert(weight_id, data);
+        let grads_data = GradientsParams::from_data::<TestBackend, 2, _>(grads_data);
+
+        let mut optim = AdaGradConfig::new().init();
+        let layer_backward = optim.step(0.1, layer.clone(), grads);
+        let mut optim = AdaGradConfig::new().init();
+        let layer_data = optim.step(0.1, layer, grads_data);
+
+        layer_data
+            .weight
+            .to_data()
+            .assert_approx_eq(&layer_backward.weight.to_data(), 5);
+    }
+
+    #[test]
+    fn test_len_and_iteration() {
+        let layer = layer();
+        let x = random_tensor();
+        let grads = GradientsParams::from_grads(layer.forward(x).backward(), &layer);
+        let weight_id = layer.weight.id.clone();
+        let bias_id = layer.bias.as_ref().unwrap().id.clone();
+
+        assert_eq!(grads.len(), 2);
+        assert!(!grads.is_empty());
+        assert_eq!(grads.total_elements(), 20 * 20 + 20);
+
+        let ids: HashSet<&ParamId> = grads.ids().collect();
+        assert_eq!(ids, HashSet::from_iter([&weight_id, &bias_id]));
+
+        let weights: Vec<_> = grads.iter::<TestBackend, 2>().collect();
+        let biases: Vec<_> = grads.iter::<TestBackend, 1>().collect();
+        assert_eq!(weights.len(), 1);
+        assert_eq!(biases.len(), 1);
+        assert_eq!(weights[0].0, &weight_id);
+        assert_eq!(weights[0].1.dims(), [20, 20]);
+        assert_eq!(biases[0].0, &bias_id);
+        assert_eq!(biases[0].1.dims(), [20]);
+    }
+
+    #[test]
+    fn test_iteration_order_is_deterministic() {
+        let layers = [layer(), layer(), layer()];
+        let grads_of = |order: [usize; 3]| {
+            let mut grads = GradientsParams::new();
+            for i in order {
+                let weight = &layers[i].weight;
+                let grad = weight.val().inner().ones_like().mul_scalar(i as f32 + 0.1);
+                grads.register::<TestBackend, 2>(weight.id.clone(), grad);
+            }
+            grads
+        };
+        let grads_1 = grads_of([0, 1, 2]);
+        let grads_2 = grads_of([2, 0, 1]);
+
+        let ids_1: Vec<&ParamId> = grads_1.ids().collect();
+        let ids_2: Vec<&ParamId> = grads_2.ids().collect();
+        assert_eq!(ids_1, ids_2);
+        assert!(ids_1.windows(2).all(|ids| ids[0] < ids[1]));
+
+        let sum_squares = |grads: &GradientsParams| {
+            grads
+                .iter::<TestBackend, 2>()
+                .map(|(_, grad)| grad.powf(2.0).sum().into_scalar())
+                .fold(0.0, |acc, sum| acc + sum)
+        };
+        assert_eq!(sum_squares(&grads_1), sum_squares(&grads_2));
+
+        let layers = layers.to_vec();
+        let norm_1 = grads_1.global_norm::<TestAutodiffBackend, _>(&layers);
+        let norm_2 = grads_2.global_norm::<TestAutodiffBackend, _>(&layers);
+        assert_eq!(norm_1.unwrap().into_scalar(), norm_2.unwrap().into_scalar());
+    }
+
+    #[test]
+    fn test_merge_sums_shared_gradients() {
+        let layer = layer();
+        let (weight, bias) = (layer.weight.val().inner(), layer.bias.as_ref().unwrap());
+        let bias_id = bias.id.clone();
+        let bias = bias.val().inner();
+        // The weight is shared by both backward passes, the bias only has a gradient in the second.
+        let mut grads_1 = GradientsParams::new();
+        grads_1.register(layer.weight.id.clone(), weight.ones_like());
+        let mut grads_2 = GradientsParams::new();
+        grads_2.register(layer.weight.id.clone(), weight.ones_like().mul_scalar(2.0));
+        grads_2.register(bias_id.clone(), bias.ones_like().mul_scalar(5.0));
+
+        let mut grads = grads_1.merge::<TestAutodiffBackend, _>(grads_2, &layer);
+
+        assert_eq!(grads.len(), 2);
+        let grad_weight: Tensor<TestBackend, 2> = grads.remove(&layer.weight.id).unwrap();
+        let grad_bias: Tensor<TestBackend, 1> = grads.remove(&bias_id).unwrap();
+        grad_weight
+            .to_data()
+            .assert_approx_eq(&weight.ones_like().mul_scalar(3.0).into_data(), 5);
+        grad_bias
+            .to_data()
+            .assert_approx_eq(&bias.ones_like().mul_scalar(5.0).into_data(), 5);
+    }
+
     fn random_tensor() -> Tensor<TestAutodiffBackend, 2> {
         Tensor::<TestAutodiffBackend, 2>::random([2, 20], Distribution::Default)
     }
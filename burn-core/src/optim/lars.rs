@@ -0,0 +1,248 @@
+use crate::grad_clipping::{tensor_norm, GradientClippingConfig, NormKind};
+use crate::module::AutodiffModule;
+use crate::{self as burn, LearningRate};
+
+use super::momentum::{Momentum, MomentumConfig, MomentumState};
+use super::validation::{validate_non_negative, validate_positive};
+use super::SimpleOptimizer;
+use crate::config::{Config, ConfigError, ValidateConfig};
+use crate::optim::adaptor::OptimizerAdaptor;
+use crate::record::Record;
+use crate::tensor::Tensor;
+use burn_tensor::backend::{AutodiffBackend, Backend};
+
+/// Configuration to create the [Lars](Lars) optimizer.
+#[derive(Config)]
+pub struct LarsConfig {
+    /// Trust coefficient scaling the layer-wise learning rate.
+    #[config(default = 0.001)]
+    trust_coefficient: f64,
+    /// Weight decay, added to the gradient and accounted for by the trust ratio.
+    #[config(default = 0.0)]
+    weight_decay: f64,
+    /// A value required for numerical stability.
+    #[config(default = 1e-8)]
+    epsilon: f64,
+    /// The norm of the parameters and gradients used by the trust ratio.
+    #[config(default = "NormKind::L2")]
+    norm: NormKind,
+    /// [Momentum](MomentumConfig) config.
+    momentum: Option<MomentumConfig>,
+    /// [Gradient Clipping](GradientClippingConfig) config.
+    grad_clipping: Option<GradientClippingConfig>,
+    /// [Gradient scale](crate::optim::adaptor::OptimizerAdaptor::with_grad_scale) of the adaptor.
+    #[config(default = 1.0)]
+    grad_scale: f32,
+}
+
+/// LARS optimizer as described in the paper
+/// [Large Batch Training of Convolutional Networks](https://arxiv.org/abs/1708.03888).
+///
+/// Each parameter is updated with its own learning rate, scaled by the trust ratio
+/// `trust_coefficient * ||param|| / (||grad|| + weight_decay * ||param||)`. The trust ratio is 1
+/// when the parameter or its gradient is zero.
+///
+/// Any [kind of norm](NormKind) can be used. The trust ratio only depends on the relative norms,
+/// except for the epsilon, which is relative to the magnitude of the elements with the
+/// [RMS](NormKind::RMS) norm instead of growing with the size of the parameter.
+pub struct Lars<B: Backend> {
+    trust_coefficient: f64,
+    weight_decay: f64,
+    epsilon: f64,
+    norm: NormKind,
+    momentum: Option<Momentum<B>>,
+}
+
+/// State of [Lars](Lars).
+#[derive(Record, Clone, new)]
+pub struct LarsState<B: Backend, const D: usize> {
+    momentum: Option<MomentumState<B, D>>,
+}
+
+impl<B: Backend> Lars<B> {
+    /// The trust ratio of the given parameter and gradient, as a tensor of shape `[1]`.
+    pub fn trust_ratio<const D: usize>(
+        &self,
+        tensor: &Tensor<B, D>,
+        grad: &Tensor<B, D>,
+    ) -> Tensor<B, 1> {
+        let param_norm = tensor_norm(tensor.clone(), &self.norm);
+        let grad_norm = tensor_norm(grad.clone(), &self.norm);
+
+        // The weight decay is part of the update the ratio bounds, so its norm is in the
+        // denominator too.
+        let denominator = param_norm
+            .clone()
+            .mul_scalar(self.weight_decay)
+            .add(grad_norm.clone())
+            .add_scalar(self.epsilon);
+        param_norm
+            .clone()
+            .mul_scalar(self.trust_coefficient)
+            .div(denominator)
+            .mask_fill(param_norm.equal_elem(0.0), 1.0)
+            .mask_fill(grad_norm.equal_elem(0.0), 1.0)
+    }
+}
+
+impl ValidateConfig for LarsConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        validate_positive("trust_coefficient", self.trust_coefficient)?;
+        validate_non_negative("weight_decay", self.weight_decay)?;
+        validate_positive("epsilon", self.epsilon)?;
+        if let Some(momentum) = &self.momentum {
+            momentum.validate()?;
+        }
+        if let Some(clipping) = &self.grad_clipping {
+            clipping.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl LarsConfig {
+    /// Initialize Lars as a [simple optimizer](SimpleOptimizer), to optimize tensors directly
+    /// with [step_tensors](crate::optim::step_tensors).
+    ///
+    /// Without weight decay, the trust ratio cancels the scale of the gradients, but the
+    /// `grad_clipping` of the config is only applied by [init](Self::init).
+    pub fn init_simple<B: Backend>(&self) -> Lars<B> {
+        Lars {
+            trust_coefficient: self.trust_coefficient,
+            weight_decay: self.weight_decay,
+            epsilon: self.epsilon,
+            norm: self.norm.clone(),
+            momentum: self.momentum.as_ref().map(Momentum::new),
+        }
+    }
+
+    /// Initialize Lars optimizer.
+    ///
+    /// # Returns
+    ///
+    /// Returns an optimizer that can be used to optimize a module.
+    pub fn init<B: AutodiffBackend, M: AutodiffModule<B>>(
+        &self,
+    ) -> OptimizerAdaptor<Lars<B::InnerBackend>, M, B> {
+        let mut optim = OptimizerAdaptor::from(self.init_simple::<B::InnerBackend>())
+            .with_grad_scale(self.grad_scale)
+            .with_config(self);
+        if let Some(config) = &self.grad_clipping {
+            optim = optim.with_grad_clipping(config.init());
+        }
+        optim
+    }
+}
+
+impl<B: Backend> SimpleOptimizer<B> for Lars<B> {
+    type State<const D: usize> = LarsState<B, D>;
+
+    fn step<const D: usize>(
+        &self,
+        lr: LearningRate,
+        tensor: Tensor<B, D>,
+        grad: Tensor<B, D>,
+        state: Option<Self::State<D>>,
+    ) -> (Tensor<B, D>, Option<Self::State<D>>) {
+        let trust_ratio = self.trust_ratio(&tensor, &grad);
+        let mut grad = grad
+            .add(tensor.clone().mul_scalar(self.weight_decay))
+            .mul(trust_ratio.reshape([1; D]));
+
+        let mut state_momentum = state.and_then(|state| state.momentum);
+        if let Some(momentum) = &self.momentum {
+            let (grad_out, state) = momentum.transform(grad, state_momentum);
+            state_momentum = Some(state);
+            grad = grad_out;
+        }
+
+        let state = LarsState::new(state_momentum);
+
+        (tensor - grad.mul_scalar(lr), Some(state))
+    }
+
+    fn to_device<const D: usize>(mut state: Self::State<D>, device: &B::Device) -> Self::State<D> {
+        state.momentum = state.momentum.map(|state| state.to_device(device));
+        state
+    }
+
+    fn state_map_tensors<const D: usize, F>(mut state: Self::State<D>, func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        state.momentum = state.momentum.map(|state| state.map_tensors(func));
+        state
+    }
+
+    fn state_num_elements<const D: usize>(state: &Self::State<D>) -> usize {
+        state
+            .momentum
+            .as_ref()
+            .map(|momentum| momentum.num_elements())
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tensor::Data;
+    use crate::TestBackend;
+
+    const LEARNING_RATE: LearningRate = 1.0;
+
+    #[test]
+    fn test_lars_update_is_scaled_by_trust_ratio() {
+        let optim = LarsConfig::new()
+            .with_trust_coefficient(0.1)
+            .init_simple::<TestBackend>();
+        let tensor = Tensor::<TestBackend, 1>::from_floats([3.0, 4.0]);
+        let grad = Tensor::<TestBackend, 1>::from_floats([0.0, 10.0]);
+
+        // The trust ratio is 0.1 * 5 / 10, so the update has the norm of the parameter times 0.1.
+        let (tensor, _) = optim.step(LEARNING_RATE, tensor, grad, None);
+
+        tensor
+            .to_data()
+            .assert_approx_eq(&Data::from([3.0, 3.5]), 5);
+    }
+
+    #[test]
+    fn test_lars_rms_norm_differs_from_l2_norm() {
+        let config = LarsConfig::new().with_epsilon(1e-3);
+        let optim_l2 = config.init_simple::<TestBackend>();
+        let optim_rms = config.with_norm(NormKind::RMS).init_simple::<TestBackend>();
+        let tensor = Tensor::<TestBackend, 1>::ones([100]);
+        let grad = Tensor::<TestBackend, 1>::ones([100]).mul_scalar(0.01);
+
+        // The gradient norms are 0.1 with L2 and 0.01 with RMS, so the epsilon shrinks the trust
+        // ratio by about 1% with L2 but 9% with RMS.
+        let ratio_l2 = optim_l2.trust_ratio(&tensor, &grad).into_scalar();
+        let ratio_rms = optim_rms.trust_ratio(&tensor, &grad).into_scalar();
+        assert!((ratio_l2 - 0.001 * 10.0 / 0.101).abs() < 1e-5);
+        assert!((ratio_rms - 0.001 * 1.0 / 0.011).abs() < 1e-5);
+
+        let (tensor_l2, _) = optim_l2.step(LEARNING_RATE, tensor.clone(), grad.clone(), None);
+        let (tensor_rms, _) = optim_rms.step(LEARNING_RATE, tensor, grad, None);
+        let diff = tensor_l2.sub(tensor_rms).abs().max().into_scalar();
+        assert!(diff > 1e-6);
+    }
+
+    #[test]
+    fn test_lars_config_validate() {
+        assert!(LarsConfig::new().validate().is_ok());
+        assert!(matches!(
+            LarsConfig::new().with_trust_coefficient(0.0).validate(),
+            Err(ConfigError::OutOfRange(message))
+                if message == "trust_coefficient must be positive, got 0."
+        ));
+        assert!(matches!(
+            LarsConfig::new()
+                .with_momentum(Some(MomentumConfig::new().with_dampening(1.0)))
+                .validate(),
+            Err(ConfigError::OutOfRange(message))
+                if message == "momentum.dampening must be in [0, 1), got 1."
+        ));
+    }
+}
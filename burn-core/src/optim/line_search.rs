@@ -0,0 +1,183 @@
+use crate as burn;
+
+use super::GradientsParams;
+use crate::config::Config;
+use crate::module::{AutodiffModule, ModuleMapper, ParamId};
+use crate::tensor::{backend::AutodiffBackend, Tensor};
+use core::marker::PhantomData;
+
+/// Configuration to create the [gradient descent with exact line search](ExactLineSearchSgd).
+#[derive(Config)]
+pub struct ExactLineSearchSgdConfig {
+    /// The first step size tried when bracketing the minimum.
+    #[config(default = 1.0)]
+    initial_step_size: f64,
+    /// Maximum number of times the bracket is doubled to contain the minimum.
+    #[config(default = 32)]
+    max_expansions: usize,
+    /// The search stops when the bracket is smaller than this value.
+    #[config(default = 1e-6)]
+    tolerance: f64,
+    /// Maximum number of iterations of the golden-section search.
+    #[config(default = 100)]
+    max_iters: usize,
+}
+
+/// Gradient descent where the step size minimizes the loss along the gradient direction.
+///
+/// The minimum is first bracketed by doubling the step size, then refined with a golden-section
+/// search, which finds the exact minimum when the loss is convex along the gradient direction.
+///
+/// # Notes
+///
+/// This is a tool to validate optimization on convex toy problems. It doesn't implement
+/// [Optimizer](super::Optimizer), since each step needs to evaluate the loss many times.
+pub struct ExactLineSearchSgd<M, B> {
+    config: ExactLineSearchSgdConfig,
+    phantom: PhantomData<(M, B)>,
+}
+
+impl ExactLineSearchSgdConfig {
+    /// Initialize gradient descent with exact line search.
+    pub fn init<B: AutodiffBackend, M: AutodiffModule<B>>(&self) -> ExactLineSearchSgd<M, B> {
+        assert!(
+            self.initial_step_size > 0.0,
+            "The initial step size must be positive."
+        );
+
+        ExactLineSearchSgd {
+            config: self.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<M, B> ExactLineSearchSgd<M, B>
+where
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    /// Perform a step along the negative gradients, with the step size minimizing the loss.
+    ///
+    /// # Arguments
+    ///
+    /// * `module` - The module to optimize.
+    /// * `grads` - The gradients of the module parameters.
+    /// * `loss` - Evaluates the loss of a module.
+    ///
+    /// # Returns
+    ///
+    /// The updated module and the step size.
+    pub fn step<F>(&self, module: M, grads: GradientsParams, loss: F) -> (M, f64)
+    where
+        F: Fn(&M) -> f64,
+    {
+        let loss_at = |step_size: f64| {
+            let candidate = module
+                .clone()
+                .map(&mut LineSearchMapper::<B>::new(&grads, step_size));
+            loss(&candidate)
+        };
+
+        // Find a step size after which the loss increases, so the minimum is in [0, 2 * size].
+        let mut size = self.config.initial_step_size;
+        let mut loss_size = loss_at(size);
+        for _ in 0..self.config.max_expansions {
+            let loss_double = loss_at(2.0 * size);
+            if loss_double > loss_size {
+                break;
+            }
+            size *= 2.0;
+            loss_size = loss_double;
+        }
+
+        // Golden-section search of the minimum in the bracket.
+        let ratio = (5f64.sqrt() - 1.0) / 2.0;
+        let (mut lower, mut upper) = (0.0, 2.0 * size);
+        let mut left = upper - ratio * (upper - lower);
+        let mut right = lower + ratio * (upper - lower);
+        let (mut loss_left, mut loss_right) = (loss_at(left), loss_at(right));
+
+        for _ in 0..self.config.max_iters {
+            if upper - lower <= self.config.tolerance {
+                break;
+            }
+
+            if loss_left < loss_right {
+                upper = right;
+                right = left;
+                loss_right = loss_left;
+                left = upper - ratio * (upper - lower);
+                loss_left = loss_at(left);
+            } else {
+                lower = left;
+                left = right;
+                loss_left = loss_right;
+                right = lower + ratio * (upper - lower);
+                loss_right = loss_at(right);
+            }
+        }
+
+        let step_size = (lower + upper) / 2.0;
+        let module = module.map(&mut LineSearchMapper::<B>::new(&grads, step_size));
+
+        (module, step_size)
+    }
+}
+
+#[derive(new)]
+struct LineSearchMapper<'a, B: AutodiffBackend> {
+    grads: &'a GradientsParams,
+    step_size: f64,
+    phantom: PhantomData<B>,
+}
+
+impl<'a, B: AutodiffBackend> ModuleMapper<B> for LineSearchMapper<'a, B> {
+    fn map<const D: usize>(&mut self, id: &ParamId, tensor: Tensor<B, D>) -> Tensor<B, D> {
+        let Some(grad) = self.grads.get::<B::InnerBackend, D>(id) else {
+            return tensor;
+        };
+
+        let is_require_grad = tensor.is_require_grad();
+        let mut tensor = Tensor::from_inner(tensor.inner() - grad.mul_scalar(self.step_size));
+        if is_require_grad {
+            tensor = tensor.require_grad();
+        }
+        tensor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        module::Param,
+        nn::{Linear, LinearConfig, LinearRecord},
+        TestAutodiffBackend,
+    };
+    use burn_tensor::{Data, ElementConversion};
+
+    #[test]
+    fn test_exact_line_search_reaches_quadratic_minimum_in_one_step() {
+        let linear = LinearConfig::new(2, 2).init_with(LinearRecord {
+            weight: Param::from(Tensor::<TestAutodiffBackend, 2>::from_floats([
+                [1.0, -2.0],
+                [0.5, 3.0],
+            ])),
+            bias: None,
+        });
+        let loss = |linear: &Linear<TestAutodiffBackend>| linear.weight.val().powf(2.0).sum();
+        let optim = ExactLineSearchSgdConfig::new().init();
+
+        let grads = GradientsParams::from_grads(loss(&linear).backward(), &linear);
+        let (linear, step_size) = optim.step(linear, grads, |linear| {
+            loss(linear).into_scalar().elem::<f64>()
+        });
+
+        assert!((step_size - 0.5).abs() < 1e-4);
+        linear
+            .weight
+            .to_data()
+            .assert_approx_eq(&Data::from([[0.0, 0.0], [0.0, 0.0]]), 3);
+    }
+}
@@ -0,0 +1,276 @@
+use crate::{
+    self as burn, grad_clipping::GradientClippingConfig, module::AutodiffModule, record::Record,
+    LearningRate,
+};
+
+use super::validation::{validate_beta, validate_positive};
+use super::{
+    decay::{WeightDecay, WeightDecayConfig, WeightDecayWarmup},
+    SimpleOptimizer,
+};
+use crate::config::{Config, ConfigError, ValidateConfig};
+use crate::optim::adaptor::OptimizerAdaptor;
+use crate::tensor::{backend::AutodiffBackend, Tensor};
+use burn_tensor::backend::Backend;
+
+/// MADGRAD configuration.
+#[derive(Config)]
+pub struct MadgradConfig {
+    /// Momentum of the averaging of the parameters toward the dual averaging iterate, `0` to use
+    /// the dual averaging iterate directly.
+    #[config(default = 0.9)]
+    momentum: f64,
+    /// A value required for numerical stability.
+    #[config(default = 1e-6)]
+    epsilon: f32,
+    /// [Weight decay](WeightDecayConfig) config.
+    weight_decay: Option<WeightDecayConfig>,
+    /// [Gradient Clipping](GradientClippingConfig) config.
+    grad_clipping: Option<GradientClippingConfig>,
+    /// [Gradient scale](crate::optim::adaptor::OptimizerAdaptor::with_grad_scale) of the adaptor.
+    #[config(default = 1.0)]
+    grad_scale: f32,
+}
+
+/// MADGRAD optimizer as described in the paper
+/// [Adaptivity without Compromise](https://arxiv.org/abs/2101.11075).
+///
+/// MADGRAD is a momentumized dual averaging method: the parameter isn't updated incrementally,
+/// it's recomputed at each step from the anchor point `x0`, the weighted sum of the gradients
+/// `s` and the weighted sum of the squared gradients `v`, with a weight of
+/// `lr * sqrt(k + 1)` at step `k`:
+///
+/// `z = x0 - s / (cbrt(v) + epsilon)`, then `x = momentum * x + (1 - momentum) * z`.
+pub struct Madgrad<B: Backend> {
+    momentum: f64,
+    epsilon: f32,
+    weight_decay: Option<WeightDecay<B>>,
+}
+
+/// MADGRAD state.
+#[derive(Record, Clone, new)]
+pub struct MadgradState<B: Backend, const D: usize> {
+    time: usize,
+    anchor: Tensor<B, D>,
+    grad_sum: Tensor<B, D>,
+    grad_sum_squared: Tensor<B, D>,
+}
+
+impl<B: Backend> SimpleOptimizer<B> for Madgrad<B> {
+    type State<const D: usize> = MadgradState<B, D>;
+
+    fn step<const D: usize>(
+        &self,
+        lr: LearningRate,
+        tensor: Tensor<B, D>,
+        mut grad: Tensor<B, D>,
+        state: Option<Self::State<D>>,
+    ) -> (Tensor<B, D>, Option<Self::State<D>>) {
+        if let Some(weight_decay) = &self.weight_decay {
+            grad = weight_decay.transform(grad, tensor.clone());
+        }
+
+        // The anchor is the parameter before the first step.
+        let (time, anchor, grad_sum, grad_sum_squared) = match state {
+            Some(state) => (
+                state.time,
+                state.anchor,
+                state.grad_sum,
+                state.grad_sum_squared,
+            ),
+            None => (0, tensor.clone(), tensor.zeros_like(), tensor.zeros_like()),
+        };
+
+        let lambda = lr * ((time + 1) as f64).sqrt();
+        let grad_sum_squared = grad_sum_squared.add(grad.clone().powf(2.0).mul_scalar(lambda));
+        let grad_sum = grad_sum.add(grad.mul_scalar(lambda));
+
+        let denominator = grad_sum_squared
+            .clone()
+            .powf(1.0 / 3.0)
+            .add_scalar(self.epsilon);
+        let dual_average = anchor.clone().sub(grad_sum.clone().div(denominator));
+
+        let mut tensor = if self.momentum == 0.0 {
+            dual_average
+        } else {
+            tensor
+                .mul_scalar(self.momentum)
+                .add(dual_average.mul_scalar(1.0 - self.momentum))
+        };
+        let state = MadgradState::new(time + 1, anchor, grad_sum, grad_sum_squared);
+        if let Some(weight_decay) = &self.weight_decay {
+            tensor = weight_decay.proximal(lr, tensor);
+        }
+
+        (tensor, Some(state))
+    }
+
+    fn to_device<const D: usize>(mut state: Self::State<D>, device: &B::Device) -> Self::State<D> {
+        state.anchor = state.anchor.to_device(device);
+        state.grad_sum = state.grad_sum.to_device(device);
+        state.grad_sum_squared = state.grad_sum_squared.to_device(device);
+        state
+    }
+
+    fn state_map_tensors<const D: usize, F>(mut state: Self::State<D>, func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        state.anchor = func(state.anchor);
+        state.grad_sum = func(state.grad_sum);
+        state.grad_sum_squared = func(state.grad_sum_squared);
+        state
+    }
+
+    fn state_num_elements<const D: usize>(state: &Self::State<D>) -> usize {
+        state.anchor.shape().num_elements()
+            + state.grad_sum.shape().num_elements()
+            + state.grad_sum_squared.shape().num_elements()
+    }
+
+    fn state_num_steps<const D: usize>(state: &Self::State<D>) -> Option<usize> {
+        Some(state.time)
+    }
+}
+
+impl ValidateConfig for MadgradConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        validate_beta("momentum", self.momentum)?;
+        validate_positive("epsilon", self.epsilon)?;
+        if let Some(weight_decay) = &self.weight_decay {
+            weight_decay.validate()?;
+        }
+        if let Some(clipping) = &self.grad_clipping {
+            clipping.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl MadgradConfig {
+    /// Initialize MADGRAD as a [simple optimizer](SimpleOptimizer), to optimize tensors directly
+    /// with [step_tensors](crate::optim::step_tensors).
+    ///
+    /// The dual average sums the gradients as given, so they should already be clipped and
+    /// unscaled, which [init](Self::init) does with the `grad_clipping` and `grad_scale` of the
+    /// config.
+    pub fn init_simple<B: Backend>(&self) -> Madgrad<B> {
+        if let Err(err) = self.validate() {
+            panic!("{err}");
+        }
+
+        Madgrad {
+            momentum: self.momentum,
+            epsilon: self.epsilon,
+            weight_decay: self.weight_decay.as_ref().map(WeightDecay::new),
+        }
+    }
+
+    /// Initialize MADGRAD optimizer.
+    ///
+    /// # Returns
+    ///
+    /// Returns an optimizer that can be used to optimize a module.
+    pub fn init<B: AutodiffBackend, M: AutodiffModule<B>>(
+        &self,
+    ) -> OptimizerAdaptor<Madgrad<B::InnerBackend>, M, B> {
+        // The adaptor completes the decay toward the initial values, and applies the warmed up
+        // decay itself.
+        let weight_decay = self
+            .weight_decay
+            .as_ref()
+            .and_then(WeightDecayConfig::for_simple_optimizer);
+        let optim = self
+            .clone()
+            .with_weight_decay(weight_decay)
+            .init_simple::<B::InnerBackend>();
+
+        let mut optim = OptimizerAdaptor::from(optim)
+            .with_grad_scale(self.grad_scale)
+            .with_config(self);
+        if let Some(config) = &self.grad_clipping {
+            optim = optim.with_grad_clipping(config.init());
+        }
+        if let Some(config) = &self.weight_decay {
+            optim = optim.with_weight_decay(config.clone());
+        }
+        if let Some(config) = self.weight_decay.as_ref().filter(|c| c.warmup_steps > 0) {
+            optim = optim.with_weight_decay_warmup(WeightDecayWarmup::new(config));
+        }
+        optim
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tensor::Data;
+    use crate::TestBackend;
+
+    const LEARNING_RATE: LearningRate = 0.1;
+
+    fn run_three_steps(
+        optim: &Madgrad<TestBackend>,
+    ) -> (Tensor<TestBackend, 1>, MadgradState<TestBackend, 1>) {
+        let mut tensor = Tensor::<TestBackend, 1>::from_floats([1.0, -0.5]);
+        let mut state = None;
+        for grad in [[0.5, -1.0], [0.25, 0.5], [-0.5, 0.75]] {
+            let (tensor_next, state_next) =
+                optim.step(LEARNING_RATE, tensor, Tensor::from_floats(grad), state);
+            tensor = tensor_next;
+            state = state_next;
+        }
+
+        (tensor, state.unwrap())
+    }
+
+    #[test]
+    fn test_madgrad_dual_averaging_reconstruction() {
+        let optim = MadgradConfig::new()
+            .with_momentum(0.0)
+            .init_simple::<TestBackend>();
+
+        let (tensor, state) = run_three_steps(&optim);
+
+        // The sums are weighted by 0.1 * sqrt(k + 1), and the parameter is recomputed from the
+        // anchor, not from the previous parameter.
+        assert_eq!(state.time, 3);
+        state
+            .anchor
+            .to_data()
+            .assert_approx_eq(&Data::from([1.0, -0.5]), 5);
+        state
+            .grad_sum
+            .to_data()
+            .assert_approx_eq(&Data::from([-0.0012472, 0.1006145]), 5);
+        state
+            .grad_sum_squared
+            .to_data()
+            .assert_approx_eq(&Data::from([0.0771401, 0.2327832]), 5);
+        tensor
+            .to_data()
+            .assert_approx_eq(&Data::from([1.0029298, -0.6635596]), 5);
+    }
+
+    #[test]
+    fn test_madgrad_momentum_averages_toward_dual_average() {
+        let optim = MadgradConfig::new().init_simple::<TestBackend>();
+
+        let (tensor, _) = run_three_steps(&optim);
+
+        tensor
+            .to_data()
+            .assert_approx_eq(&Data::from([0.9626921, -0.4937710]), 5);
+    }
+
+    #[test]
+    fn test_madgrad_config_validate() {
+        assert!(MadgradConfig::new().validate().is_ok());
+        assert!(matches!(
+            MadgradConfig::new().with_momentum(1.0).validate(),
+            Err(ConfigError::OutOfRange(message)) if message == "momentum must be in [0, 1), got 1."
+        ));
+    }
+}
@@ -0,0 +1,350 @@
+use crate as burn;
+
+use super::convergence::{retain_updated, ParamsCollector, UpdateRatios};
+use super::{base::delegate_optimizer, GradientsParams, Optimizer};
+use crate::config::Config;
+use crate::grad_clipping::{tensor_norm, GlobalNormAccumulator, NormKind};
+use crate::module::{AutodiffModule, ModuleVisitor, ParamId};
+use crate::tensor::{backend::AutodiffBackend, Tensor};
+use crate::LearningRate;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use hashbrown::{HashMap, HashSet};
+
+/// Destination of the metrics recorded by an [optimizer metrics](OptimizerMetrics) wrapper, e.g.
+/// to forward them to TensorBoard or Weights & Biases.
+///
+/// The metrics of a step are recorded one after the other, each one with the index of the step,
+/// starting at zero. The keys are:
+///
+/// * `lr` - The learning rate.
+/// * `grad_norm` - The global L2 norm of the gradients.
+/// * `grad_norm/<group>` - The L2 norm of the gradients of each group of parameters.
+/// * `clip_ratio` - The fraction of the gradients with a norm above the clipping threshold.
+/// * `update_ratio` - The average ratio `||update|| / ||param||` of the updated parameters.
+pub trait MetricsSink: Send + Sync {
+    /// Record the value of a metric, ignored by default.
+    fn record(&mut self, _step: usize, _key: &str, _value: f64) {}
+}
+
+/// Configuration to create an [optimizer metrics](OptimizerMetrics) wrapper.
+#[derive(Config)]
+pub struct OptimizerMetricsConfig {
+    /// The norm threshold of the gradient clipping of the wrapped optimizer, to record the
+    /// `clip_ratio` metric. The metric isn't recorded without a threshold.
+    clip_threshold: Option<f32>,
+    /// A value required for numerical stability.
+    #[config(default = 1e-12)]
+    epsilon: f64,
+}
+
+/// Optimizer wrapper recording the statistics of each step to a [metrics sink](MetricsSink).
+///
+/// # Notes
+///
+/// The metrics of a step are reduced on the device and read back at once after the step.
+pub struct OptimizerMetrics<O, M, B, S>
+where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+    S: MetricsSink,
+{
+    optim: O,
+    sink: S,
+    clip_threshold: Option<f32>,
+    epsilon: f64,
+    groups: Vec<(String, HashSet<ParamId>)>,
+    num_steps: usize,
+    phantom: PhantomData<(M, B)>,
+}
+
+impl OptimizerMetricsConfig {
+    /// Wrap the given optimizer to record its metrics to the given sink.
+    pub fn init<O, M, B, S>(&self, optim: O, sink: S) -> OptimizerMetrics<O, M, B, S>
+    where
+        O: Optimizer<M, B>,
+        M: AutodiffModule<B>,
+        B: AutodiffBackend,
+        S: MetricsSink,
+    {
+        OptimizerMetrics {
+            optim,
+            sink,
+            clip_threshold: self.clip_threshold,
+            epsilon: self.epsilon,
+            groups: Vec::new(),
+            num_steps: 0,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<O, M, B, S> OptimizerMetrics<O, M, B, S>
+where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+    S: MetricsSink,
+{
+    /// Record the norm of the gradients of a group of parameters, as the `grad_norm/<group>`
+    /// metric.
+    ///
+    /// # Arguments
+    ///
+    /// * `group` - The name of the group.
+    /// * `params` - The ids of the parameters of the group.
+    ///
+    /// # Returns
+    ///
+    /// The optimizer.
+    pub fn with_group<I: IntoIterator<Item = ParamId>>(mut self, group: &str, params: I) -> Self {
+        self.groups
+            .push((group.to_string(), params.into_iter().collect()));
+        self
+    }
+
+    /// The metrics sink.
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    /// The metrics of the gradients, on the device, empty without gradients.
+    fn grad_metrics(
+        &self,
+        module: &M,
+        grads: &GradientsParams,
+    ) -> Vec<(String, Tensor<B::InnerBackend, 1>)> {
+        // The first accumulator is the one of all the gradients, followed by one per group.
+        let mut accumulators: Vec<_> = (0..self.groups.len() + 1).map(|_| None).collect();
+        let mut param_norms = HashMap::new();
+        module.visit(&mut GradNorms::<B>::new(
+            grads,
+            &self.groups,
+            &mut accumulators,
+            &mut param_norms,
+            HashSet::new(),
+        ));
+        let Some(device) = param_norms.values().next().map(|norm| norm.device()) else {
+            return Vec::new();
+        };
+
+        let keys = core::iter::once("grad_norm".to_string()).chain(
+            self.groups
+                .iter()
+                .map(|(group, _)| format!("grad_norm/{group}")),
+        );
+        let mut metrics: Vec<_> = keys
+            .zip(accumulators)
+            .map(|(key, accumulator)| {
+                let norm = accumulator.map_or_else(
+                    || Tensor::zeros_device([1], &device),
+                    |accumulator| accumulator.norm(),
+                );
+                (key, norm)
+            })
+            .collect();
+
+        if let Some(threshold) = self.clip_threshold {
+            let num_params = param_norms.len();
+            let norms: Vec<_> = param_norms
+                .into_values()
+                .map(|norm| norm.to_device(&device))
+                .collect();
+            let ratio = Tensor::cat(norms, 0)
+                .greater_elem(threshold)
+                .float()
+                .sum()
+                .div_scalar(num_params as f64);
+            metrics.push(("clip_ratio".to_string(), ratio));
+        }
+
+        metrics
+    }
+
+    /// Record the metrics of a step, gathered on one device so they are read back at once.
+    fn record_metrics(&mut self, metrics: Vec<(String, Tensor<B::InnerBackend, 1>)>) {
+        let Some(device) = metrics.first().map(|(_, value)| value.device()) else {
+            return;
+        };
+
+        let (keys, values): (Vec<_>, Vec<_>) = metrics
+            .into_iter()
+            .map(|(key, value)| (key, value.to_device(&device)))
+            .unzip();
+        let values = Tensor::cat(values, 0).into_data().convert::<f64>().value;
+        for (key, value) in keys.into_iter().zip(values) {
+            self.sink.record(self.num_steps, &key, value);
+        }
+    }
+}
+
+impl<O, M, B, S> Optimizer<M, B> for OptimizerMetrics<O, M, B, S>
+where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+    S: MetricsSink,
+{
+    type Record = O::Record;
+
+    fn step(&mut self, lr: LearningRate, module: M, grads: GradientsParams) -> M {
+        self.sink.record(self.num_steps, "lr", lr);
+        let mut metrics = self.grad_metrics(&module, &grads);
+
+        let mut params = HashMap::new();
+        module.visit(&mut ParamsCollector::<B>::new(&mut params, &grads));
+
+        let module = self.optim.step(lr, module, grads);
+        retain_updated::<O, M, B>(&mut params, &self.optim);
+
+        let mut ratios = UpdateRatios::<B>::new(&mut params, self.epsilon, None, 0);
+        module.visit(&mut ratios);
+        if let Some(sum) = ratios.sum {
+            let ratio = sum.div_scalar(ratios.count as f64);
+            metrics.push(("update_ratio".to_string(), ratio));
+        }
+        self.record_metrics(metrics);
+
+        self.num_steps += 1;
+        module
+    }
+
+    fn to_record(&self) -> Self::Record {
+        self.optim.to_record()
+    }
+
+    fn load_record(mut self, record: Self::Record) -> Self {
+        self.optim = self.optim.load_record(record);
+        self
+    }
+
+    delegate_optimizer!(optim);
+}
+
+/// Accumulate the global norm of the gradients and the ones of each group, with the norm of each
+/// gradient.
+#[derive(new)]
+struct GradNorms<'a, B: AutodiffBackend> {
+    grads: &'a GradientsParams,
+    groups: &'a [(String, HashSet<ParamId>)],
+    accumulators: &'a mut Vec<Option<GlobalNormAccumulator<B::InnerBackend>>>,
+    param_norms: &'a mut HashMap<ParamId, Tensor<B::InnerBackend, 1>>,
+    visited: HashSet<ParamId>,
+}
+
+impl<'a, B: AutodiffBackend> ModuleVisitor<B> for GradNorms<'a, B> {
+    fn visit<const D: usize>(&mut self, id: &ParamId, _tensor: &Tensor<B, D>) {
+        if !self.visited.insert(id.clone()) {
+            return;
+        }
+        let Some(grad) = self.grads.get::<B::InnerBackend, D>(id) else {
+            return;
+        };
+
+        let in_groups =
+            core::iter::once(true).chain(self.groups.iter().map(|(_, params)| params.contains(id)));
+        for (accumulator, in_group) in self.accumulators.iter_mut().zip(in_groups) {
+            if in_group {
+                accumulator
+                    .get_or_insert_with(|| GlobalNormAccumulator::new(&grad.device()))
+                    .add(grad.clone());
+            }
+        }
+        self.param_norms
+            .insert(id.clone(), tensor_norm(grad, &NormKind::L2));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::{Linear, LinearConfig};
+    use crate::optim::SgdConfig;
+    use crate::TestAutodiffBackend;
+    use burn_tensor::ElementConversion;
+
+    type B = TestAutodiffBackend;
+    type M = Linear<TestAutodiffBackend>;
+
+    #[derive(Default)]
+    struct InMemorySink {
+        metrics: Vec<(usize, String, f64)>,
+    }
+
+    impl MetricsSink for InMemorySink {
+        fn record(&mut self, step: usize, key: &str, value: f64) {
+            self.metrics.push((step, key.to_string(), value));
+        }
+    }
+
+    #[test]
+    fn test_metrics_are_recorded_each_step() {
+        let mut linear: M = LinearConfig::new(4, 2).init();
+        let mut optim = OptimizerMetricsConfig::new()
+            .with_clip_threshold(Some(1.0))
+            .init(SgdConfig::new().init::<B, M>(), InMemorySink::default())
+            .with_group("weight", [linear.weight.id.clone()]);
+        let x = Tensor::<B, 2>::ones([2, 4]);
+
+        let mut grad_norms = Vec::new();
+        for _ in 0..2 {
+            let grads = GradientsParams::from_grads(linear.forward(x.clone()).backward(), &linear);
+            grad_norms.push(grads.global_norm::<B, M>(&linear).unwrap().into_scalar());
+            linear = optim.step(0.1, linear, grads);
+        }
+
+        let metrics = &optim.sink().metrics;
+        let keys = [
+            "lr",
+            "grad_norm",
+            "grad_norm/weight",
+            "clip_ratio",
+            "update_ratio",
+        ];
+        assert_eq!(metrics.len(), 2 * keys.len());
+        for (i, (step, key, value)) in metrics.iter().enumerate() {
+            assert_eq!(*step, i / keys.len());
+            assert_eq!(key, keys[i % keys.len()]);
+            assert!(value.is_finite());
+        }
+
+        // Each component of the gradients is the batch size, so the gradient of the weight has a
+        // norm of 5.7 and the one of the bias a norm of 2.8, both above the threshold.
+        let (_, _, lr) = &metrics[0];
+        let (_, _, grad_norm) = &metrics[1];
+        let (_, _, clip_ratio) = &metrics[3];
+        assert_eq!(*lr, 0.1);
+        assert!((grad_norm - grad_norms[0] as f64).abs() < 1e-4);
+        assert_eq!(*clip_ratio, 1.0);
+    }
+
+    #[test]
+    fn test_update_ratio_skips_frozen_params() {
+        let mut linear: M = LinearConfig::new(4, 2).init();
+        linear.weight = linear.weight.no_grad();
+        let bias = linear.bias.as_ref().unwrap().val().inner();
+        let mut optim = OptimizerMetricsConfig::new()
+            .init(SgdConfig::new().init::<B, M>(), InMemorySink::default());
+
+        let x = Tensor::<B, 2>::ones([2, 4]);
+        let grads = GradientsParams::from_grads(linear.forward(x).backward(), &linear);
+        let linear = optim.step(0.1, linear, grads);
+
+        // Only the bias is updated, so the average is its own ratio.
+        let update = linear.bias.unwrap().val().inner().sub(bias.clone());
+        let expected = tensor_norm(update, &NormKind::L2)
+            .div(tensor_norm(bias, &NormKind::L2))
+            .into_scalar()
+            .elem::<f64>();
+        let (_, _, ratio) = optim
+            .sink()
+            .metrics
+            .iter()
+            .find(|(_, key, _)| key == "update_ratio")
+            .unwrap();
+        assert!((ratio - expected).abs() < 1e-6);
+    }
+}
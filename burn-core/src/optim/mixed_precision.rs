@@ -0,0 +1,318 @@
+use crate as burn;
+
+use super::GradientsParams;
+use crate::config::Config;
+use crate::module::{AutodiffModule, ModuleVisitor, ParamId};
+use crate::tensor::{backend::AutodiffBackend, Tensor};
+use alloc::vec;
+use alloc::vec::Vec;
+use burn_tensor::ElementConversion;
+use core::marker::PhantomData;
+use hashbrown::HashMap;
+
+/// Smallest base-2 exponent of a normal half precision float.
+const HALF_MIN_EXPONENT: i32 = -14;
+
+/// Smallest base-2 exponent that overflows a half precision float.
+const HALF_MAX_EXPONENT: i32 = 16;
+
+/// Configuration to create a [mixed precision calibrator](MixedPrecisionCalibrator).
+#[derive(Config)]
+pub struct MixedPrecisionCalibratorConfig {
+    /// Number of observed steps before a policy is recommended.
+    #[config(default = 100)]
+    calibration_steps: usize,
+    /// Smallest base-2 exponent tracked by the histograms, smaller magnitudes are counted in
+    /// the first bin.
+    #[config(default = -32)]
+    min_exponent: i32,
+    /// Largest base-2 exponent tracked by the histograms, larger magnitudes are counted in the
+    /// last bin.
+    #[config(default = 32)]
+    max_exponent: i32,
+    /// Maximum fraction of non-zero gradient values outside of the half precision range before
+    /// a parameter is kept in full precision.
+    #[config(default = 0.01)]
+    tolerance: f64,
+}
+
+/// Precision recommended for a parameter by a [mixed precision policy](MixedPrecisionPolicy).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecommendedPrecision {
+    /// The parameter can safely run in half precision (f16).
+    Half,
+    /// The parameter is numerically sensitive and should be kept in full precision (f32).
+    Full,
+}
+
+/// Histogram of the base-2 exponents of the gradient magnitudes of a parameter.
+#[derive(Debug, Clone)]
+pub struct GradientHistogram {
+    min_exponent: i32,
+    counts: Vec<u64>,
+    zeros: u64,
+}
+
+impl GradientHistogram {
+    /// Create an empty histogram tracking the exponents in `min_exponent..=max_exponent`.
+    pub fn new(min_exponent: i32, max_exponent: i32) -> Self {
+        assert!(
+            max_exponent >= min_exponent,
+            "The max exponent must be greater or equal to the min exponent."
+        );
+
+        Self {
+            min_exponent,
+            counts: vec![0; (max_exponent - min_exponent + 1) as usize],
+            zeros: 0,
+        }
+    }
+
+    /// Add a gradient value to the histogram.
+    pub fn add(&mut self, value: f32) {
+        let magnitude = value.abs();
+
+        if magnitude == 0.0 || magnitude.is_nan() {
+            self.zeros += 1;
+            return;
+        }
+
+        let exponent = if magnitude.is_infinite() {
+            i32::MAX
+        } else {
+            libm::floorf(libm::log2f(magnitude)) as i32
+        };
+        let index = exponent
+            .saturating_sub(self.min_exponent)
+            .clamp(0, self.counts.len() as i32 - 1);
+
+        self.counts[index as usize] += 1;
+    }
+
+    /// The number of non-zero values in each bin, starting at the min exponent.
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// The number of zero (or NaN) values added to the histogram.
+    pub fn zeros(&self) -> u64 {
+        self.zeros
+    }
+
+    /// The number of non-zero values added to the histogram.
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// The fraction of non-zero values whose exponent is outside of `min..max`.
+    pub fn fraction_outside(&self, min: i32, max: i32) -> f64 {
+        let total = self.total();
+
+        if total == 0 {
+            return 0.0;
+        }
+
+        let outside: u64 = self
+            .counts
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| {
+                let exponent = self.min_exponent + *index as i32;
+                exponent < min || exponent >= max
+            })
+            .map(|(_, count)| count)
+            .sum();
+
+        outside as f64 / total as f64
+    }
+}
+
+/// Recommended precision for each parameter, as computed by a
+/// [mixed precision calibrator](MixedPrecisionCalibrator).
+#[derive(Debug, Clone, Default)]
+pub struct MixedPrecisionPolicy {
+    precisions: HashMap<ParamId, RecommendedPrecision>,
+}
+
+impl MixedPrecisionPolicy {
+    /// Get the recommended precision for the given [parameter id](ParamId).
+    pub fn get(&self, id: &ParamId) -> Option<RecommendedPrecision> {
+        self.precisions.get(id).copied()
+    }
+
+    /// Iterate over the recommended precision of each parameter.
+    pub fn iter(&self) -> impl Iterator<Item = (&ParamId, &RecommendedPrecision)> {
+        self.precisions.iter()
+    }
+
+    /// The parameters that should be kept in full precision.
+    pub fn full_precision_params(&self) -> Vec<ParamId> {
+        self.precisions
+            .iter()
+            .filter(|(_, precision)| **precision == RecommendedPrecision::Full)
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+}
+
+/// Track the gradient magnitudes of each parameter during a calibration phase to recommend
+/// which parameters can run in half precision and which should be kept in full precision.
+pub struct MixedPrecisionCalibrator {
+    config: MixedPrecisionCalibratorConfig,
+    histograms: HashMap<ParamId, GradientHistogram>,
+    steps: usize,
+}
+
+impl MixedPrecisionCalibratorConfig {
+    /// Initialize a new [mixed precision calibrator](MixedPrecisionCalibrator).
+    pub fn init(&self) -> MixedPrecisionCalibrator {
+        MixedPrecisionCalibrator {
+            config: self.clone(),
+            histograms: HashMap::new(),
+            steps: 0,
+        }
+    }
+}
+
+impl MixedPrecisionCalibrator {
+    /// Record the gradients of each parameter in the given module.
+    pub fn observe<B: AutodiffBackend, M: AutodiffModule<B>>(
+        &mut self,
+        module: &M,
+        grads: &GradientsParams,
+    ) {
+        let mut visitor = GradientHistogramVisitor::<M, B>::new(
+            grads,
+            &mut self.histograms,
+            self.config.min_exponent,
+            self.config.max_exponent,
+        );
+        module.visit(&mut visitor);
+        self.steps += 1;
+    }
+
+    /// If enough steps were observed to recommend a policy.
+    pub fn is_calibrated(&self) -> bool {
+        self.steps >= self.config.calibration_steps
+    }
+
+    /// Get the gradient histogram of the given [parameter id](ParamId).
+    pub fn histogram(&self, id: &ParamId) -> Option<&GradientHistogram> {
+        self.histograms.get(id)
+    }
+
+    /// The recommended precision policy, only available once the calibration phase is done.
+    pub fn policy(&self) -> Option<MixedPrecisionPolicy> {
+        if !self.is_calibrated() {
+            return None;
+        }
+
+        let precisions = self
+            .histograms
+            .iter()
+            .map(|(id, histogram)| {
+                let outside = histogram.fraction_outside(HALF_MIN_EXPONENT, HALF_MAX_EXPONENT);
+                let precision = match outside > self.config.tolerance {
+                    true => RecommendedPrecision::Full,
+                    false => RecommendedPrecision::Half,
+                };
+                (id.clone(), precision)
+            })
+            .collect();
+
+        Some(MixedPrecisionPolicy { precisions })
+    }
+}
+
+#[derive(new)]
+struct GradientHistogramVisitor<'a, M, B> {
+    grads: &'a GradientsParams,
+    histograms: &'a mut HashMap<ParamId, GradientHistogram>,
+    min_exponent: i32,
+    max_exponent: i32,
+    phantom: PhantomData<(M, B)>,
+}
+
+impl<'a, B, M> ModuleVisitor<B> for GradientHistogramVisitor<'a, M, B>
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+{
+    fn visit<const D: usize>(&mut self, id: &ParamId, _tensor: &Tensor<B, D>) {
+        let Some(grad) = self.grads.get::<B::InnerBackend, D>(id) else {
+            return;
+        };
+
+        let histogram = self
+            .histograms
+            .entry(id.clone())
+            .or_insert_with(|| GradientHistogram::new(self.min_exponent, self.max_exponent));
+
+        for value in grad.into_data().value {
+            histogram.add(value.elem());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nn::{Linear, LinearConfig},
+        TestAutodiffBackend,
+    };
+
+    #[test]
+    fn test_histogram_clamps_out_of_range_exponents() {
+        let mut histogram = GradientHistogram::new(-4, 4);
+
+        histogram.add(0.0);
+        histogram.add(1e-9);
+        histogram.add(3.0);
+        histogram.add(-1e9);
+
+        assert_eq!(histogram.total(), 3);
+        assert_eq!(histogram.counts()[0], 1);
+        assert_eq!(histogram.counts()[5], 1);
+        assert_eq!(histogram.counts()[8], 1);
+    }
+
+    #[test]
+    fn test_tiny_gradients_are_kept_in_full_precision() {
+        let layer = layer();
+        let [weight_id, bias_id] = param_ids(&layer);
+        let mut calibrator = MixedPrecisionCalibratorConfig::new()
+            .with_calibration_steps(2)
+            .init();
+
+        for _ in 0..2 {
+            assert!(calibrator.policy().is_none());
+            let mut grads = GradientsParams::new();
+            grads.register::<<TestAutodiffBackend as AutodiffBackend>::InnerBackend, 2>(
+                weight_id.clone(),
+                Tensor::ones([4, 4]).mul_scalar(1e-7),
+            );
+            grads.register::<<TestAutodiffBackend as AutodiffBackend>::InnerBackend, 1>(
+                bias_id.clone(),
+                Tensor::ones([4]).mul_scalar(10.0),
+            );
+            calibrator.observe(&layer, &grads);
+        }
+
+        let policy = calibrator.policy().unwrap();
+        assert_eq!(policy.get(&weight_id), Some(RecommendedPrecision::Full));
+        assert_eq!(policy.get(&bias_id), Some(RecommendedPrecision::Half));
+        assert_eq!(policy.full_precision_params(), vec![weight_id]);
+    }
+
+    fn layer() -> Linear<TestAutodiffBackend> {
+        LinearConfig::new(4, 4).with_bias(true).init()
+    }
+
+    fn param_ids(layer: &Linear<TestAutodiffBackend>) -> [ParamId; 2] {
+        [
+            layer.weight.id.clone(),
+            layer.bias.as_ref().unwrap().id.clone(),
+        ]
+    }
+}
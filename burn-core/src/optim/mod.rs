@@ -7,20 +7,85 @@ pub mod momentum;
 mod adagrad;
 mod adam;
 mod adamw;
+mod adan;
 mod base;
+mod builder;
+mod composite;
+mod convergence;
+mod custom;
+mod dead_grads;
+mod dry_run;
+#[cfg(feature = "std")]
+mod file_state;
+mod fromage;
+mod fused_adam;
+mod gauss_newton;
 mod grad_accum;
+mod grad_smoothing;
+mod gradient_descent;
 mod grads;
+mod lars;
+mod line_search;
+mod madgrad;
+mod metrics;
+mod mixed_precision;
+mod noise_scale;
+mod normalized_adagrad;
+mod pcgrad;
+mod phase;
+mod quantization;
 mod rmsprop;
+mod schedule_free;
+mod scheduled;
 mod sgd;
 mod simple;
+mod timer;
+mod transform;
+mod update_clamping;
+mod update_clipping;
+mod validation;
 mod visitor;
+mod weight_standardization;
+mod yogi;
 
 pub use adagrad::*;
 pub use adam::*;
 pub use adamw::*;
+pub use adan::*;
 pub use base::*;
+pub use builder::*;
+pub use composite::*;
+pub use convergence::*;
+pub use custom::*;
+pub use dead_grads::*;
+pub use dry_run::*;
+#[cfg(feature = "std")]
+pub use file_state::*;
+pub use fromage::*;
+pub use fused_adam::*;
+pub use gauss_newton::*;
 pub use grad_accum::*;
+pub use grad_smoothing::*;
+pub use gradient_descent::*;
 pub use grads::*;
+pub use lars::*;
+pub use line_search::*;
+pub use madgrad::*;
+pub use metrics::*;
+pub use mixed_precision::*;
+pub use noise_scale::*;
+pub use normalized_adagrad::*;
+pub use pcgrad::*;
+pub use phase::*;
+pub use quantization::*;
 pub use rmsprop::*;
+pub use schedule_free::*;
+pub use scheduled::*;
 pub use sgd::*;
 pub use simple::*;
+pub use timer::*;
+pub use transform::*;
+pub use update_clamping::*;
+pub use update_clipping::*;
+pub use weight_standardization::*;
+pub use yogi::*;
@@ -1,8 +1,10 @@
 use crate as burn;
 
-use crate::config::Config;
+use super::{validation::validate_beta, Transform};
+use crate::config::{Config, ConfigError, ValidateConfig};
 use crate::record::Record;
 use crate::tensor::{ElementConversion, Tensor};
+use crate::LearningRate;
 use burn_tensor::backend::Backend;
 
 /// Configuration to create [momentum](Momentum).
@@ -20,6 +22,13 @@ pub struct MomentumConfig {
     pub nesterov: bool,
 }
 
+impl ValidateConfig for MomentumConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        validate_beta("momentum.momentum", self.momentum)?;
+        validate_beta("momentum.dampening", self.dampening)
+    }
+}
+
 /// State of [momentum](Momentum).
 #[derive(Record, Clone, new)]
 pub struct MomentumState<B: Backend, const D: usize> {
@@ -76,6 +85,35 @@ impl<B: Backend> Momentum<B> {
     }
 }
 
+impl<B: Backend> Transform<B> for Momentum<B> {
+    type State<const D: usize> = MomentumState<B, D>;
+
+    fn apply<const D: usize>(
+        &self,
+        _lr: LearningRate,
+        _tensor: &Tensor<B, D>,
+        grad: Tensor<B, D>,
+        state: Option<Self::State<D>>,
+    ) -> (Tensor<B, D>, Self::State<D>) {
+        self.transform(grad, state)
+    }
+
+    fn to_device<const D: usize>(state: Self::State<D>, device: &B::Device) -> Self::State<D> {
+        state.to_device(device)
+    }
+
+    fn state_map_tensors<const D: usize, F>(state: Self::State<D>, func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        state.map_tensors(func)
+    }
+
+    fn state_num_elements<const D: usize>(state: &Self::State<D>) -> usize {
+        state.num_elements()
+    }
+}
+
 impl<B: Backend, const D: usize> MomentumState<B, D> {
     /// Moves the state to a device.
     ///
@@ -90,4 +128,15 @@ impl<B: Backend, const D: usize> MomentumState<B, D> {
         self.velocity = self.velocity.to_device(device);
         self
     }
+
+    /// Applies the given function to the state tensors.
+    pub fn map_tensors<F: Fn(Tensor<B, D>) -> Tensor<B, D>>(mut self, func: F) -> Self {
+        self.velocity = func(self.velocity);
+        self
+    }
+
+    /// The number of elements of the state tensors.
+    pub fn num_elements(&self) -> usize {
+        self.velocity.shape().num_elements()
+    }
 }
@@ -0,0 +1,98 @@
+use super::GradientsParams;
+use crate::grad_clipping::NormKind;
+use crate::module::AutodiffModule;
+use crate::tensor::backend::AutodiffBackend;
+use burn_tensor::ElementConversion;
+
+/// Estimator of the gradient noise scale, as described in the paper
+/// [An Empirical Model of Large-Batch Training](https://arxiv.org/abs/1812.06162).
+///
+/// The noise scale `tr(Σ) / |G|^2` compares the variance of the per-sample gradients with the
+/// squared norm of the true gradient, and is the batch size above which increasing the batch size
+/// stops reducing the number of steps significantly. It is estimated from the norms of the
+/// gradients of the same module computed on a small and a large batch.
+#[derive(new, Debug, Clone, Copy)]
+pub struct GradientNoiseScale {
+    small_batch_size: usize,
+    large_batch_size: usize,
+}
+
+impl GradientNoiseScale {
+    /// Estimate the noise scale from the gradients computed on the small and the large batch.
+    ///
+    /// The estimate is noisy when computed from a single pair of batches, and can even be
+    /// negative, so it is usually averaged over many steps. Each squared gradient norm is read
+    /// back from the device once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the large batch isn't larger than the small one.
+    pub fn estimate<B: AutodiffBackend, M: AutodiffModule<B>>(
+        &self,
+        module: &M,
+        small_grads: &GradientsParams,
+        large_grads: &GradientsParams,
+    ) -> f64 {
+        assert!(
+            self.large_batch_size > self.small_batch_size,
+            "The large batch must be larger than the small batch."
+        );
+        let (small, large) = (self.small_batch_size as f64, self.large_batch_size as f64);
+        let norm_squared_small = norm_squared(module, small_grads);
+        let norm_squared_large = norm_squared(module, large_grads);
+
+        // Unbiased estimates of the squared norm of the true gradient and of the trace of the
+        // covariance of the per-sample gradients.
+        let grad_norm_squared =
+            (large * norm_squared_large - small * norm_squared_small) / (large - small);
+        let trace_covariance =
+            (norm_squared_small - norm_squared_large) / (1.0 / small - 1.0 / large);
+
+        trace_covariance / grad_norm_squared
+    }
+}
+
+fn norm_squared<B: AutodiffBackend, M: AutodiffModule<B>>(
+    module: &M,
+    grads: &GradientsParams,
+) -> f64 {
+    grads
+        .accumulate_norm::<B, M>(module, &NormKind::L2)
+        .map_or(0.0, |accumulator| {
+            accumulator.norm().into_scalar().elem::<f64>().powi(2)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::{Linear, LinearConfig};
+    use crate::tensor::{Distribution, Tensor};
+    use crate::{TestAutodiffBackend, TestBackend};
+
+    #[test]
+    fn test_noise_scale_matches_analytic_value() {
+        let linear: Linear<TestAutodiffBackend> =
+            LinearConfig::new(1000, 100).with_bias(false).init();
+        let (small_batch_size, large_batch_size) = (4, 64);
+        // Per-sample gradients with a true gradient of ones and a per-coordinate variance of 16,
+        // so the noise scale is 16.
+        let variance = 16.0;
+        let grads = |batch_size: usize| {
+            let std = (variance / batch_size as f64).sqrt();
+            let noise =
+                Tensor::<TestBackend, 2>::random([1000, 100], Distribution::Normal(0.0, std));
+            let mut grads = GradientsParams::new();
+            grads.register(linear.weight.id.clone(), noise.add_scalar(1.0));
+            grads
+        };
+
+        let noise_scale = GradientNoiseScale::new(small_batch_size, large_batch_size).estimate(
+            &linear,
+            &grads(small_batch_size),
+            &grads(large_batch_size),
+        );
+
+        assert!((noise_scale - variance).abs() < 0.05 * variance);
+    }
+}
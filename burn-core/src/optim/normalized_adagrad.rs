@@ -0,0 +1,264 @@
+use crate::{
+    self as burn, grad_clipping::GradientClippingConfig, module::AutodiffModule, record::Record,
+    LearningRate,
+};
+
+use super::validation::{validate_non_negative, validate_positive};
+use super::{
+    decay::{WeightDecay, WeightDecayConfig, WeightDecayWarmup},
+    AdaGradConfig, LRDecay, LRDecayState, SimpleOptimizer, Transform,
+};
+use crate::config::{Config, ConfigError, ValidateConfig};
+use crate::optim::adaptor::OptimizerAdaptor;
+use crate::tensor::{backend::AutodiffBackend, Tensor};
+use burn_tensor::backend::Backend;
+
+/// Normalized AdaGrad configuration.
+#[derive(Config)]
+pub struct NormalizedAdaGradConfig {
+    /// Decay of the learning rate with the number of steps, as done by AdaGrad.
+    #[config(default = 0.)]
+    lr_decay: f64,
+    /// A value required for numerical stability.
+    #[config(default = 1e-8)]
+    epsilon: f32,
+    /// [Weight decay](WeightDecayConfig) config.
+    weight_decay: Option<WeightDecayConfig>,
+    /// [Gradient Clipping](GradientClippingConfig) config.
+    grad_clipping: Option<GradientClippingConfig>,
+    /// [Gradient scale](crate::optim::adaptor::OptimizerAdaptor::with_grad_scale) of the adaptor.
+    #[config(default = 1.0)]
+    grad_scale: f32,
+}
+
+/// Scale-invariant variant of [AdaGrad](super::AdaGrad).
+///
+/// The adaptive rates of AdaGrad are divided by their mean over the coordinates of each
+/// parameter, so only their relative values precondition the gradient and the learning rate
+/// keeps the same meaning whatever the scale of the gradients.
+///
+/// Unlike AvaGrad, from the paper
+/// [Domain-independent Dominance of Adaptive Methods](https://arxiv.org/abs/1912.01823), the
+/// rates are those of AdaGrad, computed with the current gradient.
+pub struct NormalizedAdaGrad<B: Backend> {
+    lr_decay: LRDecay,
+    weight_decay: Option<WeightDecay<B>>,
+}
+
+/// Normalized AdaGrad state.
+#[derive(Record, Clone, new)]
+pub struct NormalizedAdaGradState<B: Backend, const D: usize> {
+    lr_decay: LRDecayState<B, D>,
+}
+
+impl<B: Backend> SimpleOptimizer<B> for NormalizedAdaGrad<B> {
+    type State<const D: usize> = NormalizedAdaGradState<B, D>;
+
+    fn step<const D: usize>(
+        &self,
+        lr: LearningRate,
+        tensor: Tensor<B, D>,
+        grad: Tensor<B, D>,
+        state: Option<Self::State<D>>,
+    ) -> (Tensor<B, D>, Option<Self::State<D>>) {
+        let state_lr_decay = state.map(|state| state.lr_decay);
+
+        let (grad, _) = self.weight_decay.apply(lr, &tensor, grad, None);
+        let (grad, state_lr_decay) = self.lr_decay.apply(lr, &tensor, grad, state_lr_decay);
+
+        // Dividing by the mean rate as a tensor keeps the step asynchronous, the mean is never
+        // read back.
+        let rate_mean = self
+            .lr_decay
+            .denominator(&state_lr_decay)
+            .powf(-1.0)
+            .mean()
+            .reshape([1; D]);
+        let grad = grad.div(rate_mean);
+
+        let state = NormalizedAdaGradState::new(state_lr_decay);
+
+        let mut tensor = tensor - grad;
+        if let Some(weight_decay) = &self.weight_decay {
+            tensor = weight_decay.proximal(lr, tensor);
+        }
+
+        (tensor, Some(state))
+    }
+
+    fn to_device<const D: usize>(
+        mut state: Self::State<D>,
+        device: &<B as Backend>::Device,
+    ) -> Self::State<D> {
+        state.lr_decay = state.lr_decay.to_device(device);
+        state
+    }
+
+    fn state_map_tensors<const D: usize, F>(mut state: Self::State<D>, func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        state.lr_decay = <LRDecay as Transform<B>>::state_map_tensors(state.lr_decay, func);
+        state
+    }
+
+    fn state_num_elements<const D: usize>(state: &Self::State<D>) -> usize {
+        <LRDecay as Transform<B>>::state_num_elements(&state.lr_decay)
+    }
+
+    fn state_num_steps<const D: usize>(state: &Self::State<D>) -> Option<usize> {
+        <LRDecay as Transform<B>>::state_num_steps(&state.lr_decay)
+    }
+
+    fn state_effective_lr<const D: usize>(
+        &self,
+        lr: LearningRate,
+        state: &Self::State<D>,
+    ) -> Option<Tensor<B, D>> {
+        // The learning rates of AdaGrad are normalized by the mean adaptive rate.
+        let rate_mean = self
+            .lr_decay
+            .denominator(&state.lr_decay)
+            .powf(-1.0)
+            .mean()
+            .reshape([1; D]);
+
+        Some(
+            self.lr_decay
+                .effective_lr(lr, &state.lr_decay)
+                .div(rate_mean),
+        )
+    }
+}
+
+impl ValidateConfig for NormalizedAdaGradConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        validate_non_negative("lr_decay", self.lr_decay)?;
+        validate_positive("epsilon", self.epsilon)?;
+        if let Some(weight_decay) = &self.weight_decay {
+            weight_decay.validate()?;
+        }
+        if let Some(clipping) = &self.grad_clipping {
+            clipping.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl NormalizedAdaGradConfig {
+    /// Initialize the normalized AdaGrad as a [simple optimizer](SimpleOptimizer), to optimize
+    /// tensors directly with [step_tensors](crate::optim::step_tensors).
+    ///
+    /// Since the normalized rates cancel the scale of the gradients, up to `epsilon`, only the
+    /// `grad_clipping` of the config is missing from the simple optimizer.
+    pub fn init_simple<B: Backend>(&self) -> NormalizedAdaGrad<B> {
+        let lr_decay = AdaGradConfig::new()
+            .with_lr_decay(self.lr_decay)
+            .with_epsilon(self.epsilon)
+            .init_lr_decay::<B>();
+
+        NormalizedAdaGrad {
+            lr_decay,
+            weight_decay: self.weight_decay.as_ref().map(WeightDecay::new),
+        }
+    }
+
+    /// Initialize the normalized AdaGrad optimizer.
+    ///
+    /// # Returns
+    ///
+    /// Returns an optimizer that can be used to optimize a module.
+    pub fn init<B: AutodiffBackend, M: AutodiffModule<B>>(
+        &self,
+    ) -> OptimizerAdaptor<NormalizedAdaGrad<B::InnerBackend>, M, B> {
+        // The adaptor completes the decay toward the initial values, and applies the warmed up
+        // decay itself.
+        let weight_decay = self
+            .weight_decay
+            .as_ref()
+            .and_then(WeightDecayConfig::for_simple_optimizer);
+        let optim = self
+            .clone()
+            .with_weight_decay(weight_decay)
+            .init_simple::<B::InnerBackend>();
+
+        let mut optim = OptimizerAdaptor::from(optim)
+            .with_grad_scale(self.grad_scale)
+            .with_config(self);
+        if let Some(config) = &self.grad_clipping {
+            optim = optim.with_grad_clipping(config.init());
+        }
+        if let Some(config) = &self.weight_decay {
+            optim = optim.with_weight_decay(config.clone());
+        }
+        if let Some(config) = self.weight_decay.as_ref().filter(|c| c.warmup_steps > 0) {
+            optim = optim.with_weight_decay_warmup(WeightDecayWarmup::new(config));
+        }
+        optim
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grad_clipping::{tensor_norm, NormKind};
+    use crate::tensor::{Data, Distribution};
+    use crate::TestBackend;
+
+    const LEARNING_RATE: LearningRate = 0.01;
+
+    #[test]
+    fn test_normalized_adagrad_update_direction_is_scale_invariant() {
+        let optim = NormalizedAdaGradConfig::new().init_simple::<TestBackend>();
+        let tensor = Tensor::<TestBackend, 2>::random([4, 3], Distribution::Default);
+        let (mut tensor_unit, mut tensor_scaled) = (tensor.clone(), tensor.clone());
+        let (mut state_unit, mut state_scaled) = (None, None);
+        let direction = |tensor_updated: Tensor<TestBackend, 2>| {
+            let update = tensor.clone().sub(tensor_updated);
+            let norm = tensor_norm(update.clone(), &NormKind::L2);
+            update.div(norm.reshape([1, 1]))
+        };
+
+        for _ in 0..3 {
+            let grad = Tensor::<TestBackend, 2>::random([4, 3], Distribution::Default);
+            (tensor_unit, state_unit) =
+                optim.step(LEARNING_RATE, tensor_unit, grad.clone(), state_unit);
+            (tensor_scaled, state_scaled) = optim.step(
+                LEARNING_RATE,
+                tensor_scaled,
+                grad.mul_scalar(100.0),
+                state_scaled,
+            );
+
+            direction(tensor_scaled.clone())
+                .to_data()
+                .assert_approx_eq(&direction(tensor_unit.clone()).to_data(), 4);
+        }
+    }
+
+    #[test]
+    fn test_normalized_adagrad_mean_effective_lr_is_the_learning_rate() {
+        // AdaGrad is also scale-invariant, but its per-coordinate learning rates `update / grad`
+        // average to `lr` times the mean adaptive rate, which depends on the gradient scale.
+        let optim = NormalizedAdaGradConfig::new().init_simple::<TestBackend>();
+
+        for scale in [1.0, 100.0] {
+            let mut tensor = Tensor::<TestBackend, 2>::random([4, 3], Distribution::Default);
+            let mut state = None;
+
+            for _ in 0..3 {
+                let grad =
+                    Tensor::<TestBackend, 2>::random([4, 3], Distribution::Uniform(0.5, 1.5))
+                        .mul_scalar(scale);
+                let tensor_previous = tensor.clone();
+                (tensor, state) = optim.step(LEARNING_RATE, tensor, grad.clone(), state);
+
+                let effective_lr = tensor_previous.sub(tensor.clone()).div(grad).mean();
+                effective_lr
+                    .to_data()
+                    .assert_approx_eq(&Data::from([LEARNING_RATE as f32]), 4);
+            }
+        }
+    }
+}
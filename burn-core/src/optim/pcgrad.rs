@@ -0,0 +1,169 @@
+use super::visitor::{GradientsParamsFlatten, GradientsParamsUnflatten};
+use super::GradientsParams;
+use crate::module::{AutodiffModule, ParamId};
+use alloc::vec::Vec;
+use burn_tensor::{
+    backend::{AutodiffBackend, Backend},
+    Tensor,
+};
+use hashbrown::HashMap;
+
+type FlatGrads<B> = HashMap<ParamId, Tensor<B, 1>>;
+
+/// Combine the gradients of several tasks with the projection of
+/// [Gradient Surgery for Multi-Task Learning](https://arxiv.org/abs/2001.06782) (PCGrad).
+///
+/// When the gradient of a task conflicts with the gradient of another task, i.e. their dot
+/// product is negative, the component along the other gradient is removed from it. The tasks are
+/// projected in order, each one against the original gradients of the other tasks, then the
+/// projected gradients are summed, so a single optimizer step can be taken with the result.
+///
+/// The dot products are computed over all the parameters of the [module](AutodiffModule), a
+/// parameter without a gradient for a task counting as zero. Each projection is computed with
+/// tensor operations, so the gradients are never read back to the host.
+///
+/// # Arguments
+///
+/// * `module` - The module the gradients were computed for.
+/// * `tasks` - The gradients of each task.
+///
+/// # Returns
+///
+/// The sum of the projected gradients.
+pub fn pcgrad<B, M>(module: &M, tasks: Vec<GradientsParams>) -> GradientsParams
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+{
+    let tasks: Vec<FlatGrads<B::InnerBackend>> = tasks
+        .into_iter()
+        .map(|mut grads| {
+            let mut ids = Vec::new();
+            let mut tensors = Vec::new();
+            module.visit(&mut GradientsParamsFlatten::<M, B>::new(
+                &mut grads,
+                &mut ids,
+                &mut tensors,
+            ));
+            ids.into_iter().zip(tensors).collect()
+        })
+        .collect();
+
+    let mut sum: FlatGrads<B::InnerBackend> = HashMap::new();
+    for (i, task) in tasks.iter().enumerate() {
+        let mut projected = task.clone();
+
+        for (j, other) in tasks.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+
+            // Gradients without a common parameter can't conflict.
+            let (product, norm_squared) = match (dot(&projected, other), dot(other, other)) {
+                (Some(product), Some(norm_squared)) => (product, norm_squared),
+                _ => continue,
+            };
+            // Only the conflicting component is removed, so the coefficient is zero when the dot
+            // product is positive, or when the other gradient is zero.
+            let coefficient = product
+                .clamp_max(0.0)
+                .div(norm_squared.clone())
+                .mask_fill(norm_squared.equal_elem(0.0), 0.0);
+
+            for (id, grad) in other.iter() {
+                let component = grad.clone().mul(coefficient.clone());
+                let projected_grad = match projected.remove(id) {
+                    Some(projected_grad) => projected_grad.sub(component),
+                    None => component.neg(),
+                };
+                projected.insert(id.clone(), projected_grad);
+            }
+        }
+
+        for (id, grad) in projected {
+            let grad = match sum.remove(&id) {
+                Some(total) => total.add(grad),
+                None => grad,
+            };
+            sum.insert(id, grad);
+        }
+    }
+
+    let mut grads = GradientsParams::new();
+    module.visit(&mut GradientsParamsUnflatten::<M, B>::new(
+        &mut grads, &mut sum,
+    ));
+    grads
+}
+
+/// The dot product of two flattened gradients, as a tensor of shape `[1]`, if they share a
+/// parameter.
+fn dot<B: Backend>(lhs: &FlatGrads<B>, rhs: &FlatGrads<B>) -> Option<Tensor<B, 1>> {
+    lhs.iter()
+        .filter_map(|(id, grad)| {
+            rhs.get(id)
+                .map(|other| grad.clone().mul(other.clone()).sum())
+        })
+        .reduce(|acc, dot| acc.add(dot))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::{Linear, LinearConfig};
+    use crate::tensor::Data;
+    use crate::{TestAutodiffBackend, TestBackend};
+
+    fn task_grads(linear: &Linear<TestAutodiffBackend>, grad: [f32; 2]) -> GradientsParams {
+        let mut grads = GradientsParams::new();
+        grads.register(
+            linear.weight.id.clone(),
+            Tensor::<TestBackend, 1>::from_floats(grad).reshape([2, 1]),
+        );
+        grads
+    }
+
+    #[test]
+    fn test_pcgrad_removes_conflicting_components() {
+        let linear: Linear<TestAutodiffBackend> = LinearConfig::new(2, 1).with_bias(false).init();
+        let (grad_1, grad_2) = ([1.0, 0.0], [-1.0, 1.0]);
+
+        let mut grads = pcgrad(
+            &linear,
+            vec![task_grads(&linear, grad_1), task_grads(&linear, grad_2)],
+        );
+
+        // The first gradient loses half of the second one, and the second loses all of the first.
+        let sum: Tensor<TestBackend, 2> = grads.remove(&linear.weight.id).unwrap();
+        let sum = sum.reshape([2]);
+        sum.to_data().assert_approx_eq(&Data::from([0.5, 1.5]), 5);
+
+        // The projected sum improves every task.
+        for grad in [grad_1, grad_2] {
+            let dot = sum
+                .clone()
+                .mul(Tensor::from_floats(grad))
+                .sum()
+                .into_scalar();
+            assert!(dot > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_pcgrad_keeps_agreeing_gradients() {
+        let linear: Linear<TestAutodiffBackend> = LinearConfig::new(2, 1).with_bias(false).init();
+
+        let mut grads = pcgrad(
+            &linear,
+            vec![
+                task_grads(&linear, [1.0, 0.0]),
+                task_grads(&linear, [1.0, 1.0]),
+            ],
+        );
+
+        let sum: Tensor<TestBackend, 2> = grads.remove(&linear.weight.id).unwrap();
+        sum.reshape([2])
+            .to_data()
+            .assert_approx_eq(&Data::from([2.0, 1.0]), 5);
+    }
+}
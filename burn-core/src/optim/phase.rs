@@ -0,0 +1,309 @@
+use crate as burn;
+
+use super::{GradientsParams, Optimizer, StateTensor};
+use crate::config::Config;
+use crate::module::{AutodiffModule, ParamId};
+use crate::record::Record;
+use crate::tensor::backend::AutodiffBackend;
+use crate::LearningRate;
+use alloc::boxed::Box;
+use core::marker::PhantomData;
+use hashbrown::HashMap;
+
+/// Configuration to create a [phase optimizer](PhaseOptimizer).
+#[derive(Config)]
+pub struct PhaseOptimizerConfig {
+    /// The number of steps performed with the first optimizer before switching to the second one.
+    ///
+    /// A boundary at an epoch is given as the number of steps of the previous epochs.
+    boundary: usize,
+}
+
+/// Function initializing the state of the optimizer of the second phase from the optimizer of
+/// the first phase.
+pub type WarmStart<O1, O2> = Box<dyn Fn(&O1, O2) -> O2 + Send + Sync>;
+
+/// Optimizer switching from one optimizer to another at a phase boundary, e.g. from
+/// [Adam](super::Adam) to [SGD](super::Sgd) partway through training.
+///
+/// The steps before the boundary are delegated to the first optimizer, the other ones to the
+/// second optimizer, and the state of the inactive optimizer is kept as is. More than two phases
+/// are obtained by using another phase optimizer as the second optimizer, with a boundary relative
+/// to the start of its phase.
+pub struct PhaseOptimizer<O1, O2, M, B>
+where
+    O1: Optimizer<M, B>,
+    O2: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    first: O1,
+    // Only taken to apply the warm start.
+    then: Option<O2>,
+    boundary: usize,
+    num_steps: usize,
+    warm_start: Option<WarmStart<O1, O2>>,
+    phantom: PhantomData<(M, B)>,
+}
+
+/// [Phase optimizer](PhaseOptimizer) record.
+#[derive(Record)]
+pub struct PhaseOptimizerRecord<R1: Record, R2: Record> {
+    first: R1,
+    then: R2,
+    num_steps: usize,
+}
+
+impl PhaseOptimizerConfig {
+    /// Initialize the phase optimizer with the optimizers of both phases.
+    pub fn init<O1, O2, M, B>(&self, first: O1, then: O2) -> PhaseOptimizer<O1, O2, M, B>
+    where
+        O1: Optimizer<M, B>,
+        O2: Optimizer<M, B>,
+        M: AutodiffModule<B>,
+        B: AutodiffBackend,
+    {
+        PhaseOptimizer {
+            first,
+            then: Some(then),
+            boundary: self.boundary,
+            num_steps: 0,
+            warm_start: None,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<O1, O2, M, B> PhaseOptimizer<O1, O2, M, B>
+where
+    O1: Optimizer<M, B>,
+    O2: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    /// Initialize the state of the second optimizer with the given function when crossing the
+    /// boundary, e.g. with [clone_state_to](Optimizer::clone_state_to) when both optimizers have
+    /// the same type.
+    pub fn with_warm_start<F>(mut self, warm_start: F) -> Self
+    where
+        F: Fn(&O1, O2) -> O2 + Send + Sync + 'static,
+    {
+        self.warm_start = Some(Box::new(warm_start));
+        self
+    }
+
+    /// The number of steps performed, in both phases.
+    pub fn num_steps(&self) -> usize {
+        self.num_steps
+    }
+
+    /// If the next step is performed with the second optimizer.
+    pub fn is_second_phase(&self) -> bool {
+        self.num_steps >= self.boundary
+    }
+
+    fn then(&self) -> &O2 {
+        self.then
+            .as_ref()
+            .expect("The second optimizer is always set.")
+    }
+}
+
+impl<O1, O2, M, B> Optimizer<M, B> for PhaseOptimizer<O1, O2, M, B>
+where
+    O1: Optimizer<M, B>,
+    O2: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    type Record = PhaseOptimizerRecord<O1::Record, O2::Record>;
+
+    fn step(&mut self, lr: LearningRate, module: M, grads: GradientsParams) -> M {
+        let module = match self.is_second_phase() {
+            true => {
+                let mut then = self.then.take().unwrap();
+                if self.num_steps == self.boundary {
+                    if let Some(warm_start) = &self.warm_start {
+                        then = warm_start(&self.first, then);
+                    }
+                }
+                let module = then.step(lr, module, grads);
+                self.then = Some(then);
+                module
+            }
+            false => self.first.step(lr, module, grads),
+        };
+        self.num_steps += 1;
+
+        module
+    }
+
+    fn to_record(&self) -> Self::Record {
+        PhaseOptimizerRecord {
+            first: self.first.to_record(),
+            then: self.then().to_record(),
+            num_steps: self.num_steps,
+        }
+    }
+
+    fn load_record(mut self, record: Self::Record) -> Self {
+        self.first = self.first.load_record(record.first);
+        self.then = self.then.map(|then| then.load_record(record.then));
+        self.num_steps = record.num_steps;
+        self
+    }
+
+    fn to_device(mut self, device: &B::Device) -> Self {
+        self.first = self.first.to_device(device);
+        self.then = self.then.map(|then| then.to_device(device));
+        self
+    }
+
+    fn clone_state_to(
+        &self,
+        mut target: Self,
+        module: &M,
+        ids: &HashMap<ParamId, ParamId>,
+        adapt_shapes: bool,
+    ) -> Self {
+        target.first = self
+            .first
+            .clone_state_to(target.first, module, ids, adapt_shapes);
+        target.then = target
+            .then
+            .map(|then| self.then().clone_state_to(then, module, ids, adapt_shapes));
+        target.num_steps = self.num_steps;
+        target
+    }
+
+    fn num_params(&self) -> usize {
+        self.first.num_params() + self.then().num_params()
+    }
+
+    fn state_bytes(&self) -> usize {
+        self.first.state_bytes() + self.then().state_bytes()
+    }
+
+    fn config_json(&self) -> Option<String> {
+        match self.is_second_phase() {
+            true => self.then().config_json(),
+            false => self.first.config_json(),
+        }
+    }
+
+    fn last_updated(&self) -> Vec<ParamId> {
+        match self.num_steps > self.boundary {
+            true => self.then().last_updated(),
+            false => self.first.last_updated(),
+        }
+    }
+
+    fn export_state_tensors(&self) -> HashMap<ParamId, Vec<StateTensor<B::InnerBackend>>> {
+        match self.num_steps > self.boundary {
+            true => self.then().export_state_tensors(),
+            false => self.first.export_state_tensors(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::{Linear, LinearConfig};
+    use crate::optim::momentum::MomentumConfig;
+    use crate::optim::{AdamConfig, AdamToSgdMomentum, SgdConfig};
+    use crate::record::RecordSummary;
+    use crate::tensor::{Distribution, Tensor};
+    use crate::{TestAutodiffBackend, TestBackend};
+
+    type B = TestAutodiffBackend;
+    type M = Linear<TestAutodiffBackend>;
+
+    const LEARNING_RATE: LearningRate = 0.1;
+
+    #[test]
+    fn test_phase_boundary_switches_optimizer() {
+        let x = Tensor::<B, 2>::random([2, 4], Distribution::Default);
+        let step = |optim: &mut dyn FnMut(M, GradientsParams) -> M, linear: M| {
+            let grads = linear.forward(x.clone()).sum().backward();
+            let grads = GradientsParams::from_grads(grads, &linear);
+            optim(linear, grads)
+        };
+        let mut phase = PhaseOptimizerConfig::new(2).init(
+            AdamConfig::new().init::<B, M>(),
+            SgdConfig::new().init::<B, M>(),
+        );
+        let mut adam = AdamConfig::new().init::<B, M>();
+        let mut sgd = SgdConfig::new().init::<B, M>();
+        let mut linear_phase: M = LinearConfig::new(4, 2).init();
+        let mut linear_reference = linear_phase.clone();
+
+        for num_steps in 1..=3 {
+            linear_phase = step(
+                &mut |linear, grads| phase.step(LEARNING_RATE, linear, grads),
+                linear_phase,
+            );
+            linear_reference = match num_steps <= 2 {
+                true => step(
+                    &mut |linear, grads| adam.step(LEARNING_RATE, linear, grads),
+                    linear_reference,
+                ),
+                false => step(
+                    &mut |linear, grads| sgd.step(LEARNING_RATE, linear, grads),
+                    linear_reference,
+                ),
+            };
+
+            linear_phase
+                .weight
+                .to_data()
+                .assert_approx_eq(&linear_reference.weight.to_data(), 5);
+        }
+
+        // The state of Adam is kept after the boundary.
+        assert_eq!(phase.num_steps(), 3);
+        assert!(phase.is_second_phase());
+        let summary: RecordSummary = phase.to_record().first.summary();
+        assert_eq!(summary.num_steps, Some(2));
+    }
+
+    #[test]
+    fn test_warm_start_carries_adam_momentum_to_sgd() {
+        let x = Tensor::<B, 2>::random([2, 4], Distribution::Default);
+        let adam = AdamConfig::new();
+        let momentum = MomentumConfig::new();
+        let conversion = AdamToSgdMomentum::new(&adam, &momentum);
+        let mut phase = PhaseOptimizerConfig::new(2)
+            .init(
+                adam.init::<B, M>(),
+                SgdConfig::new()
+                    .with_momentum(Some(momentum))
+                    .init::<B, M>(),
+            )
+            .with_warm_start(move |adam, sgd| sgd.with_state_from(adam, &conversion));
+        let mut linear: M = LinearConfig::new(4, 2).init();
+
+        // The gradients don't depend on the weights, so they are the same at each step.
+        let mut grad_weight = None;
+        for _ in 0..2 {
+            let grads =
+                GradientsParams::from_grads(linear.forward(x.clone()).sum().backward(), &linear);
+            grad_weight = grads.get::<TestBackend, 2>(&linear.weight.id);
+            linear = phase.step(LEARNING_RATE, linear, grads);
+        }
+        let weight_before = linear.weight.val().inner();
+        let grads = GradientsParams::from_grads(linear.forward(x).sum().backward(), &linear);
+        linear = phase.step(LEARNING_RATE, linear, grads);
+
+        // Adam's first moment is the gradient, carried over as the velocity 0.9 / 0.1 * grad, so
+        // the first SGD step is 0.9 * grad + 0.9 * 9 * grad instead of grad with a cold start.
+        let update = weight_before.sub(linear.weight.val().inner());
+        update.to_data().assert_approx_eq(
+            &grad_weight
+                .unwrap()
+                .mul_scalar(9.0 * LEARNING_RATE)
+                .into_data(),
+            4,
+        );
+    }
+}
@@ -0,0 +1,349 @@
+use crate as burn;
+
+use super::{base::delegate_optimizer, GradientsParams, Optimizer};
+use crate::config::Config;
+use crate::module::{AutodiffModule, ModuleMapper, ParamId};
+use crate::record::Record;
+use crate::tensor::{backend::AutodiffBackend, Tensor};
+use crate::LearningRate;
+use alloc::vec::Vec;
+use burn_tensor::backend::Backend;
+use burn_tensor::{ElementConversion, Shape};
+use core::marker::PhantomData;
+use hashbrown::HashMap;
+
+/// Configuration to create a [quantization aware](QuantizationAware) optimizer.
+#[derive(Config)]
+pub struct QuantizationAwareConfig {
+    /// Number of bits of the signed integers used to quantize the weights.
+    #[config(default = 8)]
+    bits: u32,
+    /// Fixed quantization step, when not provided the step of each tensor is computed from its
+    /// maximum absolute value.
+    scale: Option<f32>,
+}
+
+/// Optimizer wrapper for quantization aware training.
+///
+/// The wrapped optimizer updates full precision master weights, while the returned module holds
+/// their quantized values, so the forward pass sees the quantized weights. Gradients of the
+/// quantized weights are applied as-is to the master weights (straight-through estimator).
+///
+/// The master weights are part of the [record](QuantizationAwareRecord), so a resumed training
+/// keeps updating them instead of the quantized weights of the saved module.
+pub struct QuantizationAware<O, M, B>
+where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    optim: O,
+    masters: Masters<B::InnerBackend>,
+    quantization: Quantization,
+    phantom: PhantomData<(M, B)>,
+}
+
+impl QuantizationAwareConfig {
+    /// Wrap the given optimizer to perform quantization aware training.
+    pub fn init<O, M, B>(&self, optim: O) -> QuantizationAware<O, M, B>
+    where
+        O: Optimizer<M, B>,
+        M: AutodiffModule<B>,
+        B: AutodiffBackend,
+    {
+        assert!(
+            self.bits >= 2 && self.bits <= 32,
+            "The number of bits must be between 2 and 32."
+        );
+
+        QuantizationAware {
+            optim,
+            masters: Masters::default(),
+            quantization: Quantization {
+                levels: ((1u64 << (self.bits - 1)) - 1) as f32,
+                scale: self.scale,
+            },
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<O, M, B> QuantizationAware<O, M, B>
+where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    /// Get the full precision master weights of the given [parameter id](ParamId).
+    pub fn master<const D: usize>(&self, id: &ParamId) -> Option<Tensor<B::InnerBackend, D>> {
+        self.masters.get(id)
+    }
+
+    /// Replace the quantized weights of the module by their full precision master weights.
+    pub fn master_module(&self, module: M) -> M {
+        module.map(&mut MasterWeightsMapper::<B>::new(&self.masters))
+    }
+}
+
+impl<O, M, B> Optimizer<M, B> for QuantizationAware<O, M, B>
+where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    type Record = QuantizationAwareRecord<O::Record, B::InnerBackend>;
+
+    fn step(&mut self, lr: LearningRate, module: M, grads: GradientsParams) -> M {
+        let module = self.master_module(module);
+        let module = self.optim.step(lr, module, grads);
+
+        let mut mapper = QuantizeMapper::<B>::new(&mut self.masters, &self.quantization);
+        module.map(&mut mapper)
+    }
+
+    fn to_record(&self) -> Self::Record {
+        QuantizationAwareRecord {
+            optim: self.optim.to_record(),
+            masters: self.masters.tensors.clone(),
+            master_shapes: self.masters.shapes.clone(),
+        }
+    }
+
+    fn load_record(mut self, record: Self::Record) -> Self {
+        self.optim = self.optim.load_record(record.optim);
+        self.masters = Masters {
+            tensors: record.masters,
+            shapes: record.master_shapes,
+        };
+        self
+    }
+
+    fn to_device(mut self, device: &B::Device) -> Self {
+        self.optim = self.optim.to_device(device);
+        self.masters.tensors = self
+            .masters
+            .tensors
+            .into_iter()
+            .map(|(id, master)| (id, master.to_device(device)))
+            .collect();
+        self
+    }
+
+    delegate_optimizer!(
+        optim,
+        [
+            clone_state_to,
+            num_params,
+            state_bytes,
+            config_json,
+            last_updated,
+            export_state_tensors
+        ]
+    );
+}
+
+/// [Quantization aware](QuantizationAware) optimizer record.
+#[derive(Record)]
+pub struct QuantizationAwareRecord<R: Record, B: Backend> {
+    /// The record of the wrapped optimizer.
+    pub optim: R,
+    /// The flattened full precision master weights.
+    pub masters: HashMap<ParamId, Tensor<B, 1>>,
+    /// The shape of each master weight.
+    pub master_shapes: HashMap<ParamId, Vec<usize>>,
+}
+
+/// The master weights, flattened so weights of any rank can be recorded.
+struct Masters<B: Backend> {
+    tensors: HashMap<ParamId, Tensor<B, 1>>,
+    shapes: HashMap<ParamId, Vec<usize>>,
+}
+
+impl<B: Backend> Default for Masters<B> {
+    fn default() -> Self {
+        Self {
+            tensors: HashMap::new(),
+            shapes: HashMap::new(),
+        }
+    }
+}
+
+impl<B: Backend> Masters<B> {
+    fn get<const D: usize>(&self, id: &ParamId) -> Option<Tensor<B, D>> {
+        let tensor = self.tensors.get(id)?;
+        let shape = self.shapes.get(id)?;
+
+        Some(tensor.clone().reshape(Shape::from(shape.clone())))
+    }
+
+    fn register<const D: usize>(&mut self, id: ParamId, tensor: Tensor<B, D>) {
+        let shape = tensor.shape();
+        let num_elements = shape.num_elements();
+        self.shapes.insert(id.clone(), shape.dims.to_vec());
+        self.tensors.insert(id, tensor.reshape([num_elements]));
+    }
+}
+
+struct Quantization {
+    levels: f32,
+    scale: Option<f32>,
+}
+
+impl Quantization {
+    fn quantize<B: Backend, const D: usize>(&self, tensor: Tensor<B, D>) -> Tensor<B, D> {
+        let scale = match self.scale {
+            Some(scale) => scale,
+            None => tensor.clone().abs().max().into_scalar().elem::<f32>() / self.levels,
+        };
+
+        if scale == 0.0 {
+            return tensor;
+        }
+
+        // Rounding is performed on positive values, where the integer conversion truncates.
+        tensor
+            .div_scalar(scale)
+            .clamp(-self.levels, self.levels)
+            .add_scalar(self.levels + 0.5)
+            .int()
+            .float()
+            .sub_scalar(self.levels)
+            .mul_scalar(scale)
+    }
+}
+
+#[derive(new)]
+struct MasterWeightsMapper<'a, B: AutodiffBackend> {
+    masters: &'a Masters<B::InnerBackend>,
+    phantom: PhantomData<B>,
+}
+
+impl<'a, B: AutodiffBackend> ModuleMapper<B> for MasterWeightsMapper<'a, B> {
+    fn map<const D: usize>(&mut self, id: &ParamId, tensor: Tensor<B, D>) -> Tensor<B, D> {
+        let Some(master) = self.masters.get::<D>(id) else {
+            return tensor;
+        };
+
+        let mut master = Tensor::from_inner(master);
+        if tensor.is_require_grad() {
+            master = master.require_grad();
+        }
+        master
+    }
+}
+
+#[derive(new)]
+struct QuantizeMapper<'a, B: AutodiffBackend> {
+    masters: &'a mut Masters<B::InnerBackend>,
+    quantization: &'a Quantization,
+    phantom: PhantomData<B>,
+}
+
+impl<'a, B: AutodiffBackend> ModuleMapper<B> for QuantizeMapper<'a, B> {
+    fn map<const D: usize>(&mut self, id: &ParamId, tensor: Tensor<B, D>) -> Tensor<B, D> {
+        let is_require_grad = tensor.is_require_grad();
+        let master = tensor.inner();
+        let quantized = self.quantization.quantize(master.clone());
+        self.masters.register(id.clone(), master);
+
+        let mut quantized = Tensor::from_inner(quantized);
+        if is_require_grad {
+            quantized = quantized.require_grad();
+        }
+        quantized
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        module::Param,
+        nn::{Linear, LinearConfig, LinearRecord},
+        optim::SgdConfig,
+        TestAutodiffBackend, TestBackend,
+    };
+    use burn_tensor::Data;
+
+    const SCALE: f32 = 0.1;
+
+    #[test]
+    fn test_master_weights_drift_smoothly_while_quantized_weights_step() {
+        let mut linear = LinearConfig::new(1, 1).init_with(LinearRecord {
+            weight: Param::from(Tensor::<TestAutodiffBackend, 2>::zeros([1, 1])),
+            bias: None,
+        });
+        let id = linear.weight.id.clone();
+        let mut optim = QuantizationAwareConfig::new()
+            .with_scale(Some(SCALE))
+            .init(SgdConfig::new().init::<TestAutodiffBackend, Linear<TestAutodiffBackend>>());
+
+        for step in 1..=8 {
+            let mut grads = GradientsParams::new();
+            grads.register::<TestBackend, 2>(id.clone(), Tensor::ones([1, 1]).mul_scalar(-1.0));
+            linear = optim.step(0.04, linear, grads);
+
+            let master = optim.master::<2>(&id).unwrap().into_data();
+            let quantized = linear.weight.to_data();
+            let master_expected = 0.04 * step as f32;
+            let quantized_expected = libm::roundf(master_expected / SCALE) * SCALE;
+
+            master.assert_approx_eq(&Data::from([[master_expected]]), 4);
+            quantized.assert_approx_eq(&Data::from([[quantized_expected]]), 4);
+        }
+
+        let master_linear = optim.master_module(linear);
+        master_linear
+            .weight
+            .to_data()
+            .assert_approx_eq(&Data::from([[0.32]]), 4);
+    }
+
+    #[test]
+    fn test_resumed_training_keeps_the_master_weights() {
+        let mut linear = LinearConfig::new(1, 1).init_with(LinearRecord {
+            weight: Param::from(Tensor::<TestAutodiffBackend, 2>::zeros([1, 1])),
+            bias: None,
+        });
+        let id = linear.weight.id.clone();
+        let optim = || {
+            QuantizationAwareConfig::new()
+                .with_scale(Some(SCALE))
+                .init(SgdConfig::new().init::<TestAutodiffBackend, Linear<TestAutodiffBackend>>())
+        };
+        let grads = || {
+            let mut grads = GradientsParams::new();
+            grads.register::<TestBackend, 2>(id.clone(), Tensor::ones([1, 1]).mul_scalar(-1.0));
+            grads
+        };
+        let mut optim_interrupted = optim();
+        for _ in 0..2 {
+            linear = optim_interrupted.step(0.04, linear, grads());
+        }
+
+        let mut optim_resumed = optim().load_record(optim_interrupted.to_record());
+        let _linear = optim_resumed.step(0.04, linear, grads());
+
+        // The master weight resumes from 0.08, not from the quantized weight of 0.1.
+        optim_resumed
+            .master::<2>(&id)
+            .unwrap()
+            .into_data()
+            .assert_approx_eq(&Data::from([[0.12]]), 4);
+    }
+
+    #[test]
+    fn test_quantize_with_max_abs_scale() {
+        let quantization = Quantization {
+            levels: 127.0,
+            scale: None,
+        };
+        let tensor = Tensor::<TestBackend, 1>::from_floats([-1.27, 0.004, 0.016, 0.5]);
+
+        let quantized = quantization.quantize(tensor);
+
+        quantized
+            .into_data()
+            .assert_approx_eq(&Data::from([-1.27, 0.0, 0.02, 0.5]), 4);
+    }
+}
@@ -3,11 +3,12 @@ use crate::{
     LearningRate,
 };
 
+use super::validation::{validate_beta, validate_non_negative, validate_positive};
 use super::{
-    decay::{WeightDecay, WeightDecayConfig},
+    decay::{WeightDecay, WeightDecayConfig, WeightDecayWarmup},
     SimpleOptimizer,
 };
-use crate::config::Config;
+use crate::config::{Config, ConfigError, ValidateConfig};
 use crate::optim::adaptor::OptimizerAdaptor;
 use crate::tensor::{backend::AutodiffBackend, Tensor};
 use burn_tensor::backend::Backend;
@@ -31,6 +32,25 @@ pub struct RMSPropConfig {
     weight_decay: Option<WeightDecayConfig>,
     /// [Gradient Clipping](GradientClippingConfig) config.
     grad_clipping: Option<GradientClippingConfig>,
+    /// [Gradient scale](crate::optim::adaptor::OptimizerAdaptor::with_grad_scale) of the adaptor.
+    #[config(default = 1.0)]
+    grad_scale: f32,
+}
+
+impl ValidateConfig for RMSPropConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        validate_beta("alpha", self.alpha)?;
+        validate_non_negative("momentum", self.momentum)?;
+        validate_positive("epsilon", self.epsilon)?;
+        if let Some(weight_decay) = &self.weight_decay {
+            weight_decay.validate()?;
+        }
+        if let Some(clipping) = &self.grad_clipping {
+            clipping.validate()?;
+        }
+
+        Ok(())
+    }
 }
 
 impl RMSPropConfig {
@@ -42,7 +62,11 @@ impl RMSPropConfig {
     pub fn init<B: AutodiffBackend, M: AutodiffModule<B>>(
         &self,
     ) -> OptimizerAdaptor<RMSProp<B::InnerBackend>, M, B> {
-        let weight_decay = self.weight_decay.as_ref().map(WeightDecay::new);
+        let weight_decay = self
+            .weight_decay
+            .as_ref()
+            .and_then(WeightDecayConfig::for_simple_optimizer)
+            .map(|config| WeightDecay::new(&config));
 
         let mut optim = OptimizerAdaptor::from(RMSProp {
             alpha: self.alpha,
@@ -52,11 +76,19 @@ impl RMSPropConfig {
                 momentum: self.momentum,
                 epsilon: self.epsilon,
             },
-        });
+        })
+        .with_grad_scale(self.grad_scale)
+        .with_config(self);
 
         if let Some(config) = &self.grad_clipping {
             optim = optim.with_grad_clipping(config.init());
         }
+        if let Some(config) = &self.weight_decay {
+            optim = optim.with_weight_decay(config.clone());
+        }
+        if let Some(config) = self.weight_decay.as_ref().filter(|c| c.warmup_steps > 0) {
+            optim = optim.with_weight_decay_warmup(WeightDecayWarmup::new(config));
+        }
 
         optim
     }
@@ -121,7 +153,12 @@ impl<B: Backend> SimpleOptimizer<B> for RMSProp<B> {
 
         // tensor param transform
         let delta = grad.mul_scalar(lr);
-        (tensor - delta, Some(state))
+        let mut tensor = tensor - delta;
+        if let Some(weight_decay) = &self.weight_decay {
+            tensor = weight_decay.proximal(lr, tensor);
+        }
+
+        (tensor, Some(state))
     }
 
     fn to_device<const D: usize>(
@@ -133,6 +170,53 @@ impl<B: Backend> SimpleOptimizer<B> for RMSProp<B> {
         state.momentum = state.momentum.map(|momentum| momentum.to_device(device));
         state
     }
+
+    fn state_map_tensors<const D: usize, F>(mut state: Self::State<D>, func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        state.square_avg.square_avg = func(state.square_avg.square_avg);
+        state.centered.grad_avg = state.centered.grad_avg.map(&func);
+        state.centered.avg = func(state.centered.avg);
+        state.momentum = state.momentum.map(|mut momentum| {
+            momentum.buf = func(momentum.buf);
+            momentum
+        });
+        state
+    }
+
+    fn state_num_elements<const D: usize>(state: &Self::State<D>) -> usize {
+        let square_avg = state.square_avg.square_avg.shape().num_elements();
+        let grad_avg = state
+            .centered
+            .grad_avg
+            .as_ref()
+            .map(|grad_avg| grad_avg.shape().num_elements())
+            .unwrap_or(0);
+        let avg = state.centered.avg.shape().num_elements();
+        let momentum = state
+            .momentum
+            .as_ref()
+            .map(|momentum| momentum.buf.shape().num_elements())
+            .unwrap_or(0);
+
+        square_avg + grad_avg + avg + momentum
+    }
+
+    fn state_effective_lr<const D: usize>(
+        &self,
+        lr: LearningRate,
+        state: &Self::State<D>,
+    ) -> Option<Tensor<B, D>> {
+        let denominator = state
+            .centered
+            .avg
+            .clone()
+            .sqrt()
+            .add_scalar(self.momentum.epsilon);
+
+        Some(denominator.ones_like().mul_scalar(lr).div(denominator))
+    }
 }
 
 /// State of [RMSProp](RMSProp)
@@ -515,9 +599,10 @@ mod tests {
             alpha: 0.99,
             epsilon: 1e-9,
             centered: false,
-            weight_decay: Some(WeightDecayConfig { penalty: 0.05 }),
+            weight_decay: Some(WeightDecayConfig::new(0.05)),
             momentum: 0.9,
             grad_clipping: None,
+            grad_scale: 1.0,
         }
         .init()
     }
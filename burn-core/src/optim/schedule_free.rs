@@ -0,0 +1,234 @@
+use crate::{
+    self as burn, grad_clipping::GradientClippingConfig, module::AutodiffModule, record::Record,
+    LearningRate,
+};
+use core::marker::PhantomData;
+
+use super::validation::{validate_beta, validate_non_negative, validate_positive};
+use super::SimpleOptimizer;
+use crate::config::{Config, ConfigError, ValidateConfig};
+use crate::optim::adaptor::OptimizerAdaptor;
+use crate::tensor::{backend::AutodiffBackend, Tensor};
+use burn_tensor::backend::Backend;
+
+/// Schedule-free AdamW configuration.
+#[derive(Config)]
+pub struct ScheduleFreeAdamWConfig {
+    /// Interpolation between the averaged and the fast iterates where the gradients are evaluated.
+    #[config(default = 0.9)]
+    beta_1: f32,
+    /// Parameter for AdamW.
+    #[config(default = 0.999)]
+    beta_2: f32,
+    /// A value required for numerical stability.
+    #[config(default = 1e-8)]
+    epsilon: f32,
+    /// Decoupled weight decay, scaled by the learning rate.
+    #[config(default = 1e-4)]
+    weight_decay: f32,
+    /// [Gradient Clipping](GradientClippingConfig) config.
+    grad_clipping: Option<GradientClippingConfig>,
+    /// [Gradient scale](crate::optim::adaptor::OptimizerAdaptor::with_grad_scale) of the adaptor.
+    #[config(default = 1.0)]
+    grad_scale: f32,
+}
+
+/// Schedule-free AdamW optimizer as described in the paper
+/// [The Road Less Scheduled](https://arxiv.org/abs/2405.15682).
+///
+/// No learning rate schedule is required: the AdamW steps are taken by a fast iterate `z`, and
+/// the averaged iterate `x` is its uniform online average. The gradients are evaluated at the
+/// interpolation `y = (1 - beta_1) * z + beta_1 * x`, which is the value held by the parameters
+/// during training.
+///
+/// # Notes
+///
+/// The averaged iterate `x` is the one to use for evaluation, see
+/// [averaged](ScheduleFreeAdamWState::averaged).
+pub struct ScheduleFreeAdamW<B: Backend> {
+    beta_1: f32,
+    beta_2: f32,
+    epsilon: f32,
+    weight_decay: f32,
+    _phantom: PhantomData<B>,
+}
+
+/// Schedule-free AdamW state.
+#[derive(Record, Clone, new)]
+pub struct ScheduleFreeAdamWState<B: Backend, const D: usize> {
+    time: usize,
+    fast: Tensor<B, D>,
+    averaged: Tensor<B, D>,
+    moment_2: Tensor<B, D>,
+}
+
+impl<B: Backend, const D: usize> ScheduleFreeAdamWState<B, D> {
+    /// The averaged iterate `x` of the parameter.
+    pub fn averaged(&self) -> Tensor<B, D> {
+        self.averaged.clone()
+    }
+}
+
+impl<B: Backend> SimpleOptimizer<B> for ScheduleFreeAdamW<B> {
+    type State<const D: usize> = ScheduleFreeAdamWState<B, D>;
+
+    fn step<const D: usize>(
+        &self,
+        lr: LearningRate,
+        tensor: Tensor<B, D>,
+        grad: Tensor<B, D>,
+        state: Option<Self::State<D>>,
+    ) -> (Tensor<B, D>, Option<Self::State<D>>) {
+        // Both iterates start from the initial parameter.
+        let (time, fast, averaged, moment_2) = match state {
+            Some(state) => (state.time, state.fast, state.averaged, state.moment_2),
+            None => (0, tensor.clone(), tensor.clone(), tensor.zeros_like()),
+        };
+        let time = time + 1;
+
+        let moment_2 = moment_2
+            .mul_scalar(self.beta_2)
+            .add(grad.clone().powf(2.0).mul_scalar(1.0 - self.beta_2));
+        let bias_correction = 1.0 - self.beta_2.powi(time as i32);
+        let denominator = moment_2
+            .clone()
+            .div_scalar(bias_correction)
+            .sqrt()
+            .add_scalar(self.epsilon);
+
+        let decay = tensor.mul_scalar(self.weight_decay);
+        let fast = fast.sub(grad.div(denominator).add(decay).mul_scalar(lr));
+
+        let weight = 1.0 / time as f64;
+        let averaged = averaged
+            .mul_scalar(1.0 - weight)
+            .add(fast.clone().mul_scalar(weight));
+
+        let tensor = fast
+            .clone()
+            .mul_scalar(1.0 - self.beta_1)
+            .add(averaged.clone().mul_scalar(self.beta_1));
+        let state = ScheduleFreeAdamWState::new(time, fast, averaged, moment_2);
+
+        (tensor, Some(state))
+    }
+
+    fn to_device<const D: usize>(mut state: Self::State<D>, device: &B::Device) -> Self::State<D> {
+        state.fast = state.fast.to_device(device);
+        state.averaged = state.averaged.to_device(device);
+        state.moment_2 = state.moment_2.to_device(device);
+        state
+    }
+
+    fn state_map_tensors<const D: usize, F>(mut state: Self::State<D>, func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        state.fast = func(state.fast);
+        state.averaged = func(state.averaged);
+        state.moment_2 = func(state.moment_2);
+        state
+    }
+
+    fn state_num_elements<const D: usize>(state: &Self::State<D>) -> usize {
+        state.fast.shape().num_elements()
+            + state.averaged.shape().num_elements()
+            + state.moment_2.shape().num_elements()
+    }
+
+    fn state_num_steps<const D: usize>(state: &Self::State<D>) -> Option<usize> {
+        Some(state.time)
+    }
+}
+
+impl ValidateConfig for ScheduleFreeAdamWConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        validate_beta("beta_1", self.beta_1)?;
+        validate_beta("beta_2", self.beta_2)?;
+        validate_positive("epsilon", self.epsilon)?;
+        validate_non_negative("weight_decay", self.weight_decay)?;
+        if let Some(clipping) = &self.grad_clipping {
+            clipping.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ScheduleFreeAdamWConfig {
+    /// Initialize schedule-free AdamW as a [simple optimizer](SimpleOptimizer), to optimize
+    /// tensors directly with [step_tensors](crate::optim::step_tensors).
+    ///
+    /// The gradients are taken as given at the interpolated point: the `grad_clipping` and
+    /// `grad_scale` of the config are only applied by the adaptor of [init](Self::init).
+    pub fn init_simple<B: Backend>(&self) -> ScheduleFreeAdamW<B> {
+        ScheduleFreeAdamW {
+            beta_1: self.beta_1,
+            beta_2: self.beta_2,
+            epsilon: self.epsilon,
+            weight_decay: self.weight_decay,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Initialize schedule-free AdamW optimizer.
+    ///
+    /// # Returns
+    ///
+    /// Returns an optimizer that can be used to optimize a module.
+    pub fn init<B: AutodiffBackend, M: AutodiffModule<B>>(
+        &self,
+    ) -> OptimizerAdaptor<ScheduleFreeAdamW<B::InnerBackend>, M, B> {
+        let optim = self.init_simple::<B::InnerBackend>();
+
+        let mut optim = OptimizerAdaptor::from(optim)
+            .with_grad_scale(self.grad_scale)
+            .with_config(self);
+        if let Some(config) = &self.grad_clipping {
+            optim = optim.with_grad_clipping(config.init());
+        }
+        optim
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tensor::Data;
+    use crate::TestBackend;
+
+    const LEARNING_RATE: LearningRate = 0.1;
+
+    #[test]
+    fn test_schedule_free_adamw_averaged_iterate_converges() {
+        let optim = ScheduleFreeAdamWConfig::new()
+            .with_weight_decay(0.0)
+            .init_simple::<TestBackend>();
+        let target = Tensor::<TestBackend, 1>::from_floats([1.0, -2.0, 3.0]);
+        let mut tensor = Tensor::<TestBackend, 1>::zeros([3]);
+        let mut state = None;
+
+        // Minimize `0.5 * ||tensor - target||^2` with a constant learning rate.
+        for _ in 0..1000 {
+            let grad = tensor.clone().sub(target.clone());
+            (tensor, state) = optim.step(LEARNING_RATE, tensor, grad, state);
+        }
+
+        let state = state.unwrap();
+        assert_eq!(state.time, 1000);
+        state
+            .averaged()
+            .to_data()
+            .assert_approx_eq(&Data::from([1.0, -2.0, 3.0]), 3);
+    }
+
+    #[test]
+    fn test_schedule_free_adamw_config_validate() {
+        assert!(ScheduleFreeAdamWConfig::new().validate().is_ok());
+        assert!(matches!(
+            ScheduleFreeAdamWConfig::new().with_weight_decay(-1e-4).validate(),
+            Err(ConfigError::OutOfRange(message))
+                if message == "weight_decay must be non-negative, got -0.0001."
+        ));
+    }
+}
@@ -0,0 +1,205 @@
+use crate as burn;
+
+use super::{base::delegate_optimizer, GradientsParams, Optimizer};
+use crate::grad_clipping::clip_by_global_norm;
+use crate::lr_scheduler::LrScheduler;
+use crate::module::AutodiffModule;
+use crate::record::Record;
+use crate::tensor::backend::AutodiffBackend;
+use crate::LearningRate;
+use core::marker::PhantomData;
+
+/// Optimizer bundled with its [learning rate scheduler](LrScheduler) and an optional clipping of
+/// the global norm of the gradients, usually created with a
+/// [training optimizer builder](super::TrainingOptimizerBuilder).
+///
+/// Each [step](ScheduledOptimizer::step) advances the scheduler, clips the gradients, then
+/// delegates to the optimizer with the scheduled learning rate.
+pub struct ScheduledOptimizer<O, S, M, B>
+where
+    O: Optimizer<M, B>,
+    S: LrScheduler,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    optim: O,
+    scheduler: S,
+    max_global_norm: Option<f32>,
+    last_lr: Option<LearningRate>,
+    phantom: PhantomData<(M, B)>,
+}
+
+/// [Scheduled optimizer](ScheduledOptimizer) record.
+#[derive(Record)]
+pub struct ScheduledOptimizerRecord<R1: Record, R2: Record> {
+    optim: R1,
+    scheduler: R2,
+}
+
+impl<O, S, M, B> ScheduledOptimizer<O, S, M, B>
+where
+    O: Optimizer<M, B>,
+    S: LrScheduler,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    /// Create a scheduled optimizer.
+    ///
+    /// # Arguments
+    ///
+    /// * `optim` - The optimizer.
+    /// * `scheduler` - The scheduler of the learning rate.
+    /// * `max_global_norm` - The maximum global L2 norm of the gradients, if clipped.
+    pub fn new(optim: O, scheduler: S, max_global_norm: Option<f32>) -> Self {
+        Self {
+            optim,
+            scheduler,
+            max_global_norm,
+            last_lr: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Perform the optimizer step with the next learning rate of the scheduler.
+    pub fn step(&mut self, module: M, mut grads: GradientsParams) -> M {
+        let lr = self.scheduler.step();
+        self.last_lr = Some(lr);
+
+        if let Some(max_norm) = self.max_global_norm {
+            if let Some(device) = module.devices().first() {
+                grads = clip_by_global_norm(&module, grads, max_norm, device);
+            }
+        }
+
+        self.optim.step(lr, module, grads)
+    }
+
+    /// Reset the learning rate schedule to its first step, e.g. for a warm restart, keeping the
+    /// state of the optimizer.
+    ///
+    /// The scheduler is [moved](LrScheduler::seek) to the step `0`.
+    pub fn reset_schedule(&mut self) {
+        self.scheduler.seek(0);
+        self.last_lr = None;
+    }
+
+    /// The learning rate of the last step, if any.
+    pub fn last_lr(&self) -> Option<LearningRate> {
+        self.last_lr
+    }
+
+    /// The optimizer.
+    pub fn optimizer(&self) -> &O {
+        &self.optim
+    }
+
+    /// The learning rate scheduler.
+    pub fn scheduler(&self) -> &S {
+        &self.scheduler
+    }
+}
+
+/// The scheduled optimizer can be used as any other optimizer, e.g. to be wrapped, in which case
+/// the learning rate given to each [step](Optimizer::step) is ignored in favor of the scheduled
+/// one.
+impl<O, S, M, B> Optimizer<M, B> for ScheduledOptimizer<O, S, M, B>
+where
+    O: Optimizer<M, B>,
+    S: LrScheduler,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    type Record = ScheduledOptimizerRecord<O::Record, S::Record>;
+
+    fn step(&mut self, _lr: LearningRate, module: M, grads: GradientsParams) -> M {
+        ScheduledOptimizer::step(self, module, grads)
+    }
+
+    fn to_record(&self) -> Self::Record {
+        ScheduledOptimizerRecord {
+            optim: self.optim.to_record(),
+            scheduler: self.scheduler.to_record(),
+        }
+    }
+
+    fn load_record(mut self, record: Self::Record) -> Self {
+        self.optim = self.optim.load_record(record.optim);
+        self.scheduler = self.scheduler.load_record(record.scheduler);
+        self
+    }
+
+    delegate_optimizer!(optim);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::{Linear, LinearConfig};
+    use crate::optim::{AdamConfig, TrainingOptimizerBuilder};
+    use crate::tensor::{Distribution, Tensor};
+    use crate::TestAutodiffBackend;
+
+    type B = TestAutodiffBackend;
+
+    #[test]
+    fn test_reset_schedule_restarts_the_lr_and_keeps_the_moments() {
+        let mut linear: Linear<B> = LinearConfig::new(4, 2).init();
+        let mut optim =
+            TrainingOptimizerBuilder::new_warmup_cosine(AdamConfig::new().init(), 0.1, 0.0, 2, 10)
+                .build();
+        let mut lrs = Vec::new();
+
+        for _ in 0..4 {
+            let x = Tensor::<B, 2>::random([3, 4], Distribution::Default);
+            let grads = GradientsParams::from_grads(linear.forward(x).sum().backward(), &linear);
+            linear = optim.step(linear, grads);
+            lrs.push(optim.last_lr().unwrap());
+        }
+        let moments = optim.optimizer().export_state_tensors();
+
+        optim.reset_schedule();
+
+        assert_eq!(optim.last_lr(), None);
+        let moments_reset = optim.optimizer().export_state_tensors();
+        assert_eq!(moments_reset.len(), 2);
+        for (id, tensors) in moments {
+            let tensors_reset = &moments_reset[&id];
+            assert_eq!(tensors.len(), tensors_reset.len());
+            for (tensor, tensor_reset) in tensors.into_iter().zip(tensors_reset) {
+                assert_eq!(
+                    tensor.tensor.into_data(),
+                    tensor_reset.tensor.clone().into_data()
+                );
+            }
+        }
+
+        let x = Tensor::<B, 2>::random([3, 4], Distribution::Default);
+        let grads = GradientsParams::from_grads(linear.forward(x).sum().backward(), &linear);
+        let _linear = optim.step(linear, grads);
+
+        assert_ne!(lrs[3], lrs[0]);
+        assert_eq!(optim.last_lr(), Some(lrs[0]));
+    }
+
+    #[test]
+    fn test_scheduled_optimizer_can_be_used_as_an_optimizer() {
+        fn step_with<O: Optimizer<Linear<B>, B>>(optim: &mut O, linear: Linear<B>) -> Linear<B> {
+            let x = Tensor::<B, 2>::random([3, 4], Distribution::Default);
+            let grads = GradientsParams::from_grads(linear.forward(x).sum().backward(), &linear);
+            optim.step(1.0, linear, grads)
+        }
+        let linear: Linear<B> = LinearConfig::new(4, 2).init();
+        let mut optim =
+            TrainingOptimizerBuilder::new_warmup_cosine(AdamConfig::new().init(), 0.1, 0.0, 2, 10)
+                .build();
+
+        let _linear = step_with(&mut optim, linear);
+
+        // The learning rate given to the step is replaced by the scheduled one.
+        assert!(optim.last_lr().unwrap() <= 0.1);
+        assert_eq!(optim.num_params(), 2);
+        let record = optim.to_record();
+        let optim = optim.load_record(record);
+        assert_eq!(optim.optimizer().num_params(), 2);
+    }
+}
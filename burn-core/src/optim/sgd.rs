@@ -2,10 +2,10 @@ use crate::grad_clipping::GradientClippingConfig;
 use crate::module::AutodiffModule;
 use crate::{self as burn, LearningRate};
 
-use super::decay::{WeightDecay, WeightDecayConfig};
+use super::decay::{WeightDecay, WeightDecayConfig, WeightDecayWarmup};
 use super::momentum::{Momentum, MomentumConfig, MomentumState};
 use super::SimpleOptimizer;
-use crate::config::Config;
+use crate::config::{Config, ConfigError, ValidateConfig};
 use crate::optim::adaptor::OptimizerAdaptor;
 use crate::record::Record;
 use crate::tensor::Tensor;
@@ -20,6 +20,9 @@ pub struct SgdConfig {
     momentum: Option<MomentumConfig>,
     /// [Gradient Clipping](GradientClippingConfig) config.
     gradient_clipping: Option<GradientClippingConfig>,
+    /// [Gradient scale](crate::optim::adaptor::OptimizerAdaptor::with_grad_scale) of the adaptor.
+    #[config(default = 1.0)]
+    grad_scale: f32,
 }
 
 /// Optimizer that implements stochastic gradient descent with momentum.
@@ -36,21 +39,62 @@ pub struct SgdState<B: Backend, const D: usize> {
     momentum: Option<MomentumState<B, D>>,
 }
 
+impl ValidateConfig for SgdConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        if let Some(momentum) = &self.momentum {
+            momentum.validate()?;
+        }
+        if let Some(weight_decay) = &self.weight_decay {
+            weight_decay.validate()?;
+        }
+        if let Some(clipping) = &self.gradient_clipping {
+            clipping.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
 impl SgdConfig {
+    /// Initialize Sgd as a [simple optimizer](SimpleOptimizer), to optimize tensors directly
+    /// with [step_tensors](crate::optim::step_tensors).
+    ///
+    /// The momentum and the weight decay are applied to the tensors, but the gradients are used
+    /// as given: the `grad_clipping` and `grad_scale` of the config only configure
+    /// [init](Self::init).
+    pub fn init_simple<B: Backend>(&self) -> Sgd<B> {
+        Sgd {
+            momentum: self.momentum.as_ref().map(Momentum::new),
+            weight_decay: self.weight_decay.as_ref().map(WeightDecay::new),
+        }
+    }
+
     /// Creates a new [SgdConfig](SgdConfig) with default values.
     pub fn init<B: AutodiffBackend, M: AutodiffModule<B>>(
         &self,
     ) -> OptimizerAdaptor<Sgd<B::InnerBackend>, M, B> {
-        let momentum = self.momentum.as_ref().map(Momentum::new);
-        let weight_decay = self.weight_decay.as_ref().map(WeightDecay::new);
-
-        let mut optim = OptimizerAdaptor::from(Sgd {
-            momentum,
-            weight_decay,
-        });
+        // The adaptor completes the decay toward the initial values, and applies the warmed up
+        // decay itself.
+        let weight_decay = self
+            .weight_decay
+            .as_ref()
+            .and_then(WeightDecayConfig::for_simple_optimizer);
+        let optim = self
+            .clone()
+            .with_weight_decay(weight_decay)
+            .init_simple::<B::InnerBackend>();
+        let mut optim = OptimizerAdaptor::from(optim)
+            .with_grad_scale(self.grad_scale)
+            .with_config(self);
         if let Some(config) = &self.gradient_clipping {
             optim = optim.with_grad_clipping(config.init());
         }
+        if let Some(config) = &self.weight_decay {
+            optim = optim.with_weight_decay(config.clone());
+        }
+        if let Some(config) = self.weight_decay.as_ref().filter(|c| c.warmup_steps > 0) {
+            optim = optim.with_weight_decay_warmup(WeightDecayWarmup::new(config));
+        }
         optim
     }
 }
@@ -83,14 +127,34 @@ impl<B: Backend> SimpleOptimizer<B> for Sgd<B> {
 
         let state = SgdState::new(state_momemtum);
         let delta = grad.mul_scalar(lr);
+        let mut tensor = tensor - delta;
+        if let Some(weight_decay) = &self.weight_decay {
+            tensor = weight_decay.proximal(lr, tensor);
+        }
 
-        (tensor - delta, Some(state))
+        (tensor, Some(state))
     }
 
     fn to_device<const D: usize>(mut state: Self::State<D>, device: &B::Device) -> Self::State<D> {
         state.momentum = state.momentum.map(|state| state.to_device(device));
         state
     }
+
+    fn state_map_tensors<const D: usize, F>(mut state: Self::State<D>, func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        state.momentum = state.momentum.map(|state| state.map_tensors(func));
+        state
+    }
+
+    fn state_num_elements<const D: usize>(state: &Self::State<D>) -> usize {
+        state
+            .momentum
+            .as_ref()
+            .map(|momentum| momentum.num_elements())
+            .unwrap_or(0)
+    }
 }
 
 #[cfg(test)]
@@ -152,6 +216,21 @@ mod tests {
         assert_eq!(record.len(), state_restored.len());
     }
 
+    #[test]
+    fn validate_should_reject_invalid_momentum() {
+        let config = SgdConfig::new().with_momentum(Some(MomentumConfig::new()));
+        assert!(config.validate().is_ok());
+
+        let config = config.with_momentum(Some(MomentumConfig::new().with_momentum(1.5)));
+        let err = config.validate().unwrap_err();
+
+        assert!(matches!(
+            err,
+            ConfigError::OutOfRange(message)
+                if message == "momentum.momentum must be in [0, 1), got 1.5."
+        ));
+    }
+
     fn random_tensor() -> Tensor<TestAutodiffBackend, 2> {
         Tensor::<TestAutodiffBackend, 2>::random(Shape::new([2, 20]), Distribution::Default)
     }
@@ -163,13 +242,14 @@ mod tests {
     fn sgd_with_all(
     ) -> OptimizerAdaptor<Sgd<TestBackend>, Linear<TestAutodiffBackend>, TestAutodiffBackend> {
         SgdConfig {
-            weight_decay: Some(WeightDecayConfig { penalty: 0.05 }),
+            weight_decay: Some(WeightDecayConfig::new(0.05)),
             momentum: Some(MomentumConfig {
                 momentum: 0.9,
                 dampening: 0.1,
                 nesterov: true,
             }),
             gradient_clipping: None,
+            grad_scale: 1.0,
         }
         .init()
     }
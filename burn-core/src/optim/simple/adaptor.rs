@@ -1,13 +1,37 @@
-use super::{record::AdaptorRecord, SimpleOptimizer};
+use super::{
+    effective_lr_stats, param_rng, record::AdaptorRecord, seed::mix, state_diff, EffectiveLrStats,
+    GradientHistory, SimpleOptimizer, StateConversion, StateDiff,
+};
 use crate::{
-    grad_clipping::GradientClipping,
-    module::{AutodiffModule, ModuleMapper, ParamId},
-    optim::{GradientsParams, Optimizer},
+    config::{config_to_json, Config},
+    grad_clipping::{
+        ClipStats, GlobalNormAccumulator, GradientClipping, GradientClippingGroups,
+        GradientClippingOrder, GradientClippingSchedule, LossAdaptiveGradientClipping, NanPolicy,
+        NormKind,
+    },
+    lr_scheduler::LrScheduler,
+    module::{AutodiffModule, ModuleMapper, ModuleVisitor, ParamId},
+    optim::{
+        decay::{
+            WeightDecay, WeightDecayConfig, WeightDecayKind, WeightDecayOverrides,
+            WeightDecayWarmup,
+        },
+        GradientsParams, Optimizer, StateTensor,
+    },
     LearningRate,
 };
-use burn_tensor::{backend::AutodiffBackend, Tensor};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use burn_tensor::{
+    backend::{AutodiffBackend, Backend},
+    Data, Distribution, ElementConversion, Shape, Tensor,
+};
 use core::marker::PhantomData;
-use hashbrown::HashMap;
+use core::ops::Range;
+use hashbrown::{HashMap, HashSet};
+
+pub use super::record::OptimizerAdaptorRecord;
 
 /// Wrapper struct that adapts any [simple optimizer](SimpleOptimizer) into
 /// an [optimizer](Optimizer).
@@ -21,6 +45,29 @@ where
     records: HashMap<ParamId, AdaptorRecord<O, B::InnerBackend>>,
     module: PhantomData<M>,
     grad_clipping: Option<GradientClipping>,
+    grad_clipping_schedule: Option<Box<dyn StepSchedule<GradientClipping>>>,
+    grad_clipping_loss_adaptive: Option<LossAdaptiveGradientClipping>,
+    grad_clipping_groups: Option<GradientClippingGroups>,
+    grad_clipping_params: Option<HashSet<ParamId>>,
+    grad_clipping_window: Option<Range<usize>>,
+    grad_clipping_nan_policy: NanPolicy,
+    grad_clipping_order: GradientClippingOrder,
+    clip_stats: ClipStats,
+    grad_transpose: bool,
+    grad_scale: f32,
+    grad_scale_scheduler: Option<Box<dyn StepSchedule<f32>>>,
+    grad_scale_multiplier: f32,
+    grad_dropout: Option<f64>,
+    seed: Option<u64>,
+    num_steps: usize,
+    skip_threshold: Option<f32>,
+    config: Option<String>,
+    last_updated: Vec<ParamId>,
+    grad_history: Option<GradientHistory<B::InnerBackend>>,
+    weight_decay: Option<WeightDecayConfig>,
+    weight_decay_overrides: Option<WeightDecayOverrides>,
+    weight_decay_warmup: Option<WeightDecayWarmup>,
+    weight_decay_init: HashMap<ParamId, Tensor<B::InnerBackend, 1>>,
 }
 
 impl<O, B, M> From<O> for OptimizerAdaptor<O, M, B>
@@ -35,6 +82,29 @@ where
             records: HashMap::new(),
             module: PhantomData,
             grad_clipping: None,
+            grad_clipping_schedule: None,
+            grad_clipping_loss_adaptive: None,
+            grad_clipping_groups: None,
+            grad_clipping_params: None,
+            grad_clipping_window: None,
+            grad_clipping_nan_policy: NanPolicy::Skip,
+            grad_clipping_order: GradientClippingOrder::BeforeWeightDecay,
+            clip_stats: ClipStats::default(),
+            grad_transpose: false,
+            grad_scale: 1.0,
+            grad_scale_scheduler: None,
+            grad_scale_multiplier: 1.0,
+            grad_dropout: None,
+            seed: None,
+            num_steps: 0,
+            skip_threshold: None,
+            config: None,
+            last_updated: Vec::new(),
+            grad_history: None,
+            weight_decay: None,
+            weight_decay_overrides: None,
+            weight_decay_warmup: None,
+            weight_decay_init: HashMap::new(),
         }
     }
 }
@@ -47,16 +117,627 @@ where
 {
     /// Sets the gradient clipping.
     ///
-    /// # Arguments
+    /// # Arguments
+    ///
+    /// * `gradient_clipping` - The gradient clipping.
+    ///
+    /// # Returns
+    ///
+    /// The optimizer.
+    pub fn with_grad_clipping(mut self, gradient_clipping: GradientClipping) -> Self {
+        self.grad_clipping = Some(gradient_clipping);
+        self
+    }
+
+    /// Sets a schedule of the gradient clipping threshold, replacing the gradient clipping set by
+    /// [with_grad_clipping](Self::with_grad_clipping).
+    ///
+    /// The schedule is advanced at the beginning of each step. Its state isn't part of the
+    /// [record](Optimizer::to_record), the schedule is moved to the
+    /// [step counter](Self::num_steps) of the record when it's loaded instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [loss adaptive](Self::with_loss_adaptive_grad_clipping) gradient clipping is
+    /// set, since the schedule would replace the adapted threshold at each step.
+    ///
+    /// # Arguments
+    ///
+    /// * `schedule` - The gradient clipping schedule.
+    ///
+    /// # Returns
+    ///
+    /// The optimizer.
+    #[cfg(feature = "std")]
+    pub fn with_grad_clipping_schedule<S: LrScheduler + 'static>(
+        mut self,
+        schedule: GradientClippingSchedule<S>,
+    ) -> Self {
+        assert!(
+            self.grad_clipping_loss_adaptive.is_none(),
+            "The gradient clipping schedule can't be combined with the loss adaptive gradient clipping."
+        );
+        self.grad_clipping_schedule = Some(Box::new(schedule));
+        self
+    }
+
+    /// Sets a gradient clipping with a threshold adapted to the training loss, replacing the
+    /// gradient clipping set by [with_grad_clipping](Self::with_grad_clipping).
+    ///
+    /// The threshold is only adapted by [step_with_loss](Self::step_with_loss), a regular step
+    /// clips with the current threshold. The adapted threshold is part of the
+    /// [record](OptimizerAdaptorRecord), so the clipping must be set before loading a record.
+    ///
+    /// # Arguments
+    ///
+    /// * `clipping` - The loss adaptive gradient clipping.
+    ///
+    /// # Returns
+    ///
+    /// The optimizer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a [gradient clipping schedule](Self::with_grad_clipping_schedule) is set, since
+    /// it would replace the adapted threshold at each step.
+    pub fn with_loss_adaptive_grad_clipping(
+        mut self,
+        clipping: LossAdaptiveGradientClipping,
+    ) -> Self {
+        assert!(
+            self.grad_clipping_schedule.is_none(),
+            "The loss adaptive gradient clipping can't be combined with a gradient clipping schedule."
+        );
+        self.grad_clipping = Some(clipping.clipping().clone());
+        self.grad_clipping_loss_adaptive = Some(clipping);
+        self
+    }
+
+    /// Sets the gradient clipping of groups of parameters.
+    ///
+    /// Parameters without a group are clipped with the gradient clipping set by
+    /// [with_grad_clipping](Self::with_grad_clipping), if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `groups` - The gradient clipping groups.
+    ///
+    /// # Returns
+    ///
+    /// The optimizer.
+    pub fn with_grad_clipping_groups(mut self, groups: GradientClippingGroups) -> Self {
+        self.grad_clipping_groups = Some(groups);
+        self
+    }
+
+    /// Restricts the gradient clipping to the given parameters, e.g. to only clip the recurrent
+    /// weights of a model. The gradients of the other parameters are never clipped, even when
+    /// they belong to a [group](Self::with_grad_clipping_groups).
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The ids of the parameters to clip.
+    ///
+    /// # Returns
+    ///
+    /// The optimizer.
+    pub fn with_grad_clipping_params<I: IntoIterator<Item = ParamId>>(mut self, params: I) -> Self {
+        self.grad_clipping_params = Some(params.into_iter().collect());
+        self
+    }
+
+    /// Restricts the gradient clipping to the steps in a window, e.g. to clip the early steps of
+    /// a training until it stabilizes. The gradients of the other steps are never clipped.
+    ///
+    /// The steps are counted from zero by the [step counter](Self::num_steps) of the optimizer,
+    /// from `start_step` included to `end_step` excluded.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_step` - The first step with clipped gradients.
+    /// * `end_step` - The first step after the window.
+    ///
+    /// # Returns
+    ///
+    /// The optimizer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start step is after the end step.
+    pub fn with_grad_clipping_window(mut self, start_step: usize, end_step: usize) -> Self {
+        assert!(
+            start_step <= end_step,
+            "The start step must not be after the end step."
+        );
+        self.grad_clipping_window = Some(start_step..end_step);
+        self
+    }
+
+    /// Sets the policy of the norm-based gradient clipping when the norm of a gradient isn't
+    /// finite. Defaults to [NanPolicy::Skip].
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The policy for gradients with a NaN or infinite norm.
+    ///
+    /// # Returns
+    ///
+    /// The optimizer.
+    pub fn with_grad_clipping_nan_policy(mut self, policy: NanPolicy) -> Self {
+        self.grad_clipping_nan_policy = policy;
+        self
+    }
+
+    /// Sets the order of the gradient clipping relative to the weight decay added to the
+    /// gradients by the optimizer. Defaults to [GradientClippingOrder::BeforeWeightDecay].
+    ///
+    /// To clip [after the weight decay](GradientClippingOrder::AfterWeightDecay), the decay is
+    /// added to the gradient before the clipping and removed after it, so the optimizer adding its
+    /// own decay steps with the clipped sum. The decoupled and
+    /// [proximal](crate::optim::decay::WeightDecayKind::L1Proximal) weight decays aren't added to
+    /// the gradients, so both orders are the same for them.
+    ///
+    /// The decay is the one [of the optimizer](Self::with_weight_decay).
+    ///
+    /// # Arguments
+    ///
+    /// * `order` - The order of the gradient clipping.
+    ///
+    /// # Returns
+    ///
+    /// The optimizer.
+    pub fn with_grad_clipping_order(mut self, order: GradientClippingOrder) -> Self {
+        self.grad_clipping_order = order;
+        self
+    }
+
+    /// Sets if gradients arriving transposed relative to their parameter, i.e. with the last two
+    /// dimensions swapped, are transposed back before the step, e.g. for custom layers storing
+    /// their weights transposed.
+    ///
+    /// Otherwise, the step panics with a diagnostic when the shape of a gradient doesn't match the
+    /// shape of its parameter.
+    ///
+    /// # Arguments
+    ///
+    /// * `transpose` - If transposed gradients are transposed back.
+    ///
+    /// # Returns
+    ///
+    /// The optimizer.
+    pub fn with_grad_transpose(mut self, transpose: bool) -> Self {
+        self.grad_transpose = transpose;
+        self
+    }
+
+    /// Sets the scale applied to the incoming gradients, before any clipping or optimizer
+    /// statistics.
+    ///
+    /// This is useful when the loss is summed over the batch instead of averaged, see
+    /// [grad_scale_from_batch_size](grad_scale_from_batch_size).
+    ///
+    /// # Arguments
+    ///
+    /// * `grad_scale` - The gradient scale.
+    ///
+    /// # Returns
+    ///
+    /// The optimizer.
+    pub fn with_grad_scale(mut self, grad_scale: f32) -> Self {
+        self.grad_scale = grad_scale;
+        self
+    }
+
+    /// Sets a schedule of a multiplier of the gradient scale, e.g. to change the weighting of a
+    /// task over time for curriculum learning.
+    ///
+    /// The multiplier is given by a [scheduler](LrScheduler), like a learning rate, which is
+    /// advanced at the beginning of each step. The gradients are scaled by the product of the
+    /// [gradient scale](Self::with_grad_scale) and the multiplier. The state of the scheduler
+    /// isn't part of the [record](Optimizer::to_record), the scheduler is moved to the
+    /// [step counter](Self::num_steps) of the record when it's loaded instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `scheduler` - The scheduler of the gradient scale multiplier.
+    ///
+    /// # Returns
+    ///
+    /// The optimizer.
+    pub fn with_grad_scale_scheduler<S: LrScheduler + 'static>(mut self, scheduler: S) -> Self {
+        self.grad_scale_scheduler = Some(Box::new(GradScaleSchedule(scheduler)));
+        self
+    }
+
+    /// The gradient scale multiplier of the last step, given by the
+    /// [gradient scale scheduler](Self::with_grad_scale_scheduler), `1.0` without a scheduler.
+    pub fn grad_scale_multiplier(&self) -> f32 {
+        self.grad_scale_multiplier
+    }
+
+    /// Sets the probability of randomly zeroing each element of the incoming gradients (DropGrad),
+    /// as a regularization. The remaining elements are scaled by `1 / (1 - prob)`, so the
+    /// gradients are unchanged in expectation.
+    ///
+    /// The dropout is applied after the gradient scale, before any clipping or optimizer
+    /// statistics. The mask is sampled with the random number generator of the backend, which can
+    /// be seeded with [seed](Backend::seed) for reproducibility, or with the random number
+    /// generator of each parameter when the optimizer is [seeded](Self::with_seed).
+    ///
+    /// # Arguments
+    ///
+    /// * `prob` - The probability of zeroing each element, in `[0, 1)`.
+    ///
+    /// # Returns
+    ///
+    /// The optimizer.
+    pub fn with_grad_dropout(mut self, prob: f64) -> Self {
+        assert!(
+            (0.0..1.0).contains(&prob),
+            "The gradient dropout probability must be in [0, 1)."
+        );
+        self.grad_dropout = Some(prob).filter(|prob| *prob > 0.0);
+        self
+    }
+
+    /// Sets the seed of the random transforms of the gradients, e.g. the
+    /// [gradient dropout](Self::with_grad_dropout), instead of using the random number generator
+    /// of the backend.
+    ///
+    /// The seed is split per parameter and per step with [param_seed](super::param_seed), so the
+    /// random values of each parameter are independent, and reproducible by another optimizer
+    /// with the same seed. The [step counter](Self::num_steps) is part of the
+    /// [record](OptimizerAdaptorRecord).
+    ///
+    /// The backends can't be seeded per tensor, so the seeded random values are sampled on the
+    /// host and uploaded to the device of each gradient at every step, which is slower than the
+    /// random number generator of the backend for large models.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The seed.
+    ///
+    /// # Returns
+    ///
+    /// The optimizer.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Sets the global gradient norm above which a step is skipped entirely, treating the batch as
+    /// corrupted.
+    ///
+    /// The norm is computed after the gradient scale and before any clipping, so the threshold is
+    /// usually a large multiple of the clipping value. When a step is skipped, a warning is logged
+    /// and neither the module nor the optimizer state is updated. The skipped step still counts as
+    /// a step of the [step counter](Self::num_steps) and of the schedules, e.g. of the gradient
+    /// clipping and the weight decay warmup, which are advanced together.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - The maximum global gradient norm.
+    ///
+    /// # Returns
+    ///
+    /// The optimizer.
+    pub fn with_skip_threshold(mut self, threshold: f32) -> Self {
+        self.skip_threshold = Some(threshold);
+        self
+    }
+
+    /// Sets the configuration the optimizer was initialized with, returned by
+    /// [config_json](Optimizer::config_json).
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The optimizer configuration.
+    ///
+    /// # Returns
+    ///
+    /// The optimizer.
+    pub fn with_config<C: Config>(mut self, config: &C) -> Self {
+        self.config = Some(config_to_json(config));
+        self
+    }
+
+    /// Keep the last gradients of each parameter, to analyze the gradient dynamics.
+    ///
+    /// This is memory-heavy, as `size` gradients are kept for each parameter, so it's disabled by
+    /// default. The history isn't part of the [record](Optimizer::to_record).
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The number of gradients kept for each parameter.
+    ///
+    /// # Returns
+    ///
+    /// The optimizer.
+    pub fn with_grad_history(mut self, size: usize) -> Self {
+        self.grad_history = Some(GradientHistory::new(size));
+        self
+    }
+
+    /// Sets the weight decay added to the gradients by the optimizer, which the
+    /// [clipping order](Self::with_grad_clipping_order) and the
+    /// [overrides](Self::with_weight_decay_overrides) are resolved against.
+    ///
+    /// It's set by the `init` of the configs of the optimizers with a coupled weight decay.
+    ///
+    /// # Arguments
+    ///
+    /// * `weight_decay` - The weight decay of the optimizer.
+    ///
+    /// # Returns
+    ///
+    /// The optimizer.
+    pub fn with_weight_decay(mut self, weight_decay: WeightDecayConfig) -> Self {
+        self.weight_decay = Some(weight_decay);
+        self
+    }
+
+    /// Sets the weight decay of some parameters, overriding the
+    /// [weight decay of the optimizer](Self::with_weight_decay).
+    ///
+    /// The override is added to the gradient after the clipping, like the coupled weight decay
+    /// of the optimizers, with the same [kind](crate::optim::decay::WeightDecayKind).
+    ///
+    /// # Arguments
+    ///
+    /// * `overrides` - The weight decay overrides.
+    ///
+    /// # Returns
+    ///
+    /// The optimizer.
+    pub fn with_weight_decay_overrides(mut self, overrides: WeightDecayOverrides) -> Self {
+        self.weight_decay_overrides = Some(overrides);
+        self
+    }
+
+    /// Sets the warmup of the weight decay of the optimizer, ramping its penalty from zero over
+    /// the first steps.
+    ///
+    /// The adaptor applies the decay with the warmed up penalty itself, so the simple optimizer
+    /// must not apply it, which is how the configs with
+    /// [warmup steps](WeightDecayConfig::warmup_steps) create it.
+    ///
+    /// The warmup is advanced at the end of each step, and its step is part of the
+    /// [record](Optimizer::to_record).
+    ///
+    /// # Arguments
+    ///
+    /// * `warmup` - The weight decay warmup.
+    ///
+    /// # Returns
+    ///
+    /// The optimizer.
+    pub fn with_weight_decay_warmup(mut self, warmup: WeightDecayWarmup) -> Self {
+        self.weight_decay_warmup = Some(warmup);
+        self
+    }
+
+    /// Sets the initial values toward which the parameters are decayed with the
+    /// [L2 to init](WeightDecayKind::L2ToInit) weight decay, e.g. the pretrained weights when
+    /// fine-tuning. The values are part of the [record](OptimizerAdaptorRecord).
+    ///
+    /// Parameters missing from the given module are decayed toward zero, e.g. a new classifier
+    /// head.
+    ///
+    /// # Arguments
+    ///
+    /// * `module` - The module with the initial values of the parameters.
+    ///
+    /// # Returns
+    ///
+    /// The optimizer.
+    pub fn with_weight_decay_init(mut self, module: &M) -> Self {
+        module.visit(&mut ParamsInit::<B>::new(&mut self.weight_decay_init));
+        self
+    }
+
+    /// The number of steps performed, the [skipped](Self::with_skip_threshold) steps included.
+    pub fn num_steps(&self) -> usize {
+        self.num_steps
+    }
+
+    /// The [statistics](ClipStats) of the norm-based gradient clipping since the creation of the
+    /// optimizer or the last [reset](Self::reset_clip_stats).
+    pub fn clip_stats(&self) -> &ClipStats {
+        &self.clip_stats
+    }
+
+    /// Clear the [statistics](ClipStats) of the gradient clipping, e.g. at the start of an epoch
+    /// so the statistics are reported per epoch.
+    pub fn reset_clip_stats(&mut self) {
+        self.clip_stats.reset();
+    }
+
+    /// The loss adaptive gradient clipping, if enabled with
+    /// [with_loss_adaptive_grad_clipping](Self::with_loss_adaptive_grad_clipping), e.g. to log its
+    /// threshold.
+    pub fn loss_adaptive_grad_clipping(&self) -> Option<&LossAdaptiveGradientClipping> {
+        self.grad_clipping_loss_adaptive.as_ref()
+    }
+
+    /// Perform an [optimizer step](Optimizer::step), first adapting the threshold of the
+    /// [loss adaptive gradient clipping](Self::with_loss_adaptive_grad_clipping) to the change of
+    /// the loss.
+    ///
+    /// # Arguments
+    ///
+    /// * `lr` - The learning rate.
+    /// * `module` - The module to update.
+    /// * `grads` - The gradients of the loss.
+    /// * `loss_previous` - The loss of the previous step.
+    /// * `loss` - The loss of the current step.
+    ///
+    /// # Returns
+    ///
+    /// The updated module.
+    pub fn step_with_loss(
+        &mut self,
+        lr: LearningRate,
+        module: M,
+        grads: GradientsParams,
+        loss_previous: f64,
+        loss: f64,
+    ) -> M {
+        if let Some(clipping) = self.grad_clipping_loss_adaptive.as_mut() {
+            self.grad_clipping = Some(clipping.update(loss_previous, loss));
+        }
+
+        self.step(lr, module, grads)
+    }
+
+    /// The history of the gradients, if enabled with
+    /// [with_grad_history](Self::with_grad_history).
+    pub fn grad_history(&self) -> Option<&GradientHistory<B::InnerBackend>> {
+        self.grad_history.as_ref()
+    }
+
+    /// Sets the state of the optimizer from the state of another optimizer, e.g. to warm-start the
+    /// optimizer of the second phase of a [phase optimizer](crate::optim::PhaseOptimizer).
+    ///
+    /// The state of every parameter of the source optimizer is converted, replacing the current
+    /// state of the optimizer.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The optimizer whose state is converted.
+    /// * `conversion` - The conversion of the state of each parameter.
+    ///
+    /// # Returns
+    ///
+    /// The optimizer.
+    pub fn with_state_from<O2, C>(
+        mut self,
+        source: &OptimizerAdaptor<O2, M, B>,
+        conversion: &C,
+    ) -> Self
+    where
+        O2: SimpleOptimizer<B::InnerBackend>,
+        C: StateConversion<B::InnerBackend, O2, O>,
+    {
+        self.records = source
+            .records
+            .iter()
+            .map(|(id, record)| (id.clone(), record.clone().convert(conversion)))
+            .collect();
+        self
+    }
+
+    /// Clear the state of the given parameters, so their next step starts from a fresh state.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The ids of the parameters to reset.
+    pub fn reset_state<I: IntoIterator<Item = ParamId>>(&mut self, params: I) {
+        for id in params {
+            self.records.remove(&id);
+        }
+    }
+
+    /// Clear the state of the given parameters and reinitialize them in the module, e.g. to reset
+    /// dead neurons when studying the loss of plasticity.
+    ///
+    /// The reinitialization is a [mapper](ModuleMapper) called with each parameter to reset with
+    /// its shape, e.g. to recompute its fan in, and must return a tensor with the same shape.
+    /// The other parameters and their state are left untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `module` - The module owning the parameters.
+    /// * `params` - The ids of the parameters to reset.
+    /// * `reinit` - The reinitialization of a parameter.
+    ///
+    /// # Returns
+    ///
+    /// The module with the reinitialized parameters.
+    pub fn reset<I, R>(&mut self, module: M, params: I, reinit: &mut R) -> M
+    where
+        I: IntoIterator<Item = ParamId>,
+        R: ModuleMapper<B::InnerBackend>,
+    {
+        let params: HashSet<ParamId> = params.into_iter().collect();
+        self.reset_state(params.iter().cloned());
+
+        module.map(&mut ParamsReinit::<B, R>::new(&params, reinit))
+    }
+
+    /// Compare the state of the optimizer with the state of another one, e.g. to debug the
+    /// divergence of two training runs. See [state_diff](super::state_diff).
+    pub fn state_diff(&self, other: &Self) -> StateDiff {
+        state_diff(&self.records, &other.records)
+    }
+
+    /// The [effective learning rate statistics](EffectiveLrStats) of each parameter with a state,
+    /// e.g. to detect when the adaptation of the optimizer collapsed the learning rate to near
+    /// zero.
     ///
-    /// * `gradient_clipping` - The gradient clipping.
+    /// The map is empty when the optimizer doesn't adapt the learning rate per coordinate.
     ///
-    /// # Returns
+    /// # Arguments
     ///
-    /// The optimizer.
-    pub fn with_grad_clipping(mut self, gradient_clipping: GradientClipping) -> Self {
-        self.grad_clipping = Some(gradient_clipping);
-        self
+    /// * `lr` - The nominal learning rate of the next step.
+    pub fn effective_lr_stats(&self, lr: LearningRate) -> HashMap<ParamId, EffectiveLrStats> {
+        effective_lr_stats(&self.optim, &self.records, lr)
+    }
+
+    /// The scale of the gradients of each group of parameters clipped by their
+    /// [global RMS](GradientClipping::GlobalRms), keyed by the name of the group, `None` for the
+    /// parameters clipped by the default gradient clipping.
+    ///
+    /// A scale of zero means the gradients are replaced with zeros, and a missing group means
+    /// its clipping is skipped by the [NanPolicy].
+    fn global_rms_scales(
+        &mut self,
+        module: &M,
+        grads: &GradientsParams,
+        grad_scale: f32,
+    ) -> HashMap<Option<String>, f32> {
+        let mut accumulators = HashMap::new();
+        module.visit(&mut GlobalRmsVisitor::<B>::new(
+            grads,
+            self.grad_clipping.as_ref(),
+            self.grad_clipping_groups.as_ref(),
+            self.grad_clipping_params.as_ref(),
+            &mut accumulators,
+            HashSet::new(),
+        ));
+
+        accumulators
+            .into_iter()
+            .filter_map(|(group, (target, accumulator))| {
+                let rms = accumulator.norm().into_scalar().elem::<f32>() * grad_scale.abs();
+                if !rms.is_finite() {
+                    return match self.grad_clipping_nan_policy {
+                        NanPolicy::Skip if rms.is_nan() => None,
+                        NanPolicy::Skip | NanPolicy::Zero => Some((group, 0.0)),
+                        NanPolicy::Error => panic!(
+                            "The global gradient RMS is {rms}, the gradients can't be clipped."
+                        ),
+                    };
+                }
+
+                self.clip_stats.record(rms, target);
+                match rms > target {
+                    true => Some((group, target / rms)),
+                    false => Some((group, 1.0)),
+                }
+            })
+            .collect()
+    }
+
+    /// Whether the [weight decay](Self::with_weight_decay) of parameters can be
+    /// [overridden](Self::with_weight_decay_overrides) by a smaller penalty, which an
+    /// [L1 proximal](WeightDecayKind::L1Proximal) decay applied by the simple optimizer forbids.
+    pub(crate) fn can_reduce_weight_decay(&self) -> bool {
+        // During a warmup, the adaptor applies the decay of the overrides itself.
+        self.weight_decay_warmup.is_some()
+            || self
+                .weight_decay
+                .as_ref()
+                .map_or(true, |config| config.kind != WeightDecayKind::L1Proximal)
     }
 
     #[cfg(test)]
@@ -65,33 +746,221 @@ where
     }
 }
 
+/// A schedule advanced once per step, which can be moved to any step when a training resumes.
+trait StepSchedule<T>: Send + Sync {
+    fn step(&mut self) -> T;
+    fn seek(&mut self, step: usize);
+}
+
+impl<S: LrScheduler> StepSchedule<GradientClipping> for GradientClippingSchedule<S> {
+    fn step(&mut self) -> GradientClipping {
+        GradientClippingSchedule::step(self)
+    }
+
+    fn seek(&mut self, step: usize) {
+        GradientClippingSchedule::seek(self, step)
+    }
+}
+
+/// The gradient scale multiplier given by a [scheduler](LrScheduler).
+struct GradScaleSchedule<S>(S);
+
+impl<S: LrScheduler> StepSchedule<f32> for GradScaleSchedule<S> {
+    fn step(&mut self) -> f32 {
+        self.0.step() as f32
+    }
+
+    fn seek(&mut self, step: usize) {
+        self.0.seek(step)
+    }
+}
+
+/// The gradient scale that turns the gradients of a loss summed over a batch of the given size
+/// into the gradients of the loss averaged over the batch.
+pub fn grad_scale_from_batch_size(batch_size: usize) -> f32 {
+    1.0 / batch_size as f32
+}
+
 impl<O, B, M> Optimizer<M, B> for OptimizerAdaptor<O, M, B>
 where
     B: AutodiffBackend,
     M: AutodiffModule<B>,
     O: SimpleOptimizer<B::InnerBackend>,
 {
-    type Record = HashMap<ParamId, AdaptorRecord<O, B::InnerBackend>>;
+    type Record = OptimizerAdaptorRecord<AdaptorRecord<O, B::InnerBackend>, B::InnerBackend>;
 
     fn step(&mut self, lr: LearningRate, module: M, mut grads: GradientsParams) -> M {
+        self.last_updated.clear();
+
+        if let Some(schedule) = self.grad_clipping_schedule.as_mut() {
+            self.grad_clipping = Some(schedule.step());
+        }
+        if let Some(scheduler) = self.grad_scale_scheduler.as_mut() {
+            self.grad_scale_multiplier = scheduler.step();
+        }
+        let grad_scale = self.grad_scale * self.grad_scale_multiplier;
+
+        if let Some(threshold) = self.skip_threshold {
+            if let Some(norm) = grads.global_norm::<B, M>(&module) {
+                let norm = norm.into_scalar().elem::<f32>() * grad_scale.abs();
+                if norm.is_nan() || norm > threshold {
+                    log::warn!(
+                        "Skipping the optimizer step, the gradient norm {norm} exceeds {threshold}."
+                    );
+                    // The schedules were advanced for this step, so the others are too.
+                    self.num_steps += 1;
+                    if let Some(warmup) = self.weight_decay_warmup.as_mut() {
+                        warmup.step();
+                    }
+                    return module;
+                }
+            }
+        }
+
+        let is_clipping = self
+            .grad_clipping_window
+            .as_ref()
+            .map_or(true, |window| window.contains(&self.num_steps));
+        let global_rms_scales = match is_clipping {
+            true => self.global_rms_scales(&module, &grads, grad_scale),
+            false => HashMap::new(),
+        };
+
+        self.num_steps += 1;
+        let seed = self
+            .seed
+            .map(|seed| mix(seed.wrapping_add(self.num_steps as u64)));
+
         let mut mapper = SimpleOptimizerMapper::<M, B, O>::new(
             &self.optim,
             &mut self.records,
             &mut grads,
             lr,
-            self.grad_clipping.as_ref(),
+            self.grad_clipping.as_ref().filter(|_| is_clipping),
+            self.grad_clipping_groups.as_ref().filter(|_| is_clipping),
+            self.grad_clipping_params.as_ref(),
+            &self.grad_clipping_nan_policy,
+            &self.grad_clipping_order,
+            self.weight_decay.as_ref(),
+            &mut self.clip_stats,
+            &global_rms_scales,
+            self.grad_transpose,
+            grad_scale,
+            self.grad_dropout,
+            seed,
+            &mut self.last_updated,
+            self.grad_history.as_mut(),
+            self.weight_decay_overrides.as_ref(),
+            self.weight_decay_warmup.as_ref(),
+            &self.weight_decay_init,
         );
-        module.map(&mut mapper)
+        let module = module.map(&mut mapper);
+
+        if let Some(warmup) = self.weight_decay_warmup.as_mut() {
+            warmup.step();
+        }
+        module
     }
 
     fn to_record(&self) -> Self::Record {
-        self.records.clone()
+        OptimizerAdaptorRecord {
+            params: self.records.clone(),
+            num_steps: self.num_steps,
+            loss_adaptive_threshold: self
+                .grad_clipping_loss_adaptive
+                .as_ref()
+                .map(LossAdaptiveGradientClipping::threshold),
+            weight_decay_init: self.weight_decay_init.clone(),
+            weight_decay_warmup_step: self
+                .weight_decay_warmup
+                .as_ref()
+                .map_or(0, WeightDecayWarmup::num_steps),
+        }
     }
 
     fn load_record(mut self, record: Self::Record) -> Self {
-        self.records = record;
+        self.records = record.params;
+        self.num_steps = record.num_steps;
+        self.weight_decay_init = record.weight_decay_init;
+        // The schedules are advanced once per step, skipped steps included.
+        if let Some(schedule) = self.grad_clipping_schedule.as_mut() {
+            schedule.seek(record.num_steps);
+        }
+        if let Some(scheduler) = self.grad_scale_scheduler.as_mut() {
+            scheduler.seek(record.num_steps);
+        }
+        if let Some(warmup) = self.weight_decay_warmup.as_mut() {
+            warmup.seek(record.weight_decay_warmup_step);
+        }
+        if let (Some(clipping), Some(threshold)) = (
+            self.grad_clipping_loss_adaptive.as_mut(),
+            record.loss_adaptive_threshold,
+        ) {
+            self.grad_clipping = Some(clipping.reset_threshold(threshold));
+        }
+        self
+    }
+
+    fn to_device(mut self, device: &B::Device) -> Self {
+        self.records = self
+            .records
+            .into_iter()
+            .map(|(id, record)| (id, record.to_device(device)))
+            .collect();
+        self.grad_history = self.grad_history.map(|history| history.to_device(device));
+        self.weight_decay_init = self
+            .weight_decay_init
+            .into_iter()
+            .map(|(id, init)| (id, init.to_device(device)))
+            .collect();
         self
     }
+
+    fn clone_state_to(
+        &self,
+        mut target: Self,
+        module: &M,
+        ids: &HashMap<ParamId, ParamId>,
+        adapt_shapes: bool,
+    ) -> Self {
+        let sources = ids
+            .iter()
+            .map(|(id_old, id_new)| (id_new.clone(), id_old.clone()))
+            .collect();
+        let mut visitor = StateTransferVisitor::<O, B>::new(
+            &self.records,
+            &mut target.records,
+            sources,
+            adapt_shapes,
+        );
+        module.visit(&mut visitor);
+        target
+    }
+
+    fn num_params(&self) -> usize {
+        self.records.len()
+    }
+
+    fn state_bytes(&self) -> usize {
+        let num_elements: usize = self.records.values().map(AdaptorRecord::num_elements).sum();
+
+        num_elements * core::mem::size_of::<<B::InnerBackend as Backend>::FloatElem>()
+    }
+
+    fn config_json(&self) -> Option<String> {
+        self.config.clone()
+    }
+
+    fn last_updated(&self) -> Vec<ParamId> {
+        self.last_updated.clone()
+    }
+
+    fn export_state_tensors(&self) -> HashMap<ParamId, Vec<StateTensor<B::InnerBackend>>> {
+        self.records
+            .iter()
+            .map(|(id, record)| (id.clone(), record.tensors()))
+            .collect()
+    }
 }
 
 #[derive(new)]
@@ -107,6 +976,22 @@ where
     lr: LearningRate,
     phantom: PhantomData<M>,
     grad_clipping: Option<&'a GradientClipping>,
+    grad_clipping_groups: Option<&'a GradientClippingGroups>,
+    grad_clipping_params: Option<&'a HashSet<ParamId>>,
+    grad_clipping_nan_policy: &'a NanPolicy,
+    grad_clipping_order: &'a GradientClippingOrder,
+    weight_decay: Option<&'a WeightDecayConfig>,
+    clip_stats: &'a mut ClipStats,
+    global_rms_scales: &'a HashMap<Option<String>, f32>,
+    grad_transpose: bool,
+    grad_scale: f32,
+    grad_dropout: Option<f64>,
+    seed: Option<u64>,
+    updated: &'a mut Vec<ParamId>,
+    grad_history: Option<&'a mut GradientHistory<B::InnerBackend>>,
+    weight_decay_overrides: Option<&'a WeightDecayOverrides>,
+    weight_decay_warmup: Option<&'a WeightDecayWarmup>,
+    weight_decay_init: &'a HashMap<ParamId, Tensor<B::InnerBackend, 1>>,
 }
 
 impl<'a, M, B, O> ModuleMapper<B> for SimpleOptimizerMapper<'a, M, B, O>
@@ -118,23 +1003,113 @@ where
     fn map<const D: usize>(&mut self, id: &ParamId, tensor: Tensor<B, D>) -> Tensor<B, D> {
         let grad = self.grads.remove(id);
 
-        if let Some(grad) = grad {
+        if let Some(mut grad) = grad {
+            grad = match_layout(id, grad, &tensor.shape(), self.grad_transpose);
+
+            if self.grad_scale != 1.0 {
+                grad = grad.mul_scalar(self.grad_scale);
+            }
+
+            if let Some(prob) = self.grad_dropout {
+                let prob_keep = 1.0 - prob;
+                let mask = match self.seed {
+                    Some(seed) => {
+                        let data = Data::<f32, D>::random(
+                            grad.shape(),
+                            Distribution::Bernoulli(prob_keep),
+                            &mut param_rng(seed, id),
+                        );
+                        Tensor::from_data_device(data.convert(), &grad.device())
+                    }
+                    None => grad.random_like(Distribution::Bernoulli(prob_keep)),
+                };
+                grad = grad.mul(mask).mul_scalar(1.0 / prob_keep);
+            }
+
             let device = grad.device();
             let is_require_grad = tensor.is_require_grad();
             let (key, record) = self.records.remove_entry(id).unzip();
 
-            let clipped_grad = if let Some(g_clipping) = self.grad_clipping {
-                g_clipping.clip_gradient(grad)
-            } else {
-                grad
+            let grad_clipping = param_clipping(
+                id,
+                self.grad_clipping,
+                self.grad_clipping_groups,
+                self.grad_clipping_params,
+            );
+
+            // The decay of the optimizer is clipped with the gradient, then removed since the
+            // optimizer adds it back.
+            let decay = self
+                .weight_decay
+                .filter(|_| grad_clipping.is_some())
+                .filter(|_| *self.grad_clipping_order == GradientClippingOrder::AfterWeightDecay)
+                .map(|config| {
+                    let mut config = config.clone();
+                    if let Some(penalty) = self.weight_decay_overrides.and_then(|o| o.penalty(id)) {
+                        config.penalty = penalty;
+                    } else if let Some(warmup) = self.weight_decay_warmup {
+                        config.penalty = warmup.penalty();
+                    }
+                    let tensor = tensor.clone().inner();
+                    let decay = WeightDecay::new(&config.within_adaptor())
+                        .transform(grad.zeros_like(), tensor.clone());
+                    match self.decay_to_init(id, &tensor) {
+                        Some(decay_to_init) => decay.add(decay_to_init),
+                        None => decay,
+                    }
+                });
+            if let Some(decay) = &decay {
+                grad = grad.add(decay.clone());
+            }
+
+            let mut clipped_grad = match grad_clipping {
+                // The global RMS of the group is computed before the step.
+                Some((group, GradientClipping::GlobalRms(_))) => {
+                    match self.global_rms_scales.get(&group.map(String::from)) {
+                        Some(scale) if *scale == 0.0 => grad.zeros_like(),
+                        Some(scale) => grad.mul_scalar(*scale),
+                        None => grad,
+                    }
+                }
+                Some((_, g_clipping)) => g_clipping.clip_gradient_with_stats(
+                    grad,
+                    self.grad_clipping_nan_policy,
+                    self.clip_stats,
+                ),
+                None => grad,
             };
 
-            let (tensor, state) = self.optimizer.step(
+            if let Some(decay) = decay {
+                clipped_grad = clipped_grad.sub(decay);
+            }
+
+            if let Some(history) = self.grad_history.as_mut() {
+                history.push(id, clipped_grad.clone());
+            }
+
+            let tensor = tensor.inner();
+            let warmed_decay = self.warmed_decay(id);
+            if let Some(decay) = &warmed_decay {
+                clipped_grad = decay.transform(clipped_grad, tensor.clone());
+            } else if let Some(overrides) = self.weight_decay_overrides {
+                clipped_grad =
+                    overrides.transform(id, self.weight_decay, clipped_grad, tensor.clone());
+            }
+            if let Some(decay_to_init) = self.decay_to_init(id, &tensor) {
+                clipped_grad = clipped_grad.add(decay_to_init);
+            }
+
+            let (mut tensor, state) = self.optimizer.step(
                 self.lr,
-                tensor.inner(),
+                tensor,
                 clipped_grad,
                 record.map(|record| O::to_device(record.into_state(), &device)),
             );
+            if let Some(decay) = &warmed_decay {
+                tensor = decay.proximal(self.lr, tensor);
+            } else if let Some(overrides) = self.weight_decay_overrides {
+                tensor = overrides.proximal(id, self.weight_decay, self.lr, tensor);
+            }
 
             if let Some(state) = state {
                 self.records.insert(
@@ -143,6 +1118,8 @@ where
                 );
             }
 
+            self.updated.push(id.clone());
+
             let mut tensor = Tensor::from_inner(tensor);
             if is_require_grad {
                 tensor = tensor.require_grad();
@@ -153,3 +1130,697 @@ where
         tensor
     }
 }
+
+impl<'a, M, B, O> SimpleOptimizerMapper<'a, M, B, O>
+where
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+    O: SimpleOptimizer<B::InnerBackend>,
+{
+    /// The decay of a parameter during a [warmup](WeightDecayWarmup), applied by the adaptor
+    /// instead of the simple optimizer, with the penalty of the override if any. `None` without
+    /// a warmup.
+    fn warmed_decay(&self, id: &ParamId) -> Option<WeightDecay<B::InnerBackend>> {
+        let mut config = self.weight_decay_warmup?.config();
+        if let Some(penalty) = self.weight_decay_overrides.and_then(|o| o.penalty(id)) {
+            // Like without a warmup, the override applies whatever the rank of the tensor.
+            config.penalty = penalty;
+            config.decay_min_rank = 0;
+        }
+
+        Some(WeightDecay::new(&config))
+    }
+
+    /// The part of the decay [toward the initialization](WeightDecayKind::L2ToInit) of a
+    /// parameter that isn't added by the L2 penalty, `-penalty * init`, with the penalty of the
+    /// override or the warmup if any. `None` when the parameter has no initial value.
+    fn decay_to_init<const D: usize>(
+        &self,
+        id: &ParamId,
+        tensor: &Tensor<B::InnerBackend, D>,
+    ) -> Option<Tensor<B::InnerBackend, D>> {
+        let config = self
+            .weight_decay
+            .filter(|config| config.kind == WeightDecayKind::L2ToInit)?;
+        let init = self.weight_decay_init.get(id)?;
+        let penalty = match self.weight_decay_overrides.and_then(|o| o.penalty(id)) {
+            Some(penalty) => penalty,
+            // Tensors below the minimum rank aren't decayed by the optimizer.
+            None if D < config.decay_min_rank => return None,
+            None => self
+                .weight_decay_warmup
+                .map_or(config.penalty, WeightDecayWarmup::penalty),
+        };
+
+        Some(init.clone().reshape(tensor.shape()).mul_scalar(-penalty))
+    }
+}
+
+/// The gradient clipping of a parameter, with the name of its group, `None` when the parameter is
+/// clipped by the default gradient clipping.
+fn param_clipping<'a>(
+    id: &ParamId,
+    grad_clipping: Option<&'a GradientClipping>,
+    grad_clipping_groups: Option<&'a GradientClippingGroups>,
+    grad_clipping_params: Option<&HashSet<ParamId>>,
+) -> Option<(Option<&'a str>, &'a GradientClipping)> {
+    if !grad_clipping_params.map_or(true, |params| params.contains(id)) {
+        return None;
+    }
+
+    let group =
+        grad_clipping_groups.and_then(|groups| Some((groups.group(id)?, groups.clipping(id)?)));
+    match group {
+        Some((group, clipping)) => Some((Some(group), clipping)),
+        None => grad_clipping.map(|clipping| (None, clipping)),
+    }
+}
+
+/// Accumulate the global RMS of the gradients of each group of parameters clipped by their
+/// [global RMS](GradientClipping::GlobalRms), with the target of the group.
+#[derive(new)]
+struct GlobalRmsVisitor<'a, B: AutodiffBackend> {
+    grads: &'a GradientsParams,
+    grad_clipping: Option<&'a GradientClipping>,
+    grad_clipping_groups: Option<&'a GradientClippingGroups>,
+    grad_clipping_params: Option<&'a HashSet<ParamId>>,
+    accumulators: &'a mut HashMap<Option<String>, (f32, GlobalNormAccumulator<B::InnerBackend>)>,
+    visited: HashSet<ParamId>,
+}
+
+impl<'a, B: AutodiffBackend> ModuleVisitor<B> for GlobalRmsVisitor<'a, B> {
+    fn visit<const D: usize>(&mut self, id: &ParamId, _tensor: &Tensor<B, D>) {
+        let clipping = param_clipping(
+            id,
+            self.grad_clipping,
+            self.grad_clipping_groups,
+            self.grad_clipping_params,
+        );
+        let Some((group, GradientClipping::GlobalRms(target))) = clipping else {
+            return;
+        };
+        if !self.visited.insert(id.clone()) {
+            return;
+        }
+        let Some(grad) = self.grads.get::<B::InnerBackend, D>(id) else {
+            return;
+        };
+
+        self.accumulators
+            .entry(group.map(String::from))
+            .or_insert_with(|| {
+                let accumulator =
+                    GlobalNormAccumulator::new(&grad.device()).with_kind(NormKind::RMS);
+                (*target, accumulator)
+            })
+            .1
+            .add(grad);
+    }
+}
+
+/// Collect the flattened values of the parameters of a module.
+#[derive(new)]
+struct ParamsInit<'a, B: AutodiffBackend> {
+    values: &'a mut HashMap<ParamId, Tensor<B::InnerBackend, 1>>,
+}
+
+impl<'a, B: AutodiffBackend> ModuleVisitor<B> for ParamsInit<'a, B> {
+    fn visit<const D: usize>(&mut self, id: &ParamId, tensor: &Tensor<B, D>) {
+        let num_elements = tensor.shape().num_elements();
+        self.values
+            .insert(id.clone(), tensor.clone().inner().reshape([num_elements]));
+    }
+}
+
+/// Reinitialize some parameters of a module.
+#[derive(new)]
+struct ParamsReinit<'a, B: AutodiffBackend, R> {
+    params: &'a HashSet<ParamId>,
+    reinit: &'a mut R,
+    phantom: PhantomData<B>,
+}
+
+impl<'a, B, R> ModuleMapper<B> for ParamsReinit<'a, B, R>
+where
+    B: AutodiffBackend,
+    R: ModuleMapper<B::InnerBackend>,
+{
+    fn map<const D: usize>(&mut self, id: &ParamId, tensor: Tensor<B, D>) -> Tensor<B, D> {
+        if !self.params.contains(id) {
+            return tensor;
+        }
+
+        let shape = tensor.shape();
+        let is_require_grad = tensor.is_require_grad();
+        let tensor = self.reinit.map(id, tensor.inner());
+        assert_eq!(
+            tensor.shape(),
+            shape,
+            "The reinitialization must keep the shape of the parameter."
+        );
+        let tensor = Tensor::from_inner(tensor);
+
+        match is_require_grad {
+            true => tensor.require_grad(),
+            false => tensor,
+        }
+    }
+}
+
+/// Make sure the gradient has the shape of its parameter, transposing it back when it arrives
+/// transposed and `transpose` is enabled.
+fn match_layout<B: Backend, const D: usize>(
+    id: &ParamId,
+    grad: Tensor<B, D>,
+    shape: &Shape<D>,
+    transpose: bool,
+) -> Tensor<B, D> {
+    let shape_grad = grad.shape();
+    if &shape_grad == shape {
+        return grad;
+    }
+
+    let is_transposed = D >= 2 && {
+        let mut dims = shape_grad.dims;
+        dims.swap(D - 2, D - 1);
+        dims == shape.dims
+    };
+
+    match (is_transposed, transpose) {
+        (true, true) => grad.transpose(),
+        (true, false) => panic!(
+            "The gradient of the parameter {id} has the shape {:?}, which is the transpose of the \
+             parameter shape {:?}. Use `with_grad_transpose(true)` to transpose it back.",
+            shape_grad.dims, shape.dims
+        ),
+        (false, _) => panic!(
+            "The gradient of the parameter {id} has the shape {:?}, which doesn't match the \
+             parameter shape {:?}.",
+            shape_grad.dims, shape.dims
+        ),
+    }
+}
+
+#[derive(new)]
+struct StateTransferVisitor<'a, O, B>
+where
+    O: SimpleOptimizer<B::InnerBackend>,
+    B: AutodiffBackend,
+{
+    records: &'a HashMap<ParamId, AdaptorRecord<O, B::InnerBackend>>,
+    records_target: &'a mut HashMap<ParamId, AdaptorRecord<O, B::InnerBackend>>,
+    sources: HashMap<ParamId, ParamId>,
+    adapt_shapes: bool,
+}
+
+impl<'a, O, B> ModuleVisitor<B> for StateTransferVisitor<'a, O, B>
+where
+    O: SimpleOptimizer<B::InnerBackend>,
+    B: AutodiffBackend,
+{
+    fn visit<const D: usize>(&mut self, id: &ParamId, tensor: &Tensor<B, D>) {
+        let source = self.sources.get(id).unwrap_or(id);
+        let record = match self.records.get(source) {
+            Some(record) => record.clone(),
+            None => return,
+        };
+
+        let dims = tensor.dims();
+        let adapt_shapes = self.adapt_shapes;
+        let state = O::state_map_tensors(record.into_state::<D>(), |state| {
+            let dims_state = state.dims();
+            if dims_state == dims {
+                return state;
+            }
+            assert!(
+                adapt_shapes,
+                "The state shape {dims_state:?} doesn't match the parameter shape {dims:?}."
+            );
+
+            let ranges = core::array::from_fn::<_, D, _>(|i| 0..usize::min(dims[i], dims_state[i]));
+            Tensor::zeros_device(dims, &state.device())
+                .slice_assign(ranges.clone(), state.slice(ranges))
+        });
+
+        self.records_target
+            .insert(id.clone(), AdaptorRecord::from_state(state));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        lr_scheduler::lambda::LambdaLrScheduler,
+        module::Module,
+        nn::{Linear, LinearConfig},
+        optim::{momentum::MomentumConfig, Sgd, SgdConfig},
+        TestAutodiffBackend, TestBackend,
+    };
+
+    type Adaptor =
+        OptimizerAdaptor<Sgd<TestBackend>, Linear<TestAutodiffBackend>, TestAutodiffBackend>;
+
+    const LEARNING_RATE: LearningRate = 0.02;
+
+    #[test]
+    fn test_grad_scale_should_turn_summed_loss_into_averaged_loss() {
+        let batch_size = 4;
+        let layer_summed = layer();
+        let layer_averaged = layer_summed.clone();
+        let x = Tensor::<TestAutodiffBackend, 2>::random([batch_size, 20], Distribution::Default);
+
+        let mut optim_summed = SgdConfig::new()
+            .with_grad_scale(grad_scale_from_batch_size(batch_size))
+            .init();
+        let mut optim_averaged = SgdConfig::new().init();
+
+        let grads = layer_summed.forward(x.clone()).sum().backward();
+        let grads = GradientsParams::from_grads(grads, &layer_summed);
+        let layer_summed = optim_summed.step(LEARNING_RATE, layer_summed, grads);
+
+        let grads = layer_averaged.forward(x).sum_dim(1).mean().backward();
+        let grads = GradientsParams::from_grads(grads, &layer_averaged);
+        let layer_averaged = optim_averaged.step(LEARNING_RATE, layer_averaged, grads);
+
+        let (record_summed, record_averaged) =
+            (layer_summed.into_record(), layer_averaged.into_record());
+        record_summed
+            .weight
+            .to_data()
+            .assert_approx_eq(&record_averaged.weight.to_data(), 5);
+        record_summed
+            .bias
+            .unwrap()
+            .to_data()
+            .assert_approx_eq(&record_averaged.bias.unwrap().to_data(), 5);
+    }
+
+    #[test]
+    fn test_grad_scale_scheduler_should_multiply_the_gradients() {
+        let mut layer: Linear<TestAutodiffBackend> =
+            LinearConfig::new(2, 1).with_bias(false).init();
+        let mut optim = SgdConfig::new()
+            .with_grad_scale(0.5)
+            .init()
+            .with_grad_scale_scheduler(LambdaLrScheduler::new(|step| 1.0 + step as f64));
+
+        // The gradient of the weight is the input, so the update is the input scaled by
+        // `0.5 * (1 + step)`.
+        for step in 0..4 {
+            let weight_before = layer.weight.val().inner();
+            let x = Tensor::<TestAutodiffBackend, 2>::from_floats([[1.0, 2.0]]);
+            let grads = GradientsParams::from_grads(layer.forward(x).backward(), &layer);
+            layer = optim.step(1.0, layer, grads);
+
+            let multiplier = 1.0 + step as f32;
+            assert_eq!(optim.grad_scale_multiplier(), multiplier);
+            weight_before
+                .sub(layer.weight.val().inner())
+                .reshape([2])
+                .into_data()
+                .assert_approx_eq(&Data::from([0.5 * multiplier, multiplier]), 5);
+        }
+    }
+
+    #[test]
+    fn test_last_updated_should_skip_frozen_params() {
+        let mut layer = layer();
+        layer.bias = layer
+            .bias
+            .map(|bias| bias.map(|tensor| tensor.set_require_grad(false)));
+        let (weight_id, bias_id) = (
+            layer.weight.id.clone(),
+            layer.bias.as_ref().unwrap().id.clone(),
+        );
+        let mut optim = optim();
+        assert!(optim.last_updated().is_empty());
+
+        let grads = layer.forward(random_tensor()).backward();
+        let grads = GradientsParams::from_grads(grads, &layer);
+        let _layer = optim.step(LEARNING_RATE, layer, grads);
+
+        let updated = optim.last_updated();
+        assert!(updated.contains(&weight_id));
+        assert!(!updated.contains(&bias_id));
+    }
+
+    #[test]
+    fn test_transposed_grads_should_be_transposed_back() {
+        // The weight isn't square, so its transpose has a different shape.
+        let layer: Linear<TestAutodiffBackend> = LinearConfig::new(20, 10).init();
+        let weight = layer.weight.val().inner();
+        let grad = Tensor::<TestBackend, 2>::random(weight.shape(), Distribution::Default);
+        let step = |grad: Tensor<TestBackend, 2>, transpose: bool| {
+            let mut grads = GradientsParams::new();
+            grads.register(layer.weight.id.clone(), grad);
+            let mut optim = SgdConfig::new().init().with_grad_transpose(transpose);
+            optim.step(LEARNING_RATE, layer.clone(), grads)
+        };
+
+        let layer_expected = step(grad.clone(), false);
+        let layer_transposed = step(grad.transpose(), true);
+
+        layer_transposed
+            .weight
+            .to_data()
+            .assert_approx_eq(&layer_expected.weight.to_data(), 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "which is the transpose of the parameter shape")]
+    fn test_transposed_grads_should_panic_with_diagnostic() {
+        // The weight isn't square, so its transpose has a different shape.
+        let layer: Linear<TestAutodiffBackend> = LinearConfig::new(20, 10).init();
+        let weight = layer.weight.val().inner();
+        let mut grads = GradientsParams::new();
+        grads.register(layer.weight.id.clone(), weight.transpose());
+
+        let mut optim = SgdConfig::new().init();
+        let _layer = optim.step(LEARNING_RATE, layer, grads);
+    }
+
+    #[test]
+    fn test_grad_dropout_should_zero_and_rescale_elements() {
+        TestBackend::seed(0);
+        let prob = 0.25;
+        let layer: Linear<TestAutodiffBackend> =
+            LinearConfig::new(100, 100).with_bias(false).init();
+        let weight = layer.weight.val().inner();
+        let mut grads = GradientsParams::new();
+        grads.register(layer.weight.id.clone(), weight.ones_like());
+        let mut optim = SgdConfig::new().init().with_grad_dropout(prob);
+
+        let layer = optim.step(1.0, layer, grads);
+
+        // With a learning rate of 1, the update is the gradient after the dropout.
+        let update = weight.sub(layer.weight.val().inner()).into_data();
+        let num_zeroed = update.value.iter().filter(|value| **value == 0.0).count();
+        let ratio = num_zeroed as f64 / update.value.len() as f64;
+        assert!((ratio - prob).abs() < 0.02, "Zeroed ratio {ratio}");
+        let scale = 1.0 / (1.0 - prob) as f32;
+        for value in update.value.iter().filter(|value| **value != 0.0) {
+            assert!((value - scale).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_seeded_grad_dropout_should_be_reproducible_per_param() {
+        let layer: Linear<TestAutodiffBackend> = LinearConfig::new(100, 100).init();
+        let updates = |layer: Linear<TestAutodiffBackend>| {
+            let weight = layer.weight.val().inner();
+            let bias = layer.bias.as_ref().unwrap().val().inner();
+            let mut grads = GradientsParams::new();
+            grads.register(layer.weight.id.clone(), weight.ones_like());
+            grads.register(layer.bias.as_ref().unwrap().id.clone(), bias.ones_like());
+            let mut optim = SgdConfig::new().init().with_grad_dropout(0.5).with_seed(42);
+
+            let layer = optim.step(1.0, layer, grads);
+            let update_weight = weight.sub(layer.weight.val().inner()).into_data();
+            let update_bias = bias.sub(layer.bias.unwrap().val().inner()).into_data();
+            (update_weight.value, update_bias.value)
+        };
+
+        let (weight_1, bias_1) = updates(layer.clone());
+        let (weight_2, bias_2) = updates(layer);
+
+        // The masks depend on the seed and the parameter, not on the optimizer.
+        assert_eq!(weight_1, weight_2);
+        assert_eq!(bias_1, bias_2);
+        assert_ne!(weight_1[..100], bias_1[..]);
+    }
+
+    #[test]
+    fn test_seeded_grad_dropout_should_resume_from_the_record() {
+        let layer: Linear<TestAutodiffBackend> = LinearConfig::new(100, 100).init();
+        let ones = |layer: &Linear<TestAutodiffBackend>| {
+            let mut grads = GradientsParams::new();
+            grads.register(
+                layer.weight.id.clone(),
+                layer.weight.val().inner().ones_like(),
+            );
+            grads
+        };
+        let optim = || SgdConfig::new().init().with_grad_dropout(0.5).with_seed(42);
+        let mut optim_uninterrupted = optim();
+        let grads = ones(&layer);
+        let layer = optim_uninterrupted.step(1.0, layer, grads);
+
+        let record = optim_uninterrupted.to_record();
+        assert_eq!(record.num_steps, 1);
+        let mut optim_resumed = optim().load_record(record);
+        let weight = |optim: &mut OptimizerAdaptor<_, _, _>| {
+            let grads = ones(&layer);
+            optim
+                .step(1.0, layer.clone(), grads)
+                .weight
+                .val()
+                .into_data()
+        };
+
+        // The resumed optimizer samples the masks of the second step, not the first one again.
+        let weight_resumed = weight(&mut optim_resumed);
+        assert_eq!(weight_resumed, weight(&mut optim_uninterrupted));
+        assert_ne!(weight_resumed, weight(&mut optim()));
+    }
+
+    #[test]
+    fn test_reset_clip_stats_should_restart_the_statistics() {
+        let mut layer: Linear<TestAutodiffBackend> =
+            LinearConfig::new(2, 1).with_bias(false).init();
+        let mut optim = SgdConfig::new()
+            .init()
+            .with_grad_clipping(GradientClipping::Norm(1.0));
+        let grads = |layer: &Linear<TestAutodiffBackend>, x: [[f32; 2]; 1]| {
+            let grads = layer.forward(Tensor::from_floats(x)).backward();
+            GradientsParams::from_grads(grads, layer)
+        };
+
+        // The gradients of the weight are the inputs, with a norm of 50.
+        for _ in 0..2 {
+            let grads = grads(&layer, [[30.0, 40.0]]);
+            layer = optim.step(LEARNING_RATE, layer, grads);
+        }
+        let stats = optim.clip_stats();
+        assert_eq!((stats.num_grads(), stats.num_clipped()), (2, 2));
+
+        optim.reset_clip_stats();
+        let stats = optim.clip_stats();
+        assert_eq!((stats.num_grads(), stats.num_clipped()), (0, 0));
+
+        // A gradient with a norm of 0.5 isn't clipped.
+        let grads = grads(&layer, [[0.3, 0.4]]);
+        let _layer = optim.step(LEARNING_RATE, layer, grads);
+        let stats = optim.clip_stats();
+        assert_eq!((stats.num_grads(), stats.num_clipped()), (1, 0));
+        assert!((stats.mean_norm().unwrap() - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_global_rms_clipping_should_scale_the_grads_to_the_target() {
+        let layer: Linear<TestAutodiffBackend> = LinearConfig::new(2, 1).with_bias(false).init();
+        let mut optim = SgdConfig::new()
+            .init()
+            .with_grad_clipping(GradientClipping::GlobalRms(0.5));
+        let weight_before = layer.weight.val();
+
+        // The gradients of the weight are the inputs, with a global RMS of 2.
+        let grads = layer.forward(Tensor::from_floats([[2.0, -2.0]])).backward();
+        let grads = GradientsParams::from_grads(grads, &layer);
+        let rms = grads.global_rms::<TestAutodiffBackend, _>(&layer).unwrap();
+        rms.into_data().assert_approx_eq(&Data::from([2.0]), 5);
+        let layer = optim.step(1.0, layer, grads);
+
+        // The gradients are scaled by 0.25, so the global RMS equals the target.
+        (weight_before.inner() - layer.weight.val().inner())
+            .into_data()
+            .assert_approx_eq(&Data::from([[0.5], [-0.5]]), 5);
+        let stats = optim.clip_stats();
+        assert_eq!((stats.num_grads(), stats.num_clipped()), (1, 1));
+    }
+
+    #[test]
+    fn test_global_rms_clipping_should_respect_the_clipped_params_and_groups() {
+        let layer: Linear<TestAutodiffBackend> = LinearConfig::new(2, 1).init();
+        let weight_id = layer.weight.id.clone();
+        let bias_id = layer.bias.as_ref().unwrap().id.clone();
+        // The gradients of the weight are the inputs, with an RMS of 2, and the gradient of the
+        // bias is 1, so the global RMS of all the gradients is the square root of 3.
+        let step = |mut optim: Adaptor| {
+            let grads = layer.forward(Tensor::from_floats([[2.0, -2.0]])).backward();
+            let grads = GradientsParams::from_grads(grads, &layer);
+            let layer_updated = optim.step(1.0, layer.clone(), grads);
+            let update = |before: Tensor<TestAutodiffBackend, 1>, after| {
+                (before.inner() - after).into_data()
+            };
+            let weight = update(
+                layer.weight.val().reshape([2]),
+                layer_updated.weight.val().inner().reshape([2]),
+            );
+            let bias = update(
+                layer.bias.as_ref().unwrap().val(),
+                layer_updated.bias.as_ref().unwrap().val().inner(),
+            );
+            (weight, bias, optim.clip_stats().num_grads())
+        };
+
+        // Only the weight is clipped, by its own RMS.
+        let optim = SgdConfig::new()
+            .init()
+            .with_grad_clipping(GradientClipping::GlobalRms(0.5))
+            .with_grad_clipping_params([weight_id.clone()]);
+        let (weight, bias, num_grads) = step(optim);
+        weight.assert_approx_eq(&Data::from([0.5, -0.5]), 5);
+        bias.assert_approx_eq(&Data::from([1.0]), 5);
+        assert_eq!(num_grads, 1);
+
+        // The bias is clipped by the RMS of its group, with the target of its group.
+        let groups = GradientClippingGroups::new()
+            .with_group("bias", GradientClipping::GlobalRms(0.25))
+            .with_params("bias", [bias_id]);
+        let optim = SgdConfig::new()
+            .init()
+            .with_grad_clipping(GradientClipping::GlobalRms(0.5))
+            .with_grad_clipping_groups(groups);
+        let (weight, bias, num_grads) = step(optim);
+        weight.assert_approx_eq(&Data::from([0.5, -0.5]), 5);
+        bias.assert_approx_eq(&Data::from([0.25]), 5);
+        assert_eq!(num_grads, 2);
+    }
+
+    #[test]
+    fn test_global_rms_clipping_should_apply_the_nan_policy() {
+        let layer: Linear<TestAutodiffBackend> = LinearConfig::new(2, 1).with_bias(false).init();
+        let weight_before = layer.weight.val().inner().into_data();
+        let mut grads = GradientsParams::new();
+        grads.register::<TestBackend, 2>(
+            layer.weight.id.clone(),
+            Tensor::from_floats([[f32::NAN], [1.0]]),
+        );
+        let mut optim = SgdConfig::new()
+            .init()
+            .with_grad_clipping(GradientClipping::GlobalRms(0.5))
+            .with_grad_clipping_nan_policy(NanPolicy::Zero);
+
+        let layer = optim.step(1.0, layer, grads);
+
+        layer
+            .weight
+            .val()
+            .inner()
+            .into_data()
+            .assert_approx_eq(&weight_before, 5);
+        assert_eq!(optim.clip_stats().num_grads(), 0);
+    }
+
+    #[test]
+    fn test_global_rms_clipping_should_skip_a_nan_rms() {
+        let layer: Linear<TestAutodiffBackend> = LinearConfig::new(2, 1).with_bias(false).init();
+        let weight_before = layer.weight.val().inner().into_data();
+        let mut grads = GradientsParams::new();
+        grads.register::<TestBackend, 2>(
+            layer.weight.id.clone(),
+            Tensor::from_floats([[f32::NAN], [1.0]]),
+        );
+        let mut optim = SgdConfig::new()
+            .init()
+            .with_grad_clipping(GradientClipping::GlobalRms(0.5))
+            .with_grad_clipping_nan_policy(NanPolicy::Skip);
+
+        let layer = optim.step(1.0, layer, grads);
+
+        // The finite gradient isn't clipped.
+        let weight = layer.weight.val().inner().into_data();
+        assert!(weight.value[0].is_nan());
+        assert_eq!(weight.value[1], weight_before.value[1] - 1.0);
+        assert_eq!(optim.clip_stats().num_grads(), 0);
+    }
+
+    struct ZerosReinit;
+
+    impl<B: Backend> ModuleMapper<B> for ZerosReinit {
+        fn map<const D: usize>(&mut self, _id: &ParamId, tensor: Tensor<B, D>) -> Tensor<B, D> {
+            assert_eq!(tensor.shape().dims, [20, 20]);
+            tensor.zeros_like()
+        }
+    }
+
+    #[test]
+    fn test_grad_clipping_window_should_only_clip_inside_the_window() {
+        let optim = || -> Adaptor {
+            SgdConfig::new()
+                .init()
+                .with_grad_clipping(GradientClipping::Value(0.5))
+                .with_grad_clipping_window(1, 3)
+        };
+        let mut layer: Linear<TestAutodiffBackend> =
+            LinearConfig::new(2, 1).with_bias(false).init();
+        let mut optim_uninterrupted = optim();
+
+        let mut deltas = Vec::new();
+        for _ in 0..4 {
+            let mut grads = GradientsParams::new();
+            grads.register::<TestBackend, 2>(
+                layer.weight.id.clone(),
+                Tensor::from_floats([[10.0], [-10.0]]),
+            );
+            let before = layer.weight.val().inner();
+            layer = optim_uninterrupted.step(1.0, layer, grads);
+            deltas.push(before - layer.weight.val().inner());
+        }
+
+        for (delta, expected) in deltas.into_iter().zip([10.0, 0.5, 0.5, 10.0]) {
+            delta
+                .into_data()
+                .assert_approx_eq(&Data::from([[expected], [-expected]]), 5);
+        }
+
+        // The step counter is restored with the record, so the window isn't restarted.
+        let optim_resumed = optim().load_record(optim_uninterrupted.to_record());
+        assert_eq!(optim_resumed.num_steps(), 4);
+    }
+
+    #[test]
+    fn test_reset_should_clear_state_and_reinit_only_given_params() {
+        let layer = layer();
+        let mut optim = optim();
+        let grads = layer.forward(random_tensor()).backward();
+        let grads = GradientsParams::from_grads(grads, &layer);
+        let layer = optim.step(LEARNING_RATE, layer, grads);
+        let bias = layer.bias.as_ref().unwrap();
+        let (weight_id, bias_id, bias_before) = (
+            layer.weight.id.clone(),
+            bias.id.clone(),
+            bias.val().into_data(),
+        );
+        assert_eq!(optim.to_record().len(), 2);
+
+        let layer = optim.reset(layer, [weight_id.clone()], &mut ZerosReinit);
+
+        let record = optim.to_record().params;
+        assert_eq!(record.len(), 1);
+        assert!(record.contains_key(&bias_id));
+        assert!(!record.contains_key(&weight_id));
+        assert!(layer.weight.is_require_grad());
+        layer
+            .weight
+            .to_data()
+            .assert_approx_eq(&Data::zeros([20, 20]), 6);
+        assert_eq!(layer.bias.unwrap().val().into_data(), bias_before);
+    }
+
+    fn random_tensor() -> Tensor<TestAutodiffBackend, 2> {
+        Tensor::<TestAutodiffBackend, 2>::random(Shape::new([2, 20]), Distribution::Default)
+    }
+
+    fn layer() -> Linear<TestAutodiffBackend> {
+        LinearConfig::new(20, 20).with_bias(true).init()
+    }
+
+    fn optim() -> Adaptor {
+        SgdConfig::new()
+            .with_weight_decay(Some(WeightDecayConfig::new(0.05)))
+            .with_momentum(Some(MomentumConfig::new()))
+            .init()
+    }
+}
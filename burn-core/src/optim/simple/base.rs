@@ -25,9 +25,159 @@ where
         state: Option<Self::State<D>>,
     ) -> (Tensor<B, D>, Option<Self::State<D>>);
 
+    /// Perform the [step](SimpleOptimizer::step) in place, updating the parameter buffer.
+    ///
+    /// The parameter is moved into the step instead of being cloned, so backends reusing the
+    /// buffers of uniquely owned tensors update it without allocating a new one, which reduces the
+    /// peak memory. Other backends fall back to allocating the updated tensor, as the functional
+    /// step does.
+    fn step_inplace<const D: usize>(
+        &self,
+        lr: LearningRate,
+        tensor: &mut Tensor<B, D>,
+        grad: Tensor<B, D>,
+        state: Option<Self::State<D>>,
+    ) -> Option<Self::State<D>> {
+        // The placeholder is empty, so swapping the parameter out doesn't allocate a buffer.
+        let placeholder = Tensor::empty_device([0; D], &tensor.device());
+        let (updated, state) = self.step(lr, core::mem::replace(tensor, placeholder), grad, state);
+        *tensor = updated;
+
+        state
+    }
+
     /// Change the device of the state.
     ///
     /// This function will be called accordindly to have the state on the same device as the
     /// gradient and the tensor when the [step](SimpleOptimizer::step) function is called.
     fn to_device<const D: usize>(state: Self::State<D>, device: &B::Device) -> Self::State<D>;
+
+    /// Apply the given function to each tensor of the state.
+    ///
+    /// This is used to adapt the state to a parameter with a different shape, see
+    /// [clone_state_to](crate::optim::Optimizer::clone_state_to).
+    ///
+    /// Optimizers that don't implement it keep the state as is, so their state can't be adapted
+    /// to another shape nor [exported](crate::optim::Optimizer::export_state_tensors).
+    fn state_map_tensors<const D: usize, F>(state: Self::State<D>, _func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        state
+    }
+
+    /// The number of elements of all the tensors in the state.
+    ///
+    /// This is used to estimate the memory footprint of the optimizer. Optimizers that don't
+    /// implement it count no element.
+    fn state_num_elements<const D: usize>(_state: &Self::State<D>) -> usize {
+        0
+    }
+
+    /// The number of steps performed with the state, when the optimizer keeps track of it.
+    fn state_num_steps<const D: usize>(_state: &Self::State<D>) -> Option<usize> {
+        None
+    }
+
+    /// The effective learning rate of each coordinate of the state, when the optimizer adapts
+    /// the learning rate per coordinate, e.g. `lr / (sqrt(sum) + epsilon)` for AdaGrad.
+    ///
+    /// This is used to diagnose the collapse of the learning rate, see
+    /// [effective_lr_stats](super::adaptor::OptimizerAdaptor::effective_lr_stats).
+    fn state_effective_lr<const D: usize>(
+        &self,
+        _lr: LearningRate,
+        _state: &Self::State<D>,
+    ) -> Option<Tensor<B, D>> {
+        None
+    }
+}
+
+/// Conversion of the state of a [simple optimizer](SimpleOptimizer) into the state of another
+/// one, e.g. to carry the state over when switching optimizers during training, see
+/// [with_state_from](super::adaptor::OptimizerAdaptor::with_state_from).
+pub trait StateConversion<B, O1, O2>
+where
+    B: Backend,
+    O1: SimpleOptimizer<B>,
+    O2: SimpleOptimizer<B>,
+{
+    /// Convert the state of a parameter.
+    fn convert<const D: usize>(&self, state: O1::State<D>) -> O2::State<D>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optim::{momentum::MomentumConfig, SgdConfig};
+    use crate::tensor::{Data, Distribution};
+    use crate::TestBackend;
+
+    const LEARNING_RATE: LearningRate = 0.02;
+
+    /// Adds one to the parameter, checking it receives the parameter itself.
+    struct AddOne {
+        expected: Data<f32, 2>,
+    }
+
+    impl<B: Backend> SimpleOptimizer<B> for AddOne {
+        type State<const D: usize> = ();
+
+        fn step<const D: usize>(
+            &self,
+            _lr: LearningRate,
+            tensor: Tensor<B, D>,
+            _grad: Tensor<B, D>,
+            _state: Option<Self::State<D>>,
+        ) -> (Tensor<B, D>, Option<Self::State<D>>) {
+            assert_eq!(tensor.to_data().convert::<f32>().value, self.expected.value);
+
+            (tensor.add_scalar(1.0), Some(()))
+        }
+
+        fn to_device<const D: usize>(state: Self::State<D>, _device: &B::Device) -> Self::State<D> {
+            state
+        }
+    }
+
+    #[test]
+    fn test_step_inplace_should_match_functional_step() {
+        let optim = SgdConfig::new()
+            .with_momentum(Some(MomentumConfig::new()))
+            .init_simple::<TestBackend>();
+        let mut tensor = Tensor::<TestBackend, 2>::random([4, 5], Distribution::Default);
+        let mut tensor_functional = tensor.clone();
+        let (mut state, mut state_functional) = (None, None);
+
+        for _ in 0..2 {
+            let grad = Tensor::<TestBackend, 2>::random([4, 5], Distribution::Default);
+            (tensor_functional, state_functional) = optim.step(
+                LEARNING_RATE,
+                tensor_functional,
+                grad.clone(),
+                state_functional,
+            );
+            state = optim.step_inplace(LEARNING_RATE, &mut tensor, grad, state);
+        }
+
+        tensor
+            .to_data()
+            .assert_approx_eq(&tensor_functional.to_data(), 6);
+    }
+
+    #[test]
+    fn test_step_inplace_should_move_the_parameter_into_the_step() {
+        let mut tensor = Tensor::<TestBackend, 2>::random([4, 5], Distribution::Default);
+        let expected = tensor.clone().add_scalar(1.0).into_data();
+        let optim = AddOne {
+            expected: tensor.to_data().convert(),
+        };
+
+        let grad = tensor.zeros_like();
+        let state = optim.step_inplace(LEARNING_RATE, &mut tensor, grad, None);
+
+        // The step got the parameter, not the placeholder, and its result replaced it.
+        assert_eq!(state, Some(()));
+        tensor.into_data().assert_approx_eq(&expected, 6);
+    }
 }
@@ -0,0 +1,128 @@
+use super::{record::AdaptorRecord, SimpleOptimizer};
+use crate::grad_clipping::{tensor_norm, NormKind};
+use crate::module::ParamId;
+use burn_tensor::{backend::Backend, ElementConversion};
+use hashbrown::HashMap;
+
+/// Difference between the states of two [optimizer adaptors](super::adaptor::OptimizerAdaptor),
+/// e.g. loaded from the checkpoints of two diverging training runs.
+#[derive(Debug, Clone, Default)]
+pub struct StateDiff {
+    /// Relative difference `||a - b|| / max(||a||, ||b||)` of each state tensor, e.g. the moments
+    /// or accumulators, for the parameters having a state in both optimizers.
+    ///
+    /// The difference is infinite when the shapes of the tensors don't match.
+    pub params: HashMap<ParamId, Vec<f64>>,
+    /// Parameters having a state in only one of the optimizers.
+    pub unmatched: Vec<ParamId>,
+}
+
+impl StateDiff {
+    /// The parameter with the largest relative difference of any of its state tensors.
+    pub fn max(&self) -> Option<(ParamId, f64)> {
+        self.params
+            .iter()
+            .flat_map(|(id, diffs)| diffs.iter().map(move |diff| (id, *diff)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(id, diff)| (id.clone(), diff))
+    }
+}
+
+/// Compute the [difference](StateDiff) between the records of two optimizer adaptors.
+pub fn state_diff<O, B>(
+    record: &HashMap<ParamId, AdaptorRecord<O, B>>,
+    other: &HashMap<ParamId, AdaptorRecord<O, B>>,
+) -> StateDiff
+where
+    O: SimpleOptimizer<B>,
+    B: Backend,
+{
+    let mut diff = StateDiff::default();
+
+    for (id, state) in record.iter() {
+        let Some(state_other) = other.get(id) else {
+            diff.unmatched.push(id.clone());
+            continue;
+        };
+
+        let tensors = state.tensors();
+        let tensors_other = state_other.tensors();
+        if tensors.len() != tensors_other.len() {
+            diff.params.insert(id.clone(), vec![f64::INFINITY]);
+            continue;
+        }
+
+        let diffs = tensors
+            .into_iter()
+            .zip(tensors_other)
+            .map(|(tensor, tensor_other)| {
+                if tensor.shape != tensor_other.shape {
+                    return f64::INFINITY;
+                }
+                let (tensor, tensor_other) = (tensor.tensor, tensor_other.tensor);
+                let tensor_other = tensor_other.to_device(&tensor.device());
+                let norm = |tensor: burn_tensor::Tensor<B, 1>| {
+                    tensor_norm(tensor, &NormKind::L2)
+                        .into_scalar()
+                        .elem::<f64>()
+                };
+                let scale = f64::max(norm(tensor.clone()), norm(tensor_other.clone()));
+
+                match scale > 0.0 {
+                    true => norm(tensor.sub(tensor_other)) / scale,
+                    false => 0.0,
+                }
+            })
+            .collect();
+        diff.params.insert(id.clone(), diffs);
+    }
+
+    diff.unmatched
+        .extend(other.keys().filter(|id| !record.contains_key(*id)).cloned());
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::{Linear, LinearConfig};
+    use crate::optim::adaptor::OptimizerAdaptor;
+    use crate::optim::{AdaGrad, AdaGradConfig, GradientsParams, Optimizer};
+    use crate::tensor::{Distribution, Tensor};
+    use crate::{TestAutodiffBackend, TestBackend};
+
+    type Optim =
+        OptimizerAdaptor<AdaGrad<TestBackend>, Linear<TestAutodiffBackend>, TestAutodiffBackend>;
+
+    #[test]
+    fn test_state_diff_highlights_accumulator_growth() {
+        let linear: Linear<TestAutodiffBackend> = LinearConfig::new(4, 2).init();
+        let x = Tensor::<TestAutodiffBackend, 2>::random([2, 4], Distribution::Default);
+        // The gradients don't depend on the parameters, so the accumulator grows linearly.
+        let train = |num_steps: usize| {
+            let mut optim: Optim = AdaGradConfig::new().init_simple().into();
+            let mut linear = linear.clone();
+            for _ in 0..num_steps {
+                let grads = linear.forward(x.clone()).backward();
+                let grads = GradientsParams::from_grads(grads, &linear);
+                linear = optim.step(0.1, linear, grads);
+            }
+            optim
+        };
+        let optim_1 = train(1);
+        let optim_3 = train(3);
+
+        let diff = optim_1.state_diff(&optim_3);
+        assert!(diff.unmatched.is_empty());
+        assert_eq!(diff.params.len(), 2);
+        for diffs in diff.params.values() {
+            // The accumulator is the only state tensor, and is 3 times larger after 3 steps.
+            assert_eq!(diffs.len(), 1);
+            assert!((diffs[0] - 2.0 / 3.0).abs() < 1e-4);
+        }
+
+        let diff = optim_1.state_diff(&optim_1);
+        assert_eq!(diff.max().unwrap().1, 0.0);
+    }
+}
@@ -0,0 +1,48 @@
+use super::{record::AdaptorRecord, SimpleOptimizer};
+use crate::module::ParamId;
+use crate::LearningRate;
+use burn_tensor::{backend::Backend, ElementConversion};
+use hashbrown::HashMap;
+
+/// Summary statistics of the effective learning rate of the coordinates of a parameter, see
+/// [state_effective_lr](SimpleOptimizer::state_effective_lr).
+///
+/// A maximum far below the nominal learning rate means the adaptation collapsed the learning
+/// rate of the whole parameter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EffectiveLrStats {
+    /// The smallest effective learning rate.
+    pub min: f64,
+    /// The mean effective learning rate.
+    pub mean: f64,
+    /// The largest effective learning rate.
+    pub max: f64,
+}
+
+/// Compute the [effective learning rate statistics](EffectiveLrStats) of each parameter having a
+/// state in the records of an optimizer adaptor.
+///
+/// The parameters are skipped when the optimizer doesn't adapt the learning rate per coordinate.
+pub fn effective_lr_stats<O, B>(
+    optim: &O,
+    records: &HashMap<ParamId, AdaptorRecord<O, B>>,
+    lr: LearningRate,
+) -> HashMap<ParamId, EffectiveLrStats>
+where
+    O: SimpleOptimizer<B>,
+    B: Backend,
+{
+    records
+        .iter()
+        .filter_map(|(id, record)| {
+            let effective_lr = record.effective_lr(optim, lr)?;
+            let stats = EffectiveLrStats {
+                min: effective_lr.clone().min().into_scalar().elem::<f64>(),
+                mean: effective_lr.clone().mean().into_scalar().elem::<f64>(),
+                max: effective_lr.max().into_scalar().elem::<f64>(),
+            };
+
+            Some((id.clone(), stats))
+        })
+        .collect()
+}
@@ -0,0 +1,60 @@
+use super::SimpleOptimizer;
+use crate::LearningRate;
+use burn_tensor::{backend::Backend, Tensor};
+
+/// Perform an optimizer step on a collection of tensors, without a module.
+///
+/// This is a functional entry point for optimization problems where the parameters aren't part
+/// of a [module](crate::module::Module): the gradients are given directly and the states are
+/// returned to the caller, who passes them back on the next step.
+///
+/// # Arguments
+///
+/// * `optim` - The optimizer.
+/// * `lr` - The learning rate.
+/// * `tensors` - The parameters to update.
+/// * `grads` - The gradients, in the same order as the parameters.
+/// * `states` - The states returned by the previous step, or an empty vector on the first step.
+///
+/// # Returns
+///
+/// The updated parameters and their states.
+///
+/// # Panics
+///
+/// Panics if the number of gradients or states doesn't match the number of parameters.
+pub fn step_tensors<B, O, const D: usize>(
+    optim: &O,
+    lr: LearningRate,
+    tensors: Vec<Tensor<B, D>>,
+    grads: Vec<Tensor<B, D>>,
+    states: Vec<Option<O::State<D>>>,
+) -> (Vec<Tensor<B, D>>, Vec<Option<O::State<D>>>)
+where
+    B: Backend,
+    O: SimpleOptimizer<B>,
+{
+    assert_eq!(
+        tensors.len(),
+        grads.len(),
+        "Each parameter must have a gradient."
+    );
+    assert!(
+        states.is_empty() || states.len() == tensors.len(),
+        "Each parameter must have a state, or none of them."
+    );
+
+    let mut states = states.into_iter();
+
+    tensors
+        .into_iter()
+        .zip(grads)
+        .map(|(tensor, grad)| {
+            let state = states
+                .next()
+                .flatten()
+                .map(|state| O::to_device(state, &grad.device()));
+            optim.step(lr, tensor, grad, state)
+        })
+        .unzip()
+}
@@ -0,0 +1,121 @@
+use crate::module::ParamId;
+use burn_tensor::{backend::Backend, Tensor};
+use hashbrown::HashMap;
+use std::collections::VecDeque;
+
+/// Rolling window of the last gradients of each parameter, kept by an
+/// [optimizer adaptor](super::adaptor::OptimizerAdaptor) to analyze the gradient dynamics, see
+/// [with_grad_history](super::adaptor::OptimizerAdaptor::with_grad_history).
+///
+/// The gradients are flattened and recorded as given to the optimizer, after scaling and
+/// clipping. The history isn't part of the optimizer record, so it isn't saved in checkpoints.
+pub struct GradientHistory<B: Backend> {
+    size: usize,
+    grads: HashMap<ParamId, VecDeque<Tensor<B, 1>>>,
+}
+
+impl<B: Backend> GradientHistory<B> {
+    /// Create an empty history keeping the last `size` gradients of each parameter.
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0, "The history must hold at least one gradient.");
+
+        Self {
+            size,
+            grads: HashMap::new(),
+        }
+    }
+
+    /// Record the gradient of a parameter, dropping its oldest gradient when the window is full.
+    pub fn push<const D: usize>(&mut self, id: &ParamId, grad: Tensor<B, D>) {
+        let (size, num_elements) = (self.size, grad.shape().num_elements());
+        let grads = self
+            .grads
+            .entry(id.clone())
+            .or_insert_with(|| VecDeque::with_capacity(size));
+
+        if grads.len() == size {
+            grads.pop_front();
+        }
+        grads.push_back(grad.reshape([num_elements]));
+    }
+
+    /// The recorded gradients of a parameter, flattened, from the oldest to the most recent.
+    pub fn grads(&self, id: &ParamId) -> Vec<Tensor<B, 1>> {
+        self.grads
+            .get(id)
+            .map(|grads| grads.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// The variance of each component of the recorded gradients of a parameter, if any.
+    pub fn variance(&self, id: &ParamId) -> Option<Tensor<B, 1>> {
+        let grads = self.grads(id);
+        if grads.is_empty() {
+            return None;
+        }
+
+        let grads = grads
+            .into_iter()
+            .map(|grad| grad.unsqueeze::<2>())
+            .collect();
+        let (variance, _) = Tensor::cat(grads, 0).var_mean_bias(0);
+        let num_elements = variance.shape().num_elements();
+
+        Some(variance.reshape([num_elements]))
+    }
+
+    /// Move the recorded gradients to the given device.
+    pub fn to_device(mut self, device: &B::Device) -> Self {
+        for grads in self.grads.values_mut() {
+            for grad in grads.iter_mut() {
+                *grad = grad.clone().to_device(device);
+            }
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nn::{Linear, LinearConfig};
+    use crate::optim::adaptor::OptimizerAdaptor;
+    use crate::optim::{GradientsParams, Optimizer, Sgd, SgdConfig};
+    use crate::tensor::{Data, Tensor};
+    use crate::{TestAutodiffBackend, TestBackend};
+
+    #[test]
+    fn test_grad_history_holds_last_gradients() {
+        let mut optim: OptimizerAdaptor<Sgd<TestBackend>, Linear<TestAutodiffBackend>, _> =
+            OptimizerAdaptor::from(SgdConfig::new().init_simple()).with_grad_history(3);
+        let mut linear: Linear<TestAutodiffBackend> = LinearConfig::new(2, 1).init();
+        let weight_id = linear.weight.id.clone();
+
+        // The gradient of the weight is the input, so it's different at each step.
+        for step in 1..=5 {
+            let x = Tensor::<TestAutodiffBackend, 2>::ones([1, 2]).mul_scalar(step);
+            let grads = GradientsParams::from_grads(linear.forward(x).backward(), &linear);
+            linear = optim.step(0.1, linear, grads);
+        }
+
+        let history = optim.grad_history().unwrap();
+        let grads = history.grads(&weight_id);
+        assert_eq!(grads.len(), 3);
+        for (grad, step) in grads.into_iter().zip([3.0, 4.0, 5.0]) {
+            grad.to_data()
+                .assert_approx_eq(&Data::from([step, step]), 5);
+        }
+        history
+            .variance(&weight_id)
+            .unwrap()
+            .to_data()
+            .assert_approx_eq(&Data::from([2.0 / 3.0, 2.0 / 3.0]), 5);
+
+        // The history isn't recorded with the optimizer state.
+        let optim_loaded: OptimizerAdaptor<Sgd<TestBackend>, Linear<TestAutodiffBackend>, _> =
+            OptimizerAdaptor::from(SgdConfig::new().init_simple())
+                .with_grad_history(3)
+                .load_record(optim.to_record());
+        let history = optim_loaded.grad_history().unwrap();
+        assert!(history.grads(&weight_id).is_empty());
+    }
+}
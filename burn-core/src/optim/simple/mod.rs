@@ -1,5 +1,17 @@
 mod base;
+mod diff;
+mod effective_lr;
+mod functional;
+mod history;
+mod seed;
+mod shard;
 pub use base::*;
+pub use diff::*;
+pub use effective_lr::*;
+pub use functional::*;
+pub use history::*;
+pub use seed::*;
+pub use shard::*;
 
 /// Adaptor module for optimizers.
 pub mod adaptor;
@@ -0,0 +1,296 @@
+use crate as burn;
+use crate::{
+    module::ParamId,
+    record::{PrecisionSettings, Record, RecordSummary},
+};
+use burn_tensor::{backend::Backend, Tensor};
+use core::ops::{Deref, DerefMut};
+use core::{fmt, marker::PhantomData};
+use hashbrown::HashMap;
+use serde::{
+    de::{value::MapAccessDeserializer, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// [Optimizer adaptor](crate::optim::adaptor::OptimizerAdaptor) record, the state of each
+/// parameter with the state of the adaptor itself.
+///
+/// The record dereferences to the state of each parameter. Records are versioned for backward
+/// compatibility: the records of version 1, only holding the state of each parameter, are loaded
+/// with the default state of the adaptor.
+#[derive(Clone)]
+pub struct OptimizerAdaptorRecord<R: Record, B: Backend> {
+    /// The state of each parameter.
+    pub params: HashMap<ParamId, R>,
+    /// The number of steps performed, so a resumed training samples the same
+    /// [seeded](crate::optim::adaptor::OptimizerAdaptor::with_seed) random values and keeps the
+    /// same [clipping window](crate::optim::adaptor::OptimizerAdaptor::with_grad_clipping_window)
+    /// as an uninterrupted one.
+    pub num_steps: usize,
+    /// The threshold of the
+    /// [loss adaptive](crate::optim::adaptor::OptimizerAdaptor::with_loss_adaptive_grad_clipping)
+    /// gradient clipping, if any.
+    pub loss_adaptive_threshold: Option<f32>,
+    /// The flattened initial values of the parameters decayed
+    /// [toward their initialization](crate::optim::decay::WeightDecayKind::L2ToInit).
+    pub weight_decay_init: HashMap<ParamId, Tensor<B, 1>>,
+    /// The number of steps of the
+    /// [weight decay warmup](crate::optim::adaptor::OptimizerAdaptor::with_weight_decay_warmup)
+    /// performed.
+    pub weight_decay_warmup_step: usize,
+}
+
+/// State of the [optimizer adaptor](crate::optim::adaptor::OptimizerAdaptor) shared by the
+/// parameters, added to the [record](OptimizerAdaptorRecord) by the version 2.
+#[derive(Record)]
+pub struct OptimizerAdaptorStateRecord<B: Backend> {
+    num_steps: usize,
+    loss_adaptive_threshold: Option<f32>,
+    weight_decay_init: HashMap<ParamId, Tensor<B, 1>>,
+    weight_decay_warmup_step: usize,
+}
+
+/// [Optimizer adaptor record](OptimizerAdaptorRecord) item.
+pub enum OptimizerAdaptorRecordItem<P, A> {
+    /// Version 1, the state of each parameter.
+    V1(P),
+    /// Version 2, the state of each parameter and the state of the adaptor.
+    V2(P, A),
+}
+
+impl<R: Record, B: Backend> From<HashMap<ParamId, R>> for OptimizerAdaptorRecord<R, B> {
+    /// The record with the given state of each parameter and the default state of the adaptor,
+    /// like a record of version 1.
+    fn from(params: HashMap<ParamId, R>) -> Self {
+        Self {
+            params,
+            num_steps: 0,
+            loss_adaptive_threshold: None,
+            weight_decay_init: HashMap::new(),
+            weight_decay_warmup_step: 0,
+        }
+    }
+}
+
+impl<R: Record, B: Backend> Deref for OptimizerAdaptorRecord<R, B> {
+    type Target = HashMap<ParamId, R>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.params
+    }
+}
+
+impl<R: Record, B: Backend> DerefMut for OptimizerAdaptorRecord<R, B> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.params
+    }
+}
+
+impl<R: Record, B: Backend> Record for OptimizerAdaptorRecord<R, B> {
+    type Item<S: PrecisionSettings> = OptimizerAdaptorRecordItem<
+        <HashMap<ParamId, R> as Record>::Item<S>,
+        OptimizerAdaptorStateRecordItem<B, S>,
+    >;
+
+    fn into_item<S: PrecisionSettings>(self) -> Self::Item<S> {
+        let state = OptimizerAdaptorStateRecord {
+            num_steps: self.num_steps,
+            loss_adaptive_threshold: self.loss_adaptive_threshold,
+            weight_decay_init: self.weight_decay_init,
+            weight_decay_warmup_step: self.weight_decay_warmup_step,
+        };
+
+        OptimizerAdaptorRecordItem::V2(self.params.into_item(), state.into_item())
+    }
+
+    fn from_item<S: PrecisionSettings>(item: Self::Item<S>) -> Self {
+        match item {
+            OptimizerAdaptorRecordItem::V1(params) => Self::from(HashMap::from_item(params)),
+            OptimizerAdaptorRecordItem::V2(params, state) => {
+                let state = OptimizerAdaptorStateRecord::<B>::from_item(state);
+
+                Self {
+                    params: HashMap::from_item(params),
+                    num_steps: state.num_steps,
+                    loss_adaptive_threshold: state.loss_adaptive_threshold,
+                    weight_decay_init: state.weight_decay_init,
+                    weight_decay_warmup_step: state.weight_decay_warmup_step,
+                }
+            }
+        }
+    }
+
+    fn summary(&self) -> RecordSummary {
+        self.params.summary()
+    }
+}
+
+impl<P: Serialize, A: Serialize> Serialize for OptimizerAdaptorRecordItem<P, A> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        match self {
+            Self::V1(params) => params.serialize(serializer),
+            Self::V2(params, state) => (params, state).serialize(serializer),
+        }
+    }
+}
+
+impl<'de, P: Deserialize<'de>, A: Deserialize<'de>> Deserialize<'de>
+    for OptimizerAdaptorRecordItem<P, A>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let visitor = ItemVisitor {
+            phantom: PhantomData,
+        };
+
+        // The version 1 is a map and the version 2 a tuple, which the human readable formats
+        // tell apart. The other formats read a tuple, see the visitor.
+        match deserializer.is_human_readable() {
+            true => deserializer.deserialize_any(visitor),
+            false => deserializer.deserialize_tuple(2, visitor),
+        }
+    }
+}
+
+struct ItemVisitor<P, A> {
+    phantom: PhantomData<(P, A)>,
+}
+
+impl<'de, P: Deserialize<'de>, A: Deserialize<'de>> Visitor<'de> for ItemVisitor<P, A> {
+    type Value = OptimizerAdaptorRecordItem<P, A>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("the state of each parameter, with the state of the adaptor")
+    }
+
+    fn visit_seq<Seq: SeqAccess<'de>>(self, mut seq: Seq) -> Result<Self::Value, Seq::Error> {
+        let params = seq
+            .next_element()?
+            .ok_or_else(|| serde::de::Error::invalid_length(0, &self))?;
+
+        // A record of version 1 ends after the state of the parameters, which the formats that
+        // aren't self-describing only report as an error when reading the state of the adaptor.
+        match seq.next_element() {
+            Ok(Some(state)) => Ok(OptimizerAdaptorRecordItem::V2(params, state)),
+            Ok(None) | Err(_) => Ok(OptimizerAdaptorRecordItem::V1(params)),
+        }
+    }
+
+    fn visit_map<Map: MapAccess<'de>>(self, map: Map) -> Result<Self::Value, Map::Error> {
+        P::deserialize(MapAccessDeserializer::new(map)).map(OptimizerAdaptorRecordItem::V1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::{Linear, LinearConfig};
+    use crate::optim::{
+        adaptor::OptimizerAdaptor, momentum::MomentumConfig, record::AdaptorRecord,
+        GradientsParams, Optimizer, Sgd, SgdConfig,
+    };
+    use crate::record::{BinBytesRecorder, FullPrecisionSettings, Recorder};
+    use crate::{TestAutodiffBackend, TestBackend};
+
+    type Adaptor =
+        OptimizerAdaptor<Sgd<TestBackend>, Linear<TestAutodiffBackend>, TestAutodiffBackend>;
+    type Params = HashMap<ParamId, AdaptorRecord<Sgd<TestBackend>, TestBackend>>;
+
+    fn trained() -> Adaptor {
+        let layer: Linear<TestAutodiffBackend> = LinearConfig::new(2, 2).init();
+        let mut optim: Adaptor = SgdConfig::new()
+            .with_momentum(Some(MomentumConfig::new()))
+            .init();
+        let mut grads = GradientsParams::new();
+        grads.register(layer.weight.id.clone(), layer.weight.val().inner());
+        optim.step(0.1, layer, grads);
+
+        optim
+    }
+
+    #[test]
+    fn test_record_of_version_1_loads_with_the_default_adaptor_state() {
+        let recorder = BinBytesRecorder::<FullPrecisionSettings>::default();
+        let params: Params = trained().to_record().params;
+        let bytes = recorder.record(params, ()).unwrap();
+
+        let record: <Adaptor as Optimizer<_, _>>::Record = recorder.load(bytes).unwrap();
+
+        assert_eq!(record.len(), 1);
+        assert_eq!(record.num_steps, 0);
+    }
+
+    #[test]
+    fn test_record_of_version_2_keeps_the_adaptor_state() {
+        let recorder = BinBytesRecorder::<FullPrecisionSettings>::default();
+        let bytes = recorder.record(trained().to_record(), ()).unwrap();
+
+        let record: <Adaptor as Optimizer<_, _>>::Record = recorder.load(bytes).unwrap();
+
+        assert_eq!(record.len(), 1);
+        assert_eq!(record.num_steps, 1);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_resumed_training_keeps_the_schedules_and_the_warmup() {
+        use crate::grad_clipping::{GradientClipping, GradientClippingSchedule};
+        use crate::lr_scheduler::lambda::LambdaLrScheduler;
+        use crate::optim::decay::WeightDecayConfig;
+
+        let optim = || -> Adaptor {
+            SgdConfig::new()
+                .with_weight_decay(Some(WeightDecayConfig::new(0.5).with_warmup_steps(4)))
+                .init()
+                .with_grad_clipping_schedule(GradientClippingSchedule::new(
+                    GradientClipping::Norm(1.0),
+                    LambdaLrScheduler::new(|step| 1.0 + step as f64),
+                ))
+                .with_grad_scale_scheduler(LambdaLrScheduler::new(|step| 1.0 / (1 + step) as f64))
+        };
+        let step = |optim: &mut Adaptor, layer: Linear<TestAutodiffBackend>| {
+            let grad = layer.weight.val().inner().ones_like().mul_scalar(3.0);
+            let mut grads = GradientsParams::new();
+            grads.register(layer.weight.id.clone(), grad);
+            optim.step(0.1, layer, grads)
+        };
+        let layer: Linear<TestAutodiffBackend> = LinearConfig::new(2, 2).init();
+
+        let mut uninterrupted = optim();
+        let mut expected = layer.clone();
+        for _ in 0..3 {
+            expected = step(&mut uninterrupted, expected);
+        }
+
+        let mut interrupted = optim();
+        let mut layer = layer;
+        for _ in 0..2 {
+            layer = step(&mut interrupted, layer);
+        }
+        let recorder = BinBytesRecorder::<FullPrecisionSettings>::default();
+        let bytes = recorder.record(interrupted.to_record(), ()).unwrap();
+        let mut resumed = optim().load_record(recorder.load(bytes).unwrap());
+        let layer = step(&mut resumed, layer);
+
+        layer
+            .weight
+            .val()
+            .into_data()
+            .assert_approx_eq(&expected.weight.val().into_data(), 5);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_record_of_version_1_loads_with_a_self_describing_format() {
+        use crate::record::NamedMpkBytesRecorder;
+
+        let recorder = NamedMpkBytesRecorder::<FullPrecisionSettings>::default();
+        let params: Params = trained().to_record().params;
+        let bytes = recorder.record(params, ()).unwrap();
+        let record: <Adaptor as Optimizer<_, _>>::Record = recorder.load(bytes).unwrap();
+        assert_eq!((record.len(), record.num_steps), (1, 0));
+
+        let bytes = recorder.record(trained().to_record(), ()).unwrap();
+        let record: <Adaptor as Optimizer<_, _>>::Record = recorder.load(bytes).unwrap();
+        assert_eq!((record.len(), record.num_steps), (1, 1));
+    }
+}
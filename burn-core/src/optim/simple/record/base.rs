@@ -1,9 +1,11 @@
 use super::{AdaptorRecordItemV1, AdaptorRecordV1};
 use crate::{
-    optim::SimpleOptimizer,
-    record::{PrecisionSettings, Record},
+    optim::{SimpleOptimizer, StateConversion, StateTensor},
+    record::{PrecisionSettings, Record, RecordSummary},
+    LearningRate,
 };
-use burn_tensor::backend::Backend;
+use alloc::vec::Vec;
+use burn_tensor::{backend::Backend, Tensor};
 use serde::{Deserialize, Serialize};
 
 /// [Optimizer adaptor](crate::optim::simple::adaptor::OptimizerAdaptor) record.
@@ -40,6 +42,12 @@ where
             AdaptorRecordItem::V1(item) => Self::V1(AdaptorRecordV1::from_item(item)),
         }
     }
+
+    fn summary(&self) -> RecordSummary {
+        match self {
+            AdaptorRecord::V1(record) => record.summary(),
+        }
+    }
 }
 
 impl<O, B> Clone for AdaptorRecord<O, B>
@@ -70,6 +78,47 @@ where
         }
     }
 
+    /// Converts the optimizer state into the state of another optimizer.
+    pub fn convert<O2, C>(self, conversion: &C) -> AdaptorRecord<O2, B>
+    where
+        O2: SimpleOptimizer<B>,
+        C: StateConversion<B, O, O2>,
+    {
+        match self {
+            AdaptorRecord::V1(record) => AdaptorRecord::V1(record.convert(conversion)),
+        }
+    }
+
+    /// The number of elements of all the tensors in the optimizer state.
+    pub fn num_elements(&self) -> usize {
+        match self {
+            AdaptorRecord::V1(record) => record.num_elements(),
+        }
+    }
+
+    /// The tensors of the optimizer state, e.g. the moments or accumulators, flattened with their
+    /// shape.
+    pub fn tensors(&self) -> Vec<StateTensor<B>> {
+        match self {
+            AdaptorRecord::V1(record) => record.tensors(),
+        }
+    }
+
+    /// The flattened effective learning rate of each coordinate of the optimizer state, when the
+    /// optimizer adapts it, see [state_effective_lr](SimpleOptimizer::state_effective_lr).
+    pub fn effective_lr(&self, optim: &O, lr: LearningRate) -> Option<Tensor<B, 1>> {
+        match self {
+            AdaptorRecord::V1(record) => record.effective_lr(optim, lr),
+        }
+    }
+
+    /// Moves the optimizer state to the given device.
+    pub fn to_device(self, device: &B::Device) -> Self {
+        match self {
+            AdaptorRecord::V1(record) => Self::V1(record.to_device(device)),
+        }
+    }
+
     /// Converts the optimizer state into the record.
     ///
     /// # Arguments
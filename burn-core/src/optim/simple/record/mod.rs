@@ -1,5 +1,7 @@
+mod adaptor;
 mod base;
 mod v1;
 
+pub use adaptor::*;
 pub use base::*;
 pub use v1::*;
@@ -1,9 +1,12 @@
 use crate::{
-    optim::SimpleOptimizer,
-    record::{PrecisionSettings, Record},
+    optim::{SimpleOptimizer, StateConversion, StateTensor},
+    record::{OptimStatePrecisionSettings, PrecisionSettings, Record, RecordSummary},
+    LearningRate,
 };
-use burn_tensor::backend::Backend;
+use alloc::vec::Vec;
+use burn_tensor::{backend::Backend, Tensor};
 use core::any::Any;
+use core::cell::RefCell;
 use serde::{Deserialize, Serialize};
 
 /// [Optimizer adaptor](crate::optim::simple::adaptor::OptimizerAdaptor) record item.
@@ -49,32 +52,36 @@ impl<O: SimpleOptimizer<B>, B: Backend> Clone for AdaptorRecordV1<O, B> {
 }
 
 /// [Optimizer adaptor](crate::optim::simple::adaptor::OptimizerAdaptor) record item.
+///
+/// The state is recorded with the [optimizer state settings](OptimStatePrecisionSettings), so
+/// only the optimizer state is quantized by the
+/// [quantized settings](crate::record::QuantizedPrecisionSettings).
 #[derive(Serialize, Deserialize)]
 #[serde(bound = "")]
 pub enum AdaptorRecordItemV1<O: SimpleOptimizer<B>, B: Backend, S: PrecisionSettings> {
     /// Rank 1.
-    Rank1(<O::State<1> as Record>::Item<S>),
+    Rank1(<O::State<1> as Record>::Item<OptimStatePrecisionSettings<S>>),
 
     /// Rank 2.
-    Rank2(<O::State<2> as Record>::Item<S>),
+    Rank2(<O::State<2> as Record>::Item<OptimStatePrecisionSettings<S>>),
 
     /// Rank 3.
-    Rank3(<O::State<3> as Record>::Item<S>),
+    Rank3(<O::State<3> as Record>::Item<OptimStatePrecisionSettings<S>>),
 
     /// Rank 4.
-    Rank4(<O::State<4> as Record>::Item<S>),
+    Rank4(<O::State<4> as Record>::Item<OptimStatePrecisionSettings<S>>),
 
     /// Rank 5.
-    Rank5(<O::State<5> as Record>::Item<S>),
+    Rank5(<O::State<5> as Record>::Item<OptimStatePrecisionSettings<S>>),
 
     /// Rank 6.
-    Rank6(<O::State<6> as Record>::Item<S>),
+    Rank6(<O::State<6> as Record>::Item<OptimStatePrecisionSettings<S>>),
 
     /// Rank 7.
-    Rank7(<O::State<7> as Record>::Item<S>),
+    Rank7(<O::State<7> as Record>::Item<OptimStatePrecisionSettings<S>>),
 
     /// Rank 8.
-    Rank8(<O::State<8> as Record>::Item<S>),
+    Rank8(<O::State<8> as Record>::Item<OptimStatePrecisionSettings<S>>),
 }
 
 impl<O, B> AdaptorRecordV1<O, B>
@@ -82,6 +89,24 @@ where
     O: SimpleOptimizer<B>,
     B: Backend,
 {
+    /// Converts the state into the state of another optimizer.
+    pub fn convert<O2, C>(self, conversion: &C) -> AdaptorRecordV1<O2, B>
+    where
+        O2: SimpleOptimizer<B>,
+        C: StateConversion<B, O, O2>,
+    {
+        match self {
+            AdaptorRecordV1::Rank1(s) => AdaptorRecordV1::Rank1(conversion.convert::<1>(s)),
+            AdaptorRecordV1::Rank2(s) => AdaptorRecordV1::Rank2(conversion.convert::<2>(s)),
+            AdaptorRecordV1::Rank3(s) => AdaptorRecordV1::Rank3(conversion.convert::<3>(s)),
+            AdaptorRecordV1::Rank4(s) => AdaptorRecordV1::Rank4(conversion.convert::<4>(s)),
+            AdaptorRecordV1::Rank5(s) => AdaptorRecordV1::Rank5(conversion.convert::<5>(s)),
+            AdaptorRecordV1::Rank6(s) => AdaptorRecordV1::Rank6(conversion.convert::<6>(s)),
+            AdaptorRecordV1::Rank7(s) => AdaptorRecordV1::Rank7(conversion.convert::<7>(s)),
+            AdaptorRecordV1::Rank8(s) => AdaptorRecordV1::Rank8(conversion.convert::<8>(s)),
+        }
+    }
+
     /// Convert the record into the state.
     ///
     /// # Returns
@@ -108,6 +133,64 @@ where
         *state
     }
 
+    /// The number of elements of all the tensors in the state.
+    pub fn num_elements(&self) -> usize {
+        match self {
+            AdaptorRecordV1::Rank1(s) => O::state_num_elements(s),
+            AdaptorRecordV1::Rank2(s) => O::state_num_elements(s),
+            AdaptorRecordV1::Rank3(s) => O::state_num_elements(s),
+            AdaptorRecordV1::Rank4(s) => O::state_num_elements(s),
+            AdaptorRecordV1::Rank5(s) => O::state_num_elements(s),
+            AdaptorRecordV1::Rank6(s) => O::state_num_elements(s),
+            AdaptorRecordV1::Rank7(s) => O::state_num_elements(s),
+            AdaptorRecordV1::Rank8(s) => O::state_num_elements(s),
+        }
+    }
+
+    /// The tensors of the state, flattened with their shape, in the order they are mapped by
+    /// [state_map_tensors](SimpleOptimizer::state_map_tensors).
+    pub fn tensors(&self) -> Vec<StateTensor<B>> {
+        match self {
+            AdaptorRecordV1::Rank1(s) => state_tensors::<O, B, 1>(s),
+            AdaptorRecordV1::Rank2(s) => state_tensors::<O, B, 2>(s),
+            AdaptorRecordV1::Rank3(s) => state_tensors::<O, B, 3>(s),
+            AdaptorRecordV1::Rank4(s) => state_tensors::<O, B, 4>(s),
+            AdaptorRecordV1::Rank5(s) => state_tensors::<O, B, 5>(s),
+            AdaptorRecordV1::Rank6(s) => state_tensors::<O, B, 6>(s),
+            AdaptorRecordV1::Rank7(s) => state_tensors::<O, B, 7>(s),
+            AdaptorRecordV1::Rank8(s) => state_tensors::<O, B, 8>(s),
+        }
+    }
+
+    /// The flattened effective learning rate of each coordinate, see
+    /// [state_effective_lr](SimpleOptimizer::state_effective_lr).
+    pub fn effective_lr(&self, optim: &O, lr: LearningRate) -> Option<Tensor<B, 1>> {
+        match self {
+            AdaptorRecordV1::Rank1(s) => optim.state_effective_lr(lr, s).map(flatten),
+            AdaptorRecordV1::Rank2(s) => optim.state_effective_lr(lr, s).map(flatten),
+            AdaptorRecordV1::Rank3(s) => optim.state_effective_lr(lr, s).map(flatten),
+            AdaptorRecordV1::Rank4(s) => optim.state_effective_lr(lr, s).map(flatten),
+            AdaptorRecordV1::Rank5(s) => optim.state_effective_lr(lr, s).map(flatten),
+            AdaptorRecordV1::Rank6(s) => optim.state_effective_lr(lr, s).map(flatten),
+            AdaptorRecordV1::Rank7(s) => optim.state_effective_lr(lr, s).map(flatten),
+            AdaptorRecordV1::Rank8(s) => optim.state_effective_lr(lr, s).map(flatten),
+        }
+    }
+
+    /// Move the state to the given device.
+    pub fn to_device(self, device: &B::Device) -> Self {
+        match self {
+            AdaptorRecordV1::Rank1(s) => AdaptorRecordV1::Rank1(O::to_device(s, device)),
+            AdaptorRecordV1::Rank2(s) => AdaptorRecordV1::Rank2(O::to_device(s, device)),
+            AdaptorRecordV1::Rank3(s) => AdaptorRecordV1::Rank3(O::to_device(s, device)),
+            AdaptorRecordV1::Rank4(s) => AdaptorRecordV1::Rank4(O::to_device(s, device)),
+            AdaptorRecordV1::Rank5(s) => AdaptorRecordV1::Rank5(O::to_device(s, device)),
+            AdaptorRecordV1::Rank6(s) => AdaptorRecordV1::Rank6(O::to_device(s, device)),
+            AdaptorRecordV1::Rank7(s) => AdaptorRecordV1::Rank7(O::to_device(s, device)),
+            AdaptorRecordV1::Rank8(s) => AdaptorRecordV1::Rank8(O::to_device(s, device)),
+        }
+    }
+
     /// Convert the state into the record.
     ///
     /// # Arguments
@@ -134,6 +217,27 @@ where
     }
 }
 
+fn flatten<B: Backend, const D: usize>(tensor: Tensor<B, D>) -> Tensor<B, 1> {
+    let num_elements = tensor.shape().num_elements();
+    tensor.reshape([num_elements])
+}
+
+fn state_tensors<O, B, const D: usize>(state: &O::State<D>) -> Vec<StateTensor<B>>
+where
+    O: SimpleOptimizer<B>,
+    B: Backend,
+{
+    let tensors = RefCell::new(Vec::new());
+    O::state_map_tensors(state.clone(), |tensor| {
+        tensors.borrow_mut().push(StateTensor {
+            shape: tensor.shape().dims.to_vec(),
+            tensor: flatten(tensor.clone()),
+        });
+        tensor
+    });
+    tensors.into_inner()
+}
+
 impl<O, B> Record for AdaptorRecordV1<O, B>
 where
     O: SimpleOptimizer<B>,
@@ -143,14 +247,30 @@ where
 
     fn into_item<S: PrecisionSettings>(self) -> Self::Item<S> {
         match self {
-            AdaptorRecordV1::Rank1(record) => AdaptorRecordItemV1::Rank1(record.into_item()),
-            AdaptorRecordV1::Rank2(record) => AdaptorRecordItemV1::Rank2(record.into_item()),
-            AdaptorRecordV1::Rank3(record) => AdaptorRecordItemV1::Rank3(record.into_item()),
-            AdaptorRecordV1::Rank4(record) => AdaptorRecordItemV1::Rank4(record.into_item()),
-            AdaptorRecordV1::Rank5(record) => AdaptorRecordItemV1::Rank5(record.into_item()),
-            AdaptorRecordV1::Rank6(record) => AdaptorRecordItemV1::Rank6(record.into_item()),
-            AdaptorRecordV1::Rank7(record) => AdaptorRecordItemV1::Rank7(record.into_item()),
-            AdaptorRecordV1::Rank8(record) => AdaptorRecordItemV1::Rank8(record.into_item()),
+            AdaptorRecordV1::Rank1(record) => {
+                AdaptorRecordItemV1::Rank1(record.into_item::<OptimStatePrecisionSettings<S>>())
+            }
+            AdaptorRecordV1::Rank2(record) => {
+                AdaptorRecordItemV1::Rank2(record.into_item::<OptimStatePrecisionSettings<S>>())
+            }
+            AdaptorRecordV1::Rank3(record) => {
+                AdaptorRecordItemV1::Rank3(record.into_item::<OptimStatePrecisionSettings<S>>())
+            }
+            AdaptorRecordV1::Rank4(record) => {
+                AdaptorRecordItemV1::Rank4(record.into_item::<OptimStatePrecisionSettings<S>>())
+            }
+            AdaptorRecordV1::Rank5(record) => {
+                AdaptorRecordItemV1::Rank5(record.into_item::<OptimStatePrecisionSettings<S>>())
+            }
+            AdaptorRecordV1::Rank6(record) => {
+                AdaptorRecordItemV1::Rank6(record.into_item::<OptimStatePrecisionSettings<S>>())
+            }
+            AdaptorRecordV1::Rank7(record) => {
+                AdaptorRecordItemV1::Rank7(record.into_item::<OptimStatePrecisionSettings<S>>())
+            }
+            AdaptorRecordV1::Rank8(record) => {
+                AdaptorRecordItemV1::Rank8(record.into_item::<OptimStatePrecisionSettings<S>>())
+            }
         }
     }
 
@@ -182,4 +302,23 @@ where
             }
         }
     }
+
+    fn summary(&self) -> RecordSummary {
+        let num_steps = match self {
+            AdaptorRecordV1::Rank1(s) => O::state_num_steps(s),
+            AdaptorRecordV1::Rank2(s) => O::state_num_steps(s),
+            AdaptorRecordV1::Rank3(s) => O::state_num_steps(s),
+            AdaptorRecordV1::Rank4(s) => O::state_num_steps(s),
+            AdaptorRecordV1::Rank5(s) => O::state_num_steps(s),
+            AdaptorRecordV1::Rank6(s) => O::state_num_steps(s),
+            AdaptorRecordV1::Rank7(s) => O::state_num_steps(s),
+            AdaptorRecordV1::Rank8(s) => O::state_num_steps(s),
+        };
+
+        RecordSummary {
+            num_params: 1,
+            num_steps,
+            param_shapes: Vec::new(),
+        }
+    }
 }
@@ -0,0 +1,34 @@
+use crate::module::ParamId;
+use alloc::string::ToString;
+use rand::{rngs::StdRng, SeedableRng};
+
+/// Derive the seed of the random transforms of a parameter, e.g. the
+/// [gradient dropout](super::adaptor::OptimizerAdaptor::with_grad_dropout), from the seed of the
+/// optimizer, see [with_seed](super::adaptor::OptimizerAdaptor::with_seed).
+///
+/// The seed of the optimizer is combined with a hash of the parameter id, so the random values of
+/// each parameter are independent and reproducible with the same seed and the same ids.
+pub fn param_seed(seed: u64, id: &ParamId) -> u64 {
+    // FNV-1a, which unlike the default hasher gives the same hash on every run and platform.
+    let hash = id
+        .to_string()
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash: u64, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        });
+
+    mix(seed ^ hash)
+}
+
+/// The random number generator of a parameter, seeded with its [seed](param_seed).
+pub fn param_rng(seed: u64, id: &ParamId) -> StdRng {
+    StdRng::seed_from_u64(param_seed(seed, id))
+}
+
+/// The SplitMix64 finalizer, so close seeds give unrelated seeds.
+pub(crate) fn mix(value: u64) -> u64 {
+    let value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    let value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+
+    value ^ (value >> 31)
+}
@@ -0,0 +1,217 @@
+use super::{adaptor::OptimizerAdaptorRecord, record::AdaptorRecord, SimpleOptimizer};
+use crate::module::ParamId;
+use crate::record::{FileRecorder, RecorderError};
+use burn_tensor::backend::Backend;
+use hashbrown::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Split the state of an [optimizer adaptor](super::adaptor::OptimizerAdaptor) into the shard of
+/// the given rank, as held by each process with ZeRO-style sharding.
+///
+/// The parameters are sorted by id and assigned to the shards in turn, so each parameter belongs
+/// to exactly one of the `world_size` shards, with their initial values for the
+/// [weight decay](crate::optim::decay::WeightDecayKind::L2ToInit). The state shared by the
+/// parameters, e.g. the step counter of the seeded transforms, is kept in every shard.
+pub fn shard_record<O, B>(
+    record: OptimizerAdaptorRecord<AdaptorRecord<O, B>, B>,
+    rank: usize,
+    world_size: usize,
+) -> OptimizerAdaptorRecord<AdaptorRecord<O, B>, B>
+where
+    O: SimpleOptimizer<B>,
+    B: Backend,
+{
+    assert!(
+        rank < world_size,
+        "The rank must be smaller than the world size."
+    );
+
+    OptimizerAdaptorRecord {
+        params: shard(record.params, rank, world_size),
+        num_steps: record.num_steps,
+        loss_adaptive_threshold: record.loss_adaptive_threshold,
+        weight_decay_init: shard(record.weight_decay_init, rank, world_size),
+        weight_decay_warmup_step: record.weight_decay_warmup_step,
+    }
+}
+
+/// The values of the parameters of the shard of the given rank.
+fn shard<T>(values: HashMap<ParamId, T>, rank: usize, world_size: usize) -> HashMap<ParamId, T> {
+    let mut values: Vec<_> = values.into_iter().collect();
+    values.sort_by_key(|(id, _)| id.to_string());
+
+    values.into_iter().skip(rank).step_by(world_size).collect()
+}
+
+/// Save the shard of the optimizer state of the given rank in the directory.
+pub fn save_shard<O, B, FR>(
+    recorder: &FR,
+    shard: OptimizerAdaptorRecord<AdaptorRecord<O, B>, B>,
+    dir: &Path,
+    rank: usize,
+) -> Result<(), RecorderError>
+where
+    O: SimpleOptimizer<B>,
+    B: Backend,
+    FR: FileRecorder,
+{
+    recorder.record(shard, shard_path(dir, rank))
+}
+
+/// Load the shards of the optimizer state saved with [save_shard] by each of the `world_size`
+/// ranks, and reassemble them into the full optimizer state on the given device.
+///
+/// The consolidated state can be loaded in a single optimizer, e.g. for inference or to save an
+/// unsharded checkpoint.
+///
+/// The shards must come from the same step and split the parameters with the same world size,
+/// otherwise a [deserialization error](RecorderError::Deserialize) is returned.
+pub fn load_sharded<O, B, FR>(
+    recorder: &FR,
+    dir: &Path,
+    device: &B::Device,
+    world_size: usize,
+) -> Result<OptimizerAdaptorRecord<AdaptorRecord<O, B>, B>, RecorderError>
+where
+    O: SimpleOptimizer<B>,
+    B: Backend,
+    FR: FileRecorder,
+{
+    let mut record = OptimizerAdaptorRecord {
+        params: HashMap::new(),
+        num_steps: 0,
+        loss_adaptive_threshold: None,
+        weight_decay_init: HashMap::new(),
+        weight_decay_warmup_step: 0,
+    };
+
+    for rank in 0..world_size {
+        let shard: OptimizerAdaptorRecord<AdaptorRecord<O, B>, B> =
+            recorder.load(shard_path(dir, rank))?;
+        if rank > 0 && shard.num_steps != record.num_steps {
+            return Err(RecorderError::Deserialize(format!(
+                "The shard {rank} was saved at step {}, the shard 0 at step {}.",
+                shard.num_steps, record.num_steps
+            )));
+        }
+        record.num_steps = shard.num_steps;
+        record.loss_adaptive_threshold = shard.loss_adaptive_threshold;
+        record.weight_decay_warmup_step = shard.weight_decay_warmup_step;
+        for (id, state) in shard.params {
+            if record.params.contains_key(&id) {
+                return Err(RecorderError::Deserialize(format!(
+                    "The state of the parameter {id} is in several shards, which were split with \
+                     another world size."
+                )));
+            }
+            record.params.insert(id, state.to_device(device));
+        }
+        record.weight_decay_init.extend(
+            shard
+                .weight_decay_init
+                .into_iter()
+                .map(|(id, init)| (id, init.to_device(device))),
+        );
+    }
+
+    Ok(record)
+}
+
+fn shard_path(dir: &Path, rank: usize) -> PathBuf {
+    dir.join(format!("optim-shard-{rank}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::{Linear, LinearConfig};
+    use crate::optim::adaptor::OptimizerAdaptor;
+    use crate::optim::{AdaGrad, AdaGradConfig, GradientsParams, Optimizer};
+    use crate::record::{BinFileRecorder, FullPrecisionSettings, Recorder};
+    use crate::tensor::{Distribution, Tensor};
+    use crate::{TestAutodiffBackend, TestBackend};
+
+    type Optim =
+        OptimizerAdaptor<AdaGrad<TestBackend>, Linear<TestAutodiffBackend>, TestAutodiffBackend>;
+
+    #[test]
+    fn test_consolidated_shards_match_unsharded_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = BinFileRecorder::<FullPrecisionSettings>::new();
+        let x = Tensor::<TestAutodiffBackend, 2>::random([2, 4], Distribution::Default);
+        let step = |optim: &mut Optim, linear: Linear<TestAutodiffBackend>| {
+            let grads = linear.forward(x.clone()).backward();
+            let grads = GradientsParams::from_grads(grads, &linear);
+            optim.step(0.1, linear, grads)
+        };
+        let mut optimizer = optim();
+        let linear = step(&mut optimizer, LinearConfig::new(4, 2).init());
+
+        let record = optimizer.to_record();
+        recorder
+            .record(record.clone(), dir.path().join("optim"))
+            .unwrap();
+        for rank in 0..2 {
+            let shard = shard_record(record.clone(), rank, 2);
+            assert_eq!(shard.len(), 1);
+            save_shard(&recorder, shard, dir.path(), rank).unwrap();
+        }
+
+        let record_unsharded = recorder.load(dir.path().join("optim")).unwrap();
+        let record_consolidated = load_sharded::<AdaGrad<TestBackend>, TestBackend, _>(
+            &recorder,
+            dir.path(),
+            &Default::default(),
+            2,
+        )
+        .unwrap();
+        assert_eq!(record_consolidated.len(), 2);
+
+        // Both states lead to the same next step.
+        let linear_unsharded = step(&mut optim().load_record(record_unsharded), linear.clone());
+        let linear_consolidated = step(&mut optim().load_record(record_consolidated), linear);
+        linear_consolidated
+            .weight
+            .to_data()
+            .assert_approx_eq(&linear_unsharded.weight.to_data(), 6);
+        linear_consolidated
+            .bias
+            .unwrap()
+            .to_data()
+            .assert_approx_eq(&linear_unsharded.bias.unwrap().to_data(), 6);
+    }
+
+    #[test]
+    fn test_shards_of_another_world_size_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = BinFileRecorder::<FullPrecisionSettings>::new();
+        let linear: Linear<TestAutodiffBackend> = LinearConfig::new(4, 2).init();
+        let mut optimizer = optim();
+        let grads = linear
+            .forward(Tensor::random([2, 4], Distribution::Default))
+            .backward();
+        let grads = GradientsParams::from_grads(grads, &linear);
+        optimizer.step(0.1, linear, grads);
+
+        // Both parameters are in the shard 0 of a single process and in a shard of two processes.
+        let record = optimizer.to_record();
+        save_shard(&recorder, shard_record(record.clone(), 0, 1), dir.path(), 0).unwrap();
+        for rank in 0..2 {
+            let shard = shard_record(record.clone(), rank, 2);
+            save_shard(&recorder, shard, dir.path(), rank + 1).unwrap();
+        }
+
+        let result = load_sharded::<AdaGrad<TestBackend>, TestBackend, _>(
+            &recorder,
+            dir.path(),
+            &Default::default(),
+            3,
+        );
+
+        assert!(matches!(result, Err(RecorderError::Deserialize(_))));
+    }
+
+    fn optim() -> Optim {
+        AdaGradConfig::new().init_simple().into()
+    }
+}
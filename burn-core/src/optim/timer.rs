@@ -0,0 +1,216 @@
+use crate as burn;
+
+use super::{base::delegate_optimizer, GradientsParams, Optimizer};
+use crate::config::Config;
+use crate::module::AutodiffModule;
+use crate::tensor::backend::AutodiffBackend;
+use crate::LearningRate;
+use core::marker::PhantomData;
+use core::time::Duration;
+use std::collections::VecDeque;
+use std::time::Instant;
+
+/// Source of time used by the [step timer](StepTimer).
+pub trait StepClock: Send + Sync {
+    /// The time elapsed since an arbitrary but fixed instant.
+    fn now(&self) -> Duration;
+}
+
+/// [Step clock](StepClock) measuring the wall-clock time.
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl StepClock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// Configuration to create a [step timer](StepTimer).
+#[derive(Config)]
+pub struct StepTimerConfig {
+    /// The number of samples in each batch, used to compute the throughput.
+    batch_size: usize,
+    /// The number of most recent steps used to compute the rolling averages.
+    #[config(default = 20)]
+    window: usize,
+}
+
+/// Optimizer wrapper measuring the time taken by each step and the throughput in samples per
+/// second, averaged over the most recent steps.
+pub struct StepTimer<O, M, B, C = SystemClock>
+where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+    C: StepClock,
+{
+    optim: O,
+    clock: C,
+    batch_size: usize,
+    window: usize,
+    durations: VecDeque<Duration>,
+    phantom: PhantomData<(M, B)>,
+}
+
+impl StepTimerConfig {
+    /// Wrap the given optimizer to measure its steps with the wall-clock time.
+    pub fn init<O, M, B>(&self, optim: O) -> StepTimer<O, M, B>
+    where
+        O: Optimizer<M, B>,
+        M: AutodiffModule<B>,
+        B: AutodiffBackend,
+    {
+        self.init_with_clock(optim, SystemClock::default())
+    }
+
+    /// Wrap the given optimizer to measure its steps with the given clock.
+    pub fn init_with_clock<O, M, B, C>(&self, optim: O, clock: C) -> StepTimer<O, M, B, C>
+    where
+        O: Optimizer<M, B>,
+        M: AutodiffModule<B>,
+        B: AutodiffBackend,
+        C: StepClock,
+    {
+        assert!(
+            self.window > 0,
+            "The window must contain at least one step."
+        );
+
+        StepTimer {
+            optim,
+            clock,
+            batch_size: self.batch_size,
+            window: self.window,
+            durations: VecDeque::with_capacity(self.window),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<O, M, B, C> StepTimer<O, M, B, C>
+where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+    C: StepClock,
+{
+    /// The duration of the last step, if any.
+    pub fn last_step_duration(&self) -> Option<Duration> {
+        self.durations.back().copied()
+    }
+
+    /// The mean duration of the steps in the window, if any.
+    pub fn mean_step_duration(&self) -> Option<Duration> {
+        if self.durations.is_empty() {
+            return None;
+        }
+
+        let total: Duration = self.durations.iter().sum();
+        Some(total / self.durations.len() as u32)
+    }
+
+    /// The number of samples processed per second by the steps in the window, if any.
+    pub fn samples_per_second(&self) -> Option<f64> {
+        let mean = self.mean_step_duration()?.as_secs_f64();
+
+        match mean > 0.0 {
+            true => Some(self.batch_size as f64 / mean),
+            false => None,
+        }
+    }
+}
+
+impl<O, M, B, C> Optimizer<M, B> for StepTimer<O, M, B, C>
+where
+    O: Optimizer<M, B>,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+    C: StepClock,
+{
+    type Record = O::Record;
+
+    fn step(&mut self, lr: LearningRate, module: M, grads: GradientsParams) -> M {
+        let start = self.clock.now();
+        let module = self.optim.step(lr, module, grads);
+        let duration = self.clock.now().saturating_sub(start);
+
+        if self.durations.len() == self.window {
+            self.durations.pop_front();
+        }
+        self.durations.push_back(duration);
+
+        module
+    }
+
+    fn to_record(&self) -> Self::Record {
+        self.optim.to_record()
+    }
+
+    fn load_record(mut self, record: Self::Record) -> Self {
+        self.optim = self.optim.load_record(record);
+        self
+    }
+
+    delegate_optimizer!(optim);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nn::{Linear, LinearConfig},
+        optim::SgdConfig,
+        TestAutodiffBackend,
+    };
+    use std::sync::Mutex;
+
+    /// Clock returning scripted instants, in milliseconds.
+    struct MockClock {
+        instants: Mutex<VecDeque<u64>>,
+    }
+
+    impl StepClock for MockClock {
+        fn now(&self) -> Duration {
+            Duration::from_millis(self.instants.lock().unwrap().pop_front().unwrap())
+        }
+    }
+
+    #[test]
+    fn test_step_timer_rolling_average() {
+        // Steps taking 100, 200, 300 and 400 milliseconds.
+        let clock = MockClock {
+            instants: Mutex::new([0, 100, 100, 300, 300, 600, 600, 1000].into()),
+        };
+        let mut optim = StepTimerConfig::new(32).with_window(2).init_with_clock(
+            SgdConfig::new().init::<TestAutodiffBackend, Linear<TestAutodiffBackend>>(),
+            clock,
+        );
+        let mut linear = LinearConfig::new(2, 2).init();
+        assert_eq!(optim.last_step_duration(), None);
+        assert_eq!(optim.samples_per_second(), None);
+
+        let mut means = Vec::new();
+        for _ in 0..4 {
+            linear = optim.step(0.1, linear, GradientsParams::new());
+            means.push(optim.mean_step_duration().unwrap());
+        }
+
+        assert_eq!(optim.last_step_duration(), Some(Duration::from_millis(400)));
+        assert_eq!(
+            means,
+            [100, 150, 250, 350].map(Duration::from_millis).to_vec()
+        );
+        let samples_per_second = optim.samples_per_second().unwrap();
+        assert!((samples_per_second - 32.0 / 0.35).abs() < 1e-6);
+    }
+}
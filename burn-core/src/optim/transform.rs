@@ -0,0 +1,300 @@
+use crate::{self as burn, record::Record, LearningRate};
+
+use super::SimpleOptimizer;
+use crate::grad_clipping::GradientClipping;
+use crate::tensor::Tensor;
+use burn_tensor::backend::Backend;
+
+/// A step of an optimizer pipeline transforming the gradient of one tensor.
+///
+/// Transforms can be [chained](Transform::chain) to build a custom optimizer declaratively, e.g.
+/// weight decay, then clipping, then an adaptive method. Each transform owns its own part of the
+/// state, and the resulting pipeline becomes a [simple optimizer](SimpleOptimizer) with
+/// [TransformOptimizer].
+pub trait Transform<B: Backend>: Send + Sync {
+    /// The state of the transform, use `()` for stateless transforms.
+    type State<const D: usize>: Record + Clone + 'static;
+
+    /// Transform the gradient of the given tensor.
+    ///
+    /// The output of the last transform of a pipeline is subtracted from the tensor, so it must
+    /// already be scaled by the learning rate, see [LearningRateScaling].
+    fn apply<const D: usize>(
+        &self,
+        lr: LearningRate,
+        tensor: &Tensor<B, D>,
+        grad: Tensor<B, D>,
+        state: Option<Self::State<D>>,
+    ) -> (Tensor<B, D>, Self::State<D>);
+
+    /// Change the device of the state.
+    fn to_device<const D: usize>(state: Self::State<D>, device: &B::Device) -> Self::State<D>;
+
+    /// Apply the given function to each tensor of the state.
+    fn state_map_tensors<const D: usize, F>(state: Self::State<D>, func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>;
+
+    /// The number of elements of all the tensors in the state.
+    fn state_num_elements<const D: usize>(state: &Self::State<D>) -> usize;
+
+    /// The number of steps performed with the state, when the transform keeps track of it.
+    fn state_num_steps<const D: usize>(_state: &Self::State<D>) -> Option<usize> {
+        None
+    }
+
+    /// Apply the given transform to the output of this one.
+    fn chain<T: Transform<B>>(self, then: T) -> Chain<Self, T>
+    where
+        Self: Sized,
+    {
+        Chain { first: self, then }
+    }
+}
+
+/// Two [transforms](Transform) applied one after the other.
+pub struct Chain<F, T> {
+    first: F,
+    then: T,
+}
+
+/// State of [chained transforms](Chain).
+#[derive(Record, Clone, new)]
+pub struct ChainState<F: Record, T: Record> {
+    first: F,
+    then: T,
+}
+
+impl<B, F, T> Transform<B> for Chain<F, T>
+where
+    B: Backend,
+    F: Transform<B>,
+    T: Transform<B>,
+{
+    type State<const D: usize> = ChainState<F::State<D>, T::State<D>>;
+
+    fn apply<const D: usize>(
+        &self,
+        lr: LearningRate,
+        tensor: &Tensor<B, D>,
+        grad: Tensor<B, D>,
+        state: Option<Self::State<D>>,
+    ) -> (Tensor<B, D>, Self::State<D>) {
+        let (state_first, state_then) = match state {
+            Some(state) => (Some(state.first), Some(state.then)),
+            None => (None, None),
+        };
+
+        let (grad, state_first) = self.first.apply(lr, tensor, grad, state_first);
+        let (grad, state_then) = self.then.apply(lr, tensor, grad, state_then);
+
+        (grad, ChainState::new(state_first, state_then))
+    }
+
+    fn to_device<const D: usize>(mut state: Self::State<D>, device: &B::Device) -> Self::State<D> {
+        state.first = F::to_device(state.first, device);
+        state.then = T::to_device(state.then, device);
+        state
+    }
+
+    fn state_map_tensors<const D: usize, M>(mut state: Self::State<D>, func: M) -> Self::State<D>
+    where
+        M: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        state.first = F::state_map_tensors(state.first, &func);
+        state.then = T::state_map_tensors(state.then, &func);
+        state
+    }
+
+    fn state_num_elements<const D: usize>(state: &Self::State<D>) -> usize {
+        F::state_num_elements(&state.first) + T::state_num_elements(&state.then)
+    }
+
+    fn state_num_steps<const D: usize>(state: &Self::State<D>) -> Option<usize> {
+        F::state_num_steps(&state.first).or_else(|| T::state_num_steps(&state.then))
+    }
+}
+
+/// An optional transform is skipped when missing.
+impl<B: Backend, T: Transform<B>> Transform<B> for Option<T> {
+    type State<const D: usize> = Option<T::State<D>>;
+
+    fn apply<const D: usize>(
+        &self,
+        lr: LearningRate,
+        tensor: &Tensor<B, D>,
+        grad: Tensor<B, D>,
+        state: Option<Self::State<D>>,
+    ) -> (Tensor<B, D>, Self::State<D>) {
+        match self {
+            Some(transform) => {
+                let (grad, state) = transform.apply(lr, tensor, grad, state.flatten());
+                (grad, Some(state))
+            }
+            None => (grad, None),
+        }
+    }
+
+    fn to_device<const D: usize>(state: Self::State<D>, device: &B::Device) -> Self::State<D> {
+        state.map(|state| T::to_device(state, device))
+    }
+
+    fn state_map_tensors<const D: usize, F>(state: Self::State<D>, func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        state.map(|state| T::state_map_tensors(state, func))
+    }
+
+    fn state_num_elements<const D: usize>(state: &Self::State<D>) -> usize {
+        state.as_ref().map(T::state_num_elements).unwrap_or(0)
+    }
+
+    fn state_num_steps<const D: usize>(state: &Self::State<D>) -> Option<usize> {
+        state.as_ref().and_then(T::state_num_steps)
+    }
+}
+
+/// Transform scaling the gradients by the learning rate, usually the last of a pipeline.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LearningRateScaling;
+
+impl<B: Backend> Transform<B> for LearningRateScaling {
+    type State<const D: usize> = ();
+
+    fn apply<const D: usize>(
+        &self,
+        lr: LearningRate,
+        _tensor: &Tensor<B, D>,
+        grad: Tensor<B, D>,
+        _state: Option<Self::State<D>>,
+    ) -> (Tensor<B, D>, Self::State<D>) {
+        (grad.mul_scalar(lr), ())
+    }
+
+    fn to_device<const D: usize>(state: Self::State<D>, _device: &B::Device) -> Self::State<D> {
+        state
+    }
+
+    fn state_map_tensors<const D: usize, F>(state: Self::State<D>, _func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        state
+    }
+
+    fn state_num_elements<const D: usize>(_state: &Self::State<D>) -> usize {
+        0
+    }
+}
+
+impl<B: Backend> Transform<B> for GradientClipping {
+    type State<const D: usize> = ();
+
+    fn apply<const D: usize>(
+        &self,
+        _lr: LearningRate,
+        _tensor: &Tensor<B, D>,
+        grad: Tensor<B, D>,
+        _state: Option<Self::State<D>>,
+    ) -> (Tensor<B, D>, Self::State<D>) {
+        (self.clip_gradient(grad), ())
+    }
+
+    fn to_device<const D: usize>(state: Self::State<D>, _device: &B::Device) -> Self::State<D> {
+        state
+    }
+
+    fn state_map_tensors<const D: usize, F>(state: Self::State<D>, _func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        state
+    }
+
+    fn state_num_elements<const D: usize>(_state: &Self::State<D>) -> usize {
+        0
+    }
+}
+
+/// [Simple optimizer](SimpleOptimizer) subtracting the output of a [transform](Transform)
+/// pipeline from the parameters.
+#[derive(new)]
+pub struct TransformOptimizer<T> {
+    transform: T,
+}
+
+impl<B: Backend, T: Transform<B>> SimpleOptimizer<B> for TransformOptimizer<T> {
+    type State<const D: usize> = T::State<D>;
+
+    fn step<const D: usize>(
+        &self,
+        lr: LearningRate,
+        tensor: Tensor<B, D>,
+        grad: Tensor<B, D>,
+        state: Option<Self::State<D>>,
+    ) -> (Tensor<B, D>, Option<Self::State<D>>) {
+        let (delta, state) = self.transform.apply(lr, &tensor, grad, state);
+
+        (tensor - delta, Some(state))
+    }
+
+    fn to_device<const D: usize>(state: Self::State<D>, device: &B::Device) -> Self::State<D> {
+        T::to_device(state, device)
+    }
+
+    fn state_map_tensors<const D: usize, F>(state: Self::State<D>, func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        T::state_map_tensors(state, func)
+    }
+
+    fn state_num_elements<const D: usize>(state: &Self::State<D>) -> usize {
+        T::state_num_elements(state)
+    }
+
+    fn state_num_steps<const D: usize>(state: &Self::State<D>) -> Option<usize> {
+        T::state_num_steps(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optim::decay::{WeightDecay, WeightDecayConfig};
+    use crate::optim::AdaGradConfig;
+    use crate::tensor::Data;
+    use crate::TestBackend;
+
+    const LEARNING_RATE: LearningRate = 0.01;
+
+    #[test]
+    fn test_chained_transforms_reproduce_adagrad() {
+        let weight_decay = WeightDecayConfig::new(0.05);
+        let config = AdaGradConfig::new().with_lr_decay(0.1);
+        let pipeline = TransformOptimizer::new(
+            WeightDecay::<TestBackend>::new(&weight_decay)
+                .chain(config.init_lr_decay::<TestBackend>()),
+        );
+        let grads = [[0.1, -0.2, 0.3], [0.4, 0.1, -0.5], [-0.2, 0.3, 0.1]];
+        // The parameters after each step of AdaGrad with the same weight decay and decay of the
+        // learning rate, before it was built from the transforms.
+        let tensors_expected = [
+            [0.490001, -0.99, 1.99],
+            [0.48128, -0.9918, 1.996432],
+            [0.484359, -0.997638, 1.993658],
+        ];
+
+        let mut tensor = Tensor::<TestBackend, 1>::from_floats([0.5, -1.0, 2.0]);
+        let mut state = None;
+        for (grad, tensor_expected) in grads.into_iter().zip(tensors_expected) {
+            let grad = Tensor::from_floats(grad);
+            (tensor, state) = pipeline.step(LEARNING_RATE, tensor, grad, state);
+
+            tensor
+                .to_data()
+                .assert_approx_eq(&Data::from(tensor_expected), 5);
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{self as burn, record::Record, LearningRate};
+
+use super::SimpleOptimizer;
+use crate::config::Config;
+use crate::grad_clipping::{tensor_norm, NormKind};
+use crate::tensor::Tensor;
+use burn_tensor::backend::Backend;
+
+/// Configuration to create the [update clamping](UpdateClamping) wrapper.
+#[derive(Config)]
+pub struct UpdateClampingConfig {
+    /// The norm of an update is clamped to this multiple of the average of the previous ones.
+    #[config(default = 3.0)]
+    max_ratio: f32,
+    /// Decay of the exponential moving average of the update norms.
+    #[config(default = 0.9)]
+    ema_decay: f32,
+    /// A value required for numerical stability.
+    #[config(default = 1e-8)]
+    epsilon: f32,
+}
+
+/// Simple optimizer wrapper clamping the update of each parameter based on its history.
+///
+/// The wrapper keeps an exponential moving average of the norm of the updates computed by the
+/// inner optimizer for each parameter, and scales down any update whose norm is larger than
+/// `max_ratio` times that average. Unlike gradient clipping, it acts on the update after the
+/// inner optimizer adapted it, and the threshold adjusts itself to each parameter.
+///
+/// The first update of a parameter only initializes the average and is never clamped.
+pub struct UpdateClamping<O> {
+    optim: O,
+    max_ratio: f32,
+    ema_decay: f32,
+    epsilon: f32,
+}
+
+/// State of [update clamping](UpdateClamping).
+#[derive(Record, Clone, new)]
+pub struct UpdateClampingState<B: Backend, T: Record> {
+    inner: Option<T>,
+    update_norm_ema: Tensor<B, 1>,
+}
+
+impl UpdateClampingConfig {
+    /// Wrap the given simple optimizer to clamp its updates.
+    ///
+    /// The wrapper is itself a [simple optimizer](SimpleOptimizer), so it can be used with
+    /// [OptimizerAdaptor](crate::optim::adaptor::OptimizerAdaptor).
+    pub fn init<O>(&self, optim: O) -> UpdateClamping<O> {
+        assert!(
+            self.max_ratio > 0.0,
+            "The maximum update ratio must be positive."
+        );
+
+        UpdateClamping {
+            optim,
+            max_ratio: self.max_ratio,
+            ema_decay: self.ema_decay,
+            epsilon: self.epsilon,
+        }
+    }
+}
+
+impl<B, O> SimpleOptimizer<B> for UpdateClamping<O>
+where
+    B: Backend,
+    O: SimpleOptimizer<B>,
+{
+    type State<const D: usize> = UpdateClampingState<B, O::State<D>>;
+
+    fn step<const D: usize>(
+        &self,
+        lr: LearningRate,
+        tensor: Tensor<B, D>,
+        grad: Tensor<B, D>,
+        state: Option<Self::State<D>>,
+    ) -> (Tensor<B, D>, Option<Self::State<D>>) {
+        let (state_inner, update_norm_ema) = match state {
+            Some(state) => (state.inner, Some(state.update_norm_ema)),
+            None => (None, None),
+        };
+
+        let (tensor_updated, state_inner) = self.optim.step(lr, tensor.clone(), grad, state_inner);
+        let update = tensor.clone().sub(tensor_updated);
+        let update_norm = tensor_norm(update.clone(), &NormKind::L2);
+
+        let (update, update_norm_ema) = match update_norm_ema {
+            Some(update_norm_ema) => {
+                // Only updates larger than `max_ratio` times the average norm are shrunk, the
+                // `epsilon` guarding against a zero update.
+                let scale = update_norm_ema
+                    .clone()
+                    .mul_scalar(self.max_ratio)
+                    .div(update_norm.clone().add_scalar(self.epsilon))
+                    .clamp_max(1.0);
+                let update_norm = update_norm.mul(scale.clone());
+                let update = update.mul(scale.reshape([1; D]));
+
+                let update_norm_ema = update_norm_ema
+                    .mul_scalar(self.ema_decay)
+                    .add(update_norm.mul_scalar(1.0 - self.ema_decay));
+
+                (update, update_norm_ema)
+            }
+            None => (update, update_norm),
+        };
+
+        let state = UpdateClampingState::new(state_inner, update_norm_ema);
+
+        (tensor - update, Some(state))
+    }
+
+    fn to_device<const D: usize>(mut state: Self::State<D>, device: &B::Device) -> Self::State<D> {
+        state.inner = state.inner.map(|state| O::to_device(state, device));
+        state.update_norm_ema = state.update_norm_ema.to_device(device);
+        state
+    }
+
+    fn state_map_tensors<const D: usize, F>(mut state: Self::State<D>, func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        state.inner = state.inner.map(|state| O::state_map_tensors(state, &func));
+        // The average norm is mapped as a tensor with a single element of the rank of the state,
+        // e.g. padded when adapted to a larger parameter, then summed back.
+        state.update_norm_ema = func(state.update_norm_ema.reshape([1; D])).sum();
+        state
+    }
+
+    fn state_num_elements<const D: usize>(state: &Self::State<D>) -> usize {
+        state.inner.as_ref().map(O::state_num_elements).unwrap_or(0)
+            + state.update_norm_ema.shape().num_elements()
+    }
+
+    fn state_num_steps<const D: usize>(state: &Self::State<D>) -> Option<usize> {
+        state.inner.as_ref().and_then(O::state_num_steps)
+    }
+
+    fn state_effective_lr<const D: usize>(
+        &self,
+        lr: LearningRate,
+        state: &Self::State<D>,
+    ) -> Option<Tensor<B, D>> {
+        state
+            .inner
+            .as_ref()
+            .and_then(|state| self.optim.state_effective_lr(lr, state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optim::{Sgd, SgdConfig, SgdState};
+    use crate::record::FullPrecisionSettings;
+    use crate::tensor::Data;
+    use crate::TestBackend;
+    use burn_tensor::ElementConversion;
+
+    const LEARNING_RATE: LearningRate = 1.0;
+    const ASSERT_PRECISION: usize = 4;
+
+    #[test]
+    fn test_update_clamping_limits_sudden_large_update() {
+        let optimizer =
+            UpdateClampingConfig::new().init(SgdConfig::new().init_simple::<TestBackend>());
+        let mut tensor = Tensor::<TestBackend, 1>::zeros([3]);
+        let mut state = None;
+
+        // Updates of norm 1, which set the average of the update norms.
+        for _ in 0..5 {
+            let grad = Tensor::from_floats([0.6, 0.8, 0.0]);
+            (tensor, state) = optimizer.step(LEARNING_RATE, tensor, grad, state);
+        }
+        tensor
+            .to_data()
+            .assert_approx_eq(&Data::from([-3.0, -4.0, 0.0]), ASSERT_PRECISION);
+
+        // An update of norm 100 is clamped to 3 times the average.
+        let grad = Tensor::from_floats([60.0, 80.0, 0.0]);
+        let (tensor, state) = optimizer.step(LEARNING_RATE, tensor, grad, state);
+        tensor
+            .to_data()
+            .assert_approx_eq(&Data::from([-4.8, -6.4, 0.0]), ASSERT_PRECISION);
+
+        // The clamped norm is folded into the average, which is kept in the record.
+        let item = state.unwrap().into_item::<FullPrecisionSettings>();
+        let state: UpdateClampingState<TestBackend, SgdState<TestBackend, 1>> =
+            Record::from_item(item);
+        let update_norm_ema = state.update_norm_ema.into_scalar().elem::<f32>();
+        assert!((update_norm_ema - 1.2).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_update_clamping_maps_the_average_norm() {
+        type Optim = UpdateClamping<Sgd<TestBackend>>;
+        let optimizer = UpdateClampingConfig::new().init(SgdConfig::new().init_simple());
+        let grad = Tensor::<TestBackend, 2>::from_floats([[0.6, 0.8]]);
+        let (_, state) = optimizer.step(LEARNING_RATE, grad.zeros_like(), grad, None);
+
+        // The average norm is padded along with the state of a larger parameter.
+        let state = Optim::state_map_tensors(state.unwrap(), |tensor| {
+            Tensor::zeros([2, 3]).slice_assign([0..1, 0..tensor.dims()[1]], tensor)
+        });
+        assert_eq!(state.update_norm_ema.dims(), [1]);
+        let update_norm_ema = state.update_norm_ema.into_scalar().elem::<f32>();
+        assert!((update_norm_ema - 1.0).abs() < 1e-4);
+
+        let state = Optim::state_map_tensors(state_with_norm(2.0), |tensor| tensor.mul_scalar(3.0));
+        let update_norm_ema = state.update_norm_ema.into_scalar().elem::<f32>();
+        assert!((update_norm_ema - 6.0).abs() < 1e-4);
+    }
+
+    fn state_with_norm(norm: f32) -> UpdateClampingState<TestBackend, SgdState<TestBackend, 2>> {
+        UpdateClampingState::new(None, Tensor::from_floats([norm]))
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{self as burn, LearningRate};
+
+use super::SimpleOptimizer;
+use crate::config::Config;
+use crate::grad_clipping::{GradientClipping, GradientClippingConfig};
+use crate::tensor::Tensor;
+use burn_tensor::backend::Backend;
+
+/// Configuration to create the [update clipping](UpdateClipping) wrapper.
+#[derive(Config)]
+pub struct UpdateClippingConfig {
+    /// The clipping of the update, by value or by norm.
+    clipping: GradientClippingConfig,
+}
+
+/// Simple optimizer wrapper clipping the update computed by the inner optimizer, e.g. the update
+/// of [Adam](super::Adam) after the adaptive scaling.
+///
+/// The update is the difference between the parameter and the parameter updated by the inner
+/// optimizer, and is clipped before being applied, so the step can't move a parameter further
+/// than the threshold regardless of the adaptive scaling. Unlike
+/// [update clamping](super::UpdateClamping), the threshold is fixed.
+pub struct UpdateClipping<O> {
+    optim: O,
+    clipping: GradientClipping,
+}
+
+impl UpdateClippingConfig {
+    /// Wrap the given simple optimizer to clip its updates.
+    ///
+    /// The wrapper is itself a [simple optimizer](SimpleOptimizer), so it can be used with
+    /// [OptimizerAdaptor](crate::optim::adaptor::OptimizerAdaptor).
+    pub fn init<O>(&self, optim: O) -> UpdateClipping<O> {
+        UpdateClipping {
+            optim,
+            clipping: self.clipping.init(),
+        }
+    }
+}
+
+impl<B, O> SimpleOptimizer<B> for UpdateClipping<O>
+where
+    B: Backend,
+    O: SimpleOptimizer<B>,
+{
+    type State<const D: usize> = O::State<D>;
+
+    fn step<const D: usize>(
+        &self,
+        lr: LearningRate,
+        tensor: Tensor<B, D>,
+        grad: Tensor<B, D>,
+        state: Option<Self::State<D>>,
+    ) -> (Tensor<B, D>, Option<Self::State<D>>) {
+        let (tensor_updated, state) = self.optim.step(lr, tensor.clone(), grad, state);
+        let update = self
+            .clipping
+            .clip_gradient(tensor.clone().sub(tensor_updated));
+
+        (tensor - update, state)
+    }
+
+    fn to_device<const D: usize>(state: Self::State<D>, device: &B::Device) -> Self::State<D> {
+        O::to_device(state, device)
+    }
+
+    fn state_map_tensors<const D: usize, F>(state: Self::State<D>, func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        O::state_map_tensors(state, func)
+    }
+
+    fn state_num_elements<const D: usize>(state: &Self::State<D>) -> usize {
+        O::state_num_elements(state)
+    }
+
+    fn state_num_steps<const D: usize>(state: &Self::State<D>) -> Option<usize> {
+        O::state_num_steps(state)
+    }
+
+    fn state_effective_lr<const D: usize>(
+        &self,
+        lr: LearningRate,
+        state: &Self::State<D>,
+    ) -> Option<Tensor<B, D>> {
+        self.optim.state_effective_lr(lr, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optim::AdamConfig;
+    use crate::tensor::Data;
+    use crate::TestBackend;
+
+    #[test]
+    fn test_update_clipping_bounds_the_adaptive_update() {
+        let optimizer = UpdateClippingConfig::new(GradientClippingConfig::Norm(1.0))
+            .init(AdamConfig::new().init_simple::<TestBackend>());
+        let grad = || Tensor::<TestBackend, 1>::from_floats([0.1, 0.2, 0.5, 1.0]);
+
+        // The first update of Adam is close to `lr` for each element whatever the gradient, so
+        // its norm is `2 * lr`.
+        let (tensor, state) =
+            optimizer.step(1.0, Tensor::<TestBackend, 1>::zeros([4]), grad(), None);
+        tensor
+            .to_data()
+            .assert_approx_eq(&Data::from([-0.5, -0.5, -0.5, -0.5]), 3);
+        assert!(state.is_some());
+
+        let (tensor, _) = optimizer.step(0.1, Tensor::<TestBackend, 1>::zeros([4]), grad(), None);
+        tensor
+            .to_data()
+            .assert_approx_eq(&Data::from([-0.1, -0.1, -0.1, -0.1]), 3);
+    }
+}
@@ -0,0 +1,45 @@
+use crate::config::ConfigError;
+use alloc::format;
+use core::fmt::Display;
+
+/// Check that a decay rate, e.g. the beta of a moving average, is in `[0, 1)`.
+pub(crate) fn validate_beta<F>(name: &str, value: F) -> Result<(), ConfigError>
+where
+    F: Into<f64> + Display + Copy,
+{
+    if (0.0..1.0).contains(&value.into()) {
+        Ok(())
+    } else {
+        Err(ConfigError::OutOfRange(format!(
+            "{name} must be in [0, 1), got {value}."
+        )))
+    }
+}
+
+/// Check that a value, e.g. an epsilon or a threshold, is strictly positive.
+pub(crate) fn validate_positive<F>(name: &str, value: F) -> Result<(), ConfigError>
+where
+    F: Into<f64> + Display + Copy,
+{
+    if value.into() > 0.0 {
+        Ok(())
+    } else {
+        Err(ConfigError::OutOfRange(format!(
+            "{name} must be positive, got {value}."
+        )))
+    }
+}
+
+/// Check that a value, e.g. a weight decay penalty, isn't negative.
+pub(crate) fn validate_non_negative<F>(name: &str, value: F) -> Result<(), ConfigError>
+where
+    F: Into<f64> + Display + Copy,
+{
+    if value.into() >= 0.0 {
+        Ok(())
+    } else {
+        Err(ConfigError::OutOfRange(format!(
+            "{name} must be non-negative, got {value}."
+        )))
+    }
+}
@@ -1,7 +1,10 @@
 use super::GradientsParams;
+use crate::grad_clipping::{GlobalNormAccumulator, NormKind};
 use crate::module::{AutodiffModule, ModuleVisitor, ParamId};
+use alloc::vec::Vec;
 use burn_tensor::{backend::AutodiffBackend, Tensor};
 use core::marker::PhantomData;
+use hashbrown::{HashMap, HashSet};
 
 #[derive(new)]
 pub struct GradientsParamsConverter<'a, M: AutodiffModule<B>, B: AutodiffBackend> {
@@ -17,16 +20,55 @@ pub struct GradientsParamsChangeDevice<'a, M: AutodiffModule<B>, B: AutodiffBack
     phatom: PhantomData<M>,
 }
 
+#[derive(new)]
+pub struct GradientsParamsFlatten<'a, M: AutodiffModule<B>, B: AutodiffBackend> {
+    grads: &'a mut GradientsParams,
+    ids: &'a mut Vec<ParamId>,
+    tensors: &'a mut Vec<Tensor<B::InnerBackend, 1>>,
+    phatom: PhantomData<M>,
+}
+
+#[derive(new)]
+pub struct GradientsParamsMerge<'a, M: AutodiffModule<B>, B: AutodiffBackend> {
+    grads: &'a mut GradientsParams,
+    other: &'a mut GradientsParams,
+    phatom: PhantomData<M>,
+}
+
+#[derive(new)]
+pub struct GradientsParamsNorm<'a, M: AutodiffModule<B>, B: AutodiffBackend> {
+    grads: &'a GradientsParams,
+    kind: &'a NormKind,
+    accumulator: &'a mut Option<GlobalNormAccumulator<B::InnerBackend>>,
+    visited: HashSet<ParamId>,
+    phatom: PhantomData<M>,
+}
+
+#[derive(new)]
+pub struct GradientsParamsUnflatten<'a, M: AutodiffModule<B>, B: AutodiffBackend> {
+    grads: &'a mut GradientsParams,
+    tensors: &'a mut HashMap<ParamId, Tensor<B::InnerBackend, 1>>,
+    phatom: PhantomData<M>,
+}
+
 impl<'a, B, M> ModuleVisitor<B> for GradientsParamsConverter<'a, M, B>
 where
     B: AutodiffBackend,
     M: AutodiffModule<B>,
 {
     fn visit<const D: usize>(&mut self, id: &ParamId, tensor: &Tensor<B, D>) {
-        if let Some(grad) = tensor.grad_remove(&mut self.grads) {
-            self.grads_params
-                .register::<B::InnerBackend, D>(id.clone(), grad);
-        }
+        // The gradient is removed, so a tensor visited more than once is only counted once.
+        let Some(grad) = tensor.grad_remove(&mut self.grads) else {
+            return;
+        };
+
+        // Tied parameters share their id with their own tensors, their gradients are summed.
+        let grad = match self.grads_params.remove::<B::InnerBackend, D>(id) {
+            Some(grad_tied) => grad_tied.add(grad),
+            None => grad,
+        };
+        self.grads_params
+            .register::<B::InnerBackend, D>(id.clone(), grad);
     }
 }
 
@@ -42,3 +84,73 @@ where
         }
     }
 }
+
+impl<'a, B, M> ModuleVisitor<B> for GradientsParamsMerge<'a, M, B>
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+{
+    fn visit<const D: usize>(&mut self, id: &ParamId, _tensor: &Tensor<B, D>) {
+        // The other gradient is removed, so a parameter visited more than once is only summed once.
+        let Some(grad_other) = self.other.remove::<B::InnerBackend, D>(id) else {
+            return;
+        };
+
+        let grad = match self.grads.remove::<B::InnerBackend, D>(id) {
+            Some(grad) => {
+                let grad_other = grad_other.to_device(&grad.device());
+                grad.add(grad_other)
+            }
+            None => grad_other,
+        };
+        self.grads.register::<B::InnerBackend, D>(id.clone(), grad);
+    }
+}
+
+impl<'a, B, M> ModuleVisitor<B> for GradientsParamsFlatten<'a, M, B>
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+{
+    fn visit<const D: usize>(&mut self, id: &ParamId, _tensor: &Tensor<B, D>) {
+        // The gradients are removed, so a parameter visited more than once is only flattened once.
+        if let Some(grad) = self.grads.remove::<B::InnerBackend, D>(id) {
+            let num_elements = grad.shape().num_elements();
+            self.ids.push(id.clone());
+            self.tensors.push(grad.reshape([num_elements]));
+        }
+    }
+}
+
+impl<'a, B, M> ModuleVisitor<B> for GradientsParamsNorm<'a, M, B>
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+{
+    fn visit<const D: usize>(&mut self, id: &ParamId, _tensor: &Tensor<B, D>) {
+        if !self.visited.insert(id.clone()) {
+            return;
+        }
+
+        if let Some(grad) = self.grads.get::<B::InnerBackend, D>(id) {
+            self.accumulator
+                .get_or_insert_with(|| {
+                    GlobalNormAccumulator::new(&grad.device()).with_kind(self.kind.clone())
+                })
+                .add(grad);
+        }
+    }
+}
+
+impl<'a, B, M> ModuleVisitor<B> for GradientsParamsUnflatten<'a, M, B>
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+{
+    fn visit<const D: usize>(&mut self, id: &ParamId, tensor: &Tensor<B, D>) {
+        if let Some(grad) = self.tensors.remove(id) {
+            self.grads
+                .register::<B::InnerBackend, D>(id.clone(), grad.reshape(tensor.shape()));
+        }
+    }
+}
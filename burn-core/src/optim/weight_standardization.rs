@@ -0,0 +1,141 @@
+use crate::{self as burn, LearningRate};
+
+use super::SimpleOptimizer;
+use crate::config::Config;
+use crate::tensor::Tensor;
+use burn_tensor::backend::Backend;
+
+/// Configuration to create the [weight standardization](WeightStandardization) wrapper.
+#[derive(Config)]
+pub struct WeightStandardizationConfig {
+    /// A value required for numerical stability.
+    #[config(default = 1e-5)]
+    epsilon: f64,
+}
+
+/// Simple optimizer wrapper applying
+/// [Weight Standardization](https://arxiv.org/abs/1903.10520) to the convolution weights after
+/// each step of the inner optimizer.
+///
+/// Each output channel of the rank-4 weights is normalized to zero mean and unit variance across
+/// its input and spatial dimensions, which pairs well with group normalization. Tensors of any
+/// other rank, e.g. biases and linear weights, are left as is.
+pub struct WeightStandardization<O> {
+    optim: O,
+    epsilon: f64,
+}
+
+impl WeightStandardizationConfig {
+    /// Wrap the given simple optimizer to standardize the convolution weights it updates.
+    pub fn init<O>(&self, optim: O) -> WeightStandardization<O> {
+        WeightStandardization {
+            optim,
+            epsilon: self.epsilon,
+        }
+    }
+}
+
+impl<O> WeightStandardization<O> {
+    /// Standardize each output channel of the given convolution weights, of shape
+    /// `[channels_out, channels_in, kernel_size_1, kernel_size_2]`.
+    ///
+    /// Tensors that aren't of rank 4 are returned as is.
+    pub fn standardize<B: Backend, const D: usize>(&self, tensor: Tensor<B, D>) -> Tensor<B, D> {
+        if D != 4 {
+            return tensor;
+        }
+
+        let shape = tensor.shape();
+        let channels_out = shape.dims[0];
+        let num_elements = shape.num_elements();
+        let weight = tensor.reshape([channels_out, num_elements / channels_out]);
+
+        let (var, mean) = weight.clone().var_mean_bias(1);
+        let weight = weight.sub(mean).div(var.add_scalar(self.epsilon).sqrt());
+
+        weight.reshape(shape)
+    }
+}
+
+impl<B, O> SimpleOptimizer<B> for WeightStandardization<O>
+where
+    B: Backend,
+    O: SimpleOptimizer<B>,
+{
+    type State<const D: usize> = O::State<D>;
+
+    fn step<const D: usize>(
+        &self,
+        lr: LearningRate,
+        tensor: Tensor<B, D>,
+        grad: Tensor<B, D>,
+        state: Option<Self::State<D>>,
+    ) -> (Tensor<B, D>, Option<Self::State<D>>) {
+        let (tensor, state) = self.optim.step(lr, tensor, grad, state);
+
+        (self.standardize(tensor), state)
+    }
+
+    fn to_device<const D: usize>(state: Self::State<D>, device: &B::Device) -> Self::State<D> {
+        O::to_device(state, device)
+    }
+
+    fn state_map_tensors<const D: usize, F>(state: Self::State<D>, func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        O::state_map_tensors(state, func)
+    }
+
+    fn state_num_elements<const D: usize>(state: &Self::State<D>) -> usize {
+        O::state_num_elements(state)
+    }
+
+    fn state_num_steps<const D: usize>(state: &Self::State<D>) -> Option<usize> {
+        O::state_num_steps(state)
+    }
+
+    fn state_effective_lr<const D: usize>(
+        &self,
+        lr: LearningRate,
+        state: &Self::State<D>,
+    ) -> Option<Tensor<B, D>> {
+        self.optim.state_effective_lr(lr, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optim::SgdConfig;
+    use crate::tensor::Distribution;
+    use crate::TestBackend;
+
+    const LEARNING_RATE: LearningRate = 0.1;
+
+    #[test]
+    fn test_weight_standardization_normalizes_output_channels() {
+        let optimizer =
+            WeightStandardizationConfig::new().init(SgdConfig::new().init_simple::<TestBackend>());
+        let weight = Tensor::<TestBackend, 4>::random([4, 3, 3, 3], Distribution::Default)
+            .mul_scalar(5.0)
+            .add_scalar(2.0);
+        let grad = Tensor::<TestBackend, 4>::random([4, 3, 3, 3], Distribution::Default);
+
+        let (weight, _) = optimizer.step(LEARNING_RATE, weight, grad, None);
+
+        let (var, mean) = weight.reshape([4, 27]).var_mean_bias(1);
+        mean.to_data()
+            .assert_approx_eq(&Tensor::<TestBackend, 2>::zeros([4, 1]).to_data(), 3);
+        var.to_data()
+            .assert_approx_eq(&Tensor::<TestBackend, 2>::ones([4, 1]).to_data(), 3);
+
+        // Tensors of other ranks only receive the update of the inner optimizer.
+        let bias = Tensor::<TestBackend, 1>::from_floats([1.0, 2.0, 3.0, 4.0]);
+        let (bias, _) = optimizer.step(LEARNING_RATE, bias, Tensor::ones([4]), None);
+        bias.to_data().assert_approx_eq(
+            &Tensor::<TestBackend, 1>::from_floats([0.9, 1.9, 2.9, 3.9]).to_data(),
+            5,
+        );
+    }
+}
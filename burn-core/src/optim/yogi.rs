@@ -0,0 +1,340 @@
+use crate::{
+    self as burn, grad_clipping::GradientClippingConfig, module::AutodiffModule, record::Record,
+    LearningRate,
+};
+
+use super::validation::{validate_beta, validate_non_negative, validate_positive};
+use super::{
+    adam::adaptive_effective_lr,
+    decay::{WeightDecay, WeightDecayConfig, WeightDecayWarmup},
+    AdaptiveMomentumState, SimpleOptimizer,
+};
+use crate::config::{Config, ConfigError, ValidateConfig};
+use crate::optim::adaptor::OptimizerAdaptor;
+use crate::tensor::{backend::AutodiffBackend, Tensor};
+use burn_tensor::{backend::Backend, ElementConversion};
+
+/// Yogi configuration.
+#[derive(Config)]
+pub struct YogiConfig {
+    /// Parameter for Yogi.
+    #[config(default = 0.9)]
+    beta_1: f32,
+    /// Parameter for Yogi.
+    #[config(default = 0.999)]
+    beta_2: f32,
+    /// A value required for numerical stability.
+    #[config(default = 1e-3)]
+    epsilon: f32,
+    /// The value the second moment is initialized with.
+    #[config(default = 1e-6)]
+    initial_accumulator_value: f32,
+    /// [Weight decay](WeightDecayConfig) config.
+    weight_decay: Option<WeightDecayConfig>,
+    /// [Gradient Clipping](GradientClippingConfig) config.
+    grad_clipping: Option<GradientClippingConfig>,
+    /// [Gradient scale](crate::optim::adaptor::OptimizerAdaptor::with_grad_scale) of the adaptor.
+    #[config(default = 1.0)]
+    grad_scale: f32,
+}
+
+/// Yogi optimizer as described in the paper [Adaptive Methods for Nonconvex Optimization](https://papers.nips.cc/paper/8186-adaptive-methods-for-nonconvex-optimization).
+///
+/// The first moment and the step counter are the same as [Adam](super::Adam), but the second
+/// moment is updated additively with `v = v - (1 - beta_2) * sign(v - grad^2) * grad^2`, which
+/// controls how fast it can grow and lets it decrease when the gradients shrink.
+pub struct Yogi<B: Backend> {
+    momentum: YogiMomentum,
+    weight_decay: Option<WeightDecay<B>>,
+}
+
+/// Yogi state.
+#[derive(Record, Clone, new)]
+pub struct YogiState<B: Backend, const D: usize> {
+    momentum: AdaptiveMomentumState<B, D>,
+}
+
+impl<B: Backend> SimpleOptimizer<B> for Yogi<B> {
+    type State<const D: usize> = YogiState<B, D>;
+
+    fn step<const D: usize>(
+        &self,
+        lr: LearningRate,
+        tensor: Tensor<B, D>,
+        mut grad: Tensor<B, D>,
+        state: Option<Self::State<D>>,
+    ) -> (Tensor<B, D>, Option<Self::State<D>>) {
+        let mut state_momentum = None;
+
+        if let Some(state) = state {
+            state_momentum = Some(state.momentum);
+        }
+
+        if let Some(weight_decay) = &self.weight_decay {
+            grad = weight_decay.transform(grad, tensor.clone());
+        }
+
+        let (grad, state_momentum) = self.momentum.transform(grad, state_momentum);
+
+        let state = YogiState::new(state_momentum);
+        let delta = grad.mul_scalar(lr);
+        let mut tensor = tensor - delta;
+        if let Some(weight_decay) = &self.weight_decay {
+            tensor = weight_decay.proximal(lr, tensor);
+        }
+
+        (tensor, Some(state))
+    }
+
+    fn to_device<const D: usize>(
+        mut state: Self::State<D>,
+        device: &<B as Backend>::Device,
+    ) -> Self::State<D> {
+        state.momentum = state.momentum.to_device(device);
+        state
+    }
+
+    fn state_map_tensors<const D: usize, F>(mut state: Self::State<D>, func: F) -> Self::State<D>
+    where
+        F: Fn(Tensor<B, D>) -> Tensor<B, D>,
+    {
+        state.momentum.moment_1 = func(state.momentum.moment_1);
+        state.momentum.moment_2 = func(state.momentum.moment_2);
+        state
+    }
+
+    fn state_num_elements<const D: usize>(state: &Self::State<D>) -> usize {
+        state.momentum.moment_1.shape().num_elements()
+            + state.momentum.moment_2.shape().num_elements()
+    }
+
+    fn state_num_steps<const D: usize>(state: &Self::State<D>) -> Option<usize> {
+        Some(state.momentum.time)
+    }
+
+    fn state_effective_lr<const D: usize>(
+        &self,
+        lr: LearningRate,
+        state: &Self::State<D>,
+    ) -> Option<Tensor<B, D>> {
+        let momentum = &state.momentum;
+        Some(adaptive_effective_lr(
+            lr,
+            &momentum.moment_2,
+            self.momentum.beta_2,
+            self.momentum.epsilon,
+            momentum.time,
+        ))
+    }
+}
+
+impl ValidateConfig for YogiConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        validate_beta("beta_1", self.beta_1)?;
+        validate_beta("beta_2", self.beta_2)?;
+        validate_positive("epsilon", self.epsilon)?;
+        validate_non_negative(
+            "initial_accumulator_value",
+            self.initial_accumulator_value as f64,
+        )?;
+        if let Some(weight_decay) = &self.weight_decay {
+            weight_decay.validate()?;
+        }
+        if let Some(clipping) = &self.grad_clipping {
+            clipping.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl YogiConfig {
+    /// Initialize Yogi optimizer.
+    ///
+    /// # Returns
+    ///
+    /// Returns an optimizer that can be used to optimize a module.
+    pub fn init<B: AutodiffBackend, M: AutodiffModule<B>>(
+        &self,
+    ) -> OptimizerAdaptor<Yogi<B::InnerBackend>, M, B> {
+        let optim = Yogi {
+            momentum: YogiMomentum {
+                beta_1: self.beta_1,
+                beta_2: self.beta_2,
+                epsilon: self.epsilon,
+                initial_accumulator_value: self.initial_accumulator_value,
+            },
+            weight_decay: self
+                .weight_decay
+                .as_ref()
+                .and_then(WeightDecayConfig::for_simple_optimizer)
+                .map(|config| WeightDecay::new(&config)),
+        };
+
+        let mut optim = OptimizerAdaptor::from(optim)
+            .with_grad_scale(self.grad_scale)
+            .with_config(self);
+        if let Some(config) = &self.grad_clipping {
+            optim = optim.with_grad_clipping(config.init());
+        }
+        if let Some(config) = &self.weight_decay {
+            optim = optim.with_weight_decay(config.clone());
+        }
+        if let Some(config) = self.weight_decay.as_ref().filter(|c| c.warmup_steps > 0) {
+            optim = optim.with_weight_decay_warmup(WeightDecayWarmup::new(config));
+        }
+        optim
+    }
+}
+
+struct YogiMomentum {
+    beta_1: f32,
+    beta_2: f32,
+    epsilon: f32,
+    initial_accumulator_value: f32,
+}
+
+impl YogiMomentum {
+    pub fn transform<B: Backend, const D: usize>(
+        &self,
+        grad: Tensor<B, D>,
+        momentum_state: Option<AdaptiveMomentumState<B, D>>,
+    ) -> (Tensor<B, D>, AdaptiveMomentumState<B, D>) {
+        let state = if let Some(mut state) = momentum_state {
+            let factor = 1.0 - self.beta_1;
+            state.moment_1 = state
+                .moment_1
+                .mul_scalar(self.beta_1)
+                .add(grad.clone().mul_scalar(factor));
+            state.moment_2 = self.update_moment_2(state.moment_2, grad);
+            state.time += 1;
+
+            state
+        } else {
+            let factor = 1.0 - self.beta_1;
+            let moment_1 = grad.clone().mul_scalar(factor);
+
+            let moment_2 = grad.zeros_like().add_scalar(self.initial_accumulator_value);
+            let moment_2 = self.update_moment_2(moment_2, grad);
+
+            AdaptiveMomentumState::new(1, moment_1, moment_2)
+        };
+
+        let time = (state.time as i32).elem();
+        let moment_1_corrected = state
+            .moment_1
+            .clone()
+            .div_scalar(1f32 - self.beta_1.powi(time));
+        let moment_2_corrected = state
+            .moment_2
+            .clone()
+            .div_scalar(1f32 - self.beta_2.powi(time));
+
+        let grad = moment_1_corrected.div(moment_2_corrected.sqrt().add_scalar(self.epsilon));
+
+        (grad, state)
+    }
+
+    fn update_moment_2<B: Backend, const D: usize>(
+        &self,
+        moment_2: Tensor<B, D>,
+        grad: Tensor<B, D>,
+    ) -> Tensor<B, D> {
+        let grad_squared = grad.powf(2.0);
+        let diff = moment_2.clone().sub(grad_squared.clone());
+        let sign = diff
+            .ones_like()
+            .mask_fill(diff.clone().lower_elem(0.0), -1.0)
+            .mask_fill(diff.equal_elem(0.0), 0.0);
+
+        moment_2.sub(sign.mul(grad_squared).mul_scalar(1.0 - self.beta_2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tensor::Data;
+    use crate::TestBackend;
+
+    const LEARNING_RATE: LearningRate = 0.1;
+    const ASSERT_PRECISION: usize = 4;
+
+    #[test]
+    fn test_yogi_optimizer_with_numbers() {
+        let optimizer = create_yogi();
+        let tensor = Tensor::<TestBackend, 1>::from_floats([1.0, -2.0, 0.5]);
+        let grad_1 = Tensor::from_floats([1.0, -2.0, 0.5]);
+        let grad_2 = Tensor::from_floats([0.1, -0.2, 0.05]);
+
+        let (tensor, state) = optimizer.step(LEARNING_RATE, tensor, grad_1, None);
+        let state = state.unwrap();
+        state
+            .momentum
+            .moment_2
+            .to_data()
+            .assert_approx_eq(&Data::from([0.6, 0.9, 0.475]), ASSERT_PRECISION);
+        tensor.to_data().assert_approx_eq(
+            &Data::from([0.959192, -1.933356, 0.477069]),
+            ASSERT_PRECISION,
+        );
+
+        let (tensor, state) = optimizer.step(LEARNING_RATE, tensor, grad_2, Some(state));
+        let state = state.unwrap();
+        state
+            .momentum
+            .moment_2
+            .to_data()
+            .assert_approx_eq(&Data::from([0.599, 0.896, 0.47475]), ASSERT_PRECISION);
+        tensor.to_data().assert_approx_eq(
+            &Data::from([0.929566, -1.884905, 0.460432]),
+            ASSERT_PRECISION,
+        );
+    }
+
+    #[test]
+    fn test_yogi_second_moment_decreases_when_gradients_shrink() {
+        let optimizer = create_yogi();
+        let tensor = Tensor::<TestBackend, 1>::from_floats([1.0, -2.0, 0.5]);
+
+        let (tensor, state) = optimizer.step(
+            LEARNING_RATE,
+            tensor,
+            Tensor::from_floats([1.0, 2.0, 3.0]),
+            None,
+        );
+        let moment_2_before = state.as_ref().unwrap().momentum.moment_2.to_data();
+
+        let (_, state) = optimizer.step(
+            LEARNING_RATE,
+            tensor,
+            Tensor::from_floats([0.01, 0.02, 0.03]),
+            state,
+        );
+        let moment_2_after = state.unwrap().momentum.moment_2.to_data();
+
+        for (before, after) in moment_2_before
+            .value
+            .iter()
+            .zip(moment_2_after.value.iter())
+        {
+            assert!(after < before);
+        }
+    }
+
+    fn create_yogi() -> Yogi<TestBackend> {
+        let config = YogiConfig::new()
+            .with_beta_2(0.9)
+            .with_initial_accumulator_value(0.5);
+
+        Yogi {
+            momentum: YogiMomentum {
+                beta_1: config.beta_1,
+                beta_2: config.beta_2,
+                epsilon: config.epsilon,
+                initial_accumulator_value: config.initial_accumulator_value,
+            },
+            weight_decay: None,
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use super::{FileRecorder, RecorderError};
+use crate::module::{Module, ModuleMapper, ModuleVisitor, ParamId};
+use alloc::vec::Vec;
+use burn_tensor::{backend::Backend, Tensor};
+use std::path::PathBuf;
+
+/// Average the parameters of several records of a module, e.g. the last checkpoints of a
+/// training run ("checkpoint soup").
+///
+/// Each record is loaded into a copy of the given module with
+/// [load_file](Module::load_file), so the records must share the structure of the module and
+/// the shape of each parameter. The parameters are matched by their position in the module, and
+/// the returned record keeps the parameter ids of the first record.
+///
+/// # Arguments
+///
+/// * `module` - The module the records were saved from.
+/// * `paths` - The paths of the records, without extension.
+/// * `recorder` - The recorder used to save the records.
+///
+/// # Returns
+///
+/// The record with the elementwise mean of each parameter, ready to be loaded in the module.
+///
+/// # Panics
+///
+/// Panics when no path is given.
+pub fn average_records<B, M, FR>(
+    module: M,
+    paths: &[PathBuf],
+    recorder: &FR,
+) -> Result<M::Record, RecorderError>
+where
+    B: Backend,
+    M: Module<B>,
+    FR: FileRecorder,
+{
+    let Some((first, others)) = paths.split_first() else {
+        panic!("At least one record should be given to average.");
+    };
+
+    let averaged = module.clone().load_file(first.clone(), recorder)?;
+    let mut sums = Vec::new();
+    averaged.visit(&mut ParamsSum::new(&mut sums, None));
+
+    for path in others {
+        let module = module.clone().load_file(path.clone(), recorder)?;
+        module.visit(&mut ParamsSum::new(&mut sums, Some(0)));
+    }
+
+    let num_records = paths.len() as f64;
+    let mut mapper = ParamsAverage {
+        sums: sums.into_iter(),
+        num_records,
+    };
+
+    Ok(averaged.map(&mut mapper).into_record())
+}
+
+/// Sum the flattened parameters of modules visited one after the other.
+#[derive(new)]
+struct ParamsSum<'a, B: Backend> {
+    sums: &'a mut Vec<Tensor<B, 1>>,
+    /// Index of the next parameter, `None` for the first module.
+    index: Option<usize>,
+}
+
+impl<'a, B: Backend> ModuleVisitor<B> for ParamsSum<'a, B> {
+    fn visit<const D: usize>(&mut self, _id: &ParamId, tensor: &Tensor<B, D>) {
+        let num_elements = tensor.shape().num_elements();
+        let tensor = tensor.clone().reshape([num_elements]);
+
+        match self.index.as_mut() {
+            Some(index) => {
+                let sum = self.sums[*index].clone().add(tensor);
+                self.sums[*index] = sum;
+                *index += 1;
+            }
+            None => self.sums.push(tensor),
+        }
+    }
+}
+
+/// Replace the parameters of a module by their average.
+struct ParamsAverage<B: Backend> {
+    sums: alloc::vec::IntoIter<Tensor<B, 1>>,
+    num_records: f64,
+}
+
+impl<B: Backend> ModuleMapper<B> for ParamsAverage<B> {
+    fn map<const D: usize>(&mut self, _id: &ParamId, tensor: Tensor<B, D>) -> Tensor<B, D> {
+        let sum = self
+            .sums
+            .next()
+            .expect("The module should have as many parameters as when it was visited.");
+
+        sum.div_scalar(self.num_records)
+            .reshape(tensor.shape())
+            .set_require_grad(tensor.is_require_grad())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nn::{Linear, LinearConfig},
+        record::{BinFileRecorder, FullPrecisionSettings},
+        TestBackend,
+    };
+
+    fn mean<const D: usize>(tensors: Vec<Tensor<TestBackend, D>>) -> Tensor<TestBackend, D> {
+        let num_tensors = tensors.len() as f64;
+        tensors
+            .into_iter()
+            .reduce(|acc, tensor| acc.add(tensor))
+            .unwrap()
+            .div_scalar(num_tensors)
+    }
+
+    #[test]
+    fn test_average_records_is_the_elementwise_mean() {
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = BinFileRecorder::<FullPrecisionSettings>::new();
+        let linears: Vec<Linear<TestBackend>> =
+            (0..3).map(|_| LinearConfig::new(4, 2).init()).collect();
+        let paths: Vec<PathBuf> = (0..3)
+            .map(|i| dir.path().join(format!("checkpoint-{i}")))
+            .collect();
+        for (linear, path) in linears.iter().zip(&paths) {
+            linear.clone().save_file(path.clone(), &recorder).unwrap();
+        }
+
+        let record = average_records(linears[0].clone(), &paths, &recorder).unwrap();
+
+        let weight = mean(linears.iter().map(|linear| linear.weight.val()).collect());
+        let bias = mean(
+            linears
+                .iter()
+                .map(|linear| linear.bias.as_ref().unwrap().val())
+                .collect(),
+        );
+        record
+            .weight
+            .to_data()
+            .assert_approx_eq(&weight.into_data(), 5);
+        record
+            .bias
+            .unwrap()
+            .to_data()
+            .assert_approx_eq(&bias.into_data(), 5);
+    }
+
+    #[test]
+    fn test_average_records_err_different_structure() {
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = BinFileRecorder::<FullPrecisionSettings>::new();
+        let paths = [dir.path().join("small"), dir.path().join("large")];
+        LinearConfig::new(4, 2)
+            .init::<TestBackend>()
+            .save_file(paths[0].clone(), &recorder)
+            .unwrap();
+        LinearConfig::new(4, 3)
+            .init::<TestBackend>()
+            .save_file(paths[1].clone(), &recorder)
+            .unwrap();
+
+        let result = average_records(
+            LinearConfig::new(4, 2).init::<TestBackend>(),
+            &paths,
+            &recorder,
+        );
+
+        assert!(matches!(result, Err(RecorderError::ShapeMismatch { .. })));
+    }
+}
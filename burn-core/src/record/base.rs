@@ -1,6 +1,7 @@
 pub use burn_derive::Record;
 
 use super::PrecisionSettings;
+use alloc::vec::Vec;
 use serde::{de::DeserializeOwned, Serialize};
 
 /// Trait to define a family of types which can be recorded using any [settings](PrecisionSettings).
@@ -13,4 +14,42 @@ pub trait Record: Send + Sync {
 
     /// Convert the given item into a record.
     fn from_item<S: PrecisionSettings>(item: Self::Item<S>) -> Self;
+
+    /// Summarize the content of the record.
+    fn summary(&self) -> RecordSummary {
+        RecordSummary::default()
+    }
+}
+
+/// Summary of the content of a [record](Record).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordSummary {
+    /// Number of parameters in the record.
+    pub num_params: usize,
+    /// Number of optimization steps, when the record keeps track of it.
+    pub num_steps: Option<usize>,
+    /// Shapes of the parameters of a module record, in the order of the module, e.g. to check
+    /// that a record matches a module before loading it.
+    pub param_shapes: Vec<Vec<usize>>,
+}
+
+impl RecordSummary {
+    /// Combine the summaries of two parts of a record.
+    ///
+    /// The number of parameters is summed, the number of steps is the maximum of both and the
+    /// shapes of the parameters of the other part follow the ones of this part.
+    pub fn merge(mut self, other: Self) -> Self {
+        let num_steps = match (self.num_steps, other.num_steps) {
+            (Some(lhs), Some(rhs)) => Some(usize::max(lhs, rhs)),
+            (lhs, rhs) => lhs.or(rhs),
+        };
+
+        self.param_shapes.extend(other.param_shapes);
+
+        Self {
+            num_params: self.num_params + other.num_params,
+            num_steps,
+            param_shapes: self.param_shapes,
+        }
+    }
 }
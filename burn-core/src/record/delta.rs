@@ -0,0 +1,404 @@
+use crate as burn;
+
+use super::{FileRecorder, Record, RecorderError};
+use crate::module::{Module, ModuleMapper, ModuleVisitor, ParamId};
+use crate::optim::{
+    adaptor::OptimizerAdaptorRecord, record::AdaptorRecord, state_diff, SimpleOptimizer,
+};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use burn_tensor::{backend::Backend, ElementConversion, Tensor};
+use std::path::PathBuf;
+
+/// Parameter of a [delta checkpoint](DeltaRecord), identified by its position in the module.
+#[derive(Record, new)]
+pub struct ParamDelta<B: Backend> {
+    /// The position of the parameter in the module.
+    pub index: usize,
+    /// The flattened value of the parameter.
+    pub value: Tensor<B, 1>,
+}
+
+/// Delta checkpoint, the parameters of a module that changed since a base checkpoint, see
+/// [save_delta].
+#[derive(Record, new)]
+pub struct DeltaRecord<B: Backend> {
+    /// The number of parameter tensors of the module, to check the structure of the base.
+    pub num_tensors: usize,
+    /// The changed parameters.
+    pub params: Vec<ParamDelta<B>>,
+}
+
+/// Delta checkpoint of the state of an [optimizer adaptor](crate::optim::adaptor::OptimizerAdaptor),
+/// the states of the parameters that changed since a base checkpoint, see [save_state_delta].
+#[derive(Record, new)]
+pub struct StateDeltaRecord<R: Record, B: Backend> {
+    /// The changed states, with the state shared by the parameters.
+    pub record: OptimizerAdaptorRecord<R, B>,
+    /// The ids of the parameters whose state was removed since the base checkpoint.
+    pub removed: Vec<String>,
+}
+
+/// Save a delta checkpoint of a module, containing only the parameters that changed since a base
+/// checkpoint, e.g. to save storage when checkpointing frequently between full checkpoints.
+///
+/// A parameter changed when one of its elements moved by more than `tolerance`. The parameters
+/// are matched by their position in the module, like [average_records](super::average_records),
+/// and must keep their shape. The module is reconstructed from the base with [load_delta].
+///
+/// Only the parameters of the module are saved, the state of the optimizer is saved with
+/// [save_state_delta].
+///
+/// # Arguments
+///
+/// * `base` - The module of the base checkpoint.
+/// * `module` - The module to save.
+/// * `tolerance` - The largest change of an element considered unchanged, `0` to keep every
+///   change.
+/// * `path` - The path of the delta checkpoint, without extension.
+/// * `recorder` - The recorder used to save the delta checkpoint.
+///
+/// # Returns
+///
+/// The number of changed parameters.
+pub fn save_delta<B, M, FR>(
+    base: &M,
+    module: &M,
+    tolerance: f64,
+    path: PathBuf,
+    recorder: &FR,
+) -> Result<usize, RecorderError>
+where
+    B: Backend,
+    M: Module<B>,
+    FR: FileRecorder,
+{
+    let mut params_base = Vec::new();
+    base.visit(&mut ParamsFlatten::new(&mut params_base));
+    let mut params = Vec::new();
+    module.visit(&mut ParamsFlatten::new(&mut params));
+
+    if params.len() != params_base.len() {
+        return Err(RecorderError::Deserialize(format!(
+            "The module has {} parameter tensors, the base {}.",
+            params.len(),
+            params_base.len()
+        )));
+    }
+
+    if let Some((param, param_base)) = params
+        .iter()
+        .zip(params_base.iter())
+        .find(|(param, param_base)| param.dims() != param_base.dims())
+    {
+        return Err(RecorderError::ShapeMismatch {
+            expected: param_base.dims().to_vec(),
+            got: param.dims().to_vec(),
+        });
+    }
+
+    let num_tensors = params_base.len();
+    let deltas: Vec<ParamDelta<B>> = params
+        .into_iter()
+        .zip(params_base)
+        .enumerate()
+        .filter(|(_, (param, param_base))| {
+            param
+                .clone()
+                .sub(param_base.clone())
+                .abs()
+                .max()
+                .into_scalar()
+                .elem::<f64>()
+                > tolerance
+        })
+        .map(|(index, (param, _))| ParamDelta::new(index, param))
+        .collect();
+    let num_changed = deltas.len();
+
+    recorder.record(DeltaRecord::new(num_tensors, deltas), path)?;
+
+    Ok(num_changed)
+}
+
+/// Load a [delta checkpoint](save_delta) on top of the module of its base checkpoint.
+///
+/// # Arguments
+///
+/// * `base` - The module of the base checkpoint.
+/// * `path` - The path of the delta checkpoint, without extension.
+/// * `recorder` - The recorder used to save the delta checkpoint.
+///
+/// # Returns
+///
+/// The base module with the changed parameters of the delta checkpoint.
+pub fn load_delta<B, M, FR>(base: M, path: PathBuf, recorder: &FR) -> Result<M, RecorderError>
+where
+    B: Backend,
+    M: Module<B>,
+    FR: FileRecorder,
+{
+    let record: DeltaRecord<B> = recorder.load(path)?;
+    let mut params_base = Vec::new();
+    base.visit(&mut ParamsFlatten::new(&mut params_base));
+    if record.num_tensors != params_base.len() {
+        return Err(RecorderError::Deserialize(format!(
+            "The delta checkpoint has {} parameter tensors, the base {}.",
+            record.num_tensors,
+            params_base.len()
+        )));
+    }
+
+    let mut deltas: Vec<Option<Tensor<B, 1>>> = (0..record.num_tensors).map(|_| None).collect();
+    for delta in record.params {
+        let Some(param) = params_base.get(delta.index) else {
+            return Err(RecorderError::Deserialize(format!(
+                "The delta checkpoint has the parameter {} of {} tensors.",
+                delta.index, record.num_tensors
+            )));
+        };
+        if param.dims() != delta.value.dims() {
+            return Err(RecorderError::ShapeMismatch {
+                expected: param.dims().to_vec(),
+                got: delta.value.dims().to_vec(),
+            });
+        }
+        deltas[delta.index] = Some(delta.value);
+    }
+
+    Ok(base.map(&mut ParamsDeltaApply {
+        deltas: deltas.into_iter(),
+    }))
+}
+
+/// Save a delta checkpoint of the state of an
+/// [optimizer adaptor](crate::optim::adaptor::OptimizerAdaptor), containing only the states that
+/// changed since a base checkpoint, to be saved along the delta of the module of [save_delta].
+///
+/// A state changed when the [relative difference](crate::optim::StateDiff) of one of its tensors
+/// exceeds `tolerance`, or when it isn't in the base. The parameters are matched by id, and the
+/// state is reconstructed from the base with [load_state_delta].
+///
+/// # Arguments
+///
+/// * `base` - The record of the optimizer of the base checkpoint.
+/// * `record` - The record of the optimizer to save.
+/// * `tolerance` - The largest relative difference of a state considered unchanged, `0` to keep
+///   every change.
+/// * `path` - The path of the delta checkpoint, without extension.
+/// * `recorder` - The recorder used to save the delta checkpoint.
+///
+/// # Returns
+///
+/// The number of changed states.
+pub fn save_state_delta<O, B, FR>(
+    base: &OptimizerAdaptorRecord<AdaptorRecord<O, B>, B>,
+    mut record: OptimizerAdaptorRecord<AdaptorRecord<O, B>, B>,
+    tolerance: f64,
+    path: PathBuf,
+    recorder: &FR,
+) -> Result<usize, RecorderError>
+where
+    O: SimpleOptimizer<B>,
+    B: Backend,
+    FR: FileRecorder,
+{
+    let diff = state_diff(&record.params, &base.params);
+    record.params.retain(|id, _| match diff.params.get(id) {
+        Some(diffs) => diffs.iter().any(|diff| *diff > tolerance),
+        None => true,
+    });
+    let removed = diff
+        .unmatched
+        .into_iter()
+        .filter(|id| base.params.contains_key(id))
+        .map(|id| id.to_string())
+        .collect();
+    let num_changed = record.params.len();
+
+    recorder.record(StateDeltaRecord::new(record, removed), path)?;
+
+    Ok(num_changed)
+}
+
+/// Load a [delta checkpoint](save_state_delta) of the state of an optimizer adaptor on top of the
+/// record of its base checkpoint.
+///
+/// # Arguments
+///
+/// * `base` - The record of the optimizer of the base checkpoint.
+/// * `path` - The path of the delta checkpoint, without extension.
+/// * `recorder` - The recorder used to save the delta checkpoint.
+///
+/// # Returns
+///
+/// The record of the base with the changed states of the delta checkpoint.
+pub fn load_state_delta<O, B, FR>(
+    mut base: OptimizerAdaptorRecord<AdaptorRecord<O, B>, B>,
+    path: PathBuf,
+    recorder: &FR,
+) -> Result<OptimizerAdaptorRecord<AdaptorRecord<O, B>, B>, RecorderError>
+where
+    O: SimpleOptimizer<B>,
+    B: Backend,
+    FR: FileRecorder,
+{
+    let delta: StateDeltaRecord<AdaptorRecord<O, B>, B> = recorder.load(path)?;
+    for id in delta.removed {
+        base.params.remove(&ParamId::from(id));
+    }
+    base.params.extend(delta.record.params);
+
+    Ok(OptimizerAdaptorRecord {
+        params: base.params,
+        ..delta.record
+    })
+}
+
+/// Collect the flattened parameters of a module.
+#[derive(new)]
+struct ParamsFlatten<'a, B: Backend> {
+    params: &'a mut Vec<Tensor<B, 1>>,
+}
+
+impl<'a, B: Backend> ModuleVisitor<B> for ParamsFlatten<'a, B> {
+    fn visit<const D: usize>(&mut self, _id: &ParamId, tensor: &Tensor<B, D>) {
+        let num_elements = tensor.shape().num_elements();
+        self.params.push(tensor.clone().reshape([num_elements]));
+    }
+}
+
+/// Replace the changed parameters of a module.
+struct ParamsDeltaApply<B: Backend> {
+    deltas: alloc::vec::IntoIter<Option<Tensor<B, 1>>>,
+}
+
+impl<B: Backend> ModuleMapper<B> for ParamsDeltaApply<B> {
+    fn map<const D: usize>(&mut self, _id: &ParamId, tensor: Tensor<B, D>) -> Tensor<B, D> {
+        let delta = self
+            .deltas
+            .next()
+            .expect("The module should have as many parameters as the delta checkpoint.");
+
+        // The deltas are checked to have the number of elements of their parameter.
+        match delta {
+            Some(value) => value
+                .to_device(&tensor.device())
+                .reshape(tensor.shape())
+                .set_require_grad(tensor.is_require_grad()),
+            None => tensor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nn::{Linear, LinearConfig},
+        optim::{adaptor::OptimizerAdaptor, AdaGrad, AdaGradConfig, GradientsParams, Optimizer},
+        record::{BinFileRecorder, FullPrecisionSettings},
+        tensor::Distribution,
+        TestAutodiffBackend, TestBackend,
+    };
+
+    #[test]
+    fn test_delta_plus_base_reconstructs_the_module() {
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = BinFileRecorder::<FullPrecisionSettings>::new();
+        let base: Linear<TestBackend> = LinearConfig::new(64, 64).init();
+        let mut module = base.clone();
+        module.bias = module
+            .bias
+            .map(|bias| bias.map(|tensor| tensor.add_scalar(1.0)));
+        let (path_base, path_delta) = (dir.path().join("base"), dir.path().join("delta"));
+        base.clone()
+            .save_file(path_base.clone(), &recorder)
+            .unwrap();
+
+        let num_changed = save_delta(&base, &module, 1e-6, path_delta.clone(), &recorder).unwrap();
+
+        // Only the bias changed, so the delta is much smaller than the base.
+        assert_eq!(num_changed, 1);
+        let size = |path: &PathBuf| std::fs::metadata(path.with_extension("bin")).unwrap().len();
+        assert!(size(&path_delta) * 10 < size(&path_base));
+
+        let base = base.load_file(path_base, &recorder).unwrap();
+        let reconstructed = load_delta(base, path_delta, &recorder).unwrap();
+        let (record, record_expected) = (reconstructed.into_record(), module.into_record());
+        assert_eq!(record.weight.to_data(), record_expected.weight.to_data());
+        assert_eq!(
+            record.bias.unwrap().to_data(),
+            record_expected.bias.unwrap().to_data()
+        );
+    }
+
+    #[test]
+    fn test_delta_rejects_a_changed_shape() {
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = BinFileRecorder::<FullPrecisionSettings>::new();
+        let base: Linear<TestBackend> = LinearConfig::new(4, 4).init();
+        let module: Linear<TestBackend> = LinearConfig::new(4, 2).init();
+
+        let result = save_delta(&base, &module, 0.0, dir.path().join("delta"), &recorder);
+
+        assert!(matches!(
+            result,
+            Err(RecorderError::ShapeMismatch { expected, got }) if expected == [16] && got == [8]
+        ));
+    }
+
+    #[test]
+    fn test_malformed_delta_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = BinFileRecorder::<FullPrecisionSettings>::new();
+        let base: Linear<TestBackend> = LinearConfig::new(4, 4).init();
+        let path = dir.path().join("delta");
+        let value = base.weight.val().reshape([16]);
+        recorder
+            .record(
+                DeltaRecord::new(2, vec![ParamDelta::new(2, value)]),
+                path.clone(),
+            )
+            .unwrap();
+
+        let result = load_delta(base, path, &recorder);
+
+        assert!(matches!(result, Err(RecorderError::Deserialize(_))));
+    }
+
+    #[test]
+    fn test_state_delta_plus_base_reconstructs_the_state() {
+        type B = TestAutodiffBackend;
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = BinFileRecorder::<FullPrecisionSettings>::new();
+        let linear: Linear<B> = LinearConfig::new(4, 2).init();
+        let mut optim = AdaGradConfig::new().init();
+        let step = |optim: &mut OptimizerAdaptor<AdaGrad<TestBackend>, Linear<B>, B>,
+                    linear: Linear<B>,
+                    only_bias: bool| {
+            let x = Tensor::<B, 2>::random([3, 4], Distribution::Default);
+            let mut grads = GradientsParams::from_grads(linear.forward(x).backward(), &linear);
+            if only_bias {
+                grads.remove::<TestBackend, 2>(&linear.weight.id);
+            }
+            optim.step(0.1, linear, grads)
+        };
+        let linear = step(&mut optim, linear, false);
+        let base = optim.to_record();
+        let _linear = step(&mut optim, linear, true);
+        let path = dir.path().join("state");
+
+        // Only the state of the bias changed.
+        let num_changed =
+            save_state_delta(&base, optim.to_record(), 1e-6, path.clone(), &recorder).unwrap();
+        assert_eq!(num_changed, 1);
+
+        let record = load_state_delta(base, path, &recorder).unwrap();
+        let diff = state_diff(&record.params, &optim.to_record().params);
+        assert!(diff.unmatched.is_empty());
+        assert_eq!(diff.params.len(), 2);
+        assert!(diff.params.values().flatten().all(|diff| *diff < 1e-6));
+    }
+}
@@ -1,4 +1,4 @@
-use super::{bin_config, PrecisionSettings, Recorder, RecorderError};
+use super::{bin_config, PrecisionSettings, Record, Recorder, RecorderError};
 use core::marker::PhantomData;
 use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 use serde::{de::DeserializeOwned, Serialize};
@@ -52,6 +52,38 @@ pub struct NamedMpkFileRecorder<S: PrecisionSettings> {
     _settings: PhantomData<S>,
 }
 
+/// Convert a record saved with a [file recorder](FileRecorder) to the format of another one, e.g.
+/// to migrate old checkpoints without re-running the training.
+///
+/// The record is loaded with the source recorder and saved with the destination recorder, so
+/// all of its state is kept, including the [parameter ids](crate::module::ParamId) of the
+/// optimizer records.
+///
+/// # Arguments
+///
+/// * `src_path` - The path of the record to convert, without extension.
+/// * `src_recorder` - The recorder the record was saved with.
+/// * `dst_path` - The path of the converted record, without extension.
+/// * `dst_recorder` - The recorder to save the converted record with.
+///
+/// # Notes
+///
+/// The record is converted to the precision settings of the destination recorder.
+pub fn convert_record<R, SR, DR>(
+    src_path: PathBuf,
+    src_recorder: &SR,
+    dst_path: PathBuf,
+    dst_recorder: &DR,
+) -> Result<(), RecorderError>
+where
+    R: Record,
+    SR: FileRecorder,
+    DR: FileRecorder,
+{
+    let record: R = src_recorder.load(src_path)?;
+    dst_recorder.record(record, dst_path)
+}
+
 impl<S: PrecisionSettings> FileRecorder for BinGzFileRecorder<S> {
     fn file_extension() -> &'static str {
         "bin.gz"
@@ -95,7 +127,7 @@ macro_rules! str2reader {
         File::open(path)
             .map_err(|err| match err.kind() {
                 std::io::ErrorKind::NotFound => RecorderError::FileNotFound(err.to_string()),
-                _ => RecorderError::Unknown(err.to_string()),
+                _ => RecorderError::Io(err.to_string()),
             })
             .map(|file| BufReader::new(file))
     }};
@@ -110,13 +142,13 @@ macro_rules! str2writer {
 
         if path.exists() {
             log::info!("File exists, replacing");
-            std::fs::remove_file(path).map_err(|err| RecorderError::Unknown(err.to_string()))?;
+            std::fs::remove_file(path).map_err(|err| RecorderError::Io(err.to_string()))?;
         }
 
         File::create(path)
             .map_err(|err| match err.kind() {
                 std::io::ErrorKind::NotFound => RecorderError::FileNotFound(err.to_string()),
-                _ => RecorderError::Unknown(err.to_string()),
+                _ => RecorderError::Io(err.to_string()),
             })
             .map(|file| BufWriter::new(file))
     }};
@@ -147,7 +179,7 @@ impl<S: PrecisionSettings> Recorder for BinGzFileRecorder<S> {
         let reader = str2reader!(file)?;
         let mut reader = GzDecoder::new(reader);
         let state = bincode::serde::decode_from_std_read(&mut reader, bin_config())
-            .map_err(|err| RecorderError::Unknown(err.to_string()))?;
+            .map_err(|err| RecorderError::Deserialize(err.to_string()))?;
 
         Ok(state)
     }
@@ -174,7 +206,7 @@ impl<S: PrecisionSettings> Recorder for BinFileRecorder<S> {
     fn load_item<I: DeserializeOwned>(&self, mut file: Self::LoadArgs) -> Result<I, RecorderError> {
         let mut reader = str2reader!(file)?;
         let state = bincode::serde::decode_from_std_read(&mut reader, bin_config())
-            .map_err(|err| RecorderError::Unknown(err.to_string()))?;
+            .map_err(|err| RecorderError::Deserialize(err.to_string()))?;
         Ok(state)
     }
 }
@@ -202,7 +234,7 @@ impl<S: PrecisionSettings> Recorder for JsonGzFileRecorder<S> {
         let reader = str2reader!(file)?;
         let reader = GzDecoder::new(reader);
         let state = serde_json::from_reader(reader)
-            .map_err(|err| RecorderError::Unknown(err.to_string()))?;
+            .map_err(|err| RecorderError::Deserialize(err.to_string()))?;
 
         Ok(state)
     }
@@ -228,7 +260,7 @@ impl<S: PrecisionSettings> Recorder for PrettyJsonFileRecorder<S> {
     fn load_item<I: DeserializeOwned>(&self, mut file: Self::LoadArgs) -> Result<I, RecorderError> {
         let reader = str2reader!(file)?;
         let state = serde_json::from_reader(reader)
-            .map_err(|err| RecorderError::Unknown(err.to_string()))?;
+            .map_err(|err| RecorderError::Deserialize(err.to_string()))?;
 
         Ok(state)
     }
@@ -257,7 +289,7 @@ impl<S: PrecisionSettings> Recorder for NamedMpkGzFileRecorder<S> {
         let reader = str2reader!(file)?;
         let reader = GzDecoder::new(reader);
         let state = rmp_serde::decode::from_read(reader)
-            .map_err(|err| RecorderError::Unknown(err.to_string()))?;
+            .map_err(|err| RecorderError::Deserialize(err.to_string()))?;
 
         Ok(state)
     }
@@ -285,7 +317,7 @@ impl<S: PrecisionSettings> Recorder for NamedMpkFileRecorder<S> {
     fn load_item<I: DeserializeOwned>(&self, mut file: Self::LoadArgs) -> Result<I, RecorderError> {
         let reader = str2reader!(file)?;
         let state = rmp_serde::decode::from_read(reader)
-            .map_err(|err| RecorderError::Unknown(err.to_string()))?;
+            .map_err(|err| RecorderError::Deserialize(err.to_string()))?;
 
         Ok(state)
     }
@@ -303,8 +335,10 @@ mod tests {
             conv::{Conv2d, Conv2dConfig},
             Linear, LinearConfig,
         },
+        optim::{momentum::MomentumConfig, AdamConfig, GradientsParams, Optimizer, SgdConfig},
         record::{BinBytesRecorder, FullPrecisionSettings},
-        TestBackend,
+        tensor::{Distribution, Tensor},
+        TestAutodiffBackend, TestBackend,
     };
 
     use crate as burn;
@@ -358,6 +392,107 @@ mod tests {
         assert_eq!(model_bytes_after, model_bytes_before);
     }
 
+    #[test]
+    fn test_load_file_err_shape_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("linear");
+        let recorder = DefaultFileRecorder::<FullPrecisionSettings>::new();
+        LinearConfig::new(4, 4)
+            .init::<TestBackend>()
+            .save_file(path.clone(), &recorder)
+            .unwrap();
+
+        let result = LinearConfig::new(4, 5)
+            .init::<TestBackend>()
+            .load_file(path, &recorder);
+
+        match result {
+            Err(RecorderError::ShapeMismatch { expected, got }) => {
+                assert_eq!(expected, vec![4, 5]);
+                assert_eq!(got, vec![4, 4]);
+            }
+            _ => panic!("Expected a shape mismatch error."),
+        }
+    }
+
+    #[test]
+    fn test_optimizer_load_file_err_deserialize() {
+        type B = TestAutodiffBackend;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("optim");
+        let recorder = DefaultFileRecorder::<FullPrecisionSettings>::new();
+        let linear = LinearConfig::new(4, 2).init::<B>();
+        let mut sgd = SgdConfig::new()
+            .with_momentum(Some(MomentumConfig::new()))
+            .init::<B, Linear<B>>();
+        let x = Tensor::<B, 2>::random([2, 4], Distribution::Default);
+        let grads = GradientsParams::from_grads(linear.forward(x).backward(), &linear);
+        let _linear = sgd.step(0.1, linear, grads);
+        recorder.record(sgd.to_record(), path.clone()).unwrap();
+
+        let result = AdamConfig::new()
+            .init::<B, Linear<B>>()
+            .load_file(path, &recorder);
+
+        assert!(matches!(result, Err(RecorderError::Deserialize(_))));
+    }
+
+    #[test]
+    fn test_optimizer_load_file_err_file_not_found() {
+        type B = TestAutodiffBackend;
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = DefaultFileRecorder::<FullPrecisionSettings>::new();
+
+        let result = AdamConfig::new()
+            .init::<B, Linear<B>>()
+            .load_file(dir.path().join("optim"), &recorder);
+
+        assert!(matches!(result, Err(RecorderError::FileNotFound(_))));
+    }
+
+    #[test]
+    fn test_convert_optimizer_record_round_trip() {
+        type B = TestAutodiffBackend;
+        let linear = LinearConfig::new(4, 2).init::<B>();
+        let x = Tensor::<B, 2>::random([2, 4], Distribution::Default);
+        let mut optim = AdamConfig::new().init::<B, Linear<B>>();
+        let grads = GradientsParams::from_grads(linear.forward(x.clone()).backward(), &linear);
+        let linear = optim.step(0.1, linear, grads);
+
+        let record = convert_bin_to_json_and_back(optim.to_record());
+        let mut optim_converted = AdamConfig::new().init::<B, Linear<B>>().load_record(record);
+
+        // The moments are kept for each parameter, so the next steps are the same.
+        let grads = GradientsParams::from_grads(linear.forward(x.clone()).backward(), &linear);
+        let linear_expected = optim.step(0.1, linear.clone(), grads);
+        let grads = GradientsParams::from_grads(linear.forward(x).backward(), &linear);
+        let linear_actual = optim_converted.step(0.1, linear, grads);
+
+        linear_actual
+            .weight
+            .to_data()
+            .assert_approx_eq(&linear_expected.weight.to_data(), 5);
+        linear_actual
+            .bias
+            .unwrap()
+            .to_data()
+            .assert_approx_eq(&linear_expected.bias.unwrap().to_data(), 5);
+    }
+
+    fn convert_bin_to_json_and_back<R: Record>(record: R) -> R {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("optim");
+        let bin = BinFileRecorder::<FullPrecisionSettings>::new();
+        let json = PrettyJsonFileRecorder::<FullPrecisionSettings>::new();
+        let path_converted = dir.path().join("optim-converted");
+
+        bin.record(record, path.clone()).unwrap();
+        convert_record::<R, _, _>(path.clone(), &bin, path.clone(), &json).unwrap();
+        convert_record::<R, _, _>(path.clone(), &json, path_converted.clone(), &bin).unwrap();
+
+        bin.load(path_converted).unwrap()
+    }
+
     #[derive(Module, Debug)]
     pub struct Model<B: Backend> {
         conv2d1: Conv2d<B>,
@@ -0,0 +1,209 @@
+use super::{BytesRecorder, Record, RecordSummary, Recorder, RecorderError};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use serde::{de::DeserializeOwned, Serialize};
+
+type Hook<A> = Arc<dyn Fn(&A, &RecordSummary) + Send + Sync>;
+
+/// Recorder calling user provided functions each time a record is saved or loaded.
+///
+/// It is created with [with_on_save](Recorder::with_on_save) or
+/// [with_on_load](Recorder::with_on_load) and records items exactly like the wrapped recorder,
+/// so records saved with one can be loaded with the other.
+pub struct HookedRecorder<R: Recorder> {
+    recorder: R,
+    on_save: Vec<Hook<R::RecordArgs>>,
+    on_load: Vec<Hook<R::LoadArgs>>,
+}
+
+impl<R: Recorder> HookedRecorder<R> {
+    /// Wrap the given recorder without any hook.
+    pub fn new(recorder: R) -> Self {
+        Self {
+            recorder,
+            on_save: Vec::new(),
+            on_load: Vec::new(),
+        }
+    }
+
+    /// Add a function called after each record is saved, with the arguments used to save it
+    /// (for instance the file path) and the [summary](RecordSummary) of the saved record.
+    pub fn with_on_save<F>(mut self, func: F) -> Self
+    where
+        F: Fn(&R::RecordArgs, &RecordSummary) + Send + Sync + 'static,
+    {
+        self.on_save.push(Arc::new(func));
+        self
+    }
+
+    /// Add a function called after each record is loaded, with the arguments used to load it
+    /// (for instance the file path) and the [summary](RecordSummary) of the loaded record.
+    pub fn with_on_load<F>(mut self, func: F) -> Self
+    where
+        F: Fn(&R::LoadArgs, &RecordSummary) + Send + Sync + 'static,
+    {
+        self.on_load.push(Arc::new(func));
+        self
+    }
+}
+
+impl<R: Recorder> Default for HookedRecorder<R> {
+    fn default() -> Self {
+        Self::new(R::default())
+    }
+}
+
+impl<R: Recorder> Clone for HookedRecorder<R> {
+    fn clone(&self) -> Self {
+        Self {
+            recorder: self.recorder.clone(),
+            on_save: self.on_save.clone(),
+            on_load: self.on_load.clone(),
+        }
+    }
+}
+
+impl<R: Recorder> core::fmt::Debug for HookedRecorder<R> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("HookedRecorder")
+            .field("recorder", &self.recorder)
+            .field("on_save", &self.on_save.len())
+            .field("on_load", &self.on_load.len())
+            .finish()
+    }
+}
+
+impl<R: Recorder> Recorder for HookedRecorder<R> {
+    type Settings = R::Settings;
+    type RecordArgs = R::RecordArgs;
+    type RecordOutput = R::RecordOutput;
+    type LoadArgs = R::LoadArgs;
+
+    fn record<Rec: Record>(
+        &self,
+        record: Rec,
+        args: Self::RecordArgs,
+    ) -> Result<Self::RecordOutput, RecorderError> {
+        let summary = record.summary();
+        let output = self.recorder.record(record, args.clone())?;
+
+        for hook in self.on_save.iter() {
+            hook(&args, &summary);
+        }
+
+        Ok(output)
+    }
+
+    fn load<Rec: Record>(&self, args: Self::LoadArgs) -> Result<Rec, RecorderError> {
+        let record: Rec = self.recorder.load(args.clone())?;
+        let summary = record.summary();
+
+        for hook in self.on_load.iter() {
+            hook(&args, &summary);
+        }
+
+        Ok(record)
+    }
+
+    fn save_item<I: Serialize>(
+        &self,
+        item: I,
+        args: Self::RecordArgs,
+    ) -> Result<Self::RecordOutput, RecorderError> {
+        self.recorder.save_item(item, args)
+    }
+
+    fn load_item<I: DeserializeOwned>(&self, args: Self::LoadArgs) -> Result<I, RecorderError> {
+        self.recorder.load_item(args)
+    }
+}
+
+impl<R: BytesRecorder> BytesRecorder for HookedRecorder<R> {}
+
+#[cfg(feature = "std")]
+impl<R: super::FileRecorder> super::FileRecorder for HookedRecorder<R> {
+    fn file_extension() -> &'static str {
+        R::file_extension()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{
+        module::Module,
+        nn::{Linear, LinearConfig},
+        optim::{AdamConfig, GradientsParams, Optimizer},
+        record::{BinFileRecorder, FullPrecisionSettings},
+        tensor::{Distribution, Tensor},
+        TestAutodiffBackend, TestBackend,
+    };
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_on_save_fires_once_per_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let saves = Arc::new(AtomicUsize::new(0));
+        let summaries = Arc::new(Mutex::new(Vec::new()));
+        let recorder = BinFileRecorder::<FullPrecisionSettings>::default().with_on_save({
+            let saves = saves.clone();
+            let summaries = summaries.clone();
+            move |_path: &PathBuf, summary: &RecordSummary| {
+                saves.fetch_add(1, Ordering::Relaxed);
+                summaries.lock().unwrap().push(summary.clone());
+            }
+        });
+        let linear = LinearConfig::new(4, 4).init::<TestBackend>();
+
+        linear
+            .clone()
+            .save_file(dir.path().join("first"), &recorder)
+            .unwrap();
+        assert_eq!(saves.load(Ordering::Relaxed), 1);
+        linear
+            .save_file(dir.path().join("second"), &recorder)
+            .unwrap();
+        assert_eq!(saves.load(Ordering::Relaxed), 2);
+
+        let summary = RecordSummary {
+            num_params: 2,
+            num_steps: None,
+            param_shapes: vec![vec![4, 4], vec![4]],
+        };
+        assert_eq!(*summaries.lock().unwrap(), vec![summary.clone(), summary]);
+    }
+
+    #[test]
+    fn test_on_load_receives_optimizer_summary() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("optim");
+        let loaded = Arc::new(Mutex::new(Vec::new()));
+        let recorder = BinFileRecorder::<FullPrecisionSettings>::default().with_on_load({
+            let loaded = loaded.clone();
+            move |path: &PathBuf, summary: &RecordSummary| {
+                loaded.lock().unwrap().push((path.clone(), summary.clone()));
+            }
+        });
+        let mut linear = LinearConfig::new(4, 4).init::<TestAutodiffBackend>();
+        let mut optim =
+            AdamConfig::new().init::<TestAutodiffBackend, Linear<TestAutodiffBackend>>();
+        for _ in 0..3 {
+            let x = Tensor::<TestAutodiffBackend, 2>::random([2, 4], Distribution::Default);
+            let grads = GradientsParams::from_grads(linear.forward(x).backward(), &linear);
+            linear = optim.step(0.01, linear, grads);
+        }
+
+        recorder.record(optim.to_record(), path.clone()).unwrap();
+        let record = recorder.load(path.clone()).unwrap();
+        let _optim = optim.load_record(record);
+
+        let summary = RecordSummary {
+            num_params: 2,
+            num_steps: Some(3),
+            param_shapes: Vec::new(),
+        };
+        assert_eq!(*loaded.lock().unwrap(), vec![(path, summary)]);
+    }
+}
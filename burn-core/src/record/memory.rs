@@ -35,7 +35,8 @@ impl<S: PrecisionSettings> Recorder for BinBytesRecorder<S> {
         Ok(bincode::serde::encode_to_vec(item, bin_config()).unwrap())
     }
     fn load_item<I: DeserializeOwned>(&self, args: Self::LoadArgs) -> Result<I, RecorderError> {
-        let state = bincode::serde::decode_borrowed_from_slice(&args, bin_config()).unwrap();
+        let state = bincode::serde::decode_borrowed_from_slice(&args, bin_config())
+            .map_err(|err| RecorderError::Deserialize(err.to_string()))?;
         Ok(state)
     }
 }
@@ -65,7 +66,7 @@ impl<S: PrecisionSettings> Recorder for NamedMpkBytesRecorder<S> {
         rmp_serde::encode::to_vec_named(&item).map_err(|e| RecorderError::Unknown(e.to_string()))
     }
     fn load_item<I: DeserializeOwned>(&self, args: Self::LoadArgs) -> Result<I, RecorderError> {
-        rmp_serde::decode::from_slice(&args).map_err(|e| RecorderError::Unknown(e.to_string()))
+        rmp_serde::decode::from_slice(&args).map_err(|e| RecorderError::Deserialize(e.to_string()))
     }
 }
 
@@ -2,18 +2,28 @@ mod primitive;
 mod tensor;
 
 mod base;
+mod hooks;
 mod memory;
 mod recorder;
 mod settings;
 
 pub use base::*;
+pub use hooks::*;
 pub use memory::*;
 pub use recorder::*;
 pub use settings::*;
 
+#[cfg(feature = "std")]
+mod average;
+#[cfg(feature = "std")]
+mod delta;
 #[cfg(feature = "std")]
 mod file;
 #[cfg(feature = "std")]
+pub use average::*;
+#[cfg(feature = "std")]
+pub use delta::*;
+#[cfg(feature = "std")]
 pub use file::*;
 
 pub use primitive::ParamSerde;
@@ -7,7 +7,7 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use super::tensor::FloatTensorSerde;
-use super::{PrecisionSettings, Record};
+use super::{PrecisionSettings, Record, RecordSummary};
 use crate::module::{Param, ParamId};
 use burn_tensor::{DataSerialize, Element};
 use hashbrown::HashMap;
@@ -30,6 +30,12 @@ impl<T: Record> Record for Vec<T> {
     fn from_item<S: PrecisionSettings>(item: Self::Item<S>) -> Self {
         item.into_iter().map(Record::from_item).collect()
     }
+
+    fn summary(&self) -> RecordSummary {
+        self.iter()
+            .map(Record::summary)
+            .fold(RecordSummary::default(), RecordSummary::merge)
+    }
 }
 
 impl<T: Record> Record for Option<T> {
@@ -42,6 +48,12 @@ impl<T: Record> Record for Option<T> {
     fn from_item<S: PrecisionSettings>(item: Self::Item<S>) -> Self {
         item.map(Record::from_item)
     }
+
+    fn summary(&self) -> RecordSummary {
+        self.iter()
+            .map(Record::summary)
+            .fold(RecordSummary::default(), RecordSummary::merge)
+    }
 }
 
 impl<const N: usize, T: Record + core::fmt::Debug> Record for [T; N] {
@@ -58,6 +70,12 @@ impl<const N: usize, T: Record + core::fmt::Debug> Record for [T; N] {
             .try_into()
             .unwrap_or_else(|_| panic!("An arrar of size {N}"))
     }
+
+    fn summary(&self) -> RecordSummary {
+        self.iter()
+            .map(Record::summary)
+            .fold(RecordSummary::default(), RecordSummary::merge)
+    }
 }
 
 impl<T: Record> Record for HashMap<ParamId, T> {
@@ -78,6 +96,12 @@ impl<T: Record> Record for HashMap<ParamId, T> {
         });
         record
     }
+
+    fn summary(&self) -> RecordSummary {
+        self.values()
+            .map(Record::summary)
+            .fold(RecordSummary::default(), RecordSummary::merge)
+    }
 }
 
 impl<E: Element> Record for DataSerialize<E> {
@@ -113,6 +137,14 @@ impl<B: Backend, const D: usize> Record for Param<Tensor<B, D>> {
                                                           // Param from a tensor.
         )
     }
+
+    fn summary(&self) -> RecordSummary {
+        RecordSummary {
+            num_params: 1,
+            num_steps: None,
+            param_shapes: alloc::vec![self.value.shape().dims.to_vec()],
+        }
+    }
 }
 
 // Type that can be serialized as is without any conversion.
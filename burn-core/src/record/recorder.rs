@@ -2,9 +2,13 @@ use core::any::type_name;
 
 use alloc::format;
 use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-use super::{BinBytesRecorder, FullPrecisionSettings, PrecisionSettings, Record};
+use super::{
+    BinBytesRecorder, FullPrecisionSettings, HookedRecorder, PrecisionSettings, Record,
+    RecordSummary,
+};
 
 #[cfg(feature = "std")]
 use super::{
@@ -54,7 +58,9 @@ pub trait Recorder: Send + Sync + core::default::Default + core::fmt::Debug + Cl
                 if let Ok(record) = self.load_item::<BurnRecordNoItem>(args.clone()) {
                     let mut message = "Unable to load record.".to_string();
                     let metadata = recorder_metadata::<Self>();
+                    let mut types_match = true;
                     if metadata.float != record.metadata.float {
+                        types_match = false;
                         message += format!(
                             "\nMetadata has a different float type: Actual {:?}, Expected {:?}",
                             record.metadata.float, metadata.float
@@ -62,6 +68,7 @@ pub trait Recorder: Send + Sync + core::default::Default + core::fmt::Debug + Cl
                         .as_str();
                     }
                     if metadata.int != record.metadata.int {
+                        types_match = false;
                         message += format!(
                             "\nMetadata has a different int type: Actual {:?}, Expected {:?}",
                             record.metadata.int, metadata.int
@@ -69,12 +76,25 @@ pub trait Recorder: Send + Sync + core::default::Default + core::fmt::Debug + Cl
                         .as_str();
                     }
                     if metadata.format != record.metadata.format {
+                        types_match = false;
                         message += format!(
                             "\nMetadata has a different format: Actual {:?}, Expected {:?}",
                             record.metadata.format, metadata.format
                         )
                         .as_str();
                     }
+
+                    if types_match {
+                        if metadata.version != record.metadata.version {
+                            return RecorderError::VersionMismatch {
+                                expected: metadata.version,
+                                got: record.metadata.version,
+                            };
+                        }
+
+                        return err;
+                    }
+
                     if metadata.version != record.metadata.version {
                         message += format!(
                             "\nMetadata has a different Burn version: Actual {:?}, Expected {:?}",
@@ -124,6 +144,40 @@ pub trait Recorder: Send + Sync + core::default::Default + core::fmt::Debug + Cl
     ///
     /// The loaded item.
     fn load_item<I: DeserializeOwned>(&self, args: Self::LoadArgs) -> Result<I, RecorderError>;
+
+    /// Call the given function after each record is saved.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - Function called with the arguments used to save the record (for instance the
+    ///   file path) and the [summary](RecordSummary) of the saved record.
+    ///
+    /// # Returns
+    ///
+    /// The recorder with the hook.
+    fn with_on_save<F>(self, func: F) -> HookedRecorder<Self>
+    where
+        F: Fn(&Self::RecordArgs, &RecordSummary) + Send + Sync + 'static,
+    {
+        HookedRecorder::new(self).with_on_save(func)
+    }
+
+    /// Call the given function after each record is loaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - Function called with the arguments used to load the record (for instance the
+    ///   file path) and the [summary](RecordSummary) of the loaded record.
+    ///
+    /// # Returns
+    ///
+    /// The recorder with the hook.
+    fn with_on_load<F>(self, func: F) -> HookedRecorder<Self>
+    where
+        F: Fn(&Self::LoadArgs, &RecordSummary) + Send + Sync + 'static,
+    {
+        HookedRecorder::new(self).with_on_load(func)
+    }
 }
 
 fn recorder_metadata<R: Recorder>() -> BurnMetadata {
@@ -142,6 +196,29 @@ pub enum RecorderError {
     /// File not found.
     FileNotFound(String),
 
+    /// Input/output error other than a missing file.
+    Io(String),
+
+    /// The recorded item can't be deserialized, e.g. a field is missing or has another type, or
+    /// doesn't match the structure it's loaded into, with the error message.
+    Deserialize(String),
+
+    /// A loaded parameter doesn't have the same shape as the parameter it replaces.
+    ShapeMismatch {
+        /// Shape of the parameter before loading.
+        expected: Vec<usize>,
+        /// Shape of the loaded parameter.
+        got: Vec<usize>,
+    },
+
+    /// The record was saved with another Burn version and can't be loaded.
+    VersionMismatch {
+        /// Burn version of the recorder.
+        expected: String,
+        /// Burn version used to save the record.
+        got: String,
+    },
+
     /// Other error.
     Unknown(String),
 }
@@ -255,6 +332,7 @@ mod tests {
     static FILE_PATH: &str = "/tmp/burn_test_record";
 
     use super::*;
+    use crate as burn;
     use burn_tensor::ElementConversion;
 
     #[test]
@@ -293,4 +371,65 @@ mod tests {
             .load::<Item<FullPrecisionSettings>>(FILE_PATH.into())
             .unwrap();
     }
+
+    #[derive(Record)]
+    struct WeightRecord {
+        weight: usize,
+    }
+
+    #[derive(Record)]
+    struct BiasRecord {
+        bias: usize,
+    }
+
+    #[test]
+    fn err_io_when_path_is_a_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("record");
+        std::fs::create_dir(path.with_extension("mpk.gz")).unwrap();
+
+        let recorder = DefaultFileRecorder::<FullPrecisionSettings>::new();
+        let result = recorder.record(WeightRecord { weight: 1 }, path);
+
+        assert!(matches!(result, Err(RecorderError::Io(_))));
+    }
+
+    #[test]
+    fn err_deserialize_when_field_is_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("record");
+
+        let recorder = DefaultFileRecorder::<FullPrecisionSettings>::new();
+        recorder
+            .record(WeightRecord { weight: 1 }, path.clone())
+            .unwrap();
+        let result = recorder.load::<BiasRecord>(path);
+
+        assert!(matches!(result, Err(RecorderError::Deserialize(_))));
+    }
+
+    #[test]
+    fn err_version_mismatch_when_saved_with_another_version() {
+        type TestRecorder = DefaultFileRecorder<FullPrecisionSettings>;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("record");
+        let mut metadata = recorder_metadata::<TestRecorder>();
+        metadata.version = "0.0.0".to_string();
+        let item = WeightRecord { weight: 1 }.into_item::<FullPrecisionSettings>();
+
+        let recorder = TestRecorder::new();
+        recorder
+            .save_item(BurnRecord { metadata, item }, path.clone())
+            .unwrap();
+        let result = recorder.load::<BiasRecord>(path);
+
+        match result {
+            Err(RecorderError::VersionMismatch { expected, got }) => {
+                assert_eq!(expected, env!("CARGO_PKG_VERSION"));
+                assert_eq!(got, "0.0.0");
+            }
+            _ => panic!("Expected a version mismatch error."),
+        }
+    }
 }
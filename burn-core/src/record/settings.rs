@@ -1,4 +1,5 @@
 use burn_tensor::Element;
+use core::marker::PhantomData;
 use serde::{de::DeserializeOwned, Serialize};
 
 /// Settings allowing to control the precision when (de)serializing items.
@@ -10,6 +11,14 @@ pub trait PrecisionSettings:
 
     /// Integer element type.
     type IntElem: Element + Serialize + DeserializeOwned;
+
+    /// If the float tensors of the optimizer state are quantized to 8-bit integers when recorded,
+    /// see [QuantizedPrecisionSettings].
+    const QUANTIZE_OPTIM_STATE: bool = false;
+
+    /// If float tensors are quantized to 8-bit integers when recorded, only enabled by the
+    /// [optimizer state settings](OptimStatePrecisionSettings).
+    const QUANTIZE_FLOAT: bool = false;
 }
 
 /// Default precision settings.
@@ -24,6 +33,23 @@ pub struct HalfPrecisionSettings;
 #[derive(Debug, Default, Clone)]
 pub struct DoublePrecisionSettings;
 
+/// Precision settings quantizing the float tensors of the optimizer state to 8-bit integers in the
+/// log domain, by blocks of values sharing a range, favoring checkpoint size over precision.
+///
+/// This is lossy, but small errors of the optimizer state are acceptable when resuming training.
+/// The other tensors, e.g. the parameters of a module, are recorded with the given settings `S`.
+#[derive(Debug, Default, Clone)]
+pub struct QuantizedPrecisionSettings<S: PrecisionSettings = FullPrecisionSettings> {
+    _settings: PhantomData<S>,
+}
+
+/// Precision settings of the optimizer state recorded with the settings `S`, quantizing its
+/// float tensors when `S` [quantizes the optimizer state](PrecisionSettings::QUANTIZE_OPTIM_STATE).
+#[derive(Debug, Default, Clone)]
+pub struct OptimStatePrecisionSettings<S: PrecisionSettings> {
+    _settings: PhantomData<S>,
+}
+
 impl PrecisionSettings for FullPrecisionSettings {
     type FloatElem = f32;
     type IntElem = f32;
@@ -34,6 +60,21 @@ impl PrecisionSettings for DoublePrecisionSettings {
     type IntElem = i64;
 }
 
+impl<S: PrecisionSettings> PrecisionSettings for QuantizedPrecisionSettings<S> {
+    type FloatElem = S::FloatElem;
+    type IntElem = S::IntElem;
+
+    const QUANTIZE_OPTIM_STATE: bool = true;
+}
+
+impl<S: PrecisionSettings> PrecisionSettings for OptimStatePrecisionSettings<S> {
+    type FloatElem = S::FloatElem;
+    type IntElem = S::IntElem;
+
+    const QUANTIZE_OPTIM_STATE: bool = S::QUANTIZE_OPTIM_STATE;
+    const QUANTIZE_FLOAT: bool = S::QUANTIZE_OPTIM_STATE;
+}
+
 impl PrecisionSettings for HalfPrecisionSettings {
     type FloatElem = half::f16;
     type IntElem = i16;
@@ -1,5 +1,6 @@
 use super::{PrecisionSettings, Record};
-use burn_tensor::{backend::Backend, Bool, DataSerialize, Int, Tensor};
+use alloc::vec::Vec;
+use burn_tensor::{backend::Backend, Bool, DataSerialize, ElementConversion, Int, Tensor};
 use serde::{Deserialize, Serialize};
 
 /// This struct implements serde to lazily serialize and deserialize a float tensor
@@ -22,6 +23,105 @@ pub struct BoolTensorSerde {
     data: DataSerialize<bool>,
 }
 
+/// Number of values sharing the quantization range of a [quantized tensor](QuantizedDataSerialize).
+const QUANTIZATION_BLOCK_SIZE: usize = 64;
+
+/// Float tensor data quantized to 8-bit integers, used when the [settings](PrecisionSettings)
+/// enable float quantization.
+///
+/// The magnitudes are quantized in the log domain by blocks of
+/// [QUANTIZATION_BLOCK_SIZE] values, keeping the sign and the exact zeros. The relative error of a
+/// value only depends on the range of its block, so small values, e.g. of the second moment of
+/// Adam, aren't rounded to zero next to large ones.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct QuantizedDataSerialize {
+    /// The signed level of each value, zero for a zero value.
+    value: Vec<i8>,
+    shape: Vec<usize>,
+    /// The log of the smallest magnitude of each block, the magnitude of the level 1.
+    offsets: Vec<f32>,
+    /// The log step between two levels of each block.
+    scales: Vec<f32>,
+}
+
+impl QuantizedDataSerialize {
+    fn quantize<E: burn_tensor::Element>(data: &DataSerialize<E>) -> Self {
+        let num_levels = i8::MAX as f32;
+        let num_blocks = data.value.len().div_ceil(QUANTIZATION_BLOCK_SIZE);
+        let mut value = Vec::with_capacity(data.value.len());
+        let mut offsets = Vec::with_capacity(num_blocks);
+        let mut scales = Vec::with_capacity(num_blocks);
+
+        for block in data.value.chunks(QUANTIZATION_BLOCK_SIZE) {
+            let logs = block
+                .iter()
+                .map(|value| libm::logf(value.elem::<f32>().abs()));
+            // Zero and non-finite values aren't part of the range.
+            let (min, max) = logs
+                .clone()
+                .filter(|log| log.is_finite())
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), log| {
+                    (min.min(log), max.max(log))
+                });
+
+            let offset = match min.is_finite() {
+                true => min,
+                false => 0.0,
+            };
+            let scale = match max > min {
+                true => (max - min) / (num_levels - 1.0),
+                false => 1.0,
+            };
+
+            value.extend(block.iter().zip(logs).map(|(value, log)| {
+                if !log.is_finite() {
+                    return 0;
+                }
+
+                let level =
+                    libm::roundf((log - offset) / scale).clamp(0.0, num_levels - 1.0) as i8 + 1;
+                match value.elem::<f32>() < 0.0 {
+                    true => -level,
+                    false => level,
+                }
+            }));
+            offsets.push(offset);
+            scales.push(scale);
+        }
+
+        Self {
+            value,
+            shape: data.shape.clone(),
+            offsets,
+            scales,
+        }
+    }
+
+    fn dequantize<E: burn_tensor::Element>(self) -> DataSerialize<E> {
+        let value = self
+            .value
+            .chunks(QUANTIZATION_BLOCK_SIZE)
+            .zip(self.offsets.into_iter().zip(self.scales))
+            .flat_map(|(block, (offset, scale))| {
+                block.iter().map(move |level| {
+                    let magnitude = match level.unsigned_abs() {
+                        0 => 0.0,
+                        level => libm::expf(offset + (level - 1) as f32 * scale),
+                    };
+
+                    match *level < 0 {
+                        true => -magnitude,
+                        false => magnitude,
+                    }
+                    .elem()
+                })
+            })
+            .collect();
+
+        DataSerialize::new(value, self.shape)
+    }
+}
+
 // --- SERDE IMPLEMENTATIONS --- //
 
 impl<S: PrecisionSettings> Serialize for FloatTensorSerde<S> {
@@ -29,6 +129,10 @@ impl<S: PrecisionSettings> Serialize for FloatTensorSerde<S> {
     where
         Se: serde::Serializer,
     {
+        if S::QUANTIZE_FLOAT {
+            return QuantizedDataSerialize::quantize(&self.data).serialize(serializer);
+        }
+
         self.data.serialize(serializer)
     }
 }
@@ -38,6 +142,11 @@ impl<'de, S: PrecisionSettings> Deserialize<'de> for FloatTensorSerde<S> {
     where
         De: serde::Deserializer<'de>,
     {
+        if S::QUANTIZE_FLOAT {
+            let data = QuantizedDataSerialize::deserialize(deserializer)?;
+            return Ok(Self::new(data.dequantize()));
+        }
+
         let data = DataSerialize::<S::FloatElem>::deserialize(deserializer)?;
 
         Ok(Self::new(data))
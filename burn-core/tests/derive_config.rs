@@ -16,6 +16,16 @@ pub struct TestStructConfig {
     other_config: TestEmptyStructConfig,
 }
 
+#[derive(Config, Debug, PartialEq)]
+pub struct TestStructWithNewFieldsConfig {
+    int: i32,
+    #[config(default = 2)]
+    int_default: i32,
+    #[config(default = "String::from(\"Allow\")")]
+    string_default: String,
+    option: Option<f32>,
+}
+
 #[derive(Config, Debug, PartialEq)]
 pub enum TestEnumConfig {
     None,
@@ -36,6 +46,18 @@ fn struct_config_should_impl_serde() {
     assert_eq!(config, config_loaded);
 }
 
+#[test]
+fn struct_config_should_load_missing_fields_with_their_default() {
+    let config = TestStructWithNewFieldsConfig::load_binary(br#"{"int": 1}"#).unwrap();
+
+    assert_eq!(config, TestStructWithNewFieldsConfig::new(1));
+}
+
+#[test]
+fn struct_config_should_not_load_missing_required_fields() {
+    assert!(TestStructWithNewFieldsConfig::load_binary(br#"{"int_default": 1}"#).is_err());
+}
+
 #[test]
 fn struct_config_should_impl_clone() {
     let config = TestStructConfig::new(2, 3.0, "Allow".to_string(), TestEmptyStructConfig::new());
@@ -37,6 +37,12 @@ impl<B: Backend> ModuleComposed<B> {
     }
 }
 
+#[derive(Module, Debug)]
+pub enum ModuleEnum<B: Backend> {
+    Basic(ModuleBasic<B>),
+    Composed(ModuleComposed<B>),
+}
+
 mod state {
     use super::*;
 
@@ -78,6 +84,32 @@ mod state {
             module_2.basic.weight_basic.to_data()
         );
     }
+
+    #[test]
+    fn should_load_from_record_enum() {
+        let module_1 = ModuleEnum::Basic(ModuleBasic::<TestBackend>::new());
+        let module_2 = ModuleEnum::Basic(ModuleBasic::<TestBackend>::new());
+        let state_1 = module_1.clone().into_record();
+
+        let module_2 = module_2.load_record(state_1);
+
+        match (module_1, module_2) {
+            (ModuleEnum::Basic(module_1), ModuleEnum::Basic(module_2)) => assert_eq!(
+                module_1.weight_basic.to_data(),
+                module_2.weight_basic.to_data()
+            ),
+            _ => panic!("The module should keep its variant."),
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_panic_load_from_record_of_another_variant() {
+        let module_1 = ModuleEnum::Basic(ModuleBasic::<TestBackend>::new());
+        let module_2 = ModuleEnum::Composed(ModuleComposed::<TestBackend>::new());
+
+        let _ = module_2.load_record(module_1.into_record());
+    }
 }
 
 mod num_params {
@@ -94,6 +126,15 @@ mod num_params {
         let module = ModuleComposed::<TestBackend>::new();
         assert_eq!(2 * 20 * 20, module.num_params());
     }
+
+    #[test]
+    fn should_calculate_num_params_enum() {
+        let module = ModuleEnum::Basic(ModuleBasic::<TestBackend>::new());
+        assert_eq!(20 * 20, module.num_params());
+
+        let module = ModuleEnum::Composed(ModuleComposed::<TestBackend>::new());
+        assert_eq!(2 * 20 * 20, module.num_params());
+    }
 }
 
 #[cfg(feature = "std")]
@@ -145,3 +186,56 @@ mod require_grad {
         y.backward()
     }
 }
+
+#[cfg(feature = "std")]
+mod optim {
+    use super::*;
+    use burn::module::list_param_ids;
+    use burn::optim::{GradientsParams, Optimizer, SgdConfig};
+
+    type Model = ModuleEnum<TestAutodiffBackend>;
+
+    #[test]
+    fn should_step_each_enum_variant() {
+        let modules = [
+            ModuleEnum::Basic(ModuleBasic::new()),
+            ModuleEnum::Composed(ModuleComposed::new()),
+        ];
+
+        for module in modules {
+            let mut optim = SgdConfig::new().init::<TestAutodiffBackend, Model>();
+            let params_before = params(&module);
+
+            // The loss is the sum of the parameters, so each parameter is decreased by the
+            // learning rate.
+            let grads = GradientsParams::from_grads(loss(&module).backward(), &module);
+            assert_eq!(grads.len(), list_param_ids(&module).len());
+            let module = optim.step(0.5, module, grads);
+
+            let params_after = params(&module);
+            assert_eq!(params_before.len(), params_after.len());
+            for (before, after) in params_before.into_iter().zip(params_after) {
+                after
+                    .into_data()
+                    .assert_approx_eq(&before.sub_scalar(0.5).into_data(), 5);
+            }
+        }
+    }
+
+    fn loss(module: &Model) -> Tensor<TestAutodiffBackend, 1> {
+        params(module)
+            .into_iter()
+            .map(|param| param.sum())
+            .reduce(|acc, sum| acc.add(sum))
+            .unwrap()
+    }
+
+    fn params(module: &Model) -> Vec<Tensor<TestAutodiffBackend, 2>> {
+        match module {
+            ModuleEnum::Basic(basic) => vec![basic.weight_basic.val()],
+            ModuleEnum::Composed(composed) => {
+                vec![composed.weight.val(), composed.basic.weight_basic.val()]
+            }
+        }
+    }
+}
@@ -120,7 +120,6 @@ impl ConfigStructAnalyzer {
                 fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
                 where
                     D: serde::Deserializer<'de> {
-                    #[derive(serde::Deserialize)]
                     #struct_gen
 
                     let serde_state = #struct_name::deserialize(deserializer)?;
@@ -133,6 +132,62 @@ impl ConfigStructAnalyzer {
         }
     }
 
+    /// The serde struct used to deserialize the config, where the missing optional fields are
+    /// `None` and the missing fields with a default get their default value, so configs saved
+    /// before a field was added can still be loaded.
+    fn gen_serde_struct_defaults(&self) -> TokenStream {
+        let struct_name = self.serde_struct_ident();
+        let mut fields = Vec::new();
+        let mut default_fns = quote! {};
+
+        for field in self.fields_required.iter() {
+            let name = field.ident();
+            let ty = &field.field.ty;
+
+            fields.push(quote! {
+                #name: #ty
+            });
+        }
+
+        for field in self.fields_option.iter() {
+            let name = field.ident();
+            let ty = &field.field.ty;
+
+            fields.push(quote! {
+                #[serde(default)]
+                #name: #ty
+            });
+        }
+
+        for (field, attribute) in self.fields_default.iter() {
+            let name = field.ident();
+            let ty = &field.field.ty;
+            let value = default_value(attribute);
+            let fn_name = Ident::new(&format!("default_{name}"), name.span());
+            let fn_path = fn_name.to_string();
+
+            default_fns.extend(quote! {
+                fn #fn_name() -> #ty {
+                    #value
+                }
+            });
+            fields.push(quote! {
+                #[serde(default = #fn_path)]
+                #name: #ty
+            });
+        }
+
+        quote! {
+            #default_fns
+
+            #[derive(serde::Deserialize)]
+            struct #struct_name {
+                #(#fields),*
+            }
+
+        }
+    }
+
     fn gen_serde_struct(&self, names: &[TokenStream]) -> TokenStream {
         let struct_name = self.serde_struct_ident();
 
@@ -172,21 +227,11 @@ impl ConfigAnalyzer for ConfigStructAnalyzer {
 
         for (field, attribute) in self.fields_default.iter() {
             let name = field.ident();
-            let value = &attribute.value;
-            match value {
-                syn::Lit::Str(value) => {
-                    let stream: proc_macro2::TokenStream = value.value().parse().unwrap();
-
-                    body.extend(quote! {
-                        #name: #stream,
-                    });
-                }
-                _ => {
-                    body.extend(quote! {
-                        #name: #value,
-                    });
-                }
-            };
+            let value = default_value(attribute);
+
+            body.extend(quote! {
+                #name: #value,
+            });
         }
 
         let body = quote! {
@@ -246,8 +291,10 @@ impl ConfigAnalyzer for ConfigStructAnalyzer {
         let name_types = self.name_types(&names);
         let struct_gen = self.gen_serde_struct(&name_types);
 
+        let struct_gen_defaults = self.gen_serde_struct_defaults();
+
         let serialize_gen = self.gen_serialize_fn(&struct_name, &struct_gen, &names);
-        let deserialize_gen = self.gen_deserialize_fn(&struct_name, &struct_gen, &names);
+        let deserialize_gen = self.gen_deserialize_fn(&struct_name, &struct_gen_defaults, &names);
 
         quote! {
             #serialize_gen
@@ -295,3 +342,11 @@ impl ConfigAnalyzer for ConfigStructAnalyzer {
         }
     }
 }
+
+/// The default value of a field, given as a literal or as an expression in a string literal.
+fn default_value(attribute: &AttributeItem) -> TokenStream {
+    match &attribute.value {
+        syn::Lit::Str(value) => value.value().parse().unwrap(),
+        value => quote! { #value },
+    }
+}
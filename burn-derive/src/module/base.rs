@@ -1,5 +1,6 @@
 use super::{
-    codegen::ModuleCodegen, codegen_struct::StructModuleCodegen, record::ModuleRecordCodegen,
+    codegen::ModuleCodegen, codegen_enum::EnumModuleCodegen, codegen_struct::StructModuleCodegen,
+    record::ModuleRecordCodegen, record_enum::EnumModuleRecordCodegen,
     record_struct::StructModuleRecordCodegen,
 };
 use crate::module::display;
@@ -25,7 +26,22 @@ pub(crate) fn derive_impl(ast: &syn::DeriveInput) -> TokenStream {
 
     let display_fn = display::display_fn(name);
 
-    let generator = StructModuleCodegen::from_ast(ast);
+    let record_name = Ident::new(format!("{}Record", name).as_str(), name.span());
+    let (generator, record_gen): (Box<dyn ModuleCodegen>, Box<dyn ModuleRecordCodegen>) =
+        match &ast.data {
+            syn::Data::Struct(_) => {
+                let generator = StructModuleCodegen::from_ast(ast);
+                let record_gen = StructModuleRecordCodegen::new(generator.fields.clone());
+                (Box::new(generator), Box::new(record_gen))
+            }
+            syn::Data::Enum(_) => {
+                let generator = EnumModuleCodegen::from_ast(ast, &record_name);
+                let record_gen = EnumModuleRecordCodegen::new(generator.variants.clone());
+                (Box::new(generator), Box::new(record_gen))
+            }
+            syn::Data::Union(_) => panic!("Only structs and enums can be derived"),
+        };
+
     let num_params_fn = generator.gen_num_params();
     let visit = generator.gen_visit();
     let map_mut = generator.gen_map();
@@ -35,8 +51,6 @@ pub(crate) fn derive_impl(ast: &syn::DeriveInput) -> TokenStream {
     let clone_fn = generator.gen_clone();
     let generics_names_except_backend = generics_names_except_backend(&ast.generics);
 
-    let record_name = Ident::new(format!("{}Record", name).as_str(), name.span());
-    let record_gen = StructModuleRecordCodegen::new(generator.fields);
     let record_struct = record_gen.gen_record_type(&record_name, &ast.generics);
 
     let gen = quote! {
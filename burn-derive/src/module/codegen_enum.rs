@@ -0,0 +1,157 @@
+use crate::shared::enum_variant::{parse_variants, EnumVariant};
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+
+use super::codegen::ModuleCodegen;
+
+pub(crate) struct EnumModuleCodegen {
+    pub name: Ident,
+    pub record_name: Ident,
+    pub variants: Vec<EnumVariant>,
+}
+
+impl ModuleCodegen for EnumModuleCodegen {
+    fn gen_num_params(&self) -> TokenStream {
+        let match_body = self.gen_variants_match_fn(|_| {
+            quote! {
+                burn::module::Module::<B>::num_params(module)
+            }
+        });
+
+        quote! {
+            fn num_params(&self) -> usize {
+                #match_body
+            }
+        }
+    }
+
+    fn gen_visit(&self) -> TokenStream {
+        let match_body = self.gen_variants_match_fn(|_| {
+            quote! {
+                burn::module::Module::visit(module, visitor)
+            }
+        });
+
+        quote! {
+            fn visit<V: burn::module::ModuleVisitor<B>>(&self, visitor: &mut V) {
+                #match_body
+            }
+        }
+    }
+
+    fn gen_map(&self) -> TokenStream {
+        let match_body = self.gen_variants_match_fn(|variant| {
+            quote! {
+                Self::#variant(burn::module::Module::map(module, mapper))
+            }
+        });
+
+        quote! {
+            fn map<M: burn::module::ModuleMapper<B>>(self, mapper: &mut M) -> Self {
+                #match_body
+            }
+        }
+    }
+
+    fn gen_valid(&self) -> TokenStream {
+        let name = &self.name;
+        let match_body = self.gen_variants_match_fn(|variant| {
+            quote! {
+                #name::#variant(burn::module::AutodiffModule::<B>::valid(module))
+            }
+        });
+
+        quote! {
+            fn valid(&self) -> Self::InnerModule {
+                #match_body
+            }
+        }
+    }
+
+    fn gen_into_record(&self) -> TokenStream {
+        let record_name = &self.record_name;
+        let match_body = self.gen_variants_match_fn(|variant| {
+            quote! {
+                #record_name::#variant(burn::module::Module::<B>::into_record(module))
+            }
+        });
+
+        quote! {
+            fn into_record(self) -> Self::Record {
+                #match_body
+            }
+        }
+    }
+
+    fn gen_load_record(&self) -> TokenStream {
+        let record_name = &self.record_name;
+        let mut match_arms = quote! {};
+
+        for variant in self.variants.iter() {
+            let ident = &variant.ident;
+
+            match_arms.extend(quote! {
+                (Self::#ident(module), #record_name::#ident(record)) => {
+                    Self::#ident(burn::module::Module::<B>::load_record(module, record))
+                }
+            });
+        }
+
+        quote! {
+            #[allow(unreachable_patterns)]
+            fn load_record(self, record: Self::Record) -> Self {
+                match (self, record) {
+                    #match_arms
+                    _ => panic!("The record variant doesn't match the module variant."),
+                }
+            }
+        }
+    }
+
+    fn gen_clone(&self) -> TokenStream {
+        let match_body = self.gen_variants_match_fn(|variant| {
+            quote! {
+                Self::#variant(module.clone())
+            }
+        });
+
+        quote! {
+            fn clone(&self) -> Self {
+                #match_body
+            }
+        }
+    }
+}
+
+impl EnumModuleCodegen {
+    pub fn from_ast(ast: &syn::DeriveInput, record_name: &Ident) -> Self {
+        Self {
+            name: ast.ident.clone(),
+            record_name: record_name.clone(),
+            variants: parse_variants(ast),
+        }
+    }
+
+    /// Generate a match on the variants of the module, binding the wrapped module to `module`.
+    fn gen_variants_match_fn<F>(&self, func: F) -> TokenStream
+    where
+        F: Fn(Ident) -> TokenStream,
+    {
+        let mut match_arms = quote! {};
+
+        for variant in self.variants.iter() {
+            let ident = &variant.ident;
+            let arm = func(ident.clone());
+
+            match_arms.extend(quote! {
+                Self::#ident(module) => #arm,
+            });
+        }
+
+        quote! {
+            match self {
+                #match_arms
+            }
+        }
+    }
+}
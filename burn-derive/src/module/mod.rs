@@ -1,7 +1,9 @@
 pub(crate) mod codegen;
+pub(crate) mod codegen_enum;
 pub(crate) mod codegen_struct;
 pub(crate) mod display;
 pub(crate) mod record;
+pub(crate) mod record_enum;
 pub(crate) mod record_struct;
 
 mod base;
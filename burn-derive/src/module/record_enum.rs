@@ -0,0 +1,36 @@
+use crate::shared::enum_variant::EnumVariant;
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::Generics;
+
+use super::record::ModuleRecordCodegen;
+
+#[derive(new)]
+pub(crate) struct EnumModuleRecordCodegen {
+    variants: Vec<EnumVariant>,
+}
+
+impl ModuleRecordCodegen for EnumModuleRecordCodegen {
+    fn gen_record_type(&self, record_name: &Ident, generics: &Generics) -> TokenStream {
+        let mut variants = quote! {};
+
+        for variant in self.variants.iter() {
+            let ty = &variant.ty;
+            let name = &variant.ident;
+
+            variants.extend(quote! {
+                /// The module record associative type.
+                #name(<#ty as burn::module::Module<B>>::Record),
+            });
+        }
+
+        quote! {
+
+            /// The record type for the module.
+            #[derive(burn::record::Record, Debug, Clone)]
+            pub enum #record_name #generics {
+                #variants
+            }
+        }
+    }
+}
@@ -2,8 +2,14 @@ use proc_macro2::{Ident, TokenStream};
 use quote::quote;
 use syn::{parse_quote, Generics};
 
-use super::{codegen::RecordItemCodegen, codegen_struct::StructRecordItemCodegen};
-use crate::shared::field::{parse_fields, FieldTypeAnalyzer};
+use super::{
+    codegen::RecordItemCodegen, codegen_enum::EnumRecordItemCodegen,
+    codegen_struct::StructRecordItemCodegen,
+};
+use crate::shared::{
+    enum_variant::parse_variants,
+    field::{parse_fields, FieldTypeAnalyzer},
+};
 
 pub(crate) fn derive_impl(ast: &syn::DeriveInput) -> proc_macro::TokenStream {
     let record_gen = RecordDeriveCodegen::from_ast(ast);
@@ -20,7 +26,7 @@ pub(crate) fn derive_impl(ast: &syn::DeriveInput) -> proc_macro::TokenStream {
 struct RecordDeriveCodegen {
     name_record: Ident,
     name_item: Ident,
-    gen: StructRecordItemCodegen,
+    gen: Box<dyn RecordItemCodegen>,
     generics: Generics,
 }
 
@@ -29,15 +35,23 @@ impl RecordDeriveCodegen {
         let name_record = ast.ident.clone();
         let name_item = Ident::new(format!("{}Item", name_record).as_str(), name_record.span());
 
-        Self {
-            name_record,
-            name_item,
-            gen: StructRecordItemCodegen::new(
+        let gen: Box<dyn RecordItemCodegen> = match &ast.data {
+            syn::Data::Enum(_) => Box::new(EnumRecordItemCodegen::new(
+                name_item.clone(),
+                parse_variants(ast),
+            )),
+            _ => Box::new(StructRecordItemCodegen::new(
                 parse_fields(ast)
                     .into_iter()
                     .map(FieldTypeAnalyzer::new)
                     .collect(),
-            ),
+            )),
+        };
+
+        Self {
+            name_record,
+            name_item,
+            gen,
             generics: ast.generics.clone(),
         }
     }
@@ -64,6 +78,7 @@ impl RecordDeriveCodegen {
         let name_item = &self.name_item;
         let into_item_fn = self.gen.gen_into_item(name_item);
         let from_item_fn = self.gen.gen_from_item();
+        let summary_fn = self.gen.gen_summary();
 
         quote! {
             impl #impl_generics burn::record::Record for #name #ty_generics #where_clause {
@@ -71,6 +86,7 @@ impl RecordDeriveCodegen {
 
                 #into_item_fn
                 #from_item_fn
+                #summary_fn
 
             }
         }
@@ -9,4 +9,6 @@ pub(crate) trait RecordItemCodegen {
     fn gen_into_item(&self, item_name: &Ident) -> TokenStream;
     /// Generate the from item function.
     fn gen_from_item(&self) -> TokenStream;
+    /// Generate the summary function.
+    fn gen_summary(&self) -> TokenStream;
 }
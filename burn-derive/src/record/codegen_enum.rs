@@ -0,0 +1,104 @@
+use crate::shared::enum_variant::EnumVariant;
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::Generics;
+
+use super::codegen::RecordItemCodegen;
+
+#[derive(new)]
+pub(crate) struct EnumRecordItemCodegen {
+    item_name: Ident,
+    variants: Vec<EnumVariant>,
+}
+
+impl RecordItemCodegen for EnumRecordItemCodegen {
+    fn gen_item_type(&self, item_name: &Ident, generics: &Generics) -> TokenStream {
+        let mut variants = quote! {};
+        let mut bounds = quote! {};
+
+        for variant in self.variants.iter() {
+            let ty = &variant.ty;
+            let name = &variant.ident;
+
+            variants.extend(quote! {
+                /// Variant to be serialized.
+                #name(<#ty as burn::record::Record>::Item<S>),
+            });
+            bounds.extend(quote!{
+                <#ty as burn::record::Record>::Item<S>: serde::Serialize + serde::de::DeserializeOwned,
+            });
+        }
+        let bound = bounds.to_string();
+
+        quote! {
+
+            /// The record item type for the module.
+            #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+            #[serde(bound = #bound)]
+            pub enum #item_name #generics {
+                #variants
+            }
+        }
+    }
+
+    fn gen_into_item(&self, item_name: &Ident) -> TokenStream {
+        let mut match_arms = quote! {};
+
+        for variant in self.variants.iter() {
+            let name = &variant.ident;
+
+            match_arms.extend(quote! {
+                Self::#name(record) => #item_name::#name(burn::record::Record::into_item::<S>(record)),
+            });
+        }
+
+        quote! {
+            fn into_item<S: burn::record::PrecisionSettings>(self) -> Self::Item<S> {
+                match self {
+                    #match_arms
+                }
+            }
+        }
+    }
+
+    fn gen_from_item(&self) -> TokenStream {
+        let item_name = &self.item_name;
+        let mut match_arms = quote! {};
+
+        for variant in self.variants.iter() {
+            let name = &variant.ident;
+
+            match_arms.extend(quote! {
+                #item_name::#name(item) => Self::#name(burn::record::Record::from_item::<S>(item)),
+            });
+        }
+
+        quote! {
+            fn from_item<S: burn::record::PrecisionSettings>(item: Self::Item<S>) -> Self {
+                match item {
+                    #match_arms
+                }
+            }
+        }
+    }
+
+    fn gen_summary(&self) -> TokenStream {
+        let mut match_arms = quote! {};
+
+        for variant in self.variants.iter() {
+            let name = &variant.ident;
+
+            match_arms.extend(quote! {
+                Self::#name(record) => burn::record::Record::summary(record),
+            });
+        }
+
+        quote! {
+            fn summary(&self) -> burn::record::RecordSummary {
+                match self {
+                    #match_arms
+                }
+            }
+        }
+    }
+}
@@ -79,4 +79,23 @@ impl RecordItemCodegen for StructRecordItemCodegen {
             }
         }
     }
+
+    fn gen_summary(&self) -> TokenStream {
+        let mut body_summary = quote! {};
+
+        for field in self.fields.iter() {
+            let name = &field.field.ident;
+
+            body_summary.extend(quote! {
+                .merge(burn::record::Record::summary(&self.#name))
+            });
+        }
+
+        quote! {
+            fn summary(&self) -> burn::record::RecordSummary {
+                burn::record::RecordSummary::default()
+                    #body_summary
+            }
+        }
+    }
 }
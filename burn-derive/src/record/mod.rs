@@ -1,4 +1,5 @@
 pub(crate) mod codegen;
+pub(crate) mod codegen_enum;
 pub(crate) mod codegen_struct;
 
 mod base;
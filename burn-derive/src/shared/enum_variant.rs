@@ -0,0 +1,39 @@
+use proc_macro2::Ident;
+use syn::{Type, Variant};
+
+/// A variant of an enum wrapping a single type, e.g. `Small(SmallModel<B>)`.
+#[derive(Clone)]
+pub struct EnumVariant {
+    pub ident: Ident,
+    pub ty: Type,
+}
+
+impl EnumVariant {
+    fn new(variant: &Variant) -> Self {
+        let err = || {
+            panic!(
+                "The variant {} should have exactly one unnamed field",
+                variant.ident
+            )
+        };
+
+        let ty = match &variant.fields {
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                fields.unnamed.first().unwrap().ty.clone()
+            }
+            _ => err(),
+        };
+
+        Self {
+            ident: variant.ident.clone(),
+            ty,
+        }
+    }
+}
+
+pub(crate) fn parse_variants(ast: &syn::DeriveInput) -> Vec<EnumVariant> {
+    match &ast.data {
+        syn::Data::Enum(enum_data) => enum_data.variants.iter().map(EnumVariant::new).collect(),
+        _ => panic!("Only enum variants can be parsed"),
+    }
+}
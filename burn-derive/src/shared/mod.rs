@@ -1,2 +1,3 @@
 pub(crate) mod attribute;
+pub(crate) mod enum_variant;
 pub(crate) mod field;
@@ -78,6 +78,23 @@ where
             .map(|primitive| Tensor::from_primitive(*primitive))
     }
 
+    /// Iterate over the IDs of the registered tensors.
+    pub fn ids(&self) -> impl Iterator<Item = &ID> {
+        self.tensors.keys()
+    }
+
+    /// Iterate over the registered tensors of the given backend and dimension, tensors of
+    /// another dimension being skipped.
+    pub fn iter<B, const D: usize>(&self) -> impl Iterator<Item = (&ID, Tensor<B, D>)>
+    where
+        B: Backend,
+    {
+        self.tensors.iter().filter_map(|(id, item)| {
+            item.downcast_ref::<TensorPrimitive<B, D>>()
+                .map(|primitive| (id, Tensor::from_primitive(primitive.clone())))
+        })
+    }
+
     /// The number of tensors registered.
     pub fn len(&self) -> usize {
         self.tensors.len()
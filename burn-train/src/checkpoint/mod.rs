@@ -2,8 +2,10 @@ mod async_checkpoint;
 mod base;
 mod file;
 mod strategy;
+mod training;
 
 pub use async_checkpoint::*;
 pub use base::*;
 pub use file::*;
 pub use strategy::*;
+pub use training::*;
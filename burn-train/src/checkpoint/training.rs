@@ -0,0 +1,152 @@
+use super::{Checkpointer, CheckpointerError};
+use burn_core::module::AutodiffModule;
+use burn_core::optim::Optimizer;
+use burn_core::record::{PrecisionSettings, Record, RecordSummary};
+use burn_core::tensor::backend::AutodiffBackend;
+use serde::{Deserialize, Serialize};
+
+/// Checkpoint of the optimizer state with the position of the data loader in the epoch, so
+/// training can be interrupted and resumed exactly within an epoch, e.g. on preemptible instances.
+///
+/// The optimizer state and the data cursor are saved in the same record, so a checkpoint can
+/// never contain one without the other.
+#[derive(new)]
+pub struct TrainingCheckpoint<R> {
+    /// The optimizer record.
+    pub optimizer: R,
+    /// Opaque position of the data loader set by the user, e.g. the number of batches already
+    /// processed in the epoch.
+    pub data_cursor: usize,
+}
+
+/// [Training checkpoint](TrainingCheckpoint) record item.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct TrainingCheckpointItem<R: Record, S: PrecisionSettings> {
+    optimizer: R::Item<S>,
+    data_cursor: usize,
+}
+
+impl<R: Record> Record for TrainingCheckpoint<R> {
+    type Item<S: PrecisionSettings> = TrainingCheckpointItem<R, S>;
+
+    fn into_item<S: PrecisionSettings>(self) -> Self::Item<S> {
+        TrainingCheckpointItem {
+            optimizer: self.optimizer.into_item(),
+            data_cursor: self.data_cursor,
+        }
+    }
+
+    fn from_item<S: PrecisionSettings>(item: Self::Item<S>) -> Self {
+        Self {
+            optimizer: R::from_item(item.optimizer),
+            data_cursor: item.data_cursor,
+        }
+    }
+
+    fn summary(&self) -> RecordSummary {
+        self.optimizer.summary()
+    }
+}
+
+impl<R: Record> TrainingCheckpoint<R> {
+    /// Save the state of the optimizer with the data cursor.
+    ///
+    /// # Arguments
+    ///
+    /// * `checkpointer` - The checkpointer.
+    /// * `epoch` - The epoch.
+    /// * `optim` - The optimizer.
+    /// * `data_cursor` - The position of the data loader in the epoch.
+    pub fn save<C, O, M, B>(
+        checkpointer: &C,
+        epoch: usize,
+        optim: &O,
+        data_cursor: usize,
+    ) -> Result<(), CheckpointerError>
+    where
+        C: Checkpointer<Self>,
+        O: Optimizer<M, B, Record = R>,
+        M: AutodiffModule<B>,
+        B: AutodiffBackend,
+    {
+        checkpointer.save(epoch, Self::new(optim.to_record(), data_cursor))
+    }
+
+    /// Restore the state of the optimizer and the data cursor.
+    ///
+    /// # Arguments
+    ///
+    /// * `checkpointer` - The checkpointer.
+    /// * `epoch` - The epoch.
+    /// * `optim` - The optimizer to load the state into.
+    ///
+    /// # Returns
+    ///
+    /// The optimizer with the restored state and the position of the data loader in the epoch.
+    pub fn restore<C, O, M, B>(
+        checkpointer: &C,
+        epoch: usize,
+        optim: O,
+    ) -> Result<(O, usize), CheckpointerError>
+    where
+        C: Checkpointer<Self>,
+        O: Optimizer<M, B, Record = R>,
+        M: AutodiffModule<B>,
+        B: AutodiffBackend,
+    {
+        let checkpoint = checkpointer.restore(epoch)?;
+
+        Ok((
+            optim.load_record(checkpoint.optimizer),
+            checkpoint.data_cursor,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkpoint::FileCheckpointer;
+    use crate::TestAutodiffBackend;
+    use burn_core::nn::{Linear, LinearConfig};
+    use burn_core::optim::{AdamConfig, GradientsParams};
+    use burn_core::record::{BinFileRecorder, FullPrecisionSettings};
+    use burn_core::tensor::{Distribution, Tensor};
+    use tempfile::TempDir;
+
+    type B = TestAutodiffBackend;
+    type M = Linear<TestAutodiffBackend>;
+
+    #[test]
+    fn test_resume_within_epoch_continues_steps_and_cursor() {
+        let temp_dir = TempDir::new().unwrap();
+        let checkpointer = FileCheckpointer::new(
+            BinFileRecorder::<FullPrecisionSettings>::new(),
+            temp_dir.path().to_str().unwrap(),
+            "training",
+        );
+        let mut linear: M = LinearConfig::new(4, 2).init();
+        let mut optim = AdamConfig::new().init::<B, M>();
+
+        for _ in 0..50 {
+            linear = step(&mut optim, linear);
+        }
+        TrainingCheckpoint::save::<_, _, M, B>(&checkpointer, 1, &optim, 50).unwrap();
+
+        let (mut optim, data_cursor) =
+            TrainingCheckpoint::restore::<_, _, M, B>(&checkpointer, 1, AdamConfig::new().init())
+                .unwrap();
+        assert_eq!(data_cursor, 50);
+        assert_eq!(optim.to_record().summary().num_steps, Some(50));
+
+        step(&mut optim, linear);
+        assert_eq!(optim.to_record().summary().num_steps, Some(51));
+    }
+
+    fn step<O: Optimizer<M, B>>(optim: &mut O, linear: M) -> M {
+        let x = Tensor::random([2, 4], Distribution::Default);
+        let grads = GradientsParams::from_grads(linear.forward(x).backward(), &linear);
+        optim.step(0.01, linear, grads)
+    }
+}
@@ -0,0 +1,133 @@
+use crate::metric::Numeric;
+
+/// Exponential moving average of a scalar metric, e.g. to smooth the training loss before
+/// logging it or before early stopping.
+///
+/// The average is updated with `ema = decay * ema + (1 - decay) * value`, starting from zero.
+/// With debiasing, the average is divided by `1 - decay^t` after `t` updates, so that the first
+/// values aren't biased toward zero, like the moments of Adam.
+#[derive(Debug, Clone)]
+pub struct EmaMeter {
+    decay: f64,
+    debias: bool,
+    ema: f64,
+    count: usize,
+}
+
+impl EmaMeter {
+    /// Create a new [exponential moving average meter](EmaMeter) with debiasing.
+    ///
+    /// # Arguments
+    ///
+    /// * `decay` - The decay of the average, in `[0, 1)`. A higher decay is smoother, but lags
+    ///   further behind: the average of a linear trend lags by `decay / (1 - decay)` updates.
+    pub fn new(decay: f64) -> Self {
+        assert!((0.0..1.0).contains(&decay), "The decay must be in [0, 1).");
+
+        Self {
+            decay,
+            debias: true,
+            ema: 0.0,
+            count: 0,
+        }
+    }
+
+    /// Enable or disable the debiasing of the average.
+    pub fn with_debias(mut self, debias: bool) -> Self {
+        self.debias = debias;
+        self
+    }
+
+    /// Update the average with a new value.
+    ///
+    /// # Returns
+    ///
+    /// The updated average.
+    pub fn update(&mut self, value: f64) -> f64 {
+        self.ema = self.decay * self.ema + (1.0 - self.decay) * value;
+        self.count += 1;
+
+        self.value()
+    }
+
+    /// The number of values the average was updated with.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Reset the average.
+    pub fn reset(&mut self) {
+        self.ema = 0.0;
+        self.count = 0;
+    }
+}
+
+impl Numeric for EmaMeter {
+    /// The current average, `NaN` before the first update.
+    fn value(&self) -> f64 {
+        if self.count == 0 {
+            return f64::NAN;
+        }
+
+        if self.debias {
+            self.ema / (1.0 - self.decay.powi(self.count as i32))
+        } else {
+            self.ema
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ema_tracks_noisy_trend_with_lag() {
+        let mut meter = EmaMeter::new(0.9);
+
+        // A linear trend with an alternating noise of amplitude 1.
+        let mut ema = 0.0;
+        for step in 0..200 {
+            let noise = if step % 2 == 0 { 1.0 } else { -1.0 };
+            ema = meter.update(step as f64 + noise);
+        }
+
+        // The average lags by 0.9 / (1 - 0.9) = 9 steps, and the noise is mostly filtered out.
+        let trend = 199.0;
+        assert!((ema - (trend - 9.0)).abs() < 0.1, "{ema}");
+        assert_eq!(meter.count(), 200);
+    }
+
+    #[test]
+    fn test_ema_converges_to_noisy_mean() {
+        let mut meter = EmaMeter::new(0.9);
+
+        for step in 0..100 {
+            let noise = if step % 2 == 0 { 0.5 } else { -0.5 };
+            meter.update(2.0 + noise);
+        }
+
+        assert!((meter.value() - 2.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_ema_debias_corrects_early_values() {
+        let mut biased = EmaMeter::new(0.9).with_debias(false);
+        let mut debiased = EmaMeter::new(0.9);
+        assert!(debiased.value().is_nan());
+
+        for step in 1..=3 {
+            let value_biased = biased.update(5.0);
+            let value_debiased = debiased.update(5.0);
+
+            // Starting from zero, the biased average only reaches 5 * (1 - 0.9^t).
+            let expected = 5.0 * (1.0 - 0.9_f64.powi(step));
+            assert!((value_biased - expected).abs() < 1e-9);
+            assert!((value_debiased - 5.0).abs() < 1e-9);
+        }
+
+        debiased.reset();
+        assert!(debiased.value().is_nan());
+        assert!((debiased.update(3.0) - 3.0).abs() < 1e-9);
+    }
+}
@@ -9,6 +9,7 @@ mod cpu_temp;
 mod cpu_use;
 #[cfg(feature = "metrics")]
 mod cuda;
+mod ema;
 mod learning_rate;
 mod loss;
 #[cfg(feature = "metrics")]
@@ -22,6 +23,7 @@ pub use cpu_temp::*;
 pub use cpu_use::*;
 #[cfg(feature = "metrics")]
 pub use cuda::*;
+pub use ema::*;
 pub use learning_rate::*;
 pub use loss::*;
 #[cfg(feature = "metrics")]